@@ -0,0 +1,89 @@
+use crate::models::{AnalogicalInsight, PsychologicalProfile, TemporalAnalysis};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// Entries kept before the cache starts over; a session rarely gets near it
+const MAX_CACHED_ANALYSES: usize = 64;
+
+/// Output of the deterministic (non-LLM) cognitive stages for one input
+#[derive(Debug, Clone)]
+pub struct CachedAnalysis {
+    pub analogies: Vec<AnalogicalInsight>,
+    pub temporal: TemporalAnalysis,
+    pub psychological: PsychologicalProfile,
+    /// How long the stages took when first computed
+    pub compute_time: Duration,
+    /// Analogy corpus generation the analysis was computed against
+    pub corpus_generation: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalysisCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Sum of the original compute time of every hit
+    pub time_saved: Duration,
+}
+
+/// Content-addressed cache of analysis results keyed on `(input hash, domain)`,
+/// so re-running an input with a different persona or chaos level skips the
+/// analysis. Entries computed against an older analogy corpus never hit.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisCache {
+    entries: HashMap<(u64, String), CachedAnalysis>,
+    stats: AnalysisCacheStats,
+}
+
+impl AnalysisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(input: &str, domain: &str) -> (u64, String) {
+        let mut hasher = DefaultHasher::new();
+        input.hash(&mut hasher);
+        (hasher.finish(), domain.to_lowercase())
+    }
+
+    pub fn get(&mut self, input: &str, domain: &str, corpus_generation: u64) -> Option<&CachedAnalysis> {
+        let key = Self::key(input, domain);
+        match self.entries.get(&key) {
+            Some(entry) if entry.corpus_generation == corpus_generation => {
+                self.stats.hits += 1;
+                self.stats.time_saved += entry.compute_time;
+                tracing::debug!("Analysis cache hit for domain '{}', saved {:?}", domain, entry.compute_time);
+                self.entries.get(&key)
+            },
+            _ => {
+                self.stats.misses += 1;
+                None
+            },
+        }
+    }
+
+    pub fn insert(&mut self, input: &str, domain: &str, analysis: CachedAnalysis) {
+        if self.entries.len() >= MAX_CACHED_ANALYSES {
+            tracing::debug!("Analysis cache full, starting over");
+            self.entries.clear();
+        }
+        self.entries.insert(Self::key(input, domain), analysis);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> AnalysisCacheStats {
+        self.stats
+    }
+}
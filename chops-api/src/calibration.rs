@@ -0,0 +1,213 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An idea the user has scored by hand, used to calibrate the heuristic scorers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabeledExample {
+    pub content: String,
+    pub creativity: f64,
+    pub feasibility: f64,
+    /// Chaos level the idea was generated with (0.0-1.0), feeds the creativity heuristic
+    #[serde(default = "default_chaos_level")]
+    pub chaos_level: f64,
+    /// Unexpected elements the chaos engine gave the idea, which also feed the creativity heuristic
+    #[serde(default)]
+    pub unexpected_elements: usize,
+}
+
+fn default_chaos_level() -> f64 {
+    0.5
+}
+
+/// `calibrated = offset + scale * raw`, clamped to [0.0, 1.0]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LinearCalibration {
+    pub offset: f64,
+    pub scale: f64,
+}
+
+impl LinearCalibration {
+    pub fn identity() -> Self {
+        Self { offset: 0.0, scale: 1.0 }
+    }
+
+    pub fn apply(&self, raw: f64) -> f64 {
+        (self.offset + self.scale * raw).clamp(0.0, 1.0)
+    }
+
+    /// Ordinary least squares fit of `(raw, label)` pairs. Falls back to a pure
+    /// offset when the raw scores have no spread to fit a slope against. Pairs
+    /// with a NaN or infinite value are skipped.
+    pub fn fit(pairs: &[(f64, f64)]) -> Self {
+        let pairs: Vec<(f64, f64)> = pairs.iter().copied().filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+        if pairs.is_empty() {
+            return Self::identity();
+        }
+
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+        let covariance = pairs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+        let variance = pairs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+
+        if variance < 1e-9 {
+            return Self { offset: mean_y - mean_x, scale: 1.0 };
+        }
+
+        let scale = covariance / variance;
+        Self { offset: mean_y - scale * mean_x, scale }
+    }
+
+    pub fn mean_absolute_error(&self, pairs: &[(f64, f64)]) -> f64 {
+        if pairs.is_empty() {
+            return 0.0;
+        }
+        pairs.iter().map(|(x, y)| (self.apply(*x) - y).abs()).sum::<f64>() / pairs.len() as f64
+    }
+}
+
+impl Default for LinearCalibration {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Per-user adjustments applied on top of the heuristic idea scores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreCalibration {
+    pub creativity: LinearCalibration,
+    pub feasibility: LinearCalibration,
+    pub sample_count: usize,
+    pub fitted_at: DateTime<Utc>,
+}
+
+impl ScoreCalibration {
+    #[tracing::instrument(name = "score_calibration_fit", level = "debug", skip(creativity_pairs, feasibility_pairs))]
+    pub fn fit(creativity_pairs: &[(f64, f64)], feasibility_pairs: &[(f64, f64)]) -> CHOPSResult<Self> {
+        if creativity_pairs.len() < 2 || feasibility_pairs.len() < 2 {
+            return Err(CHOPSError::InvalidParameter(
+                "Calibration needs at least 2 labeled examples".to_string()
+            ));
+        }
+        let finite = |pairs: &[(f64, f64)]| pairs.iter().all(|(x, y)| x.is_finite() && y.is_finite());
+        if !finite(creativity_pairs) || !finite(feasibility_pairs) {
+            return Err(CHOPSError::InvalidParameter(
+                "Calibration scores must be finite numbers".to_string()
+            ));
+        }
+
+        let calibration = Self {
+            creativity: LinearCalibration::fit(creativity_pairs),
+            feasibility: LinearCalibration::fit(feasibility_pairs),
+            sample_count: creativity_pairs.len(),
+            fitted_at: Utc::now(),
+        };
+
+        tracing::debug!("Fitted calibration from {} samples: creativity {:?}, feasibility {:?}",
+            calibration.sample_count, calibration.creativity, calibration.feasibility);
+        Ok(calibration)
+    }
+
+    pub fn load_examples(path: &std::path::Path) -> CHOPSResult<Vec<LabeledExample>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(CHOPSError::FileSystemError)?;
+
+        let examples: Vec<LabeledExample> = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to parse labeled examples: {}", e)))?;
+
+        for (i, example) in examples.iter().enumerate() {
+            if !(0.0..=1.0).contains(&example.creativity) || !(0.0..=1.0).contains(&example.feasibility) {
+                return Err(CHOPSError::InvalidParameter(
+                    format!("Example {} has labels outside [0.0, 1.0]", i + 1)
+                ));
+            }
+        }
+
+        Ok(examples)
+    }
+
+    pub fn load_from_file(path: &std::path::Path) -> CHOPSResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(CHOPSError::FileSystemError)?;
+
+        let calibration = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to load calibration: {}", e)))?;
+
+        Ok(Some(calibration))
+    }
+
+    pub fn save_to_file(&self, path: &std::path::Path) -> CHOPSResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(CHOPSError::FileSystemError)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize calibration: {}", e)))?;
+
+        std::fs::write(path, content)
+            .map_err(CHOPSError::FileSystemError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_reproduces_linear_labels() {
+        let pairs: Vec<(f64, f64)> = [0.2, 0.4, 0.6, 0.8].iter().map(|&x| (x, 0.1 + 0.8 * x)).collect();
+        let fitted = LinearCalibration::fit(&pairs);
+        assert!((fitted.offset - 0.1).abs() < 1e-9, "{:?}", fitted);
+        assert!((fitted.scale - 0.8).abs() < 1e-9, "{:?}", fitted);
+        for (raw, label) in &pairs {
+            assert!((fitted.apply(*raw) - label).abs() < 1e-9);
+        }
+        assert!(fitted.mean_absolute_error(&pairs) < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_with_identical_labels_is_flat() {
+        let fitted = LinearCalibration::fit(&[(0.2, 0.7), (0.5, 0.7), (0.9, 0.7)]);
+        assert!(fitted.scale.abs() < 1e-9, "{:?}", fitted);
+        assert!((fitted.apply(0.0) - 0.7).abs() < 1e-9);
+        assert!((fitted.apply(1.0) - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_without_raw_spread_is_a_pure_offset() {
+        let fitted = LinearCalibration::fit(&[(0.5, 0.6), (0.5, 0.8)]);
+        assert_eq!(fitted.scale, 1.0);
+        assert!((fitted.offset - 0.2).abs() < 1e-9, "{:?}", fitted);
+    }
+
+    #[test]
+    fn test_fit_of_nothing_is_the_identity() {
+        let fitted = LinearCalibration::fit(&[]);
+        assert_eq!((fitted.offset, fitted.scale), (0.0, 1.0));
+        assert_eq!(LinearCalibration::fit(&[(f64::NAN, 0.5)]).scale, 1.0);
+    }
+
+    #[test]
+    fn test_fit_skips_nan_pairs() {
+        let fitted = LinearCalibration::fit(&[(0.2, 0.3), (f64::NAN, 0.9), (0.6, f64::NAN), (0.8, 0.9)]);
+        assert!(fitted.offset.is_finite() && fitted.scale.is_finite(), "{:?}", fitted);
+        assert!((fitted.apply(0.2) - 0.3).abs() < 1e-9);
+        assert!((fitted.apply(0.8) - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calibration_needs_two_finite_examples() {
+        let pairs = [(0.2, 0.3), (0.8, 0.9)];
+        assert!(matches!(ScoreCalibration::fit(&pairs[..1], &pairs), Err(CHOPSError::InvalidParameter(_))));
+        assert!(matches!(ScoreCalibration::fit(&pairs, &[]), Err(CHOPSError::InvalidParameter(_))));
+        assert!(matches!(ScoreCalibration::fit(&[(0.2, f64::NAN), (0.8, 0.9)], &pairs), Err(CHOPSError::InvalidParameter(_))));
+        assert_eq!(ScoreCalibration::fit(&pairs, &pairs).unwrap().sample_count, 2);
+    }
+}
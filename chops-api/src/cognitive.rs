@@ -1,7 +1,8 @@
-use chops_core::{CHOPSResult, CHOPSError, PersonaType};
+use chops_core::{cmp_scores, to_yaml, CHOPSError, CHOPSResult, PersonaTrackRecord, PersonaType, Renderable, SimilarityStrategy};
 use chops_persona::PersonaEngine;
 use chops_chaos::ChaosEngine;
-use crate::{ClaudeClient, models::{*, RealityBendType}};
+use crate::{correlation, cosine_similarity, AnalysisCache, AnalysisCacheStats, CachedAnalysis, ClaudeClient, ContextBudget, EmbeddingClient, GrowthPhase, IdeaOptions, TrendAnalyzer, models::{*, RealityBendType}};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -14,99 +15,112 @@ pub struct CognitiveArchitecture {
     temporal_processor: TemporalProcessor,
     psychological_analyzer: PsychologicalAnalyzer,
     reality_calibrator: RealityCalibrator,
+    analysis_cache: AnalysisCache,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct AnalogicalReasoningEngine {
     domain_patterns: HashMap<String, Vec<DomainPattern>>,
     cross_domain_mappings: Vec<CrossDomainMapping>,
+    /// Bumped whenever the pattern corpus changes, invalidating cached analyses
+    corpus_generation: u64,
+    similarity_strategy: SimilarityStrategy,
+    /// Set when the strategy is `Embedding`
+    embeddings: Option<EmbeddingClient>,
 }
 
+/// Lowest similarity at which a source pattern counts as an analogy
+const ANALOGY_SIMILARITY_THRESHOLD: f64 = 0.4;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainPattern {
     pub name: String,
     pub description: String,
+    #[serde(default)]
     pub structural_elements: Vec<String>,
+    #[serde(default)]
     pub behavioral_dynamics: Vec<String>,
+    #[serde(default)]
     pub success_metrics: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CrossDomainMapping {
-    pub source_domain: String,
-    pub target_domain: String,
-    pub mapping_strength: f64,
-    pub successful_analogies: u32,
+/// Source-domain patterns by domain name, as a JSON or TOML file. TOML files
+/// use one array of tables per domain, e.g. `[["supply chain"]]`.
+pub fn read_domains_file(path: &std::path::Path) -> CHOPSResult<std::collections::BTreeMap<String, Vec<DomainPattern>>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(CHOPSError::FileSystemError)?;
+    let is_toml = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+    let domains: std::collections::BTreeMap<String, Vec<DomainPattern>> = if is_toml {
+        toml::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Invalid domains file {}: {}", path.display(), e)))?
+    } else {
+        serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Invalid domains file {}: {}", path.display(), e)))?
+    };
+    
+    if domains.is_empty() {
+        return Err(CHOPSError::InvalidParameter(format!("{} defines no domains", path.display())));
+    }
+    for (name, patterns) in &domains {
+        if normalize_domain(name).is_empty() {
+            return Err(CHOPSError::InvalidParameter(format!("{} has a domain with no name", path.display())));
+        }
+        if patterns.is_empty() {
+            return Err(CHOPSError::InvalidParameter(format!("Domain '{}' has no patterns", name)));
+        }
+        if let Some(pattern) = patterns.iter().find(|pattern| pattern.name.trim().is_empty() || pattern.description.trim().is_empty()) {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "Every pattern in '{}' needs a name and a description (pattern '{}')", name, pattern.name
+            )));
+        }
+    }
+    Ok(domains)
 }
 
-#[derive(Debug, Clone)]
-pub struct TemporalProcessor {
-    historical_patterns: Vec<HistoricalPattern>,
-    trend_analyzers: HashMap<String, TrendAnalyzer>,
+fn normalize_domain(name: &str) -> String {
+    name.trim().to_lowercase()
 }
 
-#[derive(Debug, Clone)]
-pub struct TrendAnalyzer {
-    domain: String,
-    pattern_recognition: PatternRecognition,
-    extrapolation_engine: ExtrapolationEngine,
+/// An input that has been through stages 1-5, ready to generate from
+struct PreparedIdea {
+    enhanced_prompt: String,
+    analogies: Vec<AnalogicalInsight>,
+    temporal: TemporalAnalysis,
+    psychological: PsychologicalProfile,
+    reality: RealityDistortionField,
 }
 
+/// A target-domain concept and the structural roles it can play
 #[derive(Debug, Clone)]
-pub struct PatternRecognition {
-    cyclic_patterns: Vec<CyclicPattern>,
-    growth_patterns: Vec<GrowthPattern>,
-    disruption_indicators: Vec<DisruptionIndicator>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CyclicPattern {
-    pub name: String,
-    pub period_years: f64,
-    pub amplitude: f64,
-    pub confidence: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GrowthPattern {
-    pub pattern_type: GrowthType,
-    pub rate: f64,
-    pub saturation_point: Option<f64>,
-    pub current_phase: GrowthPhase,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum GrowthType {
-    Linear,
-    Exponential,
-    Logistic,
-    Cyclical,
-    Chaotic,
+struct TargetConcept {
+    name: String,
+    roles: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum GrowthPhase {
-    Inception,
-    Growth,
-    Maturity,
-    Decline,
-    Disruption,
+pub struct CrossDomainMapping {
+    pub source_domain: String,
+    pub target_domain: String,
+    pub mapping_strength: f64,
+    pub successful_analogies: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DisruptionIndicator {
-    pub indicator_name: String,
-    pub strength: f64,
-    pub time_horizon: String,
-    pub probability: f64,
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct TemporalProcessor {
+    historical_patterns: Vec<HistoricalPattern>,
+    trend_analyzers: HashMap<String, TrendAnalyzer>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct ExtrapolationEngine {
     mathematical_models: Vec<MathematicalModel>,
     scenario_generators: Vec<ScenarioGenerator>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct MathematicalModel {
     model_type: ModelType,
@@ -123,6 +137,7 @@ pub enum ModelType {
     Oscillatory,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct ScenarioGenerator {
     scenario_type: ScenarioType,
@@ -138,6 +153,7 @@ pub enum ScenarioType {
     Disruptive,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct PsychologicalAnalyzer {
     pattern_detectors: Vec<PatternDetector>,
@@ -145,6 +161,7 @@ pub struct PsychologicalAnalyzer {
     motivation_analyzers: Vec<MotivationAnalyzer>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct PatternDetector {
     pattern_type: PsychologicalPatternType,
@@ -171,6 +188,7 @@ pub enum DetectionAlgorithm {
     ProjectionAnalysis,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct BehavioralModel {
     model_name: String,
@@ -178,6 +196,7 @@ pub struct BehavioralModel {
     accuracy_score: f64,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct MotivationAnalyzer {
     motivation_type: MotivationType,
@@ -205,11 +224,13 @@ pub enum AnalysisDepth {
 
 #[derive(Debug, Clone)]
 pub struct RealityCalibrator {
+    #[allow(dead_code)]
     feasibility_models: Vec<FeasibilityModel>,
     impossibility_detectors: Vec<ImpossibilityDetector>,
     paradox_resolvers: Vec<ParadoxResolver>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct FeasibilityModel {
     domain: String,
@@ -236,13 +257,16 @@ pub enum ConstraintType {
     Temporal,
 }
 
+/// Looks for one kind of impossibility in an idea's wording
 #[derive(Debug, Clone)]
 pub struct ImpossibilityDetector {
     detector_type: ImpossibilityType,
+    /// Findings scoring below this are dropped
     threshold: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ImpossibilityType {
     PhysicsViolation,
     LogicalContradiction,
@@ -251,13 +275,34 @@ pub enum ImpossibilityType {
     InformationParadox,
 }
 
+chops_core::impl_enum_str!(ImpossibilityType {
+    PhysicsViolation => "physics-violation",
+    LogicalContradiction => "logical-contradiction",
+    ResourceConstraint => "resource-constraint",
+    TimeParadox => "time-paradox",
+    InformationParadox => "information-paradox",
+});
+
+/// Something an idea asks of reality that reality won't give
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImpossibilityFinding {
+    pub impossibility_type: ImpossibilityType,
+    /// The wording that gave it away
+    pub evidence: String,
+    pub description: String,
+    /// How sure the detector is that this can't be built as stated (0.0-1.0)
+    pub score: f64,
+}
+
+/// Suggests ways through one type of paradox, in order of preference
 #[derive(Debug, Clone)]
 pub struct ParadoxResolver {
     paradox_type: ParadoxType,
     resolution_strategies: Vec<ResolutionStrategy>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ParadoxType {
     Logical,
     Temporal,
@@ -266,7 +311,16 @@ pub enum ParadoxType {
     Ontological,
 }
 
-#[derive(Debug, Clone)]
+chops_core::impl_enum_str!(ParadoxType {
+    Logical => "logical",
+    Temporal => "temporal",
+    Causal => "causal",
+    Semantic => "semantic",
+    Ontological => "ontological",
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ResolutionStrategy {
     Reframe,
     Contextualize,
@@ -275,10 +329,46 @@ pub enum ResolutionStrategy {
     Transform,
 }
 
+chops_core::impl_enum_str!(ResolutionStrategy {
+    Reframe => "reframe",
+    Contextualize => "contextualize",
+    Transcend => "transcend",
+    Accept => "accept",
+    Transform => "transform",
+});
+
+/// A detected paradox and the suggested way through it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ParadoxResolution {
+    pub paradox_type: ParadoxType,
+    pub paradox: String,
+    pub strategy: ResolutionStrategy,
+    pub suggestion: String,
+    /// Other strategies that would also work, most suitable first
+    pub alternatives: Vec<ResolutionStrategy>,
+}
+
+/// Prompt words that point at a persona, with how the rationale describes them.
+/// Fear language is read from the psychological profile instead.
+const PERSONA_CUES: [(PersonaType, &str, &[&str]); 6] = [
+    (PersonaType::ChaosEngineer, "is about failure and resilience", &["outage", "outages", "resilience", "resilient", "failover", "incident", "incidents", "downtime", "fault"]),
+    (PersonaType::TimeTraveler, "looks far ahead", &["future", "legacy", "decade", "decades", "forecast", "tomorrow", "obsolete", "longevity"]),
+    (PersonaType::PunkHacker, "wants to break the rules", &["hack", "hacks", "break", "bypass", "rebel", "subvert", "underground", "jailbreak"]),
+    (PersonaType::ZenMaster, "asks for simplicity and calm", &["simplify", "simple", "simplicity", "calm", "minimal", "clarity", "balance", "mindful"]),
+    (PersonaType::MindReader, "centres on what people really want", &["user", "users", "intent", "intention", "customer", "customers", "behavior", "behaviour"]),
+    (PersonaType::MadScientist, "calls for wild experimentation", &["experiment", "experimental", "invent", "invention", "prototype", "wild", "radical", "crazy"]),
+];
+
+/// Each voiced fear counts this many keyword hits toward the empathetic persona
+const FEAR_CUE_WEIGHT: usize = 2;
+
 impl CognitiveArchitecture {
-    #[tracing::instrument(name = "cognitive_architecture_new", level = "info")]
-    pub fn new(claude_client: ClaudeClient) -> Self {
+    /// Builds the architecture on a [`ClaudeClient`], or on any other
+    /// `Box<dyn LlmClient>` provider, which runs the same pipeline
+    #[tracing::instrument(name = "cognitive_architecture_new", level = "info", skip(client))]
+    pub fn new(client: impl Into<ClaudeClient>) -> Self {
         tracing::info!("Initializing CognitiveArchitecture with full processing stack");
+        let claude_client = client.into();
         
         tracing::debug!("Creating PersonaEngine");
         let persona_engine = PersonaEngine::new();
@@ -308,88 +398,475 @@ impl CognitiveArchitecture {
             temporal_processor,
             psychological_analyzer,
             reality_calibrator,
+            analysis_cache: AnalysisCache::new(),
         }
     }
+
+    /// Picks a persona when the user didn't name one. The persona memory has
+    /// found best for the domain wins; without history, clear signals in the
+    /// prompt's psychological profile or wording; otherwise `fallback`.
+    #[tracing::instrument(name = "recommend_persona", level = "debug", skip(self, input))]
+    pub async fn recommend_persona(
+        &self,
+        input: &str,
+        domain: &str,
+        remembered: Option<PersonaTrackRecord>,
+        fallback: PersonaType,
+    ) -> CHOPSResult<PersonaRecommendation> {
+        let profile = self.psychological_analyzer.analyze_psychological_patterns(input).await?;
+        let fears = PsychologicalAnalyzer::fear_cues(input);
+        tracing::debug!("Profile lists {} hidden fears, {} voiced", profile.hidden_fears.len(), fears.len());
+
+        let lowered = input.to_lowercase();
+        let words: Vec<&str> = lowered
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let mut best: Option<(PersonaType, usize, String)> = None;
+        if !fears.is_empty() {
+            best = Some((
+                PersonaType::EmpatheticAI,
+                fears.len() * FEAR_CUE_WEIGHT,
+                format!("the prompt voices fear ({}), so an empathetic voice fits", quote_cues(&fears)),
+            ));
+        }
+        for (persona, description, keywords) in PERSONA_CUES.iter() {
+            let hits: Vec<String> = keywords.iter()
+                .filter(|keyword| words.contains(keyword))
+                .map(|keyword| keyword.to_string())
+                .collect();
+            if hits.is_empty() || best.as_ref().is_some_and(|(_, score, _)| *score >= hits.len()) {
+                continue;
+            }
+            best = Some((persona.clone(), hits.len(), format!("the prompt {} ({})", description, quote_cues(&hits))));
+        }
+
+        let recommendation = match (remembered, best) {
+            (Some(record), best) => {
+                let mut rationale = format!(
+                    "it has scored best in {} so far (creativity × satisfaction {:.2} over {} idea{})",
+                    domain, record.score, record.uses, if record.uses == 1 { "" } else { "s" }
+                );
+                if let Some((_, _, cue)) = best.filter(|(persona, _, _)| *persona == record.persona) {
+                    rationale.push_str(&format!(", and {}", cue));
+                }
+                PersonaRecommendation { persona: record.persona, rationale, source: RecommendationSource::Memory }
+            },
+            (None, Some((persona, _, rationale))) => PersonaRecommendation {
+                persona,
+                rationale: format!("{}, with no history in {} to go on", rationale, domain),
+                source: RecommendationSource::Profile,
+            },
+            (None, None) => PersonaRecommendation {
+                persona: fallback,
+                rationale: format!("nothing in the prompt stands out and there is no history for {} to go on, so the configured default", domain),
+                source: RecommendationSource::Default,
+            },
+        };
+        tracing::info!("Recommended {} from {:?}", recommendation.persona, recommendation.source);
+        Ok(recommendation)
+    }
+
+    /// Adds source-domain analogy patterns; cached analyses made without them stop hitting
+    pub fn add_domain_patterns(&mut self, domain: &str, patterns: Vec<DomainPattern>) {
+        self.analogical_reasoner.add_domain_patterns(domain, patterns);
+    }
+
+    pub fn analysis_cache_stats(&self) -> AnalysisCacheStats {
+        self.analysis_cache.stats()
+    }
+
+    pub fn clear_analysis_cache(&mut self) {
+        self.analysis_cache.clear();
+    }
+
+    /// Feeds the temporal stage the series its trend analysis reads (e.g.
+    /// [`chops_core::MemorySystem::tag_activity`]). Cached analyses built on
+    /// other series are dropped.
+    pub fn observe_trends(&mut self, series: Vec<(String, Vec<f64>)>) {
+        if self.temporal_processor.observe_series(series) {
+            tracing::debug!("Trend series changed, clearing analysis cache");
+            self.analysis_cache.clear();
+        }
+    }
+
+    pub fn claude_client(&self) -> &ClaudeClient {
+        &self.claude_client
+    }
+
+    pub fn claude_client_mut(&mut self) -> &mut ClaudeClient {
+        &mut self.claude_client
+    }
+
+    pub fn chaos_engine(&self) -> &ChaosEngine {
+        &self.chaos_engine
+    }
+
+    pub fn chaos_engine_mut(&mut self) -> &mut ChaosEngine {
+        &mut self.chaos_engine
+    }
+
+    pub fn analogical_reasoner_mut(&mut self) -> &mut AnalogicalReasoningEngine {
+        &mut self.analogical_reasoner
+    }
+
+    /// Generates straight from `prompt` with the chaos engine at `level`,
+    /// skipping the analysis stages so chaos is the only thing that differs
+    /// between calls. The engine's own level is put back afterwards.
+    #[tracing::instrument(name = "generate_at_chaos_level", level = "info", skip(self, prompt))]
+    pub async fn generate_at_chaos_level(
+        &mut self,
+        prompt: &str,
+        persona: PersonaType,
+        domain: &str,
+        level: u8,
+    ) -> CHOPSResult<GeneratedIdeaResponse> {
+        let previous_level = self.chaos_engine.chaos_level;
+        self.chaos_engine.set_chaos_level(level)?;
+        let result = self.claude_client
+            .generate_idea_with_persona(&self.persona_engine, &mut self.chaos_engine, prompt, persona, domain)
+            .await;
+        self.chaos_engine.chaos_level = previous_level;
+        result
+    }
+
+    /// Generates the same prompt once per model, concurrently. Every run gets
+    /// its own copy of the client and chaos engine, seeded alike, so the model
+    /// is the only thing that differs. Runs come back in the order given.
+    #[tracing::instrument(name = "compare_models", level = "info", skip(self, prompt))]
+    pub async fn compare_models(
+        &self,
+        prompt: &str,
+        persona: PersonaType,
+        domain: &str,
+        level: u8,
+        seed: u64,
+        models: &[String],
+    ) -> CHOPSResult<Vec<ModelRun>> {
+        let mut runs = Vec::with_capacity(models.len());
+        for model in models {
+            let mut client = self.claude_client.clone();
+            client.set_model(model.clone());
+            let mut chaos_engine = self.chaos_engine.clone();
+            chaos_engine.set_chaos_level(level)?;
+            chaos_engine.set_seed(Some(seed));
+            let persona = persona.clone();
+            let persona_engine = &self.persona_engine;
+
+            runs.push(async move {
+                let started = std::time::Instant::now();
+                let result = client
+                    .generate_idea_with_persona(persona_engine, &mut chaos_engine, prompt, persona, domain)
+                    .await;
+                tracing::debug!("{} finished in {:?}", model, started.elapsed());
+                ModelRun { model: model.clone(), latency: started.elapsed(), result }
+            });
+        }
+        Ok(futures_util::future::join_all(runs).await)
+    }
+
+    pub fn persona_engine(&self) -> &PersonaEngine {
+        &self.persona_engine
+    }
+
+    pub fn persona_engine_mut(&mut self) -> &mut PersonaEngine {
+        &mut self.persona_engine
+    }
     
-    #[tracing::instrument(name = "process_complex_idea", level = "info", skip(self))]
     pub async fn process_complex_idea(
         &mut self,
         input: &str,
         persona: PersonaType,
         domain: &str,
         complexity_level: f64,
+    ) -> CHOPSResult<ComplexIdeaResult> {
+        self.process_complex_idea_with_context(input, persona, domain, complexity_level, None, &IdeaOptions::default()).await
+    }
+
+    /// Like `process_complex_idea`, with extra grounding text (a prior idea or
+    /// reference material) added to the synthesized prompt and `options` for
+    /// the generation.
+    #[tracing::instrument(name = "process_complex_idea", level = "info", skip(self, grounding, options))]
+    pub async fn process_complex_idea_with_context(
+        &mut self,
+        input: &str,
+        persona: PersonaType,
+        domain: &str,
+        complexity_level: f64,
+        grounding: Option<&GroundingContext>,
+        options: &IdeaOptions,
     ) -> CHOPSResult<ComplexIdeaResult> {
         tracing::info!("Processing complex idea with persona: {:?}, domain: '{}', complexity: {:.2}", 
             persona, domain, complexity_level);
         tracing::debug!("Input length: {} characters", input.len());
         
         // Multi-stage processing pipeline
+        let prepared = self.prepare_idea(input, domain, complexity_level, grounding).await?;
         
-        // Stage 1: Analogical reasoning
-        tracing::debug!("Stage 1: Running analogical reasoning");
-        let analogies = self.analogical_reasoner
-            .find_cross_domain_analogies(input, domain)
+        // Stage 6: Generate with full cognitive stack
+        tracing::debug!("Stage 6: Generating idea with full cognitive stack");
+        let generated_idea = self.claude_client
+            .generate_idea_with_options(
+                &self.persona_engine,
+                &mut self.chaos_engine,
+                &prepared.enhanced_prompt,
+                persona,
+                domain,
+                options,
+            )
             .await?;
-        tracing::debug!("Found {} analogical insights", analogies.len());
         
-        // Stage 2: Temporal analysis
-        tracing::debug!("Stage 2: Running temporal analysis");
-        let temporal_analysis = self.temporal_processor
-            .analyze_temporal_implications(input, domain)
-            .await?;
-        tracing::debug!("Temporal analysis complete with {} future projections", temporal_analysis.future_projections.len());
+        let enriched_result = self.enrich_prepared(generated_idea, prepared).await?;
+        tracing::info!("Complex idea processing complete - synthesis quality: {:.2}", enriched_result.synthesis_quality);
+        Ok(enriched_result)
+    }
+    
+    /// Runs `process_complex_idea_with_context` for each request, all with the
+    /// same grounding and options, with up to `concurrency` generations in
+    /// flight. Stages 1-5 run first, in order, on this architecture; each
+    /// generation then gets a fork of the client and its own copy of the chaos
+    /// engine. `on_result` sees each result as soon as it finishes, and all of
+    /// them come back in request order.
+    #[tracing::instrument(name = "process_complex_ideas", level = "info", skip(self, requests, grounding, options, on_result), fields(count = requests.len()))]
+    pub async fn process_complex_ideas(
+        &mut self,
+        requests: &[IdeaRequest],
+        complexity_level: f64,
+        grounding: Option<&GroundingContext>,
+        options: &IdeaOptions,
+        concurrency: usize,
+        mut on_result: impl FnMut(usize, &CHOPSResult<ComplexIdeaResult>),
+    ) -> Vec<CHOPSResult<ComplexIdeaResult>> {
+        use futures_util::stream::{FuturesUnordered, StreamExt};
         
-        // Stage 3: Psychological profiling
-        tracing::debug!("Stage 3: Running psychological analysis");
-        let psychological_profile = self.psychological_analyzer
-            .analyze_psychological_patterns(input)
+        let mut prepared = Vec::with_capacity(requests.len());
+        for request in requests {
+            prepared.push(self.prepare_idea(&request.input, &request.domain, complexity_level, grounding).await);
+        }
+        
+        let mut results: Vec<Option<CHOPSResult<ComplexIdeaResult>>> = (0..requests.len()).map(|_| None).collect();
+        let mut forks = Vec::new();
+        {
+            let this = &*self;
+            let semaphore = tokio::sync::Semaphore::new(concurrency.max(1));
+            let mut pending = FuturesUnordered::new();
+            for (index, prepared) in prepared.into_iter().enumerate() {
+                let prepared = match prepared {
+                    Ok(prepared) => prepared,
+                    Err(e) => {
+                        let result = Err(e);
+                        on_result(index, &result);
+                        results[index] = Some(result);
+                        continue;
+                    },
+                };
+                let request = &requests[index];
+                let semaphore = &semaphore;
+                pending.push(async move {
+                    let _permit = semaphore.acquire().await.expect("the semaphore is never closed");
+                    let mut client = this.claude_client.fork();
+                    let mut chaos_engine = this.chaos_engine.clone();
+                    let generated = client
+                        .generate_idea_with_options(
+                            &this.persona_engine,
+                            &mut chaos_engine,
+                            &prepared.enhanced_prompt,
+                            request.persona.clone(),
+                            &request.domain,
+                            options,
+                        )
+                        .await;
+                    let result = match generated {
+                        Ok(idea) => this.enrich_prepared(idea, prepared).await,
+                        Err(e) => Err(e),
+                    };
+                    (index, result, client)
+                });
+            }
+            
+            while let Some((index, result, client)) = pending.next().await {
+                tracing::debug!("Batch idea {} finished, ok: {}", index, result.is_ok());
+                on_result(index, &result);
+                results[index] = Some(result);
+                forks.push(client);
+            }
+        }
+        
+        for fork in &forks {
+            self.claude_client.absorb_usage(fork);
+        }
+        results.into_iter()
+            .map(|result| result.expect("every batch request produces a result"))
+            .collect()
+    }
+    
+    /// Stage 4 on its own: the reality calibrator's verdict on `input`, for
+    /// pre-filtering ideas without paying for generation. Never touches the LLM.
+    #[tracing::instrument(name = "assess_only", level = "info", skip(self))]
+    pub async fn assess_only(&self, input: &str, complexity_level: f64) -> CHOPSResult<RealityDistortionField> {
+        if input.trim().is_empty() {
+            return Err(CHOPSError::InvalidParameter("Nothing to assess: the idea is empty".to_string()));
+        }
+        if !(0.0..=1.0).contains(&complexity_level) {
+            return Err(CHOPSError::InvalidParameter(format!("Complexity level must be 0.0-1.0, got {}", complexity_level)));
+        }
+        let reality = self.reality_calibrator
+            .assess_reality_compatibility(input, complexity_level)
             .await?;
-        tracing::debug!("Psychological profile generated with {} unspoken desires", psychological_profile.unspoken_desires.len());
+        tracing::debug!("Reality assessment complete - distortion level: {:.2}", reality.distortion_level);
+        Ok(reality)
+    }
+
+    /// Stages 1-5: analysis, reality calibration and prompt synthesis
+    async fn prepare_idea(
+        &mut self,
+        input: &str,
+        domain: &str,
+        complexity_level: f64,
+        grounding: Option<&GroundingContext>,
+    ) -> CHOPSResult<PreparedIdea> {
+        // Stages 1-3 depend only on the input and domain, so they come from
+        // the cache when only the persona or chaos changed
+        let CachedAnalysis { analogies, temporal, psychological, .. } =
+            self.analyze_input(input, domain).await?;
         
         // Stage 4: Reality calibration
         tracing::debug!("Stage 4: Running reality calibration");
-        let reality_assessment = self.reality_calibrator
+        let reality = self.reality_calibrator
             .assess_reality_compatibility(input, complexity_level)
             .await?;
-        tracing::debug!("Reality assessment complete - distortion level: {:.2}", reality_assessment.distortion_level);
+        tracing::debug!("Reality assessment complete - distortion level: {:.2}", reality.distortion_level);
         
         // Stage 5: AI consciousness synthesis
         tracing::debug!("Stage 5: Synthesizing enhanced prompt");
         let enhanced_prompt = self.synthesize_enhanced_prompt(
             input,
             &analogies,
-            &temporal_analysis,
-            &psychological_profile,
-            &reality_assessment,
+            &temporal,
+            &psychological,
+            &reality,
+            grounding,
         ).await?;
         tracing::debug!("Enhanced prompt synthesized - length: {} characters", enhanced_prompt.len());
         
-        // Stage 6: Generate with full cognitive stack
-        tracing::debug!("Stage 6: Generating idea with full cognitive stack");
-        let generated_idea = self.claude_client
-            .generate_idea_with_persona(
-                &self.persona_engine,
-                &mut self.chaos_engine,
-                &enhanced_prompt,
-                persona,
-                domain,
-            )
-            .await?;
-        
-        // Stage 7: Post-process and enrich
+        Ok(PreparedIdea { enhanced_prompt, analogies, temporal, psychological, reality })
+    }
+    
+    /// Stage 7: Post-process and enrich
+    async fn enrich_prepared(&self, generated_idea: GeneratedIdeaResponse, prepared: PreparedIdea) -> CHOPSResult<ComplexIdeaResult> {
         tracing::debug!("Stage 7: Enriching with cognitive insights");
-        let enriched_result = self.enrich_with_cognitive_insights(
+        self.enrich_with_cognitive_insights(
             generated_idea,
-            analogies,
-            temporal_analysis,
-            psychological_profile,
-            reality_assessment,
-        ).await?;
+            prepared.analogies,
+            prepared.temporal,
+            prepared.psychological,
+            prepared.reality,
+        ).await
+    }
+    
+    /// Runs the deterministic analysis stages, or reuses their cached output
+    async fn analyze_input(&mut self, input: &str, domain: &str) -> CHOPSResult<CachedAnalysis> {
+        let corpus_generation = self.analogical_reasoner.corpus_generation();
+        if let Some(cached) = self.analysis_cache.get(input, domain, corpus_generation) {
+            tracing::debug!("Stages 1-3: Reusing cached analysis");
+            return Ok(cached.clone());
+        }
         
-        tracing::info!("Complex idea processing complete - synthesis quality: {:.2}", enriched_result.synthesis_quality);
-        Ok(enriched_result)
+        let started = std::time::Instant::now();
+        
+        // Stage 1: Analogical reasoning
+        tracing::debug!("Stage 1: Running analogical reasoning");
+        let analogies = self.analogical_reasoner
+            .find_cross_domain_analogies(input, domain)
+            .await?;
+        tracing::debug!("Found {} analogical insights", analogies.len());
+        
+        // Stage 2: Temporal analysis
+        tracing::debug!("Stage 2: Running temporal analysis");
+        let temporal = self.temporal_processor
+            .analyze_temporal_implications(input, domain)
+            .await?;
+        tracing::debug!("Temporal analysis complete with {} future projections", temporal.future_projections.len());
+        
+        // Stage 3: Psychological profiling
+        tracing::debug!("Stage 3: Running psychological analysis");
+        let psychological = self.psychological_analyzer
+            .analyze_psychological_patterns(input)
+            .await?;
+        tracing::debug!("Psychological profile generated with {} unspoken desires", psychological.unspoken_desires.len());
+        
+        let analysis = CachedAnalysis {
+            analogies,
+            temporal,
+            psychological,
+            compute_time: started.elapsed(),
+            corpus_generation,
+        };
+        tracing::debug!("Analysis computed in {:?}", analysis.compute_time);
+        self.analysis_cache.insert(input, domain, analysis.clone());
+        Ok(analysis)
     }
     
+    /// Checks a generated idea against the user's constraints, one verdict each
+    #[tracing::instrument(name = "assess_constraints", level = "info", skip(self, idea))]
+    pub async fn assess_constraints(
+        &mut self,
+        idea: &GeneratedIdeaResponse,
+        constraints: &[String],
+    ) -> CHOPSResult<FeasibilityReport> {
+        let verdicts = self.claude_client.check_constraints(&idea.content, constraints).await?;
+        Ok(self.reality_calibrator.build_feasibility_report(&idea.content, constraints, &verdicts))
+    }
+
+    /// A collaboration whose participants speak as their assigned personas
+    pub async fn collaborate(
+        &mut self,
+        topic: &str,
+        mode: CollaborationMode,
+        participants: Vec<CollaborationParticipant>,
+        rounds: u8,
+    ) -> CHOPSResult<CollaborationSession> {
+        self.claude_client.collaborate(Some(&self.persona_engine), topic, mode, participants, rounds).await
+    }
+
+    /// Finds the paradoxes among `constraints`, picks a resolution strategy
+    /// for each and asks the model how to apply it
+    #[tracing::instrument(name = "resolve_constraint_paradoxes", level = "info", skip(self))]
+    pub async fn resolve_constraint_paradoxes(&mut self, constraints: &[String]) -> CHOPSResult<ParadoxReport> {
+        let constraints: Vec<String> = constraints.iter()
+            .map(|constraint| constraint.trim().to_string())
+            .filter(|constraint| !constraint.is_empty())
+            .collect();
+        if constraints.is_empty() {
+            return Err(CHOPSError::InvalidParameter("No constraints to reconcile: pass them with --constraints".to_string()));
+        }
+
+        let (paradoxes, other_findings): (Vec<ImpossibilityFinding>, Vec<ImpossibilityFinding>) = self.reality_calibrator
+            .detect_constraint_paradoxes(&constraints)
+            .into_iter()
+            .partition(|finding| finding.paradox_type().is_some());
+        let resolutions = self.reality_calibrator.resolve_paradoxes(&paradoxes);
+        tracing::debug!("Resolving {} paradoxes among {} constraints", resolutions.len(), constraints.len());
+
+        // Nothing to explain means nothing to pay for
+        let explanations = if resolutions.is_empty() {
+            Vec::new()
+        } else {
+            self.claude_client.explain_paradox_resolutions(&constraints, &resolutions).await?
+        };
+
+        let entries = paradoxes.into_iter()
+            .zip(resolutions)
+            .zip(explanations.into_iter().chain(std::iter::repeat(String::new())))
+            .map(|((finding, resolution), explanation)| {
+                let explanation = if explanation.is_empty() { resolution.suggestion.clone() } else { explanation };
+                ParadoxReportEntry { finding, resolution, explanation }
+            })
+            .collect();
+        Ok(ParadoxReport { constraints, entries, other_findings })
+    }
+
     async fn synthesize_enhanced_prompt(
         &self,
         base_input: &str,
@@ -397,11 +874,29 @@ impl CognitiveArchitecture {
         temporal: &TemporalAnalysis,
         psychological: &PsychologicalProfile,
         reality: &RealityDistortionField,
+        grounding: Option<&GroundingContext>,
     ) -> CHOPSResult<String> {
         let mut prompt = String::new();
         
         prompt.push_str("Enhanced cognitive processing request:\n\n");
         prompt.push_str(&format!("Base input: {}\n\n", base_input));
+
+        if let Some(grounding) = grounding {
+            let (content, truncated) = ContextBudget::default().fit(&grounding.content);
+            if truncated {
+                tracing::debug!("Grounding context '{}' trimmed to budget", grounding.label);
+            }
+            match grounding.kind {
+                GroundingKind::PriorIdea => prompt.push_str(&format!(
+                    "Build on this earlier idea ({}). Use it as grounding and take it somewhere new rather than just refining it:\n{}\n\n",
+                    grounding.label, content
+                )),
+                GroundingKind::ReferenceText => prompt.push_str(&format!(
+                    "Reference material ({}):\n{}\n\n",
+                    grounding.label, content
+                )),
+            }
+        }
         
         if !analogies.is_empty() {
             prompt.push_str("Analogical insights to consider:\n");
@@ -413,7 +908,7 @@ impl CognitiveArchitecture {
                     analogy.analogy_description
                 ));
             }
-            prompt.push_str("\n");
+            prompt.push('\n');
         }
         
         if !temporal.future_projections.is_empty() {
@@ -426,15 +921,31 @@ impl CognitiveArchitecture {
                     (projection.probability * 100.0) as u32
                 ));
             }
-            prompt.push_str("\n");
+            prompt.push('\n');
         }
         
+        let trends = &temporal.trend_analysis;
+        if !(trends.emerging_trends.is_empty() && trends.declining_trends.is_empty() && trends.disruptive_potentials.is_empty()) {
+            prompt.push_str("Trends in the user's earlier ideas for this domain:\n");
+            for (label, items) in [
+                ("Rising", &trends.emerging_trends),
+                ("Fading", &trends.declining_trends),
+                ("Shifting", &trends.disruptive_potentials),
+                ("Converging", &trends.convergence_points),
+            ] {
+                if !items.is_empty() {
+                    prompt.push_str(&format!("- {}: {}\n", label, items.iter().take(3).cloned().collect::<Vec<_>>().join("; ")));
+                }
+            }
+            prompt.push('\n');
+        }
+
         if !psychological.unspoken_desires.is_empty() {
             prompt.push_str("Psychological insights:\n");
             for desire in psychological.unspoken_desires.iter().take(2) {
                 prompt.push_str(&format!("- Unspoken desire: {}\n", desire));
             }
-            prompt.push_str("\n");
+            prompt.push('\n');
         }
         
         if reality.distortion_level > 0.3 {
@@ -442,7 +953,7 @@ impl CognitiveArchitecture {
             for element in reality.impossible_elements.iter().take(2) {
                 prompt.push_str(&format!("- {}\n", element));
             }
-            prompt.push_str("\n");
+            prompt.push('\n');
         }
         
         prompt.push_str("Generate a response that synthesizes these multi-dimensional insights into a coherent, innovative solution.");
@@ -471,6 +982,7 @@ impl CognitiveArchitecture {
             synthesis_quality,
             emergence_indicators,
             implementation_roadmap,
+            feasibility_report: None,
         })
     }
     
@@ -579,7 +1091,7 @@ impl CognitiveArchitecture {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComplexIdeaResult {
     pub base_idea: GeneratedIdeaResponse,
     pub analogical_insights: Vec<AnalogicalInsight>,
@@ -589,16 +1101,132 @@ pub struct ComplexIdeaResult {
     pub synthesis_quality: f64,
     pub emergence_indicators: Vec<EmergenceIndicator>,
     pub implementation_roadmap: ImplementationRoadmap,
+    /// Present when the summon had constraints to check the idea against
+    #[serde(default)]
+    pub feasibility_report: Option<FeasibilityReport>,
+}
+
+impl ComplexIdeaResult {
+    /// JSON Schema for summon results, derived from the structs so it can't drift
+    pub fn json_schema() -> CHOPSResult<String> {
+        let schema = schemars::schema_for!(ComplexIdeaResult);
+        serde_json::to_string_pretty(&schema)
+            .map_err(|e| CHOPSError::UnexpectedError(format!("Failed to serialize JSON schema: {}", e)))
+    }
+}
+
+impl Renderable for ComplexIdeaResult {
+    fn title(&self) -> String {
+        format!("CHOPS idea by {} ({})", self.base_idea.persona_used, &self.base_idea.id.to_string()[..8])
+    }
+
+    fn render_markdown(&self) -> String {
+        let idea = &self.base_idea;
+        let mut out = format!(
+            "# {}\n\nGenerated {} at chaos {:.2}\n\n## Idea\n\n{}\n",
+            self.title(),
+            idea.generated_at.format("%Y-%m-%d %H:%M UTC"),
+            idea.chaos_level,
+            idea.content.trim()
+        );
+
+        if let Some(plan) = &idea.plan {
+            out.push_str(&format!("\n## Plan\n\n{}\n", plan.trim()));
+        }
+
+        out.push_str("\n## Scores\n\n");
+        let mut scores = vec![
+            ("Creativity", idea.creativity_score),
+            ("Feasibility", idea.feasibility_score),
+            ("Novelty", idea.novelty_score),
+            ("Excitement", idea.excitement_factor),
+            ("Coherence", idea.coherence_score),
+        ];
+        if let Some(fidelity) = idea.voice_fidelity {
+            scores.push(("Voice", fidelity));
+        }
+        for (name, score) in scores {
+            out.push_str(&format!("- {}: {:.0}%\n", name, score * 100.0));
+        }
+        out.push_str(&format!("- Overall: {:.0}% ({})\n", idea.calculate_overall_score() * 100.0, idea.get_quality_tier()));
+
+        if let Some(report) = &self.feasibility_report {
+            out.push_str("\n## Constraints\n\n");
+            for check in &report.checks {
+                let mark = match check.status {
+                    ConstraintStatus::Satisfied => "✅",
+                    ConstraintStatus::Violated => "❌",
+                    ConstraintStatus::Unclear => "❔",
+                };
+                if check.note.is_empty() {
+                    out.push_str(&format!("- {} {}\n", mark, check.constraint));
+                } else {
+                    out.push_str(&format!("- {} {} ({})\n", mark, check.constraint, check.note));
+                }
+            }
+        }
+
+        if !self.analogical_insights.is_empty() {
+            out.push_str("\n## Analogies\n\n");
+            for insight in &self.analogical_insights {
+                out.push_str(&format!("- {} → {}: {}\n", insight.source_domain, insight.target_domain, insight.analogy_description));
+            }
+        }
+
+        let roadmap = &self.implementation_roadmap;
+        if !roadmap.phases.is_empty() {
+            out.push_str(&format!(
+                "\n## Roadmap\n\n{} weeks, {:.0}% estimated success\n\n",
+                roadmap.total_duration_weeks,
+                roadmap.success_probability * 100.0
+            ));
+            for phase in &roadmap.phases {
+                out.push_str(&format!("- {} ({} weeks)\n", phase.phase_name, phase.duration_weeks));
+            }
+        }
+
+        out
+    }
+
+    fn render_json(&self) -> CHOPSResult<String> {
+        serde_json::to_string_pretty(self).map_err(CHOPSError::SerializationError)
+    }
+
+    fn render_yaml(&self) -> CHOPSResult<String> {
+        to_yaml(self)
+    }
+}
+
+/// One idea for [`CognitiveArchitecture::process_complex_ideas`] to generate
+#[derive(Debug, Clone)]
+pub struct IdeaRequest {
+    pub input: String,
+    pub domain: String,
+    pub persona: PersonaType,
+}
+
+/// Extra text a generation should build on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundingContext {
+    pub kind: GroundingKind,
+    pub label: String,
+    pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GroundingKind {
+    PriorIdea,
+    ReferenceText,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EmergenceIndicator {
     pub indicator_type: EmergenceType,
     pub strength: f64,
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum EmergenceType {
     NovelCoherence,
     ChaosOrder,
@@ -607,7 +1235,15 @@ pub enum EmergenceType {
     SynthesisBreakthrough,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+chops_core::impl_enum_str!(EmergenceType {
+    NovelCoherence => "novel-coherence",
+    ChaosOrder => "chaos-order",
+    SurpriseValue => "surprise-value",
+    ComplexitySimplicity => "complexity-simplicity",
+    SynthesisBreakthrough => "synthesis-breakthrough",
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImplementationRoadmap {
     pub total_duration_weeks: u32,
     pub phases: Vec<ImplementationPhase>,
@@ -616,7 +1252,7 @@ pub struct ImplementationRoadmap {
     pub success_probability: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ImplementationPhase {
     pub phase_name: String,
     pub duration_weeks: u32,
@@ -625,7 +1261,7 @@ pub struct ImplementationPhase {
     pub risk_level: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceRequirements {
     pub developer_weeks: u32,
     pub research_weeks: u32,
@@ -634,6 +1270,12 @@ pub struct ResourceRequirements {
 }
 
 // Implementation of trait-required methods for each component
+impl Default for AnalogicalReasoningEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AnalogicalReasoningEngine {
     pub fn new() -> Self {
         let mut domain_patterns = HashMap::new();
@@ -647,25 +1289,91 @@ impl AnalogicalReasoningEngine {
         Self {
             domain_patterns,
             cross_domain_mappings: Vec::new(),
+            corpus_generation: 0,
+            similarity_strategy: SimilarityStrategy::WordOverlap,
+            embeddings: None,
+        }
+    }
+    
+    pub fn corpus_generation(&self) -> u64 {
+        self.corpus_generation
+    }
+    
+    pub fn similarity_strategy(&self) -> SimilarityStrategy {
+        self.similarity_strategy
+    }
+    
+    /// Matches analogies by embedding similarity from now on
+    pub fn use_embeddings(&mut self, client: EmbeddingClient) {
+        tracing::debug!("Analogies will be matched with embeddings from {}", client.model());
+        self.similarity_strategy = SimilarityStrategy::Embedding;
+        self.embeddings = Some(client);
+        self.corpus_generation += 1;
+    }
+    
+    /// Adds source-domain patterns analogies can be drawn from
+    pub fn add_domain_patterns(&mut self, domain: &str, patterns: Vec<DomainPattern>) {
+        self.add_domain(domain.to_string(), patterns);
+    }
+    
+    /// Registers a source domain. Names are case-insensitive and an existing
+    /// domain is merged into: its patterns are kept, and a pattern with the
+    /// same name as one of them replaces it.
+    pub fn add_domain(&mut self, name: String, patterns: Vec<DomainPattern>) {
+        let name = normalize_domain(&name);
+        tracing::debug!("Adding {} patterns to source domain '{}'", patterns.len(), name);
+        
+        let existing = self.domain_patterns.entry(name).or_default();
+        for pattern in patterns {
+            match existing.iter_mut().find(|known| known.name.eq_ignore_ascii_case(&pattern.name)) {
+                Some(known) => *known = pattern,
+                None => existing.push(pattern),
+            }
         }
+        self.corpus_generation += 1;
+    }
+    
+    pub fn has_domain(&self, name: &str) -> bool {
+        self.domain_patterns.contains_key(&normalize_domain(name))
+    }
+    
+    /// Reads a domains file and registers every domain in it, returning the
+    /// domain names and how many patterns each brought
+    pub fn load_domains_from_file(&mut self, path: &std::path::Path) -> CHOPSResult<Vec<(String, usize)>> {
+        let domains = read_domains_file(path)?;
+        let added = domains.iter().map(|(name, patterns)| (normalize_domain(name), patterns.len())).collect();
+        for (name, patterns) in domains {
+            self.add_domain(name, patterns);
+        }
+        Ok(added)
     }
     
     pub async fn find_cross_domain_analogies(
-        &self,
+        &mut self,
         concept: &str,
         target_domain: &str,
     ) -> CHOPSResult<Vec<AnalogicalInsight>> {
         let mut insights = Vec::new();
+        let target_concepts = self.extract_target_concepts(concept, target_domain);
+        tracing::debug!("Extracted {} target concepts for domain '{}'", target_concepts.len(), target_domain);
+        let embedded = self.embed_concept_and_patterns(concept, target_domain).await;
         
+        let target_key = normalize_domain(target_domain);
         for (source_domain, patterns) in &self.domain_patterns {
-            if source_domain == target_domain {
+            if *source_domain == target_key {
                 continue; // Skip same domain
             }
             
             for pattern in patterns {
-                let similarity = self.calculate_concept_similarity(concept, &pattern.description);
+                let similarity = match &embedded {
+                    Some((concept_vector, vectors)) => vectors.get(&pattern.description)
+                        .map(|vector| cosine_similarity(concept_vector, vector))
+                        .unwrap_or(0.0),
+                    None => self.calculate_concept_similarity(concept, &pattern.description),
+                };
                 
-                if similarity > 0.4 {
+                if similarity > ANALOGY_SIMILARITY_THRESHOLD {
+                    let structural_mappings = self.create_structural_mappings(pattern, &target_concepts);
                     let insight = AnalogicalInsight {
                         source_domain: source_domain.clone(),
                         target_domain: target_domain.to_string(),
@@ -673,9 +1381,9 @@ impl AnalogicalReasoningEngine {
                             "{} is like {} in {}: {}",
                             concept, pattern.name, source_domain, pattern.description
                         ),
-                        structural_mappings: self.create_structural_mappings(pattern),
-                        novel_insights: self.generate_novel_insights(concept, pattern),
-                        practical_applications: self.suggest_practical_applications(concept, pattern),
+                        novel_insights: self.generate_novel_insights(concept, pattern, &structural_mappings),
+                        practical_applications: self.suggest_practical_applications(source_domain, pattern, &structural_mappings),
+                        structural_mappings,
                         confidence_score: similarity,
                         surprise_factor: self.calculate_surprise_factor(source_domain, target_domain),
                     };
@@ -689,7 +1397,9 @@ impl AnalogicalReasoningEngine {
         insights.sort_by(|a, b| {
             let score_a = a.confidence_score * a.surprise_factor;
             let score_b = b.confidence_score * b.surprise_factor;
-            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+            chops_core::cmp_scores(score_a, score_b)
+                .then_with(|| a.source_domain.cmp(&b.source_domain))
+                .then_with(|| a.analogy_description.cmp(&b.analogy_description))
         });
         
         Ok(insights.into_iter().take(5).collect())
@@ -750,6 +1460,38 @@ impl AnalogicalReasoningEngine {
         ]
     }
     
+    /// The concept's vector and each candidate pattern description's, when
+    /// matching by embedding. Falls back to word overlap (`None`) if the
+    /// endpoint fails, so an outage degrades analogies rather than the summon.
+    async fn embed_concept_and_patterns(
+        &mut self,
+        concept: &str,
+        target_domain: &str,
+    ) -> Option<(Vec<f32>, HashMap<String, Vec<f32>>)> {
+        if self.similarity_strategy != SimilarityStrategy::Embedding {
+            return None;
+        }
+        let client = self.embeddings.as_mut()?;
+        
+        let target_key = normalize_domain(target_domain);
+        let mut texts = vec![concept];
+        texts.extend(self.domain_patterns.iter()
+            .filter(|(domain, _)| **domain != target_key)
+            .flat_map(|(_, patterns)| patterns.iter().map(|pattern| pattern.description.as_str())));
+        
+        match client.embed(&texts).await {
+            Ok(mut vectors) => {
+                let concept_vector = vectors.remove(0);
+                let by_description = texts[1..].iter().map(|text| text.to_string()).zip(vectors).collect();
+                Some((concept_vector, by_description))
+            },
+            Err(e) => {
+                tracing::warn!("Embedding similarity unavailable, using word overlap: {}", e);
+                None
+            },
+        }
+    }
+    
     fn calculate_concept_similarity(&self, concept: &str, pattern_description: &str) -> f64 {
         // Simple word overlap similarity
         let concept_lower = concept.to_lowercase();
@@ -772,31 +1514,176 @@ impl AnalogicalReasoningEngine {
         }
     }
     
-    fn create_structural_mappings(&self, pattern: &DomainPattern) -> Vec<crate::models::StructuralMapping> {
-        pattern.structural_elements
+    /// Concepts in the target domain that source elements can map onto: known
+    /// domain concepts mentioned in the input first, then the rest of the
+    /// domain vocabulary, then salient words from the input itself.
+    fn extract_target_concepts(&self, concept: &str, target_domain: &str) -> Vec<TargetConcept> {
+        let input_lower = concept.to_lowercase();
+        let vocabulary = Self::target_domain_vocabulary(target_domain);
+
+        let (mentioned, unmentioned): (Vec<_>, Vec<_>) = vocabulary
             .iter()
-            .enumerate()
-            .map(|(i, element)| crate::models::StructuralMapping {
-                source_element: element.clone(),
-                target_element: format!("target_element_{}", i),
-                relationship: "structural_correspondence".to_string(),
-                strength: 0.7,
+            .partition(|(name, _)| input_lower.contains(name));
+
+        let mut targets: Vec<TargetConcept> = mentioned
+            .into_iter()
+            .chain(unmentioned)
+            .map(|(name, roles)| TargetConcept {
+                name: name.to_string(),
+                roles: roles.iter().map(|role| role.to_string()).collect(),
             })
-            .collect()
+            .collect();
+
+        const STOPWORDS: [&str; 16] = [
+            "about", "these", "those", "their", "there", "which", "while", "would",
+            "could", "should", "within", "without", "where", "other", "focus", "generating",
+        ];
+
+        for word in input_lower.split(|c: char| !c.is_alphanumeric()) {
+            if word.len() < 5 || STOPWORDS.contains(&word) {
+                continue;
+            }
+            if targets.iter().any(|target| target.name.contains(word)) {
+                continue;
+            }
+            targets.push(TargetConcept {
+                name: word.to_string(),
+                roles: vec![word.to_string()],
+            });
+            if targets.len() >= 16 {
+                break;
+            }
+        }
+
+        targets
+    }
+
+    fn target_domain_vocabulary(target_domain: &str) -> Vec<(&'static str, &'static [&'static str])> {
+        let domain = target_domain.to_lowercase();
+
+        if ["software", "code", "develop", "programming", "engineering", "system"]
+            .iter()
+            .any(|keyword| domain.contains(keyword))
+        {
+            vec![
+                ("rate limiter", &["load", "force", "pressure", "stress", "capacity"][..]),
+                ("load balancer", &["distribution", "support", "network", "complementary"][..]),
+                ("monitoring pipeline", &["sensor", "signal", "feedback", "frequency"][..]),
+                ("request handler", &["processor", "oscillator", "driving"][..]),
+                ("autoscaler", &["actuator", "adaptation", "amplification", "change"][..]),
+                ("database", &["foundation", "storage", "memory"][..]),
+                ("message queue", &["exchange", "resource", "rhythmic", "buffer"][..]),
+                ("api contract", &["alignment", "harmony", "complementary", "consonance"][..]),
+                ("cache", &["natural", "resonance", "efficiency"][..]),
+            ]
+        } else {
+            Vec::new()
+        }
     }
     
-    fn generate_novel_insights(&self, concept: &str, pattern: &DomainPattern) -> Vec<String> {
-        vec![
-            format!("What if {} followed the {} pattern?", concept, pattern.name),
-            format!("How could {} benefit from {} dynamics?", concept, pattern.name),
-        ]
+    fn create_structural_mappings(&self, pattern: &DomainPattern, targets: &[TargetConcept]) -> Vec<crate::models::StructuralMapping> {
+        let mut used = std::collections::HashSet::new();
+        let mut mappings = Vec::new();
+
+        for element in &pattern.structural_elements {
+            let element_tokens: Vec<&str> = element.split('_').collect();
+
+            let best_match = targets
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !used.contains(i))
+                .map(|(i, target)| (i, target, Self::role_affinity(&element_tokens, &target.roles)))
+                .filter(|(_, _, affinity)| *affinity > 0)
+                .max_by_key(|(i, _, affinity)| (*affinity, std::cmp::Reverse(*i)));
+
+            let mapping = match best_match {
+                Some((i, target, affinity)) => {
+                    used.insert(i);
+                    crate::models::StructuralMapping {
+                        source_element: element.clone(),
+                        target_element: target.name.clone(),
+                        relationship: "functional_role".to_string(),
+                        strength: (0.6 + affinity as f64 * 0.15).min(0.95),
+                    }
+                },
+                None => match targets.iter().enumerate().find(|(i, _)| !used.contains(i)) {
+                    Some((i, target)) => {
+                        used.insert(i);
+                        crate::models::StructuralMapping {
+                            source_element: element.clone(),
+                            target_element: target.name.clone(),
+                            relationship: "structural_correspondence".to_string(),
+                            strength: 0.4,
+                        }
+                    },
+                    None => continue,
+                },
+            };
+
+            mappings.push(mapping);
+        }
+
+        mappings
+    }
+
+    fn role_affinity(element_tokens: &[&str], roles: &[String]) -> usize {
+        element_tokens
+            .iter()
+            .filter(|token| {
+                roles.iter().any(|role| role.starts_with(*token) || token.starts_with(role.as_str()))
+            })
+            .count()
     }
     
-    fn suggest_practical_applications(&self, concept: &str, pattern: &DomainPattern) -> Vec<String> {
-        vec![
-            format!("Apply {} principles to improve {}", pattern.name, concept),
-            format!("Use {} success metrics to evaluate {}", pattern.name, concept),
-        ]
+    fn generate_novel_insights(&self, concept: &str, pattern: &DomainPattern, mappings: &[crate::models::StructuralMapping]) -> Vec<String> {
+        let mut insights: Vec<String> = mappings
+            .iter()
+            .take(2)
+            .map(|mapping| format!(
+                "What if the {} acted as the {} in {}?",
+                mapping.target_element,
+                mapping.source_element.replace('_', " "),
+                pattern.name
+            ))
+            .collect();
+
+        if insights.is_empty() {
+            insights.push(format!("What if {} followed the {} pattern?", concept, pattern.name));
+        }
+
+        if let (Some(mapping), Some(dynamic)) = (mappings.first(), pattern.behavioral_dynamics.first()) {
+            insights.push(format!(
+                "How would the {} change if it was driven by {}?",
+                mapping.target_element,
+                dynamic.replace('_', " ")
+            ));
+        }
+
+        insights
+    }
+    
+    fn suggest_practical_applications(&self, source_domain: &str, pattern: &DomainPattern, mappings: &[crate::models::StructuralMapping]) -> Vec<String> {
+        let mut applications = Vec::new();
+
+        for (mapping, dynamic) in mappings.iter().zip(pattern.behavioral_dynamics.iter()) {
+            applications.push(format!(
+                "Design the {} around {} the way the {} does in {}",
+                mapping.target_element,
+                dynamic.replace('_', " "),
+                mapping.source_element.replace('_', " "),
+                source_domain
+            ));
+        }
+
+        if let Some(metric) = pattern.success_metrics.first() {
+            let subject = mappings
+                .first()
+                .map(|mapping| mapping.target_element.clone())
+                .unwrap_or_else(|| "the solution".to_string());
+            applications.push(format!("Measure the {} by {}", subject, metric.replace('_', " ")));
+        }
+
+        applications
     }
     
     fn calculate_surprise_factor(&self, source_domain: &str, target_domain: &str) -> f64 {
@@ -810,6 +1697,12 @@ impl AnalogicalReasoningEngine {
     }
 }
 
+impl Default for TemporalProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TemporalProcessor {
     pub fn new() -> Self {
         Self {
@@ -857,14 +1750,71 @@ impl TemporalProcessor {
         ]
     }
     
+    /// Replaces the series trends are read from (one per topic); returns
+    /// whether they differ from the ones already held
+    pub fn observe_series(&mut self, series: Vec<(String, Vec<f64>)>) -> bool {
+        let analyzers: HashMap<String, TrendAnalyzer> = series
+            .into_iter()
+            .map(|(name, values)| (name.clone(), TrendAnalyzer::new(&name, values)))
+            .collect();
+        let changed = analyzers != self.trend_analyzers;
+        self.trend_analyzers = analyzers;
+        changed
+    }
+
+    /// Sorts the observed topics by what their series are doing. Nothing is
+    /// reported without observations rather than guessing.
     fn analyze_trends(&self, domain: &str) -> TrendAnalysis {
-        TrendAnalysis {
-            emerging_trends: vec![format!("AI integration in {}", domain)],
-            declining_trends: vec!["legacy systems".to_string()],
-            stable_patterns: vec!["user-centric design".to_string()],
-            disruptive_potentials: vec!["quantum computing".to_string()],
-            convergence_points: vec!["AI + automation".to_string()],
+        let mut analysis = TrendAnalysis {
+            emerging_trends: Vec::new(),
+            declining_trends: Vec::new(),
+            stable_patterns: Vec::new(),
+            disruptive_potentials: Vec::new(),
+            convergence_points: Vec::new(),
+        };
+
+        let mut analyzers: Vec<&TrendAnalyzer> = self.trend_analyzers.values().collect();
+        analyzers.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut emerging: Vec<&TrendAnalyzer> = Vec::new();
+
+        for analyzer in analyzers {
+            let report = analyzer.report();
+            let Some(growth) = &report.growth else {
+                continue;
+            };
+
+            match growth.current_phase {
+                GrowthPhase::Inception | GrowthPhase::Growth => {
+                    analysis.emerging_trends.push(format!("{} ({:?} growth)", analyzer.name, growth.pattern_type).to_lowercase());
+                    emerging.push(analyzer);
+                },
+                GrowthPhase::Decline => analysis.declining_trends.push(analyzer.name.clone()),
+                GrowthPhase::Maturity => match report.cycles.first() {
+                    Some(cycle) => analysis.stable_patterns.push(format!("{} (recurs every ~{:.0} periods)", analyzer.name, cycle.period)),
+                    None => analysis.stable_patterns.push(analyzer.name.clone()),
+                },
+                GrowthPhase::Disruption => {},
+            }
+            if let Some(disruption) = report.disruptions.first() {
+                analysis.disruptive_potentials.push(format!(
+                    "{}: {} over the {}", analyzer.name, disruption.indicator_name, disruption.time_horizon
+                ));
+            }
         }
+
+        // Rising topics that rise together are converging
+        for (i, a) in emerging.iter().enumerate() {
+            for b in &emerging[i + 1..] {
+                if correlation(&a.series, &b.series) > 0.7 {
+                    analysis.convergence_points.push(format!("{} + {}", a.name, b.name));
+                }
+            }
+        }
+
+        tracing::debug!("Trends in '{}' from {} series: {} emerging, {} declining, {} stable, {} disruptive",
+            domain, self.trend_analyzers.len(), analysis.emerging_trends.len(), analysis.declining_trends.len(),
+            analysis.stable_patterns.len(), analysis.disruptive_potentials.len());
+        analysis
     }
     
     fn create_timeline_scenarios(&self, concept: &str) -> Vec<TimelineScenario> {
@@ -894,6 +1844,12 @@ impl TemporalProcessor {
     }
 }
 
+impl Default for PsychologicalAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PsychologicalAnalyzer {
     pub fn new() -> Self {
         Self {
@@ -938,8 +1894,24 @@ impl PsychologicalAnalyzer {
         desires
     }
     
-    fn detect_hidden_fears(&self, _input: &str) -> Vec<String> {
-        vec!["Fear of technical failure".to_string(), "Fear of complexity overwhelming users".to_string()]
+    fn detect_hidden_fears(&self, input: &str) -> Vec<String> {
+        let voiced = Self::fear_cues(input);
+        if voiced.is_empty() {
+            return vec!["Fear of technical failure".to_string(), "Fear of complexity overwhelming users".to_string()];
+        }
+        voiced.iter().map(|cue| format!("Voiced fear signalled by '{}'", cue)).collect()
+    }
+
+    /// Fear language actually present in the input, in order of appearance
+    pub fn fear_cues(input: &str) -> Vec<String> {
+        let fear_stems = ["afraid", "scare", "fear", "worr", "anxi", "panic", "dread", "overwhelm", "stress", "burnout", "nervous"];
+        let mut cues: Vec<String> = Vec::new();
+        for word in input.to_lowercase().split(|c: char| !c.is_alphanumeric()) {
+            if fear_stems.iter().any(|stem| word.starts_with(stem)) && !cues.iter().any(|cue| cue == word) {
+                cues.push(word.to_string());
+            }
+        }
+        cues
     }
     
     fn detect_unconscious_patterns(&self, _input: &str) -> Vec<String> {
@@ -963,12 +1935,33 @@ impl PsychologicalAnalyzer {
     }
 }
 
+impl Default for RealityCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RealityCalibrator {
     pub fn new() -> Self {
+        let detectors = [
+            ImpossibilityType::PhysicsViolation,
+            ImpossibilityType::LogicalContradiction,
+            ImpossibilityType::ResourceConstraint,
+            ImpossibilityType::TimeParadox,
+            ImpossibilityType::InformationParadox,
+        ];
         Self {
             feasibility_models: Vec::new(),
-            impossibility_detectors: Vec::new(),
-            paradox_resolvers: Vec::new(),
+            impossibility_detectors: detectors.into_iter()
+                .map(|detector_type| ImpossibilityDetector { detector_type, threshold: IMPOSSIBILITY_THRESHOLD })
+                .collect(),
+            paradox_resolvers: vec![
+                ParadoxResolver::new(ParadoxType::Logical, &[ResolutionStrategy::Contextualize, ResolutionStrategy::Reframe, ResolutionStrategy::Transform]),
+                ParadoxResolver::new(ParadoxType::Temporal, &[ResolutionStrategy::Reframe, ResolutionStrategy::Transform]),
+                ParadoxResolver::new(ParadoxType::Causal, &[ResolutionStrategy::Transform, ResolutionStrategy::Reframe]),
+                ParadoxResolver::new(ParadoxType::Semantic, &[ResolutionStrategy::Reframe, ResolutionStrategy::Contextualize]),
+                ParadoxResolver::new(ParadoxType::Ontological, &[ResolutionStrategy::Transcend, ResolutionStrategy::Reframe]),
+            ],
         }
     }
     
@@ -978,34 +1971,119 @@ impl RealityCalibrator {
         complexity_level: f64,
     ) -> CHOPSResult<RealityDistortionField> {
         let distortion_level = complexity_level * 0.8; // Higher complexity = more distortion
+        let impossibilities = self.detect_impossibilities(concept);
+        let paradox_resolutions = self.resolve_paradoxes(&impossibilities);
+        tracing::debug!("Reality check found {} impossibilities and {} paradoxes", impossibilities.len(), paradox_resolutions.len());
         
         Ok(RealityDistortionField {
             distortion_level,
-            impossible_elements: self.identify_impossible_elements(concept),
-            paradox_injections: self.find_paradoxes(concept),
+            impossible_elements: impossibilities.iter()
+                .map(|finding| format!("{}: {}", finding.impossibility_type, finding.description))
+                .collect(),
+            paradox_injections: paradox_resolutions.iter().map(|resolution| resolution.paradox.clone()).collect(),
             reality_bends: self.catalog_reality_bends(concept, distortion_level),
             coherence_maintenance: 1.0 - distortion_level * 0.5,
             feasibility_impact: distortion_level * -0.3,
+            impossibilities,
+            paradox_resolutions,
         })
     }
     
-    fn identify_impossible_elements(&self, concept: &str) -> Vec<String> {
-        let impossible_keywords = ["infinite", "impossible", "magic", "telepathy"];
-        let mut elements = Vec::new();
-        
-        for keyword in &impossible_keywords {
-            if concept.to_lowercase().contains(keyword) {
-                elements.push(format!("Impossible element detected: {}", keyword));
-            }
+    /// Turns the model's per-constraint verdicts into a report. Constraints the
+    /// model skipped count as unclear, and impossibilities in the idea are
+    /// flagged as violating reality itself.
+    pub fn build_feasibility_report(
+        &self,
+        concept: &str,
+        constraints: &[String],
+        verdicts: &[ConstraintCheck],
+    ) -> FeasibilityReport {
+        let mut checks: Vec<ConstraintCheck> = constraints
+            .iter()
+            .map(|constraint| {
+                verdicts
+                    .iter()
+                    .find(|verdict| verdict.constraint.trim().eq_ignore_ascii_case(constraint.trim()))
+                    .cloned()
+                    .unwrap_or_else(|| ConstraintCheck {
+                        constraint: constraint.clone(),
+                        status: ConstraintStatus::Unclear,
+                        note: "no verdict returned".to_string(),
+                    })
+            })
+            .collect();
+
+        for finding in self.detect_impossibilities(concept) {
+            checks.push(ConstraintCheck {
+                constraint: finding.impossibility_type.requirement().to_string(),
+                status: ConstraintStatus::Violated,
+                note: finding.description,
+            });
         }
-        
-        elements
+
+        tracing::debug!("Feasibility report: {} checks, {} violated", checks.len(),
+            checks.iter().filter(|check| check.status == ConstraintStatus::Violated).count());
+        FeasibilityReport { checks }
     }
-    
-    fn find_paradoxes(&self, _concept: &str) -> Vec<String> {
-        vec!["Potential paradox: simultaneous simplicity and complexity".to_string()]
+
+    /// Every detector's findings, strongest first
+    pub fn detect_impossibilities(&self, concept: &str) -> Vec<ImpossibilityFinding> {
+        let text = ConceptText::new(concept);
+        let mut findings: Vec<ImpossibilityFinding> = self.impossibility_detectors.iter()
+            .flat_map(|detector| detector.detect(&text))
+            .collect();
+        findings.sort_by(|a, b| cmp_scores(a.score, b.score));
+        findings
     }
-    
+
+    /// A suggested way through each finding that is a paradox; resource
+    /// limits are constraints to design around, not paradoxes
+    pub fn resolve_paradoxes(&self, findings: &[ImpossibilityFinding]) -> Vec<ParadoxResolution> {
+        findings.iter()
+            .filter_map(|finding| {
+                let paradox_type = finding.paradox_type()?;
+                self.paradox_resolvers.iter()
+                    .find(|resolver| resolver.paradox_type == paradox_type)
+                    .map(|resolver| resolver.resolve(finding))
+            })
+            .collect()
+    }
+
+    /// Impossibilities in a set of constraints read together, plus pairs that
+    /// demand the same thing "always" and "never" across two constraints
+    pub fn detect_constraint_paradoxes(&self, constraints: &[String]) -> Vec<ImpossibilityFinding> {
+        let mut findings = self.detect_impossibilities(&constraints.join(". "));
+
+        let texts: Vec<ConceptText> = constraints.iter().map(|constraint| ConceptText::new(constraint)).collect();
+        // Each pair of constraints once, whichever of the two says "always"
+        let mut paired: Vec<(usize, usize)> = Vec::new();
+        for (i, always) in texts.iter().enumerate() {
+            if !always.contains("always") {
+                continue;
+            }
+            for (j, never) in texts.iter().enumerate() {
+                if i == j || !never.contains("never") || paired.contains(&(i.min(j), i.max(j))) {
+                    continue;
+                }
+                let never_words = chops_core::content_words(&constraints[j]);
+                let Some(shared) = chops_core::content_words(&constraints[i]).into_iter()
+                    .find(|word| !REQUIREMENT_WORDS.contains(&word.as_str()) && never_words.contains(word)) else {
+                    continue;
+                };
+                paired.push((i.min(j), i.max(j)));
+                findings.push(ImpossibilityFinding::new(
+                    ImpossibilityType::LogicalContradiction,
+                    &format!("{} / {}", constraints[i].trim(), constraints[j].trim()),
+                    &format!("asks for \"{}\" to always and never happen", shared),
+                    0.7,
+                ));
+            }
+        }
+        findings.sort_by(|a, b| cmp_scores(a.score, b.score));
+        tracing::debug!("Found {} impossibilities across {} constraints", findings.len(), constraints.len());
+        findings
+    }
+
     fn catalog_reality_bends(&self, _concept: &str, distortion_level: f64) -> Vec<RealityBend> {
         if distortion_level > 0.5 {
             vec![
@@ -1021,4 +2099,410 @@ impl RealityCalibrator {
             Vec::new()
         }
     }
-}
\ No newline at end of file
+}
+
+/// Weakest finding a detector reports
+const IMPOSSIBILITY_THRESHOLD: f64 = 0.5;
+
+/// Findings weaker than this are mild tensions, resolved by accepting the trade-off
+const MILD_PARADOX_SCORE: f64 = 0.6;
+
+/// Phrases that give away an impossibility: (phrase, score, why)
+type Cue = (&'static str, f64, &'static str);
+
+const PHYSICS_CUES: &[Cue] = &[
+    ("faster than light", 0.95, "nothing carries information faster than light"),
+    ("ftl", 0.95, "nothing carries information faster than light"),
+    ("perpetual motion", 0.95, "perpetual motion breaks the laws of thermodynamics"),
+    ("infinite energy", 0.9, "energy is conserved, so it can't come from nowhere"),
+    ("unlimited energy", 0.9, "energy is conserved, so it can't come from nowhere"),
+    ("free energy", 0.8, "energy is conserved, so it can't come from nowhere"),
+    ("reverse entropy", 0.9, "the entropy of a closed system never decreases"),
+    ("reverses entropy", 0.9, "the entropy of a closed system never decreases"),
+    ("100% efficient", 0.7, "no real machine converts energy without losses"),
+    ("100% efficiency", 0.7, "no real machine converts energy without losses"),
+    ("zero latency", 0.6, "signals take time to travel, so latency can shrink but not vanish"),
+    ("telepathy", 0.85, "there is no channel for reading minds directly"),
+    ("telepathic", 0.85, "there is no channel for reading minds directly"),
+    ("magic", 0.5, "relies on magic rather than a mechanism"),
+    ("impossible", 0.5, "describes itself as impossible"),
+];
+
+const RESOURCE_CUES: &[Cue] = &[
+    ("100% uptime", 0.6, "every system has some downtime; promise a number of nines instead"),
+    ("never goes down", 0.6, "every system has some downtime; promise a number of nines instead"),
+    ("zero cost", 0.5, "running anything costs something, even if someone else pays"),
+];
+
+const TIME_CUES: &[Cue] = &[
+    ("back in time", 0.85, "changing the past would break causality"),
+    ("time travel", 0.7, "travelling in time would break causality"),
+    ("time machine", 0.7, "travelling in time would break causality"),
+    ("retroactively change", 0.75, "changing the past would break causality"),
+];
+
+const INFORMATION_CUES: &[Cue] = &[
+    ("knows everything", 0.85, "no system can hold complete knowledge"),
+    ("know everything", 0.85, "no system can hold complete knowledge"),
+    ("omniscient", 0.85, "no system can hold complete knowledge"),
+    ("read minds", 0.8, "intent has to be inferred from signals, not read"),
+    ("reads minds", 0.8, "intent has to be inferred from signals, not read"),
+    ("reads the users mind", 0.8, "intent has to be inferred from signals, not read"),
+    ("read the users mind", 0.8, "intent has to be inferred from signals, not read"),
+    ("predicts everything", 0.8, "the future can be estimated, never known"),
+    ("predict everything", 0.8, "the future can be estimated, never known"),
+    ("perfect prediction", 0.75, "the future can be estimated, never known"),
+    ("100% accurate", 0.7, "predictions about people and the future always carry error"),
+    ("100% accuracy", 0.7, "predictions about people and the future always carry error"),
+    ("never wrong", 0.7, "predictions about people and the future always carry error"),
+    ("solves the halting problem", 0.95, "whether an arbitrary program halts is undecidable"),
+    ("solve the halting problem", 0.95, "whether an arbitrary program halts is undecidable"),
+    ("compress any file", 0.85, "no lossless compressor shrinks every input"),
+    ("compresses any file", 0.85, "no lossless compressor shrinks every input"),
+    ("infinite compression", 0.9, "no lossless compressor shrinks every input"),
+];
+
+/// Words that make a following resource unbounded
+const UNBOUNDED: &[&str] = &["infinite", "infinitely", "unlimited", "limitless", "boundless", "endless"];
+
+const RESOURCES: &[&str] = &[
+    "storage", "memory", "bandwidth", "compute", "computing", "scale", "scalability", "capacity",
+    "throughput", "users", "data", "money", "budget", "resources", "speed", "power", "disk", "cpu",
+];
+
+/// Events that can't be acted on before they happen
+const EFFECTS: &[&str] = &["written", "happen", "happens", "happened", "occur", "occurs", "asked", "sent", "exist", "exists", "created", "typed"];
+
+/// Words that phrase a requirement rather than say what it's about
+const REQUIREMENT_WORDS: &[&str] = &["always", "never", "must", "should", "shall", "has", "have", "need", "needs", "every", "ever"];
+
+/// Requirements that rule each other out: (one side, other side, score, why)
+const CONTRADICTIONS: &[(&[&str], &[&str], f64, &str)] = &[
+    (&["always sync", "always syncs", "always in sync", "synchronous"], &["never block", "never blocks", "non blocking", "without blocking"], 0.75,
+        "staying in sync means waiting for the other side"),
+    (&["stateless"], &["remembers", "stateful", "keeps state", "persistent sessions"], 0.75,
+        "remembering anything is state"),
+    (&["zero dependencies", "no dependencies"], &["depends on", "built on top of"], 0.75,
+        "building on something is a dependency"),
+    (&["fully offline", "no network", "without internet"], &["real time sync", "always online", "live updates", "real time collaboration"], 0.75,
+        "live updates need a connection"),
+    (&["immutable"], &["edit in place", "modify in place", "mutable"], 0.75,
+        "in-place edits are mutation"),
+    (&["anonymous", "anonymity"], &["personalized", "personalised", "identifies each user", "tracks each user"], 0.75,
+        "personalizing means knowing who someone is"),
+    (&["zero configuration", "zero config", "no configuration"], &["fully configurable", "infinitely configurable", "configure everything"], 0.75,
+        "every option is configuration"),
+    (&["end to end encrypted", "end to end encryption"], &["server side search", "server can read", "server reads"], 0.75,
+        "the server can't read what only the ends can decrypt"),
+];
+
+impl ImpossibilityType {
+    /// The requirement an idea fails when this is found
+    pub fn requirement(&self) -> &'static str {
+        match self {
+            ImpossibilityType::PhysicsViolation => "physically possible",
+            ImpossibilityType::LogicalContradiction => "logically consistent",
+            ImpossibilityType::ResourceConstraint => "within resource limits",
+            ImpossibilityType::TimeParadox => "causally ordered",
+            ImpossibilityType::InformationParadox => "knowable",
+        }
+    }
+}
+
+impl ImpossibilityFinding {
+    fn new(impossibility_type: ImpossibilityType, evidence: &str, description: &str, score: f64) -> Self {
+        Self { impossibility_type, evidence: evidence.to_string(), description: description.to_string(), score }
+    }
+
+    /// What kind of paradox this is, if it is one
+    pub fn paradox_type(&self) -> Option<ParadoxType> {
+        match self.impossibility_type {
+            ImpossibilityType::PhysicsViolation => Some(ParadoxType::Ontological),
+            ImpossibilityType::LogicalContradiction => Some(ParadoxType::Logical),
+            ImpossibilityType::ResourceConstraint => None,
+            ImpossibilityType::TimeParadox if self.evidence.contains("before") => Some(ParadoxType::Causal),
+            ImpossibilityType::TimeParadox => Some(ParadoxType::Temporal),
+            ImpossibilityType::InformationParadox => Some(ParadoxType::Semantic),
+        }
+    }
+}
+
+impl ImpossibilityDetector {
+    fn detect(&self, text: &ConceptText) -> Vec<ImpossibilityFinding> {
+        let kind = &self.detector_type;
+        let mut findings = match kind {
+            ImpossibilityType::PhysicsViolation => text.cue_findings(kind, PHYSICS_CUES),
+            ImpossibilityType::LogicalContradiction => Self::contradictions(text),
+            ImpossibilityType::ResourceConstraint => Self::unbounded_resources(text),
+            ImpossibilityType::TimeParadox => Self::effects_before_causes(text),
+            ImpossibilityType::InformationParadox => text.cue_findings(kind, INFORMATION_CUES),
+        };
+        findings.retain(|finding| finding.score >= self.threshold);
+        findings
+    }
+
+    /// Requirement pairs that rule each other out, plus "always ... never" in one sentence
+    fn contradictions(text: &ConceptText) -> Vec<ImpossibilityFinding> {
+        let mut findings: Vec<ImpossibilityFinding> = CONTRADICTIONS.iter()
+            .filter_map(|(one, other, score, why)| {
+                let one = one.iter().find(|phrase| text.contains(phrase))?;
+                let other = other.iter().find(|phrase| text.contains(phrase) && !one.contains(*phrase))?;
+                Some(ImpossibilityFinding::new(
+                    ImpossibilityType::LogicalContradiction,
+                    &format!("{} / {}", one, other),
+                    &format!("asks for both \"{}\" and \"{}\": {}", one, other, why),
+                    *score,
+                ))
+            })
+            .collect();
+
+        if findings.is_empty() {
+            if let Some(sentence) = text.sentences.iter().find(|sentence| sentence.contains(" always ") && sentence.contains(" never ")) {
+                findings.push(ImpossibilityFinding::new(
+                    ImpossibilityType::LogicalContradiction,
+                    sentence.trim(),
+                    "asks for something to always and never happen at once",
+                    0.6,
+                ));
+            }
+        }
+        findings
+    }
+
+    fn unbounded_resources(text: &ConceptText) -> Vec<ImpossibilityFinding> {
+        let mut findings = text.cue_findings(&ImpossibilityType::ResourceConstraint, RESOURCE_CUES);
+        if let Some((bound, resource)) = text.followed_by(UNBOUNDED, RESOURCES, 2) {
+            findings.push(ImpossibilityFinding::new(
+                ImpossibilityType::ResourceConstraint,
+                &format!("{} {}", bound, resource),
+                &format!("no system has unbounded {}", resource),
+                0.8,
+            ));
+        } else if let Some(bound) = UNBOUNDED.iter().find(|word| text.contains(word) && !text.contains(&format!("{} energy", word))) {
+            findings.push(ImpossibilityFinding::new(
+                ImpossibilityType::ResourceConstraint,
+                bound,
+                "assumes something unbounded",
+                0.5,
+            ));
+        }
+        findings
+    }
+
+    fn effects_before_causes(text: &ConceptText) -> Vec<ImpossibilityFinding> {
+        let mut findings = text.cue_findings(&ImpossibilityType::TimeParadox, TIME_CUES);
+        if let Some((before, effect)) = text.followed_by(&["before"], EFFECTS, 3) {
+            findings.push(ImpossibilityFinding::new(
+                ImpossibilityType::TimeParadox,
+                &format!("{} ... {}", before, effect),
+                "acts on something before it exists, so effect precedes cause",
+                0.75,
+            ));
+        }
+        findings
+    }
+}
+
+impl ParadoxResolver {
+    fn new(paradox_type: ParadoxType, resolution_strategies: &[ResolutionStrategy]) -> Self {
+        Self { paradox_type, resolution_strategies: resolution_strategies.to_vec() }
+    }
+
+    /// The resolver's preferred strategy, or accepting the trade-off when the
+    /// paradox is mild
+    fn resolve(&self, finding: &ImpossibilityFinding) -> ParadoxResolution {
+        let (strategy, alternatives) = if finding.score < MILD_PARADOX_SCORE {
+            (ResolutionStrategy::Accept, self.resolution_strategies.clone())
+        } else {
+            let (first, rest) = self.resolution_strategies.split_first().expect("resolvers have at least one strategy");
+            (first.clone(), rest.to_vec())
+        };
+        let evidence = &finding.evidence;
+        let suggestion = match strategy {
+            ResolutionStrategy::Contextualize => format!(
+                "Give each side its own context: decide where each half of \"{}\" holds, per code path, per user or per moment", evidence),
+            ResolutionStrategy::Reframe => format!(
+                "Reframe \"{}\": ask what it is really for and meet that need with estimates, history or signals instead", evidence),
+            ResolutionStrategy::Transform => format!(
+                "Turn \"{}\" into a feedback loop: act on the earliest signals so it feels ahead of time", evidence),
+            ResolutionStrategy::Transcend => format!(
+                "Keep the experience, drop the physics: make \"{}\" feel true through caching, prefetching or efficiency", evidence),
+            ResolutionStrategy::Accept => format!(
+                "Keep the tension in \"{}\" and make the trade-off explicit, with a sensible default", evidence),
+        };
+        ParadoxResolution {
+            paradox_type: self.paradox_type.clone(),
+            paradox: format!("{} paradox: {}", self.paradox_type, finding.description),
+            strategy,
+            suggestion,
+            alternatives,
+        }
+    }
+}
+
+/// An idea's wording, lowercased with punctuation dropped so phrases match
+/// on word boundaries ("faster-than-light" reads as "faster than light")
+struct ConceptText {
+    /// Words separated by single spaces, with a space at either end
+    padded: String,
+    words: Vec<String>,
+    sentences: Vec<String>,
+}
+
+impl ConceptText {
+    fn new(concept: &str) -> Self {
+        let normalize = |text: &str| -> String {
+            let cleaned: String = text.chars()
+                .filter(|c| *c != '\'' && *c != '’')
+                .flat_map(char::to_lowercase)
+                .map(|c| if c.is_alphanumeric() || c == '%' { c } else { ' ' })
+                .collect();
+            format!(" {} ", cleaned.split_whitespace().collect::<Vec<_>>().join(" "))
+        };
+        let padded = normalize(concept);
+        Self {
+            words: padded.split_whitespace().map(str::to_string).collect(),
+            sentences: concept.split(['.', '!', '?', '\n', ';']).map(normalize).collect(),
+            padded,
+        }
+    }
+
+    fn contains(&self, phrase: &str) -> bool {
+        self.padded.contains(&format!(" {} ", phrase))
+    }
+
+    fn cue_findings(&self, kind: &ImpossibilityType, cues: &[Cue]) -> Vec<ImpossibilityFinding> {
+        cues.iter()
+            .filter(|(phrase, _, _)| self.contains(phrase))
+            .map(|(phrase, score, why)| ImpossibilityFinding::new(kind.clone(), phrase, why, *score))
+            .collect()
+    }
+
+    /// The first word from `first` followed within `window` words by one from `then`
+    fn followed_by(&self, first: &[&str], then: &[&str], window: usize) -> Option<(String, String)> {
+        self.words.iter().enumerate()
+            .filter(|(_, word)| first.contains(&word.as_str()))
+            .find_map(|(index, word)| {
+                self.words.iter().skip(index + 1).take(window)
+                    .find(|next| then.contains(&next.as_str()))
+                    .map(|next| (word.clone(), next.clone()))
+            })
+    }
+}
+
+fn quote_cues(cues: &[String]) -> String {
+    cues.iter().map(|cue| format!("'{}'", cue)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(concept: &str) -> Vec<ImpossibilityFinding> {
+        RealityCalibrator::new().detect_impossibilities(concept)
+    }
+
+    fn kinds(concept: &str) -> Vec<ImpossibilityType> {
+        findings(concept).into_iter().map(|finding| finding.impossibility_type).collect()
+    }
+
+    #[test]
+    fn test_physics_detector() {
+        let found = findings("A faster-than-light messaging app");
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::PhysicsViolation);
+        assert_eq!(found[0].evidence, "faster than light");
+        assert!(kinds("A messaging app with end-to-end encryption").is_empty());
+    }
+
+    #[test]
+    fn test_contradiction_detector() {
+        let found = findings("A database that is always in sync but never blocks");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::LogicalContradiction);
+        assert_eq!(found[0].evidence, "always in sync / never blocks");
+
+        let found = findings("The cache is always warm and never stale");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::LogicalContradiction);
+        assert_eq!(found[0].score, 0.6);
+
+        // Simple and complex in one pitch is a design goal, not a paradox
+        assert!(kinds("A simple interface over a complex billing system").is_empty());
+    }
+
+    #[test]
+    fn test_resource_detector() {
+        let found = findings("A photo app with unlimited free storage");
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::ResourceConstraint);
+        assert_eq!(found[0].evidence, "unlimited storage");
+        assert_eq!(kinds("A hosting plan with 100% uptime"), vec![ImpossibilityType::ResourceConstraint]);
+        // Infinite energy is a physics violation, not an unbounded resource
+        assert_eq!(kinds("A battery with infinite energy"), vec![ImpossibilityType::PhysicsViolation]);
+    }
+
+    #[test]
+    fn test_time_paradox_detector() {
+        let found = findings("A bug tracker that ships fixes before bugs happen");
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::TimeParadox);
+        assert_eq!(found[0].paradox_type(), Some(ParadoxType::Causal));
+
+        let found = findings("A time machine for your calendar");
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::TimeParadox);
+        assert_eq!(found[0].paradox_type(), Some(ParadoxType::Temporal));
+
+        assert!(kinds("Back up your files before the weekend").is_empty());
+    }
+
+    #[test]
+    fn test_information_detector() {
+        let found = findings("A linter that solves the halting problem");
+        assert_eq!(found[0].impossibility_type, ImpossibilityType::InformationParadox);
+        assert_eq!(found[0].paradox_type(), Some(ParadoxType::Semantic));
+        assert!(kinds("A linter that flags unreachable code").is_empty());
+    }
+
+    #[test]
+    fn test_constraint_pairs_are_checked_alongside_other_contradictions() {
+        let constraints: Vec<String> = [
+            "The cache is always in sync",
+            "Writes never block",
+            "Audit entries are always kept",
+            "Audit entries are never kept for guests",
+        ].iter().map(|constraint| constraint.to_string()).collect();
+        let found = RealityCalibrator::new().detect_constraint_paradoxes(&constraints);
+        let evidence: Vec<&str> = found.iter().map(|finding| finding.evidence.as_str()).collect();
+        assert_eq!(evidence, vec![
+            "always in sync / never block",
+            "Audit entries are always kept / Audit entries are never kept for guests",
+        ]);
+        assert!(found.iter().all(|finding| finding.impossibility_type == ImpossibilityType::LogicalContradiction));
+    }
+
+    #[test]
+    fn test_constraint_pair_is_reported_once() {
+        let constraints = vec![
+            "Backups always run at night and never at noon".to_string(),
+            "Backups never run during deploys and always after them".to_string(),
+        ];
+        let found = RealityCalibrator::new().detect_constraint_paradoxes(&constraints);
+        let pairs = found.iter().filter(|finding| finding.description.contains("\"backups\"")).count();
+        assert_eq!(pairs, 1);
+    }
+
+    #[test]
+    fn test_resolvers_pick_a_strategy_per_paradox() {
+        let calibrator = RealityCalibrator::new();
+        let strategy = |concept: &str| -> Vec<ResolutionStrategy> {
+            calibrator.resolve_paradoxes(&calibrator.detect_impossibilities(concept))
+                .into_iter().map(|resolution| resolution.strategy).collect()
+        };
+        assert_eq!(strategy("A faster than light messaging app"), vec![ResolutionStrategy::Transcend]);
+        assert_eq!(strategy("A database that is always in sync but never blocks"), vec![ResolutionStrategy::Contextualize]);
+        assert_eq!(strategy("A bug tracker that ships fixes before bugs happen"), vec![ResolutionStrategy::Transform]);
+        assert_eq!(strategy("A time machine for your calendar"), vec![ResolutionStrategy::Reframe]);
+        assert_eq!(strategy("A linter that solves the halting problem"), vec![ResolutionStrategy::Reframe]);
+        // Mild tensions are accepted; resource limits aren't paradoxes at all
+        assert_eq!(strategy("A magic onboarding flow"), vec![ResolutionStrategy::Accept]);
+        assert!(strategy("A photo app with unlimited storage").is_empty());
+    }
+}
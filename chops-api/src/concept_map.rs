@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+/// Key entities of an idea and how they relate, as a directed graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConceptGraph {
+    pub nodes: Vec<ConceptNode>,
+    pub edges: Vec<ConceptEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptNode {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptEdge {
+    pub from: String,
+    pub to: String,
+    pub relationship: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConceptMapFormat {
+    Mermaid,
+    Dot,
+}
+
+chops_core::impl_enum_str!(ConceptMapFormat {
+    Mermaid => "mermaid",
+    Dot => "dot",
+});
+
+impl ConceptGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `Subject -> relationship -> Object` lines, skipping anything else
+    pub fn from_relations(text: &str) -> Self {
+        let mut graph = Self::new();
+        for line in text.lines() {
+            let line = strip_list_marker(line.trim());
+            let parts: Vec<&str> = line.split("->").map(str::trim).collect();
+            if let [subject, relationship, object] = parts[..] {
+                if !subject.is_empty() && !object.is_empty() {
+                    graph.add_relation(subject, relationship, object);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Adds an edge, creating nodes for labels not seen before (case-insensitively)
+    pub fn add_relation(&mut self, subject: &str, relationship: &str, object: &str) {
+        let from = self.node_id(subject);
+        let to = self.node_id(object);
+        let duplicate = self.edges
+            .iter()
+            .any(|edge| edge.from == from && edge.to == to && edge.relationship.eq_ignore_ascii_case(relationship));
+        if !duplicate {
+            self.edges.push(ConceptEdge { from, to, relationship: relationship.to_string() });
+        }
+    }
+
+    fn node_id(&mut self, label: &str) -> String {
+        if let Some(node) = self.nodes.iter().find(|node| node.label.eq_ignore_ascii_case(label)) {
+            return node.id.clone();
+        }
+        let id = format!("n{}", self.nodes.len());
+        self.nodes.push(ConceptNode { id: id.clone(), label: label.to_string() });
+        id
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn render(&self, format: ConceptMapFormat) -> String {
+        match format {
+            ConceptMapFormat::Mermaid => self.to_mermaid(),
+            ConceptMapFormat::Dot => self.to_dot(),
+        }
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+        for node in &self.nodes {
+            out.push_str(&format!("    {}[\"{}\"]\n", node.id, node.label.replace('"', "'")));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "    {} -->|{}| {}\n",
+                edge.from,
+                edge.relationship.replace(['|', '"'], "'"),
+                edge.to
+            ));
+        }
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = String::from("digraph concepts {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            out.push_str(&format!("    {} [label=\"{}\"];\n", node.id, escape(&node.label)));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!("    {} -> {} [label=\"{}\"];\n", edge.from, edge.to, escape(&edge.relationship)));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Drops a leading `-`, `*` or `1.` / `1)` list marker
+fn strip_list_marker(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return rest.trim_start();
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(['.', ')']) {
+            return rest.trim_start();
+        }
+    }
+    line
+}
@@ -0,0 +1,91 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use std::path::Path;
+
+/// Rough token budget for text injected into prompts. Uses the usual
+/// ~4 characters per token estimate rather than a real tokenizer.
+#[derive(Debug, Clone, Copy)]
+pub struct ContextBudget {
+    pub max_tokens: usize,
+}
+
+const CHARS_PER_TOKEN: usize = 4;
+
+impl ContextBudget {
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    pub fn estimate_tokens(text: &str) -> usize {
+        text.chars().count().div_ceil(CHARS_PER_TOKEN)
+    }
+
+    /// Trims `text` to the budget, preferring to cut at a line or word break.
+    /// Returns the fitted text and whether anything was cut.
+    pub fn fit(&self, text: &str) -> (String, bool) {
+        let max_chars = self.max_tokens * CHARS_PER_TOKEN;
+        if text.chars().count() <= max_chars {
+            return (text.to_string(), false);
+        }
+
+        let cut: String = text.chars().take(max_chars).collect();
+        let boundary = cut
+            .rfind('\n')
+            .filter(|i| *i > max_chars / 2)
+            .or_else(|| cut.rfind(' ').filter(|i| *i > max_chars / 2))
+            .unwrap_or(cut.len());
+
+        tracing::debug!("Context trimmed from ~{} to ~{} tokens", Self::estimate_tokens(text), self.max_tokens);
+        (format!("{}\n[...truncated]", cut[..boundary].trim_end()), true)
+    }
+}
+
+impl Default for ContextBudget {
+    fn default() -> Self {
+        Self::new(1500)
+    }
+}
+
+/// Tokens all reference files attached to one prompt may take up together
+pub const REFERENCE_CONTEXT_TOKENS: usize = 6000;
+
+/// A file attached to a request as reference material (`summon --context-file`)
+#[derive(Debug, Clone)]
+pub struct ReferenceFile {
+    pub label: String,
+    pub content: String,
+}
+
+impl ReferenceFile {
+    pub fn read(path: &Path) -> CHOPSResult<Self> {
+        if !path.is_file() {
+            return Err(CHOPSError::InvalidParameter(format!("Context file {} is not a readable file", path.display())));
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            CHOPSError::InvalidParameter(format!("Cannot read context file {}: {}", path.display(), e))
+        })?;
+        Ok(Self { label: path.display().to_string(), content })
+    }
+}
+
+/// Fits every reference into one shared budget. Files are visited smallest
+/// first, each offered an even share of what is left, so short files are kept
+/// whole and the tokens they don't need go to the larger ones. Returns the
+/// fitted contents in the original order and the labels of trimmed files.
+pub fn fit_references(files: &[ReferenceFile], budget: &ContextBudget) -> (Vec<String>, Vec<String>) {
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    order.sort_by_key(|&i| files[i].content.len());
+
+    let mut fitted = vec![String::new(); files.len()];
+    let mut trimmed = Vec::new();
+    let mut remaining = budget.max_tokens;
+    for (position, &i) in order.iter().enumerate() {
+        let share = remaining / (files.len() - position);
+        let (content, was_trimmed) = ContextBudget::new(share).fit(&files[i].content);
+        remaining = remaining.saturating_sub(ContextBudget::estimate_tokens(&content));
+        if was_trimmed {
+            trimmed.push(files[i].label.clone());
+        }
+        fitted[i] = content;
+    }
+    (fitted, trimmed)
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+/// Domain used when nothing in the prompt points anywhere more specific
+pub const FALLBACK_DOMAIN: &str = "software";
+
+/// Guesses a domain from free text by counting keyword hits per known domain
+#[derive(Debug, Clone)]
+pub struct DomainClassifier {
+    domains: Vec<(String, Vec<String>)>,
+}
+
+impl DomainClassifier {
+    pub fn new() -> Self {
+        let domains = [
+            ("web", &["web", "http", "browser", "frontend", "backend", "api", "rest", "html", "css", "javascript", "react", "server"][..]),
+            ("databases", &["database", "sql", "query", "index", "schema", "postgres", "storage", "transaction", "orm"][..]),
+            ("security", &["security", "auth", "authentication", "encryption", "vulnerability", "exploit", "attack", "password", "crypto", "permission"][..]),
+            ("performance", &["performance", "latency", "throughput", "fast", "slow", "optimize", "cache", "bottleneck", "profiling", "memory"][..]),
+            ("debugging", &["debug", "debugging", "bug", "crash", "error", "stack trace", "breakpoint", "log", "failure"][..]),
+            ("architecture", &["architecture", "microservice", "monolith", "scalability", "modular", "design pattern", "distributed", "system design"][..]),
+            ("ui", &["ui", "ux", "user interface", "design", "layout", "accessibility", "animation", "button", "usability"][..]),
+            ("devops", &["deploy", "deployment", "ci", "pipeline", "docker", "kubernetes", "infrastructure", "monitoring", "cloud"][..]),
+            ("machine learning", &["machine learning", "ml", "model", "training", "neural", "dataset", "inference", "ai", "llm"][..]),
+            ("mobile", &["mobile", "ios", "android", "app store", "smartphone", "tablet"][..]),
+            ("games", &["game", "gameplay", "player", "level design", "physics engine", "rendering", "shader"][..]),
+        ];
+
+        Self {
+            domains: domains
+                .iter()
+                .map(|(name, keywords)| (name.to_string(), keywords.iter().map(|k| k.to_string()).collect()))
+                .collect(),
+        }
+    }
+
+    /// Adds domains from config; a name that already exists gets its keywords replaced
+    pub fn with_domains(mut self, extra: &HashMap<String, Vec<String>>) -> Self {
+        for (name, keywords) in extra {
+            let keywords: Vec<String> = keywords.iter().map(|k| k.to_lowercase()).collect();
+            match self.domains.iter_mut().find(|(existing, _)| existing == name) {
+                Some((_, existing_keywords)) => *existing_keywords = keywords,
+                None => self.domains.push((name.clone(), keywords)),
+            }
+        }
+        self
+    }
+
+    /// Best matching domain for `text`, or [`FALLBACK_DOMAIN`] when nothing matches.
+    /// Ties go to the domain listed first.
+    #[tracing::instrument(name = "infer_domain", level = "debug", skip(self, text))]
+    pub fn infer(&self, text: &str) -> String {
+        let text = text.to_lowercase();
+        let words: Vec<&str> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let mut best: Option<(&str, usize)> = None;
+        for (name, keywords) in &self.domains {
+            let hits = keywords
+                .iter()
+                .filter(|keyword| {
+                    // Multi-word keywords match as phrases, single words must match whole words
+                    if keyword.contains(' ') {
+                        text.contains(keyword.as_str())
+                    } else {
+                        words.contains(&keyword.as_str())
+                    }
+                })
+                .count();
+
+            if hits > 0 && best.is_none_or(|(_, best_hits)| hits > best_hits) {
+                best = Some((name, hits));
+            }
+        }
+
+        match best {
+            Some((name, hits)) => {
+                tracing::debug!("Inferred domain '{}' from {} keyword hits", name, hits);
+                name.to_string()
+            },
+            None => {
+                tracing::debug!("No domain keywords found, falling back to '{}'", FALLBACK_DOMAIN);
+                FALLBACK_DOMAIN.to_string()
+            }
+        }
+    }
+}
+
+impl Default for DomainClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Infers a domain from prompt text using the built-in domain list
+pub fn infer_domain(prompt: &str) -> String {
+    DomainClassifier::new().infer(prompt)
+}
@@ -0,0 +1,157 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, error};
+use crate::client::status_error;
+
+/// Client for an OpenAI-compatible `/v1/embeddings` endpoint that remembers
+/// every vector it has fetched, so pattern descriptions are embedded once
+#[derive(Debug, Clone)]
+pub struct EmbeddingClient {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    cache: HashMap<String, Vec<f32>>,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: Vec<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingClient {
+    #[tracing::instrument(name = "embedding_client_new", level = "debug", skip(api_key))]
+    pub fn new(base_url: &str, model: &str, api_key: Option<String>) -> CHOPSResult<Self> {
+        if base_url.trim().is_empty() {
+            return Err(CHOPSError::InvalidParameter("Embedding endpoint URL is empty".to_string()));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(CHOPSError::NetworkError)?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key,
+            cache: HashMap::new(),
+        })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn cached(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Vectors for `texts` in order, fetching only those not already cached
+    #[tracing::instrument(name = "embed_texts", level = "debug", skip(self, texts), fields(count = texts.len()))]
+    pub async fn embed(&mut self, texts: &[&str]) -> CHOPSResult<Vec<Vec<f32>>> {
+        let mut missing: Vec<&str> = Vec::new();
+        for &text in texts {
+            if !self.cache.contains_key(text) && !missing.contains(&text) {
+                missing.push(text);
+            }
+        }
+
+        if !missing.is_empty() {
+            debug!("Fetching {} embeddings from {} ({} cached)", missing.len(), self.base_url, self.cache.len());
+            let mut request = self.client
+                .post(format!("{}/v1/embeddings", self.base_url))
+                .json(&EmbeddingRequest { model: &self.model, input: missing.clone() });
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
+            }
+            let response = request.send().await.map_err(CHOPSError::NetworkError)?;
+
+            if !response.status().is_success() {
+                let error = status_error(response).await;
+                error!("Embeddings API error: {:?}", error);
+                return Err(error);
+            }
+
+            let body: EmbeddingResponse = response.json().await.map_err(CHOPSError::NetworkError)?;
+            if body.data.len() != missing.len() {
+                return Err(CHOPSError::ApiError(format!(
+                    "Asked for {} embeddings but got {}", missing.len(), body.data.len()
+                )));
+            }
+            for data in body.data {
+                let text = missing.get(data.index)
+                    .ok_or_else(|| CHOPSError::ApiError(format!("Embedding index {} out of range", data.index)))?;
+                self.cache.insert(text.to_string(), data.embedding);
+            }
+        }
+
+        texts.iter()
+            .map(|text| self.cache.get(*text).cloned()
+                .ok_or_else(|| CHOPSError::ApiError(format!("No embedding returned for \"{}\"", text))))
+            .collect()
+    }
+}
+
+/// Cosine of the angle between two vectors; 0.0 when either is empty, zero
+/// or they differ in length
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let (dot, norm_a, norm_b) = a.iter().zip(b).fold((0.0, 0.0, 0.0), |(dot, norm_a, norm_b), (&x, &y)| {
+        let (x, y) = (x as f64, y as f64);
+        (dot + x * y, norm_a + x * x, norm_b + y * y)
+    });
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity_orders_by_angle() {
+        let concept = [1.0, 0.0, 0.0];
+        let candidates: [(&str, [f32; 3]); 4] = [
+            ("opposite", [-1.0, 0.0, 0.0]),
+            ("orthogonal", [0.0, 1.0, 0.0]),
+            ("close", [0.9, 0.1, 0.0]),
+            ("scaled twin", [3.0, 0.0, 0.0]),
+        ];
+        let mut ranked: Vec<(&str, f64)> = candidates.iter()
+            .map(|(name, vector)| (*name, cosine_similarity(&concept, vector)))
+            .collect();
+        ranked.sort_by(|a, b| chops_core::cmp_scores(a.1, b.1));
+
+        let order: Vec<&str> = ranked.iter().map(|(name, _)| *name).collect();
+        assert_eq!(order, vec!["scaled twin", "close", "orthogonal", "opposite"]);
+        assert!((ranked[0].1 - 1.0).abs() < 1e-9);
+        assert!((ranked[3].1 + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cosine_similarity_degenerate_vectors() {
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 2.0], &[1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+}
@@ -0,0 +1,605 @@
+use chops_core::{
+    Analogy, CHOPSConfig, CHOPSError, CHOPSResult, GeneratedIdea, LlmProvider, MemorySystem, MutateParams, PersonaType, SimilarityStrategy, StructuralMapping,
+};
+use chops_chaos::{ChaosEngineConfig, PatternRecognizer, QuantumClientConfig};
+use chops_persona::DriftLimits;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::{ClaudeClient, CognitiveArchitecture, CollaborationMode, CollaborationParticipant, CollaborationSession, EmbeddingClient, IdeaOptions, MockLlmClient, OpenAiClient, ComplexIdeaResult, GroundingContext, MutationResult, ParadoxReport, ProphecyResponse, ScoreCalibration};
+
+/// Chaos level summons use when none is asked for
+pub const DEFAULT_SUMMON_CHAOS: u8 = 5;
+
+/// Subdirectory of the data dir holding domains added with `chops domains add`
+pub const DOMAINS_DIR: &str = "domains";
+
+/// CHOPS as a library: the cognitive architecture, memory and config the CLI
+/// runs on, put together by [`ChopsEngine::builder`]
+pub struct ChopsEngine {
+    pub cognitive_architecture: CognitiveArchitecture,
+    pub memory_system: MemorySystem,
+    pub config: CHOPSConfig,
+    /// Holds the score calibration, chaos presets and backups; `None` persists nothing
+    pub data_dir: Option<PathBuf>,
+    pub memory_path: Option<PathBuf>,
+    /// Session episode ideas from this engine are ranked under
+    pub session_id: Uuid,
+    /// Settings that were invalid and skipped while building
+    pub warnings: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ChopsEngineBuilder {
+    config: Option<CHOPSConfig>,
+    backend: Option<ClaudeClient>,
+    data_dir: Option<PathBuf>,
+    memory_path: Option<PathBuf>,
+}
+
+/// What to summon an idea for; everything but the prompt has a default
+#[derive(Debug, Clone)]
+pub struct SummonRequest {
+    pub prompt: String,
+    pub persona: PersonaType,
+    pub domain: String,
+    /// Chaos level on the CLI's 1-11 scale
+    pub chaos_level: u8,
+    pub grounding: Option<GroundingContext>,
+    /// Idea this one refines, for lineage in memory
+    pub parent_id: Option<Uuid>,
+    pub options: IdeaOptions,
+}
+
+impl SummonRequest {
+    pub fn new(prompt: &str) -> Self {
+        Self {
+            prompt: prompt.to_string(),
+            persona: PersonaType::default(),
+            domain: "software development".to_string(),
+            chaos_level: DEFAULT_SUMMON_CHAOS,
+            grounding: None,
+            parent_id: None,
+            options: IdeaOptions::default(),
+        }
+    }
+}
+
+impl ChopsEngineBuilder {
+    /// Settings to build from (default: `CHOPSConfig::default()`)
+    pub fn config(mut self, config: CHOPSConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Client to generate with, a [`ClaudeClient`] or any `Box<dyn LlmClient>`
+    /// (default: chosen from the config's provider, network settings and API key)
+    pub fn backend(mut self, client: impl Into<ClaudeClient>) -> Self {
+        self.backend = Some(client.into());
+        self
+    }
+
+    /// Directory for memory, calibration and chaos presets
+    pub fn data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
+
+    /// Memory file to load and save (default: `memory.json` in the data dir)
+    pub fn memory_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.memory_path = Some(path.into());
+        self
+    }
+
+    #[tracing::instrument(name = "chops_engine_build", level = "info", skip(self))]
+    pub fn build(self) -> CHOPSResult<ChopsEngine> {
+        let config = self.config.unwrap_or_default();
+        let mut warnings = Vec::new();
+
+        let mut claude_client = match self.backend {
+            Some(client) => client,
+            None => default_backend(&config)?,
+        };
+        claude_client.set_prompt_caching(config.behavior_settings.prompt_caching);
+        claude_client.set_max_request_bytes(config.behavior_settings.max_request_bytes);
+        claude_client.set_voice_check_threshold(config.behavior_settings.voice_check_threshold);
+        claude_client.configure_sampling(
+            config.behavior_settings.temperature,
+            config.behavior_settings.top_p,
+            config.behavior_settings.top_k,
+        )?;
+
+        // Apply any saved score calibration
+        if let Some(dir) = &self.data_dir {
+            match ScoreCalibration::load_from_file(&dir.join("calibration.json")) {
+                Ok(calibration) => claude_client.set_calibration(calibration),
+                Err(e) => tracing::warn!("Ignoring unreadable score calibration: {}", e),
+            }
+        }
+
+        tracing::debug!("Initializing cognitive architecture");
+        let mut cognitive_architecture = CognitiveArchitecture::new(claude_client);
+        configure_architecture(&mut cognitive_architecture, &config, self.data_dir.as_deref(), &mut warnings);
+
+        let memory_path = self.memory_path.or_else(|| self.data_dir.as_ref().map(|dir| dir.join("memory.json")));
+        let mut memory_system = match &memory_path {
+            Some(path) => {
+                tracing::debug!("Loading memory system from: {}", path.display());
+                MemorySystem::load_from_file(path)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to load memory system, creating new one: {}", e);
+                        MemorySystem::new()
+                    })
+            },
+            None => MemorySystem::new(),
+        };
+        let session_id = memory_system.begin_session(chrono::Utc::now());
+
+        if let Some(path) = memory_path.as_deref().map(patterns_path) {
+            tracing::debug!("Loading chaos patterns from: {}", path.display());
+            match PatternRecognizer::load_from_file(&path) {
+                Ok(recognizer) => cognitive_architecture.chaos_engine_mut().load_patterns(recognizer),
+                Err(e) => tracing::warn!("Failed to load chaos patterns, starting without them: {}", e),
+            }
+        }
+
+        for warning in &warnings {
+            tracing::warn!("{}", warning);
+        }
+        tracing::info!("CHOPS engine built with {} setting warnings", warnings.len());
+
+        Ok(ChopsEngine {
+            cognitive_architecture,
+            memory_system,
+            config,
+            data_dir: self.data_dir,
+            memory_path,
+            session_id,
+            warnings,
+        })
+    }
+}
+
+/// The client the config asks for. Offline and air-gapped runs need no API
+/// key: offline ones answer with the mock client's canned completions, and
+/// air-gapped ones either talk to a local backend or refuse LLM calls
+/// outright. A local backend speaks the Messages API, whichever provider is
+/// configured, and only gets the Claude key when the user opted in.
+fn default_backend(config: &CHOPSConfig) -> CHOPSResult<ClaudeClient> {
+    if config.network.offline || config.default_settings.provider == LlmProvider::Mock {
+        tracing::info!("Offline mode, LLM calls get canned mock completions");
+        return Ok(ClaudeClient::with_provider(Box::new(MockLlmClient::new())));
+    }
+    match &config.network.local_api_url {
+        Some(url) => {
+            if config.network.air_gapped && !is_local_endpoint(url) {
+                return Err(CHOPSError::ConfigError(format!(
+                    "Air-gapped mode only talks to a local backend, but network.local_api_url is {}. \
+                    Use a loopback or private network address.", url
+                )));
+            }
+            let api_key = if config.network.send_api_key_to_local {
+                config.get_claude_api_key().ok().map(str::to_string)
+            } else {
+                None
+            };
+            ClaudeClient::local(url.clone(), api_key)
+        },
+        None if config.network.air_gapped => {
+            tracing::info!("Air-gapped mode without a local backend, LLM calls are disabled");
+            ClaudeClient::offline()
+        },
+        None => match config.default_settings.provider {
+            LlmProvider::Claude => {
+                tracing::debug!("Retrieving Claude API key from configuration");
+                ClaudeClient::new(config.get_claude_api_key()?.to_string())
+            },
+            LlmProvider::OpenAi => {
+                tracing::debug!("Retrieving OpenAI API key from configuration");
+                let provider = OpenAiClient::new(config.get_openai_api_key()?.to_string())?;
+                Ok(ClaudeClient::with_provider(Box::new(provider)))
+            },
+            LlmProvider::Mock => Ok(ClaudeClient::with_provider(Box::new(MockLlmClient::new()))),
+        },
+    }
+}
+
+/// Whether `url` points at this machine or a private network: localhost,
+/// loopback, private, link-local or unique local addresses
+fn is_local_endpoint(url: &str) -> bool {
+    let Some(host) = reqwest::Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)) else {
+        return false;
+    };
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        Ok(IpAddr::V6(ip)) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00 || (ip.segments()[0] & 0xffc0) == 0xfe80,
+        Err(_) => false,
+    }
+}
+
+/// Whether `url` is OpenAI's own API, the only place the OpenAI key may go
+fn is_openai_endpoint(url: &str) -> bool {
+    reqwest::Url::parse(url).is_ok_and(|url| url.scheme() == "https" && url.host_str() == Some("api.openai.com"))
+}
+
+/// Applies the entropy, persona, chaos and analogy settings from `config`, the
+/// custom personas in its template directories, plus the chaos preset activated
+/// with `chops chaos load` and the analogy domains added with `chops domains add`.
+/// Invalid settings are skipped and reported in `warnings`.
+fn configure_architecture(
+    architecture: &mut CognitiveArchitecture,
+    config: &CHOPSConfig,
+    data_dir: Option<&Path>,
+    warnings: &mut Vec<String>,
+) {
+    architecture.chaos_engine_mut().entropy_generator.configure_quantum(QuantumClientConfig {
+        timeout_ms: config.entropy_settings.quantum_timeout_ms,
+        retry_attempts: config.entropy_settings.quantum_retry_attempts,
+        ..QuantumClientConfig::default()
+    });
+    if !config.network.allows_network() {
+        architecture.chaos_engine_mut().entropy_generator.disable_quantum();
+    }
+    if let Err(e) = architecture.persona_engine_mut().set_drift_limits(DriftLimits {
+        drift_rate: config.persona_drift.drift_rate,
+        max_drift: config.persona_drift.max_drift,
+    }) {
+        warnings.push(format!("Ignoring persona_drift settings: {}", e));
+    }
+    let (_, persona_warnings) = architecture.persona_engine_mut().load_custom_personas(&config.template_directories);
+    warnings.extend(persona_warnings);
+    for (persona_name, customization) in &config.persona_customizations {
+        let Ok(persona) = persona_name.parse::<PersonaType>() else {
+            continue;
+        };
+        if let Some(sequences) = &customization.stop_sequences {
+            architecture.persona_engine_mut().set_stop_sequences(persona.clone(), sequences.clone());
+        }
+        let Some(affinity) = &customization.chaos_affinity else {
+            continue;
+        };
+        if let Err(e) = architecture.persona_engine_mut().set_chaos_affinity(persona, affinity.clone()) {
+            warnings.push(format!("Ignoring chaos_affinity for {}: {}", persona_name, e));
+        }
+    }
+    if let Err(e) = architecture.chaos_engine_mut().set_tier_boundaries(config.chaos_tiers) {
+        warnings.push(format!("Ignoring chaos_tiers settings: {}", e));
+    }
+
+    if config.analogy.strategy == SimilarityStrategy::Embedding {
+        let analogy = &config.analogy;
+        if !config.network.allows_network() {
+            warnings.push("Ignoring embedding analogy matching without network access (air-gapped or offline)".to_string());
+        } else {
+            let api_key = analogy.embedding_api_key.clone().or_else(|| {
+                is_openai_endpoint(&analogy.embedding_url).then(|| config.api_keys.openai_api_key.clone()).flatten()
+            });
+            match EmbeddingClient::new(&analogy.embedding_url, &analogy.embedding_model, api_key) {
+                Ok(client) => architecture.analogical_reasoner_mut().use_embeddings(client),
+                Err(e) => warnings.push(format!("Ignoring analogy settings: {}", e)),
+            }
+        }
+    }
+
+    if let Some(path) = data_dir.map(|dir| dir.join("chaos.json")).filter(|path| path.exists()) {
+        let loaded = ChaosEngineConfig::load_from_file(&path)
+            .and_then(|preset| architecture.chaos_engine_mut().import_config(preset));
+        if let Err(e) = loaded {
+            warnings.push(format!("Ignoring chaos preset {}: {}", path.display(), e));
+        }
+    }
+
+    if let Some(entries) = data_dir.and_then(|dir| std::fs::read_dir(dir.join(DOMAINS_DIR)).ok()) {
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+        paths.sort();
+        for path in paths {
+            match architecture.analogical_reasoner_mut().load_domains_from_file(&path) {
+                Ok(domains) => tracing::debug!("Loaded {} analogy domains from {}", domains.len(), path.display()),
+                Err(e) => warnings.push(format!("Ignoring analogy domains {}: {}", path.display(), e)),
+            }
+        }
+    }
+}
+
+impl ChopsEngine {
+    pub fn builder() -> ChopsEngineBuilder {
+        ChopsEngineBuilder::default()
+    }
+
+    /// Generates an idea through the full cognitive stack and remembers it
+    #[tracing::instrument(name = "chops_engine_summon", level = "info", skip(self, request), fields(persona = %request.persona))]
+    pub async fn summon(&mut self, request: &SummonRequest) -> CHOPSResult<ComplexIdeaResult> {
+        let result = self.cognitive_architecture
+            .process_complex_idea_with_context(
+                &request.prompt,
+                request.persona.clone(),
+                &request.domain,
+                request.chaos_level as f64 / 11.0,
+                request.grounding.as_ref(),
+                &request.options,
+            )
+            .await?;
+        self.remember_idea(&result, request.chaos_level, &request.domain, request.parent_id);
+        Ok(result)
+    }
+
+    /// Rewrites `content` as `params` ask, within their change budget and mutation classes
+    pub async fn mutate(&mut self, content: &str, params: &MutateParams) -> CHOPSResult<MutationResult> {
+        self.cognitive_architecture.claude_client_mut().mutate_code(content, params).await
+    }
+
+    /// Predicts where `domain` is headed by `year` (default 2030)
+    pub async fn prophecy(&mut self, domain: &str, year: Option<u32>, context: &str) -> CHOPSResult<ProphecyResponse> {
+        self.cognitive_architecture.claude_client_mut().generate_future_prophecy(domain, year, context).await
+    }
+
+    /// Runs a multi-round collaboration of `mode` among `participants`, each
+    /// speaking as their assigned persona
+    pub async fn collaborate(
+        &mut self,
+        topic: &str,
+        mode: CollaborationMode,
+        participants: Vec<CollaborationParticipant>,
+        rounds: u8,
+    ) -> CHOPSResult<CollaborationSession> {
+        self.cognitive_architecture.collaborate(topic, mode, participants, rounds).await
+    }
+
+    /// Pairs each paradox among `constraints` with a way through it
+    pub async fn paradox(&mut self, constraints: &[String]) -> CHOPSResult<ParadoxReport> {
+        self.cognitive_architecture.resolve_constraint_paradoxes(constraints).await
+    }
+
+    /// Records a summoned idea in memory: the idea itself, the session
+    /// leaderboard, persona/domain effectiveness and its token cost
+    pub fn remember_idea(
+        &mut self,
+        result: &ComplexIdeaResult,
+        chaos_level: u8,
+        domain: &str,
+        parent_id: Option<Uuid>,
+    ) -> GeneratedIdea {
+        let base = &result.base_idea;
+        let tokens_used = base.usage.as_ref().map(|usage| {
+            (usage.input_tokens + usage.output_tokens) as u64
+                + usage.cache_creation_input_tokens.unwrap_or(0) as u64
+                + usage.cache_read_input_tokens.unwrap_or(0) as u64
+        }).unwrap_or(0);
+        let idea = GeneratedIdea {
+            id: base.id,
+            title: extract_title_from_content(&base.content),
+            description: base.content.clone(),
+            persona_used: base.persona_used.clone(),
+            chaos_level: chaos_level as f64 / 11.0,
+            creativity_score: base.creativity_score,
+            feasibility_score: base.feasibility_score,
+            novelty_score: base.novelty_score,
+            excitement_factor: base.excitement_factor,
+            tags: extract_tags_from_content(&base.content),
+            implementation_hints: result.implementation_roadmap.critical_path.clone(),
+            potential_risks: vec![], // Could be extracted from reality distortion
+            experimental_variations: vec![], // Convert from chaos variations
+            analogies: result.analogical_insights.iter().map(|insight| {
+                Analogy {
+                    source_domain: insight.source_domain.clone(),
+                    source_pattern: insight.analogy_description.clone(),
+                    target_concept: domain.to_string(),
+                    structural_mapping: StructuralMapping {
+                        source_domain: insight.source_domain.clone(),
+                        target_concept: domain.to_string(),
+                        source_elements: vec![],
+                        target_elements: vec![],
+                        relationship_mappings: vec![],
+                    },
+                    insight: insight.analogy_description.clone(),
+                    confidence: insight.confidence_score,
+                    novelty_score: insight.surprise_factor,
+                    practical_applicability: 0.7, // Default value
+                }
+            }).collect(),
+            timestamp: chrono::Utc::now(),
+            parent_id,
+            tokens_used,
+            domain: Some(domain.to_string()),
+        };
+        self.memory_system.record_session_idea(self.session_id, &idea, Some(base.coherence_score));
+        self.memory_system.record_persona_domain(&idea, domain);
+        self.memory_system.add_idea(idea.clone());
+
+        if let Some(usage) = &base.usage {
+            let cost = self.cognitive_architecture.claude_client().pricing().cost(usage);
+            self.memory_system.record_persona_cost(&base.persona_used, tokens_used, cost);
+        }
+        idea
+    }
+
+    /// Writes memory, and the learned chaos patterns beside it, to the memory
+    /// path; a no-op for engines without one
+    pub fn save_memory(&self) -> CHOPSResult<()> {
+        match &self.memory_path {
+            Some(path) => {
+                self.memory_system.save_to_file(path)?;
+                self.cognitive_architecture.chaos_engine().pattern_recognizer.save_to_file(&patterns_path(path))
+            },
+            None => {
+                tracing::debug!("Running in-memory only, skipping memory save");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Learned chaos patterns live next to memory.json
+fn patterns_path(memory_path: &Path) -> PathBuf {
+    memory_path.with_file_name("patterns.json")
+}
+
+fn extract_title_from_content(content: &str) -> String {
+    // Extract the first line or first sentence as title
+    if let Some(first_line) = content.lines().next() {
+        let title = first_line.trim();
+        if title.chars().count() > 100 {
+            format!("{}...", title.chars().take(97).collect::<String>())
+        } else {
+            title.to_string()
+        }
+    } else {
+        "Generated Idea".to_string()
+    }
+}
+
+fn extract_tags_from_content(content: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    // Extract common technical terms as tags
+    let keywords = [
+        "ai", "machine learning", "algorithm", "api", "database", "framework",
+        "architecture", "performance", "security", "testing", "automation",
+        "cloud", "microservices", "blockchain", "quantum", "neural",
+        "optimization", "scalability", "user experience", "innovation"
+    ];
+
+    let content_lower = content.to_lowercase();
+    for keyword in &keywords {
+        if content_lower.contains(keyword) {
+            tags.push(keyword.to_string());
+        }
+    }
+
+    // Limit to 5 most relevant tags
+    tags.truncate(5);
+
+    if tags.is_empty() {
+        tags.push("innovative".to_string());
+        tags.push("creative".to_string());
+    }
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LlmClient, RecommendationSource};
+    use chops_core::Renderable;
+
+    #[test]
+    fn test_local_endpoints() {
+        for url in ["http://localhost:8080", "http://127.0.0.1:11434/", "http://[::1]:8080", "http://10.1.2.3", "http://192.168.0.7:80", "http://[fd00::1]"] {
+            assert!(is_local_endpoint(url), "{} should be local", url);
+        }
+        for url in ["https://api.anthropic.com", "http://8.8.8.8", "http://localhost.evil.com", "http://[2001:db8::1]", "not a url"] {
+            assert!(!is_local_endpoint(url), "{} should not be local", url);
+        }
+    }
+
+    #[test]
+    fn test_openai_key_only_goes_to_openai() {
+        assert!(is_openai_endpoint("https://api.openai.com"));
+        assert!(is_openai_endpoint("https://api.openai.com/"));
+        assert!(!is_openai_endpoint("http://api.openai.com"));
+        assert!(!is_openai_endpoint("https://api.openai.com.evil.net"));
+        assert!(!is_openai_endpoint("http://localhost:8080"));
+    }
+
+    #[test]
+    fn test_air_gapped_rejects_remote_local_api_url() {
+        let mut config = CHOPSConfig::default();
+        config.network.air_gapped = true;
+        config.network.local_api_url = Some("https://llm.example.com".to_string());
+        assert!(matches!(default_backend(&config), Err(CHOPSError::ConfigError(_))));
+
+        config.network.local_api_url = Some("http://127.0.0.1:8080".to_string());
+        assert!(default_backend(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_seeded_memory_picks_the_recommended_persona() {
+        let dir = std::env::temp_dir().join(format!("chops-recommend-{}", uuid::Uuid::new_v4()));
+        let mut config = CHOPSConfig::default();
+        config.network.offline = true;
+        let mut engine = ChopsEngine::builder()
+            .config(config)
+            .backend(Box::new(MockLlmClient::new()) as Box<dyn LlmClient>)
+            .data_dir(&dir)
+            .build()
+            .unwrap();
+        let outage_prompt = "ideas for surviving the next outage and incident";
+
+        let fresh = engine.cognitive_architecture
+            .recommend_persona(outage_prompt, "web dev", None, PersonaType::default())
+            .await
+            .unwrap();
+        assert_eq!(fresh.persona, PersonaType::ChaosEngineer);
+        assert_eq!(fresh.source, RecommendationSource::Profile);
+
+        let result = engine.cognitive_architecture
+            .process_complex_idea_with_context("tidy logs", PersonaType::ZenMaster, "web dev", 0.5, None, &IdeaOptions::default())
+            .await
+            .unwrap();
+        engine.remember_idea(&result, 5, "web dev", None);
+        let remembered = engine.memory_system.persona_track_record("web dev");
+        let recommendation = engine.cognitive_architecture
+            .recommend_persona(outage_prompt, "web dev", remembered, PersonaType::default())
+            .await
+            .unwrap();
+        assert_eq!(recommendation.persona, PersonaType::ZenMaster);
+        assert_eq!(recommendation.source, RecommendationSource::Memory);
+        assert!(recommendation.rationale.contains("scored best in web dev"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// `item` rendered as JSON reads back as a `T` that serializes the same
+    fn assert_json_round_trips<T: Renderable + serde::Serialize + serde::de::DeserializeOwned>(item: &T) {
+        let json = item.render_json().unwrap();
+        let parsed: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), serde_json::to_value(item).unwrap());
+        assert!(!json.contains('\u{1b}'), "JSON output carries ANSI escapes");
+    }
+
+    #[tokio::test]
+    async fn test_results_round_trip_through_json() {
+        let dir = std::env::temp_dir().join(format!("chops-round-trip-{}", uuid::Uuid::new_v4()));
+        let mut config = CHOPSConfig::default();
+        config.network.offline = true;
+        let mut engine = ChopsEngine::builder()
+            .config(config)
+            .backend(Box::new(MockLlmClient::new()) as Box<dyn LlmClient>)
+            .data_dir(&dir)
+            .build()
+            .unwrap();
+
+        let idea = engine.summon(&SummonRequest::new("tidy logs")).await.unwrap();
+        assert_json_round_trips(&idea);
+
+        let params = MutateParams {
+            input_file: "lib.py".to_string(),
+            direction: "creative".to_string(),
+            inject_personality: true,
+            add_easter_eggs: false,
+            make_weird: false,
+            keep_functional: true,
+            max_change: None,
+            allowed_classes: Vec::new(),
+            verify_syntax: false,
+        };
+        let mutation = engine.mutate("class Cache:\n    def __init__(self):\n        self.items = {}\n", &params).await.unwrap();
+        assert_json_round_trips(&mutation);
+
+        let prophecy = engine.prophecy("web development", Some(2030), "").await.unwrap();
+        assert_json_round_trips(&prophecy);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_long_multibyte_title_is_cut_on_a_character_boundary() {
+        let title = extract_title_from_content(&format!("{}\nbody", "é".repeat(120)));
+        assert_eq!(title, format!("{}...", "é".repeat(97)));
+        assert_eq!(extract_title_from_content("Short title\nbody"), "Short title");
+    }
+}
@@ -1,7 +1,35 @@
 pub mod client;
 pub mod models;
 pub mod cognitive;
+pub mod calibration;
+pub mod context;
+pub mod domain;
+pub mod concept_map;
+pub mod pricing;
+pub mod analysis_cache;
+pub mod stream;
+pub mod structured;
+pub mod trend;
+pub mod engine;
+pub mod provider;
+pub mod embeddings;
+pub mod sections;
+pub mod scoring;
 
 pub use client::*;
 pub use models::*;
-pub use cognitive::*;
\ No newline at end of file
+pub use cognitive::*;
+pub use calibration::*;
+pub use context::*;
+pub use domain::*;
+pub use concept_map::*;
+pub use pricing::*;
+pub use analysis_cache::*;
+pub use stream::*;
+pub use structured::*;
+pub use trend::*;
+pub use engine::*;
+pub use provider::*;
+pub use embeddings::*;
+pub use sections::*;
+pub use scoring::*;
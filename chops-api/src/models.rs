@@ -1,14 +1,20 @@
-use chops_core::PersonaType;
+use chops_core::{to_yaml, CHOPSError, CHOPSResult, MutationClass, PersonaType, Renderable};
 use chops_chaos::ChaosVariation;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc, Datelike};
-use crate::client::{ClaudeResponse, Usage};
+use crate::client::{ClaudeMessage, ClaudeResponse, ContentBlock, MessageRole, Usage};
+use crate::cognitive::{ImpossibilityFinding, ParadoxResolution};
+use crate::sections::IdeaSection;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GeneratedIdeaResponse {
     pub id: Uuid,
     pub content: String,
+    /// `content` split at its headings; always at least one section
+    #[serde(default)]
+    pub sections: Vec<IdeaSection>,
     pub persona_used: PersonaType,
     pub chaos_level: f64,
     pub creativity_score: f64,
@@ -18,8 +24,23 @@ pub struct GeneratedIdeaResponse {
     pub chaos_variations: Vec<ChaosVariation>,
     pub unexpected_elements: Vec<String>,
     pub coherence_score: f64,
+    /// Tool-use, thinking and other non-text blocks, in response order
+    #[serde(default)]
+    pub other_blocks: Vec<ContentBlock>,
     pub raw_response: ClaudeResponse,
     pub usage: Option<Usage>,
+    /// The approach the persona outlined before writing the idea (`summon --with-plan`)
+    #[serde(default)]
+    pub plan: Option<String>,
+    /// How well the wording matched the persona's expected voice (0.0-1.0)
+    #[serde(default)]
+    pub voice_fidelity: Option<f64>,
+    /// The response stream was cut off and this is the partial content
+    #[serde(default)]
+    pub truncated: bool,
+    /// The idea as JSON, validated against the user's schema (`summon --output-schema`)
+    #[serde(default)]
+    pub structured_output: Option<serde_json::Value>,
     pub generated_at: DateTime<Utc>,
 }
 
@@ -56,6 +77,34 @@ pub struct ProphecyResponse {
     pub generated_at: DateTime<Utc>,
 }
 
+impl Renderable for ProphecyResponse {
+    fn title(&self) -> String {
+        format!("Prophecy for {} in {}", self.domain, self.target_year)
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = format!(
+            "# {}\n\nProphesied {} with {:.0}% confidence\n",
+            self.title(),
+            self.generated_at.format("%Y-%m-%d %H:%M UTC"),
+            self.confidence_level * 100.0
+        );
+        if !self.context.trim().is_empty() {
+            out.push_str(&format!("\n## Context\n\n{}\n", self.context.trim()));
+        }
+        out.push_str(&format!("\n## Prophecy\n\n{}\n", self.prophecy.trim()));
+        out
+    }
+
+    fn render_json(&self) -> CHOPSResult<String> {
+        serde_json::to_string_pretty(self).map_err(CHOPSError::SerializationError)
+    }
+
+    fn render_yaml(&self) -> CHOPSResult<String> {
+        to_yaml(self)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MutationResult {
     pub original_content: String,
@@ -65,6 +114,39 @@ pub struct MutationResult {
     pub easter_eggs: Vec<String>,
     pub weirdness_level: f64,
     pub functionality_preserved: bool,
+    /// Share of lines that differ from the original (0.0-1.0)
+    #[serde(default)]
+    pub change_ratio: f64,
+    /// Why the mutation was reverted to the original: the parse error of
+    /// the last attempt, when no attempt still parsed
+    #[serde(default)]
+    pub syntax_error: Option<String>,
+}
+
+impl Renderable for MutationResult {
+    fn title(&self) -> String {
+        format!("CHOPS mutation ({} changes)", self.mutations_applied.len())
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{:.0}% of lines changed\n", self.title(), self.change_ratio * 100.0);
+        if !self.mutations_applied.is_empty() {
+            out.push_str("\n## Mutations\n\n");
+            for mutation in &self.mutations_applied {
+                out.push_str(&format!("- [{}] {}: {}\n", mutation.mutation_type, mutation.location, mutation.description));
+            }
+        }
+        out.push_str(&format!("\n## Mutated code\n\n```\n{}\n```\n", self.mutated_content.trim_end()));
+        out
+    }
+
+    fn render_json(&self) -> CHOPSResult<String> {
+        serde_json::to_string_pretty(self).map_err(CHOPSError::SerializationError)
+    }
+
+    fn render_yaml(&self) -> CHOPSResult<String> {
+        to_yaml(self)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +159,9 @@ pub struct MutationDescription {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MutationType {
+    CommentInjection,
+    NamingChange,
+    LogicRewrite,
     PersonalityInjection,
     EasterEggAddition,
     WeirdnessEnhancement,
@@ -85,6 +170,42 @@ pub enum MutationType {
     ChaosElement,
 }
 
+chops_core::impl_enum_str!(MutationType {
+    CommentInjection => "comment",
+    NamingChange => "naming",
+    LogicRewrite => "logic",
+    PersonalityInjection => "personality",
+    EasterEggAddition => "easter-egg",
+    WeirdnessEnhancement => "weirdness",
+    StructuralModification => "structural",
+    ConceptualShift => "conceptual",
+    ChaosElement => "chaos",
+});
+
+impl MutationType {
+    /// The broad class a change of this type belongs to. Types that may reach
+    /// into code count as logic, the riskiest class.
+    pub fn class(&self) -> MutationClass {
+        match self {
+            MutationType::CommentInjection => MutationClass::Comments,
+            MutationType::NamingChange => MutationClass::Naming,
+            _ => MutationClass::Logic,
+        }
+    }
+
+    pub fn impact_level(&self) -> f64 {
+        match self {
+            MutationType::CommentInjection => 0.1,
+            MutationType::EasterEggAddition => 0.2,
+            MutationType::NamingChange | MutationType::PersonalityInjection => 0.3,
+            MutationType::StructuralModification => 0.6,
+            MutationType::WeirdnessEnhancement => 0.7,
+            MutationType::LogicRewrite | MutationType::ConceptualShift => 0.8,
+            MutationType::ChaosElement => 0.9,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollaborationSession {
     pub session_id: Uuid,
@@ -107,6 +228,26 @@ pub enum CollaborationMode {
     Synthesis,
 }
 
+chops_core::impl_enum_str!(CollaborationMode {
+    Debate => "debate",
+    Brainstorm => "brainstorm",
+    Consensus => "consensus",
+    DevilsAdvocate => "devils-advocate",
+    Synthesis => "synthesis",
+});
+
+/// A saved multi-turn conversation that can be resumed in interactive mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSession {
+    pub session_id: Uuid,
+    pub persona: PersonaType,
+    pub chaos_level: u8,
+    pub domain: String,
+    pub messages: Vec<ClaudeMessage>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollaborationParticipant {
     pub name: String,
@@ -132,7 +273,7 @@ pub struct CollaborationContribution {
     pub insight_level: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ContributionType {
     Argument,
     Question,
@@ -143,7 +284,102 @@ pub enum ContributionType {
     Challenge,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+chops_core::impl_enum_str!(ContributionType {
+    Argument => "argument",
+    Question => "question",
+    CounterPoint => "counter-point",
+    BuildingOn => "building-on",
+    Synthesis => "synthesis",
+    Insight => "insight",
+    Challenge => "challenge",
+});
+
+impl CollaborationMode {
+    /// Who takes part when the user names no one. In devils-advocate mode
+    /// the last participant is the advocate.
+    pub fn default_participants(&self) -> Vec<CollaborationParticipant> {
+        let participants: &[(&str, &str, &str)] = match self {
+            CollaborationMode::Debate => &[
+                ("Proponent", "debater", "the idea is worth pursuing"),
+                ("Skeptic", "debater", "the idea is not worth pursuing"),
+            ],
+            CollaborationMode::Brainstorm | CollaborationMode::Synthesis => &[
+                ("Visionary", "ideator", "think big and far ahead"),
+                ("Pragmatist", "ideator", "what could ship next month"),
+                ("Wildcard", "ideator", "borrow from unrelated fields"),
+            ],
+            CollaborationMode::Consensus => &[
+                ("Builder", "stakeholder", "speed of delivery"),
+                ("Guardian", "stakeholder", "safety and maintainability"),
+                ("User advocate", "stakeholder", "the people using it"),
+            ],
+            CollaborationMode::DevilsAdvocate => &[
+                ("Proposer", "proposer", "make the idea work"),
+                ("Devil's advocate", "advocate", "expose the weaknesses of every proposal"),
+            ],
+        };
+        participants.iter()
+            .map(|(name, role, perspective)| CollaborationParticipant {
+                name: name.to_string(),
+                role: role.to_string(),
+                perspective: perspective.to_string(),
+                persona_type: None,
+            })
+            .collect()
+    }
+
+    /// The kind of contribution a participant makes in `round` (1-based);
+    /// `advocate` is the devil's advocate answering a proposal
+    pub fn contribution_type(&self, round: u8, advocate: bool) -> ContributionType {
+        match (self, round) {
+            (CollaborationMode::DevilsAdvocate, _) if advocate => ContributionType::Challenge,
+            (CollaborationMode::Brainstorm, _) => ContributionType::Insight,
+            (CollaborationMode::Synthesis, 1) => ContributionType::Insight,
+            (CollaborationMode::Synthesis, _) => ContributionType::Synthesis,
+            (_, 1) => ContributionType::Argument,
+            (CollaborationMode::Debate, _) => ContributionType::CounterPoint,
+            (CollaborationMode::Consensus | CollaborationMode::DevilsAdvocate, _) => ContributionType::BuildingOn,
+        }
+    }
+}
+
+impl CollaborationSession {
+    pub fn new(topic: &str, mode: CollaborationMode, participants: Vec<CollaborationParticipant>) -> Self {
+        Self {
+            session_id: Uuid::new_v4(),
+            topic: topic.to_string(),
+            mode,
+            participants,
+            rounds: Vec::new(),
+            synthesis: None,
+            insights: Vec::new(),
+            created_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+
+    pub fn contributions(&self) -> impl Iterator<Item = &CollaborationContribution> {
+        self.rounds.iter().flat_map(|round| round.contributions.iter())
+    }
+
+    /// Every round so far as plain text, for prompts
+    pub fn transcript(&self) -> String {
+        let mut transcript = String::new();
+        for round in self.rounds.iter().filter(|round| !round.contributions.is_empty()) {
+            transcript.push_str(&format!("=== Round {} ===\n", round.round_number));
+            for contribution in &round.contributions {
+                transcript.push_str(&format!("\n{} ({}): {}\n", contribution.participant, contribution.contribution_type, contribution.content.trim()));
+            }
+            if let Some(summary) = &round.summary {
+                transcript.push_str(&format!("\nRound summary: {}\n", summary.trim()));
+            }
+            transcript.push('\n');
+        }
+        transcript
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AnalogicalInsight {
     pub source_domain: String,
     pub target_domain: String,
@@ -155,7 +391,7 @@ pub struct AnalogicalInsight {
     pub surprise_factor: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StructuralMapping {
     pub source_element: String,
     pub target_element: String,
@@ -163,7 +399,7 @@ pub struct StructuralMapping {
     pub strength: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemporalAnalysis {
     pub current_state: String,
     pub historical_patterns: Vec<HistoricalPattern>,
@@ -172,7 +408,7 @@ pub struct TemporalAnalysis {
     pub timeline_scenarios: Vec<TimelineScenario>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HistoricalPattern {
     pub pattern_name: String,
     pub description: String,
@@ -181,7 +417,7 @@ pub struct HistoricalPattern {
     pub cyclical_nature: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FutureProjection {
     pub scenario_name: String,
     pub description: String,
@@ -191,7 +427,7 @@ pub struct FutureProjection {
     pub potential_impact: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TrendAnalysis {
     pub emerging_trends: Vec<String>,
     pub declining_trends: Vec<String>,
@@ -200,7 +436,7 @@ pub struct TrendAnalysis {
     pub convergence_points: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TimelineScenario {
     pub scenario_id: String,
     pub name: String,
@@ -210,7 +446,7 @@ pub struct TimelineScenario {
     pub desirability: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TimelineEvent {
     pub year: u32,
     pub event_description: String,
@@ -218,7 +454,7 @@ pub struct TimelineEvent {
     pub uncertainty: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PsychologicalProfile {
     pub unspoken_desires: Vec<String>,
     pub hidden_fears: Vec<String>,
@@ -229,7 +465,26 @@ pub struct PsychologicalProfile {
     pub subconscious_needs: Vec<String>,
 }
 
+/// Where an automatic persona pick came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecommendationSource {
+    /// Language in the prompt itself
+    Profile,
+    /// The persona that has scored best in this domain before
+    Memory,
+    /// Nothing to go on, or recommendations turned off; the configured default persona
+    Default,
+}
+
+/// A persona chosen on the user's behalf, with the one-line reason for it
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonaRecommendation {
+    pub persona: PersonaType,
+    pub rationale: String,
+    pub source: RecommendationSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RealityDistortionField {
     pub distortion_level: f64,
     pub impossible_elements: Vec<String>,
@@ -237,9 +492,108 @@ pub struct RealityDistortionField {
     pub reality_bends: Vec<RealityBend>,
     pub coherence_maintenance: f64,
     pub feasibility_impact: f64,
+    /// What the impossibility detectors found, strongest first
+    #[serde(default)]
+    pub impossibilities: Vec<ImpossibilityFinding>,
+    /// A suggested way through each detected paradox
+    #[serde(default)]
+    pub paradox_resolutions: Vec<ParadoxResolution>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Per-constraint verdicts on an idea, so feasibility can be audited
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FeasibilityReport {
+    pub checks: Vec<ConstraintCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConstraintCheck {
+    pub constraint: String,
+    pub status: ConstraintStatus,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum ConstraintStatus {
+    Satisfied,
+    Violated,
+    Unclear,
+}
+
+chops_core::impl_enum_str!(ConstraintStatus {
+    Satisfied => "pass",
+    Violated => "fail",
+    Unclear => "unclear",
+});
+
+impl FeasibilityReport {
+    pub fn count(&self, status: ConstraintStatus) -> usize {
+        self.checks.iter().filter(|check| check.status == status).count()
+    }
+
+    pub fn all_satisfied(&self) -> bool {
+        self.count(ConstraintStatus::Satisfied) == self.checks.len()
+    }
+}
+
+/// The paradoxes hiding in a set of constraints, each paired with a way through
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ParadoxReport {
+    pub constraints: Vec<String>,
+    pub entries: Vec<ParadoxReportEntry>,
+    /// Impossibilities that aren't paradoxes, such as unbounded resources
+    #[serde(default)]
+    pub other_findings: Vec<ImpossibilityFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ParadoxReportEntry {
+    pub finding: ImpossibilityFinding,
+    pub resolution: ParadoxResolution,
+    /// Why the strategy fits these constraints; the resolver's suggestion
+    /// when the model gave no explanation
+    pub explanation: String,
+}
+
+impl Renderable for ParadoxReport {
+    fn title(&self) -> String {
+        format!("Paradox report ({} paradoxes)", self.entries.len())
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n## Constraints\n\n", self.title());
+        for constraint in &self.constraints {
+            out.push_str(&format!("- {}\n", constraint));
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            let resolution = &entry.resolution;
+            out.push_str(&format!("\n## {}. {} paradox\n\n", i + 1, resolution.paradox_type));
+            out.push_str(&format!("{} (\"{}\", {:.0}%)\n\n", entry.finding.description, entry.finding.evidence, entry.finding.score * 100.0));
+            out.push_str(&format!("**Strategy: {}**\n\n{}\n", resolution.strategy, entry.explanation));
+            if !resolution.alternatives.is_empty() {
+                let alternatives: Vec<String> = resolution.alternatives.iter().map(ToString::to_string).collect();
+                out.push_str(&format!("\nAlso worth trying: {}\n", alternatives.join(", ")));
+            }
+        }
+        if !self.other_findings.is_empty() {
+            out.push_str("\n## Other limits\n\n");
+            for finding in &self.other_findings {
+                out.push_str(&format!("- [{}] {}\n", finding.impossibility_type, finding.description));
+            }
+        }
+        out
+    }
+
+    fn render_json(&self) -> CHOPSResult<String> {
+        serde_json::to_string_pretty(self).map_err(CHOPSError::SerializationError)
+    }
+
+    fn render_yaml(&self) -> CHOPSResult<String> {
+        to_yaml(self)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RealityBend {
     pub bend_type: RealityBendType,
     pub description: String,
@@ -248,7 +602,7 @@ pub struct RealityBend {
     pub potential_breakthrough: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum RealityBendType {
     PhysicsViolation,
     LogicParadox,
@@ -259,6 +613,16 @@ pub enum RealityBendType {
     InformationTranscendence,
 }
 
+chops_core::impl_enum_str!(RealityBendType {
+    PhysicsViolation => "physics-violation",
+    LogicParadox => "logic-paradox",
+    TimeDistortion => "time-distortion",
+    CausalityLoop => "causality-loop",
+    DimensionalShift => "dimensional-shift",
+    ConsciousnessHack => "consciousness-hack",
+    InformationTranscendence => "information-transcendence",
+});
+
 impl GeneratedIdeaResponse {
     pub fn calculate_overall_score(&self) -> f64 {
         let weights = IdeaScoreWeights::default();
@@ -271,16 +635,7 @@ impl GeneratedIdeaResponse {
     }
     
     pub fn get_quality_tier(&self) -> QualityTier {
-        let overall_score = self.calculate_overall_score();
-        
-        match overall_score {
-            s if s >= 0.9 => QualityTier::Transcendent,
-            s if s >= 0.8 => QualityTier::Brilliant,
-            s if s >= 0.7 => QualityTier::Excellent,
-            s if s >= 0.6 => QualityTier::Good,
-            s if s >= 0.5 => QualityTier::Decent,
-            _ => QualityTier::NeedsWork,
-        }
+        QualityTier::from_score(self.calculate_overall_score())
     }
 }
 
@@ -316,6 +671,19 @@ pub enum QualityTier {
     NeedsWork,
 }
 
+impl QualityTier {
+    pub fn from_score(overall_score: f64) -> Self {
+        match overall_score {
+            s if s >= 0.9 => QualityTier::Transcendent,
+            s if s >= 0.8 => QualityTier::Brilliant,
+            s if s >= 0.7 => QualityTier::Excellent,
+            s if s >= 0.6 => QualityTier::Good,
+            s if s >= 0.5 => QualityTier::Decent,
+            _ => QualityTier::NeedsWork,
+        }
+    }
+}
+
 impl std::fmt::Display for QualityTier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -350,6 +718,76 @@ impl DebateResult {
     }
 }
 
+impl ConversationSession {
+    pub fn new(persona: PersonaType, chaos_level: u8, domain: String) -> Self {
+        let now = Utc::now();
+        Self {
+            session_id: Uuid::new_v4(),
+            persona,
+            chaos_level,
+            domain,
+            messages: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn add_message(&mut self, role: MessageRole, content: String) {
+        self.messages.push(ClaudeMessage { role, content });
+        self.updated_at = Utc::now();
+    }
+
+    /// The last `max_messages` messages, trimmed so they open on a user turn
+    pub fn recent_messages(&self, max_messages: usize) -> Vec<ClaudeMessage> {
+        let mut start = self.messages.len().saturating_sub(max_messages);
+        while self.messages.get(start).is_some_and(|message| !matches!(message.role, MessageRole::User)) {
+            start += 1;
+        }
+        self.messages[start..].to_vec()
+    }
+
+    /// Number of completed user/assistant exchanges
+    pub fn turn_count(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|message| matches!(message.role, MessageRole::Assistant))
+            .count()
+    }
+
+    #[tracing::instrument(name = "conversation_load", level = "debug")]
+    pub fn load_from_file(path: &std::path::Path) -> CHOPSResult<Self> {
+        tracing::debug!("Loading conversation session from: {}", path.display());
+
+        let content = std::fs::read_to_string(path)
+            .map_err(CHOPSError::FileSystemError)?;
+
+        let session: ConversationSession = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to load conversation: {}", e)))?;
+
+        tracing::debug!("Loaded conversation {} with {} messages", session.session_id, session.messages.len());
+        Ok(session)
+    }
+
+    #[tracing::instrument(name = "conversation_save", level = "debug", skip(self))]
+    pub fn save_to_file(&self, path: &std::path::Path) -> CHOPSResult<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .map_err(CHOPSError::FileSystemError)?;
+            }
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize conversation: {}", e)))?;
+
+        std::fs::write(path, content)
+            .map_err(CHOPSError::FileSystemError)?;
+
+        tracing::debug!("Saved conversation {} to: {}", self.session_id, path.display());
+        Ok(())
+    }
+}
+
 impl ProphecyResponse {
     pub fn get_confidence_tier(&self) -> ConfidenceTier {
         match self.confidence_level {
@@ -363,11 +801,7 @@ impl ProphecyResponse {
     
     pub fn years_ahead(&self) -> u32 {
         let current_year = chrono::Utc::now().year() as u32;
-        if self.target_year > current_year {
-            self.target_year - current_year
-        } else {
-            0
-        }
+        self.target_year.saturating_sub(current_year)
     }
 }
 
@@ -390,4 +824,42 @@ impl std::fmt::Display for ConfidenceTier {
             ConfidenceTier::Speculative => write!(f, "🔮 Speculative"),
         }
     }
-}
\ No newline at end of file
+}
+
+/// One model's attempt at a prompt in `compare-models`
+#[derive(Debug)]
+pub struct ModelRun {
+    pub model: String,
+    /// Wall time for the whole generation, retries included
+    pub latency: std::time::Duration,
+    pub result: CHOPSResult<GeneratedIdeaResponse>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contribution_type_per_mode() {
+        use ContributionType::*;
+        let expected = [
+            (CollaborationMode::Debate, [Argument, CounterPoint, CounterPoint]),
+            (CollaborationMode::Brainstorm, [Insight, Insight, Insight]),
+            (CollaborationMode::Consensus, [Argument, BuildingOn, BuildingOn]),
+            (CollaborationMode::DevilsAdvocate, [Argument, BuildingOn, BuildingOn]),
+            (CollaborationMode::Synthesis, [Insight, Synthesis, Synthesis]),
+        ];
+        for (mode, types) in expected {
+            let actual: Vec<ContributionType> = (1..=3).map(|round| mode.contribution_type(round, false)).collect();
+            assert_eq!(actual, types, "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_only_the_devils_advocate_challenges() {
+        for round in 1..=3 {
+            assert_eq!(CollaborationMode::DevilsAdvocate.contribution_type(round, true), ContributionType::Challenge);
+            assert_eq!(CollaborationMode::Debate.contribution_type(round, true), CollaborationMode::Debate.contribution_type(round, false));
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::client::{Usage, UsageStats};
+use crate::provider::MOCK_MODEL;
+
+/// USD per million tokens for a model family
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+const PER_MILLION: f64 = 1_000_000.0;
+
+impl ModelPricing {
+    /// List prices by model family; unknown models are priced as Sonnet and
+    /// the offline mock is free
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_lowercase();
+        if model == MOCK_MODEL {
+            Self { input: 0.0, output: 0.0, cache_write: 0.0, cache_read: 0.0 }
+        } else if model.contains("opus") {
+            Self { input: 15.0, output: 75.0, cache_write: 18.75, cache_read: 1.50 }
+        } else if model.contains("3-5-haiku") {
+            Self { input: 0.80, output: 4.0, cache_write: 1.0, cache_read: 0.08 }
+        } else if model.contains("haiku") {
+            Self { input: 0.25, output: 1.25, cache_write: 0.30, cache_read: 0.03 }
+        } else if model.contains("gpt-4o-mini") {
+            Self { input: 0.15, output: 0.60, cache_write: 0.15, cache_read: 0.075 }
+        } else if model.contains("gpt-4o") {
+            Self { input: 2.50, output: 10.0, cache_write: 2.50, cache_read: 1.25 }
+        } else {
+            Self { input: 3.0, output: 15.0, cache_write: 3.75, cache_read: 0.30 }
+        }
+    }
+
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens.unwrap_or(0) as f64 * self.cache_write
+            + usage.cache_read_input_tokens.unwrap_or(0) as f64 * self.cache_read)
+            / PER_MILLION
+    }
+
+    pub fn stats_cost(&self, stats: &UsageStats) -> f64 {
+        (stats.input_tokens as f64 * self.input
+            + stats.output_tokens as f64 * self.output
+            + stats.cache_creation_input_tokens as f64 * self.cache_write
+            + stats.cache_read_input_tokens as f64 * self.cache_read)
+            / PER_MILLION
+    }
+}
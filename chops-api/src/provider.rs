@@ -0,0 +1,469 @@
+use chops_core::{CHOPSError, CHOPSResult, LlmProvider, MAX_STOP_SEQUENCES};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, warn};
+use crate::client::{messages_request, status_error};
+use crate::stream::take_utf8;
+use crate::{ClaudeMessage, ClaudeResponse, ContentBlock, ContextBudget, MessageRole, Usage};
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com";
+pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o";
+/// Model name the mock client reports; priced at zero
+pub const MOCK_MODEL: &str = "chops-mock";
+/// Most stop sequences the chat completions API accepts
+const OPENAI_MAX_STOP_SEQUENCES: usize = 4;
+
+/// One completion request, independent of any provider's wire format
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub model: String,
+    pub system: Option<String>,
+    /// Earlier turns of the conversation, oldest first, sent ahead of `prompt`
+    pub history: Vec<ClaudeMessage>,
+    pub prompt: String,
+    pub max_tokens: u32,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub top_k: Option<u32>,
+    pub stop_sequences: Vec<String>,
+    /// Mark the system prompt cacheable, where the provider supports that
+    pub prompt_caching: bool,
+    /// Keep a streamed response that was cut off (flagged truncated) instead of failing it
+    pub allow_partial: bool,
+    pub timeout: Duration,
+}
+
+impl LlmRequest {
+    pub fn new(model: &str, prompt: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            system: None,
+            history: Vec::new(),
+            prompt: prompt.to_string(),
+            max_tokens: 4096,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: Vec::new(),
+            prompt_caching: false,
+            allow_partial: false,
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+/// A chat model provider. Every provider answers in the Messages API shape
+/// ([`ClaudeResponse`]), so the persona and chaos pipeline built on top of it
+/// doesn't care which one it talks to.
+pub trait LlmClient: std::fmt::Debug + Send + Sync {
+    fn provider(&self) -> LlmProvider;
+
+    fn model(&self) -> &str;
+
+    /// Most stop sequences one request may carry
+    fn max_stop_sequences(&self) -> usize {
+        MAX_STOP_SEQUENCES
+    }
+
+    /// The body sent for `request`, which the size guard measures
+    fn request_body(&self, request: &LlmRequest, stream: bool) -> CHOPSResult<Vec<u8>> {
+        Ok(serde_json::to_vec(&messages_request(request, stream))?)
+    }
+
+    /// Sends one request; with `tokens`, text is forwarded as it arrives
+    fn complete<'a>(&'a self, request: &'a LlmRequest, tokens: Option<&'a mpsc::Sender<String>>) -> BoxFuture<'a, CHOPSResult<ClaudeResponse>>;
+
+    /// The reply to a bare prompt
+    fn generate<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, CHOPSResult<String>> {
+        Box::pin(async move {
+            let request = LlmRequest::new(self.model(), prompt);
+            self.complete(&request, None).await?.text()
+        })
+    }
+}
+
+/// Client for the OpenAI chat completions API
+#[derive(Debug, Clone)]
+pub struct OpenAiClient {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletion {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    model: String,
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    /// Set on complete responses
+    message: Option<ChatContent>,
+    /// Set on streamed chunks
+    delta: Option<ChatContent>,
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatContent {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u32,
+}
+
+impl OpenAiClient {
+    #[tracing::instrument(name = "openai_client_new", level = "info", skip(api_key))]
+    pub fn new(api_key: String) -> CHOPSResult<Self> {
+        tracing::info!("Creating new OpenAI client");
+
+        if !api_key.starts_with("sk-") {
+            tracing::error!("Invalid OpenAI API key format - must start with 'sk-'");
+            return Err(CHOPSError::AuthenticationError(
+                "Invalid OpenAI API key format. Must start with 'sk-'".to_string()
+            ));
+        }
+
+        Self::with_base_url(api_key, OPENAI_BASE_URL.to_string())
+    }
+
+    /// Client for any endpoint that speaks the chat completions API
+    pub fn with_base_url(api_key: String, base_url: String) -> CHOPSResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(CHOPSError::NetworkError)?;
+
+        tracing::info!("OpenAI client created successfully with model: {}", DEFAULT_OPENAI_MODEL);
+
+        Ok(Self {
+            client,
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: DEFAULT_OPENAI_MODEL.to_string(),
+        })
+    }
+
+    pub fn set_model(&mut self, model: String) {
+        self.model = model;
+    }
+
+    /// `request` in the chat completions wire format
+    fn chat_request(request: &LlmRequest, stream: bool) -> CHOPSResult<ChatCompletionRequest> {
+        if request.stop_sequences.len() > OPENAI_MAX_STOP_SEQUENCES {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "{} stop sequences configured for this request, OpenAI allows at most {}",
+                request.stop_sequences.len(), OPENAI_MAX_STOP_SEQUENCES
+            )));
+        }
+
+        let mut messages = Vec::new();
+        if let Some(system) = &request.system {
+            messages.push(ChatMessage { role: "system", content: system.clone() });
+        }
+        messages.extend(request.history.iter().map(|message| ChatMessage { role: chat_role(&message.role), content: message.content.clone() }));
+        messages.push(ChatMessage { role: chat_role(&MessageRole::User), content: request.prompt.clone() });
+
+        Ok(ChatCompletionRequest {
+            model: request.model.clone(),
+            messages,
+            max_tokens: request.max_tokens,
+            temperature: request.temperature,
+            top_p: request.top_p,
+            stop: request.stop_sequences.clone(),
+            stream,
+            stream_options: stream.then_some(StreamOptions { include_usage: true }),
+        })
+    }
+
+    async fn send(&self, request: &LlmRequest, tokens: Option<&mpsc::Sender<String>>) -> CHOPSResult<ClaudeResponse> {
+        if request.top_k.is_some() {
+            debug!("OpenAI has no top_k sampling, ignoring it");
+        }
+        let body = Self::chat_request(request, tokens.is_some())?;
+
+        debug!("Making OpenAI API request to {}", self.base_url);
+
+        let response = self.client
+            .post(format!("{}/v1/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .timeout(request.timeout)
+            .send()
+            .await
+            .map_err(CHOPSError::NetworkError)?;
+
+        if !response.status().is_success() {
+            let error = status_error(response).await;
+            error!("OpenAI API error: {:?}", error);
+            return Err(error);
+        }
+
+        match tokens {
+            Some(tokens) => read_chat_stream(response, tokens).await,
+            None => {
+                let completion: ChatCompletion = response.json().await.map_err(CHOPSError::NetworkError)?;
+                debug!("Successfully received OpenAI response");
+                let choice = completion.choices.into_iter().next()
+                    .ok_or_else(|| CHOPSError::ApiError("No choices in response".to_string()))?;
+                let text = choice.message.and_then(|message| message.content).unwrap_or_default();
+                Ok(messages_response(completion.id, completion.model, text, choice.finish_reason, completion.usage))
+            },
+        }
+    }
+}
+
+impl LlmClient for OpenAiClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::OpenAi
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_stop_sequences(&self) -> usize {
+        OPENAI_MAX_STOP_SEQUENCES
+    }
+
+    fn request_body(&self, request: &LlmRequest, stream: bool) -> CHOPSResult<Vec<u8>> {
+        Ok(serde_json::to_vec(&Self::chat_request(request, stream)?)?)
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest, tokens: Option<&'a mpsc::Sender<String>>) -> BoxFuture<'a, CHOPSResult<ClaudeResponse>> {
+        Box::pin(self.send(request, tokens))
+    }
+}
+
+/// Answers every request with the same canned idea and never touches the
+/// network, so `summon`, `mutate` and `prophecy` run end to end offline.
+/// The reply names the prompt's length, so scoring still sees a prompt-shaped
+/// input, and is otherwise fixed, so runs are reproducible. Mutation prompts
+/// get their file back unchanged, with no mutations listed.
+#[derive(Debug, Clone, Default)]
+pub struct MockLlmClient;
+
+impl MockLlmClient {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The canned completion for `request`
+    pub fn completion_text(request: &LlmRequest) -> String {
+        if let Some(code) = mutation_target(&request.prompt) {
+            return format!("```\n{}\n```\n\nMUTATIONS:\n", code);
+        }
+        let prompt_chars = request.prompt.chars().count()
+            + request.system.as_deref().map_or(0, |system| system.chars().count());
+        format!(
+            "Offline Rehearsal: Deterministic Replay for the Idea Pipeline\n\n\
+            **Core Concept**\n\
+            This is a canned offline completion for a {} character prompt; no network call was made. \
+            It describes a hybrid system that would combine an event-sourced framework with an adaptive \
+            caching algorithm. What if every request could be recorded once and replayed against a novel scheduler?\n\n\
+            **Implementation Roadmap**\n\
+            1. Define the API and the database schema for recorded requests.\n\
+            2. Build the replay tool and the scheduling algorithm as a library.\n\
+            3. Add a testing process that checks each replay matches its recording.\n\n\
+            **Risks**\n\
+            - Recordings drift from production traffic over time.\n\
+            - Replays can hide timing-dependent bugs.\n\n\
+            **Why It Matters**\n\
+            Consider the alternative: paying for API calls just to watch the pipeline run. \
+            A deterministic stand-in makes the whole system testable end to end.",
+            prompt_chars,
+        )
+    }
+
+    async fn send(&self, request: &LlmRequest, tokens: Option<&mpsc::Sender<String>>) -> CHOPSResult<ClaudeResponse> {
+        let text = Self::completion_text(request);
+        debug!("Answering a {} character prompt with the canned offline completion", request.prompt.len());
+        if let Some(tokens) = tokens {
+            for word in text.split_inclusive(' ') {
+                if tokens.send(word.to_string()).await.is_err() {
+                    break;
+                }
+            }
+        }
+        let input_tokens = ContextBudget::estimate_tokens(&request.prompt)
+            + request.system.as_deref().map_or(0, ContextBudget::estimate_tokens);
+        let usage = Usage {
+            input_tokens: input_tokens as u32,
+            output_tokens: ContextBudget::estimate_tokens(&text) as u32,
+            cache_creation_input_tokens: None,
+            cache_read_input_tokens: None,
+        };
+        Ok(ClaudeResponse {
+            id: "msg_offline".to_string(),
+            model: MOCK_MODEL.to_string(),
+            role: MessageRole::Assistant,
+            content: vec![ContentBlock {
+                content_type: "text".to_string(),
+                text,
+                extra: HashMap::new(),
+            }],
+            stop_reason: Some("end_turn".to_string()),
+            stop_sequence: None,
+            usage: Some(usage),
+            truncated: false,
+        })
+    }
+}
+
+impl LlmClient for MockLlmClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::Mock
+    }
+
+    fn model(&self) -> &str {
+        MOCK_MODEL
+    }
+
+    fn complete<'a>(&'a self, request: &'a LlmRequest, tokens: Option<&'a mpsc::Sender<String>>) -> BoxFuture<'a, CHOPSResult<ClaudeResponse>> {
+        Box::pin(self.send(request, tokens))
+    }
+}
+
+/// The file a mutation prompt asks to mutate: the fenced block of a prompt
+/// that wants a `MUTATIONS:` list back
+fn mutation_target(prompt: &str) -> Option<&str> {
+    if !prompt.contains("MUTATIONS:") {
+        return None;
+    }
+    let (fenced, _) = prompt.rsplit_once("```")?;
+    let (_, code) = fenced.rsplit_once("```")?;
+    // The prompt template indents the fence lines
+    Some(code.strip_prefix('\n')?.trim_start_matches(' ').trim_end())
+}
+
+fn chat_role(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+    }
+}
+
+/// Reads a streamed chat completion, forwarding each content delta to `tokens`.
+/// A stream that ends without `[DONE]` was cut off and is an error.
+async fn read_chat_stream(mut response: reqwest::Response, tokens: &mpsc::Sender<String>) -> CHOPSResult<ClaudeResponse> {
+    let mut buffer = String::new();
+    let mut pending: Vec<u8> = Vec::new();
+    let (mut id, mut model, mut text) = (String::new(), String::new(), String::new());
+    let mut finish_reason = None;
+    let mut usage = None;
+    let mut done = false;
+
+    while let Some(chunk) = response.chunk().await.map_err(CHOPSError::NetworkError)? {
+        pending.extend_from_slice(&chunk);
+        buffer.push_str(&take_utf8(&mut pending)?.replace("\r\n", "\n"));
+
+        while let Some(end) = buffer.find("\n\n") {
+            let event: String = buffer.drain(..end + 2).collect();
+            for data in event.lines().filter_map(|line| line.strip_prefix("data:")).map(str::trim) {
+                if data == "[DONE]" {
+                    done = true;
+                    continue;
+                }
+                let chunk: ChatCompletion = serde_json::from_str(data)?;
+                id = chunk.id;
+                model = chunk.model;
+                usage = chunk.usage.or(usage);
+                let Some(choice) = chunk.choices.into_iter().next() else {
+                    continue;
+                };
+                finish_reason = choice.finish_reason.or(finish_reason);
+                if let Some(delta) = choice.delta.and_then(|delta| delta.content).filter(|delta| !delta.is_empty()) {
+                    text.push_str(&delta);
+                    if tokens.send(delta).await.is_err() {
+                        debug!("Token stream receiver dropped, assembling silently");
+                    }
+                }
+            }
+        }
+    }
+
+    if !done {
+        return Err(CHOPSError::ApiError("Stream ended before [DONE]".to_string()));
+    }
+    debug!("Successfully assembled streamed OpenAI response");
+    Ok(messages_response(id, model, text, finish_reason, usage))
+}
+
+/// A chat completion in the Messages API shape the pipeline works with
+fn messages_response(id: String, model: String, text: String, finish_reason: Option<String>, usage: Option<ChatUsage>) -> ClaudeResponse {
+    let stop_reason = finish_reason.map(|reason| match reason.as_str() {
+        "stop" => "end_turn".to_string(),
+        "length" => "max_tokens".to_string(),
+        _ => reason,
+    });
+    ClaudeResponse {
+        id,
+        model,
+        role: MessageRole::Assistant,
+        content: vec![ContentBlock {
+            content_type: "text".to_string(),
+            text,
+            extra: HashMap::new(),
+        }],
+        stop_reason,
+        stop_sequence: None,
+        // Cached prompt tokens are part of prompt_tokens on OpenAI
+        usage: usage.map(|usage| {
+            let cached = usage.prompt_tokens_details.map(|details| details.cached_tokens).unwrap_or(0);
+            Usage {
+                input_tokens: usage.prompt_tokens.saturating_sub(cached),
+                output_tokens: usage.completion_tokens,
+                cache_creation_input_tokens: None,
+                cache_read_input_tokens: (cached > 0).then_some(cached),
+            }
+        }),
+        truncated: false,
+    }
+}
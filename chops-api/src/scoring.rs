@@ -0,0 +1,156 @@
+use chops_core::PersonaType;
+
+/// Rates generated ideas with keyword heuristics. The same for every
+/// provider; [`crate::ScoreCalibration`] adjusts creativity and feasibility
+/// toward the user's own judgement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicScorer;
+
+impl HeuristicScorer {
+    /// Creativity of an idea, before calibration, given the chaos level
+    /// (0.0-1.0) it was generated with and how many unexpected elements it got
+    pub fn creativity_score(&self, content: &str, chaos_applied: f64, unexpected_elements: usize) -> f64 {
+        let mut score = 0.5; // Base score
+
+        // Length and complexity
+        let word_count = content.split_whitespace().count();
+        if word_count > 100 {
+            score += 0.1;
+        }
+
+        // Chaos influence
+        score += chaos_applied * 0.3;
+
+        // Keyword analysis for creative indicators
+        let creative_keywords = [
+            "innovative", "revolutionary", "breakthrough", "novel", "unprecedented",
+            "paradigm", "transform", "reimagine", "disrupt", "evolve"
+        ];
+
+        let creative_count = creative_keywords.iter()
+            .map(|&keyword| {
+                content.to_lowercase().matches(keyword).count()
+            })
+            .sum::<usize>();
+
+        score += (creative_count as f64 * 0.05).min(0.2);
+
+        // Unexpected elements influence
+        score += unexpected_elements as f64 * 0.02;
+
+        score.min(1.0)
+    }
+
+    /// Feasibility of an idea, before calibration
+    pub fn feasibility_score(&self, content: &str) -> f64 {
+        let mut score = 0.7; // Start optimistic
+
+        // Check for impossible/fantasy elements
+        let impossible_keywords = [
+            "magic", "impossible", "violate physics", "time travel", "telepathy",
+            "infinite", "zero cost", "perpetual motion"
+        ];
+
+        let impossible_count = impossible_keywords.iter()
+            .map(|&keyword| {
+                content.to_lowercase().matches(keyword).count()
+            })
+            .sum::<usize>();
+
+        score -= impossible_count as f64 * 0.1;
+
+        // Check for technical feasibility indicators
+        let feasible_keywords = [
+            "implementation", "algorithm", "database", "api", "framework",
+            "library", "tool", "method", "process", "system"
+        ];
+
+        let feasible_count = feasible_keywords.iter()
+            .map(|&keyword| {
+                content.to_lowercase().matches(keyword).count()
+            })
+            .sum::<usize>();
+
+        score += feasible_count as f64 * 0.02;
+
+        score.clamp(0.0, 1.0)
+    }
+
+    pub fn novelty_score(&self, content: &str) -> f64 {
+        let mut score = 0.5;
+
+        // Check for novel combinations
+        let combination_indicators = ["combine", "merge", "blend", "fusion", "hybrid"];
+        let combination_count = combination_indicators.iter()
+            .map(|&indicator| {
+                content.to_lowercase().matches(indicator).count()
+            })
+            .sum::<usize>();
+
+        score += combination_count as f64 * 0.05;
+
+        // Check for unique perspective indicators
+        let perspective_indicators = ["what if", "imagine", "consider", "alternatively"];
+        let perspective_count = perspective_indicators.iter()
+            .map(|&indicator| {
+                content.to_lowercase().matches(indicator).count()
+            })
+            .sum::<usize>();
+
+        score += perspective_count as f64 * 0.03;
+
+        score.min(1.0)
+    }
+
+    /// How exciting an idea reads in the given persona's voice
+    pub fn excitement_score(&self, content: &str, persona_type: &PersonaType) -> f64 {
+        let mut score = 0.5;
+
+        // Exclamation marks and emotional language
+        let exclamation_count = content.matches('!').count();
+        score += (exclamation_count as f64 * 0.02).min(0.1);
+
+        // Persona-specific excitement indicators
+        match persona_type {
+            PersonaType::MadScientist => {
+                let mad_scientist_excitement = ["breakthrough", "impossible", "revolutionary"];
+                let count = mad_scientist_excitement.iter()
+                    .map(|&word| content.to_lowercase().matches(word).count())
+                    .sum::<usize>();
+                score += count as f64 * 0.05;
+            },
+            PersonaType::ChaosEngineer => {
+                let chaos_excitement = ["chaos", "destruction", "antifragile", "emergence"];
+                let count = chaos_excitement.iter()
+                    .map(|&word| content.to_lowercase().matches(word).count())
+                    .sum::<usize>();
+                score += count as f64 * 0.05;
+            },
+            _ => {
+                // Default excitement assessment
+            }
+        }
+
+        score.min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impossible_ideas_score_less_feasible() {
+        let grounded = HeuristicScorer.feasibility_score("an api and a database behind a caching library");
+        let fantasy = HeuristicScorer.feasibility_score("perpetual motion via time travel and magic");
+        assert!(grounded > 0.7 && fantasy < 0.7, "{} {}", grounded, fantasy);
+    }
+
+    #[test]
+    fn test_excitement_follows_the_personas_own_words() {
+        let content = "chaos breeds emergence";
+        let engineer = HeuristicScorer.excitement_score(content, &PersonaType::ChaosEngineer);
+        let zen = HeuristicScorer.excitement_score(content, &PersonaType::ZenMaster);
+        assert!(engineer > zen, "{} {}", engineer, zen);
+    }
+}
@@ -0,0 +1,112 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Heading given to text that comes before any heading, or to a whole idea
+/// written without headings
+pub const UNTITLED_SECTION: &str = "Body";
+
+/// Longest line, in words, still taken for a bold or numbered heading
+const MAX_HEADING_WORDS: usize = 10;
+
+/// One headed part of a generated idea, e.g. "Implementation Roadmap"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IdeaSection {
+    pub heading: String,
+    pub body: String,
+}
+
+/// Splits generated text at its headings: markdown `#` headings, whole-line
+/// bold titles (`**Risks**`), and numbered titles that are bold or end in a
+/// colon (`2. **Core Concept**`, `3) Risks:`). Plain numbered list items and
+/// anything inside code fences stay in the body. Text before the first
+/// heading, or all of it when there are none, goes in a "Body" section.
+pub fn split_sections(content: &str) -> Vec<IdeaSection> {
+    let mut sections = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut body: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let next = if in_fence { None } else { section_heading(trimmed) };
+        match next {
+            Some(next) => {
+                push_section(&mut sections, heading.take(), &body);
+                heading = Some(next);
+                body.clear();
+            },
+            None => body.push(line),
+        }
+    }
+    push_section(&mut sections, heading, &body);
+
+    if sections.is_empty() {
+        sections.push(IdeaSection { heading: UNTITLED_SECTION.to_string(), body: String::new() });
+    }
+    sections
+}
+
+fn push_section(sections: &mut Vec<IdeaSection>, heading: Option<String>, body: &[&str]) {
+    let body = body.join("\n").trim_matches('\n').trim_end().to_string();
+    match heading {
+        Some(heading) => sections.push(IdeaSection { heading, body }),
+        // Blank lines before the first heading aren't a section
+        None if body.trim().is_empty() => {},
+        None => sections.push(IdeaSection { heading: UNTITLED_SECTION.to_string(), body }),
+    }
+}
+
+/// The heading text if `line` is a section heading
+fn section_heading(line: &str) -> Option<String> {
+    if let Some(rest) = line.strip_prefix('#') {
+        let text = rest.trim_start_matches('#');
+        let level = line.len() - text.len();
+        // `#tag` and `#######` aren't headings
+        if level > 6 || !(text.is_empty() || text.starts_with(char::is_whitespace)) {
+            return None;
+        }
+        let text = text.trim().trim_end_matches('#').trim();
+        return (!text.is_empty()).then(|| clean_heading(text));
+    }
+
+    let (numbered, title) = match strip_number(line) {
+        Some(title) => (true, title),
+        None => (false, line),
+    };
+    let bold = unwrap_emphasis(title);
+    let colon = numbered && title.ends_with(':') && !title.starts_with(['-', '*']);
+    if (bold.is_none() && !colon) || title.split_whitespace().count() > MAX_HEADING_WORDS {
+        return None;
+    }
+    let text = clean_heading(bold.unwrap_or(title));
+    (!text.is_empty()).then_some(text)
+}
+
+/// `title` out of `1. title` or `12) title`
+fn strip_number(line: &str) -> Option<&str> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 2 {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?;
+    rest.starts_with(' ').then(|| rest.trim())
+}
+
+/// The text inside `**text**` or `__text__` when they wrap the whole line,
+/// allowing a colon just outside the markers
+fn unwrap_emphasis(text: &str) -> Option<&str> {
+    let text = text.strip_suffix(':').unwrap_or(text);
+    ["**", "__"].iter().find_map(|marker| {
+        let inner = text.strip_prefix(marker)?.strip_suffix(marker)?;
+        (!inner.is_empty() && !inner.contains(marker)).then_some(inner)
+    })
+}
+
+fn clean_heading(text: &str) -> String {
+    let text = unwrap_emphasis(text).unwrap_or(text);
+    let text = strip_number(text).unwrap_or(text);
+    text.trim().trim_end_matches(':').trim().to_string()
+}
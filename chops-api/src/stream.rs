@@ -0,0 +1,261 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use std::collections::HashMap;
+use tracing::{debug, warn};
+use crate::{ClaudeResponse, ContentBlock, ContextBudget, MessageRole, Usage};
+
+/// Folds the Messages API's server-sent events back into a [`ClaudeResponse`].
+/// Feed it raw chunks as they arrive; events may be split across chunks.
+#[derive(Debug, Default)]
+pub struct StreamAssembler {
+    buffer: String,
+    id: String,
+    model: String,
+    blocks: Vec<ContentBlock>,
+    stop_reason: Option<String>,
+    stop_sequence: Option<String>,
+    usage: Option<Usage>,
+    /// Whether `message_delta` reported the final output token count
+    output_counted: bool,
+    /// Set by `message_stop`; a stream that ends without it was cut off
+    complete: bool,
+}
+
+/// A response assembled from a stream, possibly cut off before `message_stop`
+#[derive(Debug, Clone)]
+pub struct StreamedResponse {
+    pub response: ClaudeResponse,
+    pub truncated: bool,
+}
+
+impl StreamAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consumes a chunk of the event stream and returns the text deltas it
+    /// completed, in order. An `error` event from the API becomes an error.
+    pub fn push(&mut self, chunk: &str) -> CHOPSResult<Vec<String>> {
+        self.buffer.push_str(&chunk.replace("\r\n", "\n"));
+
+        let mut deltas = Vec::new();
+        while let Some(end) = self.buffer.find("\n\n") {
+            let event: String = self.buffer.drain(..end + 2).collect();
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(str::trim_start)
+                .collect();
+            if data.is_empty() {
+                continue;
+            }
+            if let Some(text) = self.apply(&serde_json::from_str(&data.join("\n"))?)? {
+                deltas.push(text);
+            }
+        }
+        Ok(deltas)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn apply(&mut self, event: &serde_json::Value) -> CHOPSResult<Option<String>> {
+        let event_type = event["type"].as_str().unwrap_or_default();
+        match event_type {
+            "message_start" => {
+                let message = &event["message"];
+                self.id = message["id"].as_str().unwrap_or_default().to_string();
+                self.model = message["model"].as_str().unwrap_or_default().to_string();
+                self.usage = serde_json::from_value(message["usage"].clone()).ok();
+            },
+            "content_block_start" => {
+                let block: ContentBlock = serde_json::from_value(event["content_block"].clone())?;
+                self.blocks.push(block);
+            },
+            "content_block_delta" => {
+                let Some(text) = event["delta"]["text"].as_str() else {
+                    return Ok(None);
+                };
+                match self.blocks.last_mut() {
+                    Some(block) => block.text.push_str(text),
+                    None => self.blocks.push(ContentBlock {
+                        content_type: "text".to_string(),
+                        text: text.to_string(),
+                        extra: HashMap::new(),
+                    }),
+                }
+                return Ok(Some(text.to_string()));
+            },
+            "message_delta" => {
+                self.stop_reason = event["delta"]["stop_reason"].as_str().map(str::to_string);
+                self.stop_sequence = event["delta"]["stop_sequence"].as_str().map(str::to_string);
+                if let Some(output_tokens) = event["usage"]["output_tokens"].as_u64() {
+                    let usage = self.usage.get_or_insert_with(empty_usage);
+                    usage.output_tokens = output_tokens as u32;
+                    self.output_counted = true;
+                }
+            },
+            "message_stop" => self.complete = true,
+            "error" => {
+                return Err(CHOPSError::ApiError(format!(
+                    "Stream error: {}",
+                    event["error"]["message"].as_str().unwrap_or("unknown error")
+                )));
+            },
+            // ping, content_block_stop and anything newer carry nothing we keep
+            other => debug!("Ignoring stream event '{}'", other),
+        }
+        Ok(None)
+    }
+
+    /// The response so far. Without `message_stop` it is flagged truncated and,
+    /// if the API never reported output tokens, they are estimated from the text
+    /// received so the partial still counts toward usage.
+    pub fn finish(self) -> StreamedResponse {
+        let truncated = !self.complete;
+        let mut usage = self.usage;
+        if truncated && !self.output_counted {
+            let received: String = self.blocks.iter().map(|block| block.text.as_str()).collect();
+            usage.get_or_insert_with(empty_usage).output_tokens = ContextBudget::estimate_tokens(&received) as u32;
+        }
+
+        StreamedResponse {
+            response: ClaudeResponse {
+                id: self.id,
+                model: self.model,
+                role: MessageRole::Assistant,
+                content: self.blocks,
+                stop_reason: self.stop_reason,
+                stop_sequence: self.stop_sequence,
+                usage,
+                truncated,
+            },
+            truncated,
+        }
+    }
+}
+
+impl StreamedResponse {
+    /// Applies the partial-response policy: a truncated stream is an error (so
+    /// the request is retried from scratch) unless partial content is allowed,
+    /// in which case it is kept with a warning
+    pub fn accept(self, allow_partial: bool) -> CHOPSResult<Self> {
+        if !self.truncated {
+            return Ok(self);
+        }
+        let tokens = self.response.usage.as_ref().map(|usage| usage.output_tokens).unwrap_or(0);
+        if !allow_partial {
+            return Err(CHOPSError::ApiError(format!(
+                "Stream ended before message_stop after ~{} output tokens", tokens
+            )));
+        }
+        warn!("Stream ended before message_stop; keeping ~{} tokens of partial content", tokens);
+        Ok(self)
+    }
+}
+
+/// Takes the complete UTF-8 text off the front of `pending`, leaving the bytes
+/// of a character split across chunks for the next one
+pub(crate) fn take_utf8(pending: &mut Vec<u8>) -> CHOPSResult<String> {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(text) => text.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(e) => return Err(CHOPSError::ApiError(format!("Stream is not valid UTF-8: {}", e))),
+    };
+    Ok(String::from_utf8(pending.drain(..valid).collect()).expect("validated above"))
+}
+
+fn empty_usage() -> Usage {
+    Usage {
+        input_tokens: 0,
+        output_tokens: 0,
+        cache_creation_input_tokens: None,
+        cache_read_input_tokens: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAM: &str = concat!(
+        "event: message_start\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-test\",\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Solar \"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"kites\"}}\n\n",
+    );
+    const ENDING: &str = concat!(
+        "event: message_delta\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"output_tokens\":7}}\n\n",
+        "event: message_stop\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+
+    fn assemble(chunks: &[&str]) -> (StreamAssembler, Vec<String>) {
+        let mut assembler = StreamAssembler::new();
+        let mut deltas = Vec::new();
+        for chunk in chunks {
+            deltas.extend(assembler.push(chunk).unwrap());
+        }
+        (assembler, deltas)
+    }
+
+    #[test]
+    fn test_events_split_across_chunks_assemble() {
+        let full = format!("{}{}", STREAM, ENDING);
+        let chunks: Vec<&str> = full.as_bytes().chunks(17).map(|chunk| std::str::from_utf8(chunk).unwrap()).collect();
+        let (assembler, deltas) = assemble(&chunks);
+
+        assert_eq!(deltas, vec!["Solar ", "kites"]);
+        assert!(assembler.is_complete());
+        let streamed = assembler.finish();
+        assert!(!streamed.truncated);
+        assert!(!streamed.response.truncated);
+        assert_eq!(streamed.response.content[0].text, "Solar kites");
+        assert_eq!(streamed.response.stop_reason.as_deref(), Some("end_turn"));
+        assert_eq!(streamed.response.usage.unwrap().output_tokens, 7);
+    }
+
+    #[test]
+    fn test_stream_cut_off_before_message_stop_is_truncated() {
+        let (assembler, _) = assemble(&[STREAM]);
+        assert!(!assembler.is_complete());
+
+        let streamed = assembler.finish();
+        assert!(streamed.truncated);
+        assert!(streamed.response.truncated);
+        // The API never reported output tokens, so they're estimated from the partial
+        let usage = streamed.response.usage.as_ref().unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, ContextBudget::estimate_tokens("Solar kites") as u32);
+
+        assert!(streamed.clone().accept(false).is_err());
+        let kept = streamed.accept(true).unwrap();
+        assert_eq!(kept.response.content[0].text, "Solar kites");
+    }
+
+    #[test]
+    fn test_error_event_is_an_error() {
+        let mut assembler = StreamAssembler::new();
+        let error = assembler
+            .push("event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n")
+            .unwrap_err();
+        assert!(error.to_string().contains("Overloaded"));
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_split_character_for_next_chunk() {
+        let bytes = "café".as_bytes();
+        let mut pending = bytes[..bytes.len() - 1].to_vec();
+        assert_eq!(take_utf8(&mut pending).unwrap(), "caf");
+        assert_eq!(pending.len(), 1);
+
+        pending.push(bytes[bytes.len() - 1]);
+        assert_eq!(take_utf8(&mut pending).unwrap(), "é");
+        assert!(pending.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use jsonschema::JSONSchema;
+use serde_json::Value;
+use std::path::Path;
+
+/// Re-prompts allowed after the first answer fails schema validation
+pub const DEFAULT_SCHEMA_RETRIES: u8 = 2;
+
+/// A user-provided JSON Schema that generated ideas must conform to
+#[derive(Debug)]
+pub struct OutputSchema {
+    /// Where the schema came from, for messages
+    pub source: String,
+    pub schema: Value,
+    validator: JSONSchema,
+}
+
+impl OutputSchema {
+    pub fn from_file(path: &Path) -> CHOPSResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            CHOPSError::InvalidParameter(format!("Cannot read output schema {}: {}", path.display(), e))
+        })?;
+        let schema: Value = serde_json::from_str(&content).map_err(|e| {
+            CHOPSError::InvalidParameter(format!("Output schema {} is not valid JSON: {}", path.display(), e))
+        })?;
+        Self::from_value(schema, &path.display().to_string())
+    }
+
+    pub fn from_value(schema: Value, source: &str) -> CHOPSResult<Self> {
+        let validator = JSONSchema::compile(&schema).map_err(|e| {
+            CHOPSError::InvalidParameter(format!("Output schema {} is not a valid JSON Schema: {}", source, e))
+        })?;
+        Ok(Self { source: source.to_string(), schema, validator })
+    }
+
+    /// Appended to the generation prompt so the model answers in JSON
+    pub fn prompt_instruction(&self) -> String {
+        format!(
+            "Respond with a single JSON value and nothing else: no prose and no markdown outside it. \
+            It must validate against this JSON Schema:\n```json\n{}\n```",
+            serde_json::to_string_pretty(&self.schema).unwrap_or_default()
+        )
+    }
+
+    /// The JSON value found in `text` if it conforms, otherwise every problem found
+    pub fn validate(&self, text: &str) -> Result<Value, Vec<String>> {
+        let Some(candidate) = extract_json(text) else {
+            return Err(vec!["no JSON value found in the response".to_string()]);
+        };
+        let value: Value = serde_json::from_str(candidate)
+            .map_err(|e| vec![format!("response is not valid JSON: {}", e)])?;
+
+        if let Err(errors) = self.validator.validate(&value) {
+            return Err(errors
+                .map(|error| {
+                    let path = error.instance_path.to_string();
+                    if path.is_empty() { error.to_string() } else { format!("{}: {}", path, error) }
+                })
+                .collect());
+        }
+        Ok(value)
+    }
+
+    /// Instruction for a re-prompt after validation failed
+    pub fn correction_note(errors: &[String]) -> String {
+        format!(
+            "Your previous answer did not match the required JSON Schema:\n- {}\nReply again with corrected JSON only.",
+            errors.join("\n- ")
+        )
+    }
+}
+
+/// The JSON in a response: a fenced ```json block if there is one, otherwise
+/// the span from the first opening brace or bracket to the last closing one
+fn extract_json(text: &str) -> Option<&str> {
+    if let Some(start) = text.find("```json") {
+        let body = &text[start + "```json".len()..];
+        if let Some(end) = body.find("```") {
+            return Some(body[..end].trim());
+        }
+    }
+
+    let start = text.find(['{', '['])?;
+    let closing = if text[start..].starts_with('{') { '}' } else { ']' };
+    let end = text.rfind(closing)?;
+    (end > start).then(|| &text[start..=end])
+}
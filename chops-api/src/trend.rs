@@ -0,0 +1,441 @@
+use serde::{Deserialize, Serialize};
+
+/// Fewest points worth analysing; shorter series give an empty report
+const MIN_SERIES_LEN: usize = 4;
+/// A richer growth model must beat a simpler one's R² by this much to be chosen
+const MODEL_PREFERENCE_MARGIN: f64 = 0.02;
+/// Weakest autocorrelation still reported as a cycle
+const MIN_CYCLE_CORRELATION: f64 = 0.3;
+/// Z-score past which a recent shift in level counts as a disruption
+const LEVEL_SHIFT_Z: f64 = 2.0;
+/// Ratio of recent to earlier spread that counts as a volatility spike
+const VOLATILITY_RATIO: f64 = 2.0;
+
+/// Watches one named series, e.g. how often a tag appears in a domain's ideas
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrendAnalyzer {
+    pub name: String,
+    pub series: Vec<f64>,
+}
+
+/// What [`TrendAnalyzer::analyze`] found in a series
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrendReport {
+    /// Strongest first
+    pub cycles: Vec<CyclicPattern>,
+    pub growth: Option<GrowthPattern>,
+    /// R² of the chosen growth model, 0.0-1.0
+    pub fit_quality: f64,
+    pub disruptions: Vec<DisruptionIndicator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CyclicPattern {
+    pub name: String,
+    /// Length of one cycle, in samples of the series
+    pub period: f64,
+    pub amplitude: f64,
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrowthPattern {
+    pub pattern_type: GrowthType,
+    /// Change per sample: the slope for linear fits, the relative rate otherwise
+    pub rate: f64,
+    /// Level a logistic fit levels off at
+    pub saturation_point: Option<f64>,
+    pub current_phase: GrowthPhase,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GrowthType {
+    Linear,
+    Exponential,
+    Logistic,
+    Cyclical,
+    Chaotic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GrowthPhase {
+    Inception,
+    Growth,
+    Maturity,
+    Decline,
+    Disruption,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisruptionIndicator {
+    pub indicator_name: String,
+    pub strength: f64,
+    pub time_horizon: String,
+    pub probability: f64,
+}
+
+/// A fitted growth curve
+struct Fit {
+    growth_type: GrowthType,
+    intercept: f64,
+    rate: f64,
+    saturation_point: Option<f64>,
+    r_squared: f64,
+}
+
+impl Fit {
+    fn predict(&self, t: f64) -> f64 {
+        match (&self.growth_type, self.saturation_point) {
+            (GrowthType::Exponential, _) => (self.intercept + self.rate * t).exp(),
+            (GrowthType::Logistic, Some(ceiling)) => ceiling / (1.0 + (-(self.intercept + self.rate * t)).exp()),
+            _ => self.intercept + self.rate * t,
+        }
+    }
+}
+
+impl TrendAnalyzer {
+    pub fn new(name: &str, series: Vec<f64>) -> Self {
+        Self { name: name.to_string(), series }
+    }
+
+    /// Finds cycles by autocorrelation of the detrended series, fits linear,
+    /// exponential and logistic growth (keeping the simplest good fit), and
+    /// flags recent level shifts, volatility spikes and trend reversals
+    #[tracing::instrument(name = "trend_analyze", level = "debug", skip(series), fields(len = series.len()))]
+    pub fn analyze(series: &[f64]) -> TrendReport {
+        if series.len() < MIN_SERIES_LEN || series.iter().any(|value| !value.is_finite()) {
+            tracing::debug!("Series too short or not finite, nothing to analyse");
+            return TrendReport::default();
+        }
+
+        let linear = fit_linear(series);
+        let residuals: Vec<f64> = series.iter().enumerate()
+            .map(|(t, value)| value - (linear.0 + linear.1 * t as f64))
+            .collect();
+        let cycles = detect_cycles(&residuals);
+        let (disruptions, abrupt) = detect_disruptions(series);
+
+        let mut fit = best_growth_fit(series);
+        if fit.r_squared < 0.3 {
+            fit.growth_type = match cycles.first() {
+                Some(cycle) if cycle.confidence > 0.5 => GrowthType::Cyclical,
+                _ => GrowthType::Chaotic,
+            };
+        }
+        let current_phase = growth_phase(series, &fit, abrupt);
+        tracing::debug!("Trend: {:?} (R² {:.2}) in {:?}, {} cycles, {} disruptions",
+            fit.growth_type, fit.r_squared, current_phase, cycles.len(), disruptions.len());
+
+        TrendReport {
+            cycles,
+            fit_quality: fit.r_squared.clamp(0.0, 1.0),
+            growth: Some(GrowthPattern {
+                pattern_type: fit.growth_type,
+                rate: fit.rate,
+                saturation_point: fit.saturation_point,
+                current_phase,
+            }),
+            disruptions,
+        }
+    }
+
+    pub fn report(&self) -> TrendReport {
+        Self::analyze(&self.series)
+    }
+}
+
+/// Least-squares line through (index, value), as (intercept, slope)
+fn fit_linear(series: &[f64]) -> (f64, f64) {
+    let n = series.len() as f64;
+    let mean_t = (n - 1.0) / 2.0;
+    let mean_y = series.iter().sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (t, value) in series.iter().enumerate() {
+        let dt = t as f64 - mean_t;
+        covariance += dt * (value - mean_y);
+        variance += dt * dt;
+    }
+    let slope = if variance > 0.0 { covariance / variance } else { 0.0 };
+    (mean_y - slope * mean_t, slope)
+}
+
+fn r_squared(series: &[f64], predict: impl Fn(f64) -> f64) -> f64 {
+    let mean = series.iter().sum::<f64>() / series.len() as f64;
+    let total: f64 = series.iter().map(|value| (value - mean).powi(2)).sum();
+    let residual: f64 = series.iter().enumerate().map(|(t, value)| (value - predict(t as f64)).powi(2)).sum();
+    if total == 0.0 {
+        // A flat series is explained perfectly by a flat fit and not at all by anything else
+        return if residual < 1e-12 { 1.0 } else { 0.0 };
+    }
+    1.0 - residual / total
+}
+
+fn best_growth_fit(series: &[f64]) -> Fit {
+    let (intercept, slope) = fit_linear(series);
+    let mut best = Fit {
+        growth_type: GrowthType::Linear,
+        intercept,
+        rate: slope,
+        saturation_point: None,
+        r_squared: r_squared(series, |t| intercept + slope * t),
+    };
+
+    // Log-space fits need strictly positive values
+    if series.iter().any(|value| *value <= 0.0) {
+        return best;
+    }
+
+    let logs: Vec<f64> = series.iter().map(|value| value.ln()).collect();
+    let (log_intercept, log_slope) = fit_linear(&logs);
+    let exponential = r_squared(series, |t| (log_intercept + log_slope * t).exp());
+    if exponential > best.r_squared + MODEL_PREFERENCE_MARGIN {
+        best = Fit { growth_type: GrowthType::Exponential, intercept: log_intercept, rate: log_slope, saturation_point: None, r_squared: exponential };
+    }
+
+    // Logistic: try a few ceilings above the peak and linearise with the logit
+    let peak = series.iter().cloned().fold(f64::MIN, f64::max);
+    for ceiling in [1.05, 1.1, 1.25, 1.5, 2.0, 3.0].map(|factor| peak * factor) {
+        let logits: Vec<f64> = series.iter().map(|value| (value / (ceiling - value)).ln()).collect();
+        let (a, r) = fit_linear(&logits);
+        let logistic = r_squared(series, |t| ceiling / (1.0 + (-(a + r * t)).exp()));
+        if r > 0.0 && logistic > best.r_squared + MODEL_PREFERENCE_MARGIN {
+            best = Fit { growth_type: GrowthType::Logistic, intercept: a, rate: r, saturation_point: Some(ceiling), r_squared: logistic };
+        }
+    }
+    best
+}
+
+/// Local peaks of the autocorrelation of `residuals`, strongest first (at most two)
+fn detect_cycles(residuals: &[f64]) -> Vec<CyclicPattern> {
+    let n = residuals.len();
+    let energy: f64 = residuals.iter().map(|value| value * value).sum();
+    if energy == 0.0 || n < 2 * MIN_SERIES_LEN {
+        return Vec::new();
+    }
+
+    let autocorrelation: Vec<f64> = (0..=n / 2)
+        .map(|lag| residuals.iter().zip(&residuals[lag..]).map(|(a, b)| a * b).sum::<f64>() / energy)
+        .collect();
+    // Beyond ~2/sqrt(n) a correlation is unlikely to be noise
+    let threshold = MIN_CYCLE_CORRELATION.max(2.0 / (n as f64).sqrt());
+    let amplitude = (2.0 * energy / n as f64).sqrt();
+
+    let mut cycles: Vec<CyclicPattern> = (2..autocorrelation.len().saturating_sub(1))
+        .filter(|&lag| {
+            autocorrelation[lag] > threshold
+                && autocorrelation[lag] >= autocorrelation[lag - 1]
+                && autocorrelation[lag] >= autocorrelation[lag + 1]
+        })
+        .map(|lag| CyclicPattern {
+            name: format!("{}-sample cycle", lag),
+            period: lag as f64,
+            amplitude,
+            confidence: autocorrelation[lag].min(1.0),
+        })
+        .collect();
+    cycles.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    cycles.truncate(2);
+    cycles
+}
+
+fn mean_and_spread(values: &[f64]) -> (f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+/// Spread of `values` around their own best straight line
+fn detrended_spread(values: &[f64]) -> f64 {
+    let (intercept, slope) = fit_linear(values);
+    let squared: f64 = values.iter().enumerate().map(|(t, value)| (value - intercept - slope * t as f64).powi(2)).sum();
+    (squared / values.len() as f64).sqrt()
+}
+
+/// Checks the most recent quarter of the series (at least two samples)
+/// against a forecast from the growth curve fitted to what came before, and
+/// the two halves' directions against each other. Also returns whether the
+/// series broke abruptly (a strong level shift or volatility spike).
+fn detect_disruptions(series: &[f64]) -> (Vec<DisruptionIndicator>, bool) {
+    let recent_len = (series.len() / 4).max(2);
+    let (earlier, recent) = series.split_at(series.len() - recent_len);
+    let horizon = format!("last {} samples", recent_len);
+    // Keep a flat history from making any change look infinitely surprising
+    let scale = series.iter().map(|value| value.abs()).fold(0.0, f64::max).max(1e-9);
+    let earlier_fit = best_growth_fit(earlier);
+    let earlier_spread = (earlier.iter().enumerate()
+        .map(|(t, value)| (value - earlier_fit.predict(t as f64)).powi(2))
+        .sum::<f64>() / earlier.len() as f64)
+        .sqrt();
+    let baseline_spread = earlier_spread.max(scale * 0.05);
+
+    let mut disruptions = Vec::new();
+    let mut abrupt = false;
+
+    let surprise = recent.iter().enumerate()
+        .map(|(i, value)| value - earlier_fit.predict((earlier.len() + i) as f64))
+        .sum::<f64>() / recent.len() as f64;
+    let z = surprise / baseline_spread;
+    if z.abs() > LEVEL_SHIFT_Z {
+        let strength = (z.abs() / (2.0 * LEVEL_SHIFT_Z)).min(1.0);
+        abrupt |= strength > 0.6;
+        disruptions.push(DisruptionIndicator {
+            indicator_name: if z > 0.0 { "sudden jump".to_string() } else { "sudden drop".to_string() },
+            strength,
+            time_horizon: horizon.clone(),
+            probability: 1.0 - (-(z.abs() - LEVEL_SHIFT_Z)).exp() * 0.5,
+        });
+    }
+
+    let ratio = detrended_spread(recent) / detrended_spread(earlier).max(scale * 0.05);
+    if ratio > VOLATILITY_RATIO {
+        let strength = (ratio / (2.0 * VOLATILITY_RATIO)).min(1.0);
+        abrupt |= strength > 0.6;
+        disruptions.push(DisruptionIndicator {
+            indicator_name: "volatility spike".to_string(),
+            strength,
+            time_horizon: horizon.clone(),
+            probability: (1.0 - VOLATILITY_RATIO / ratio).clamp(0.0, 1.0),
+        });
+    }
+
+    let half = series.len() / 2;
+    let (_, first_slope) = fit_linear(&series[..half]);
+    let (_, second_slope) = fit_linear(&series[half..]);
+    // Both halves must move by a meaningful share of the range
+    let meaningful = scale * 0.5 / series.len() as f64;
+    if first_slope.signum() != second_slope.signum() && first_slope.abs() > meaningful && second_slope.abs() > meaningful {
+        let strength = (second_slope - first_slope).abs() / (first_slope.abs() + second_slope.abs());
+        disruptions.push(DisruptionIndicator {
+            indicator_name: if second_slope > 0.0 { "trend reversal upward".to_string() } else { "trend reversal downward".to_string() },
+            strength: strength.min(1.0),
+            time_horizon: format!("last {} samples", series.len() - half),
+            probability: strength.min(1.0) * 0.8,
+        });
+    }
+
+    disruptions.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    (disruptions, abrupt)
+}
+
+/// Where the series stands now, judged from its most recent third
+fn growth_phase(series: &[f64], fit: &Fit, abrupt: bool) -> GrowthPhase {
+    let n = series.len();
+    let peak = series.iter().cloned().fold(f64::MIN, f64::max);
+    let floor = series.iter().cloned().fold(f64::MAX, f64::min);
+    let range = peak - floor;
+    let last = series[n - 1];
+    if range == 0.0 {
+        return if last == 0.0 { GrowthPhase::Inception } else { GrowthPhase::Maturity };
+    }
+
+    let recent = &series[n - (n / 3).max(3)..];
+    let history = &series[..n - recent.len()];
+    let (_, recent_slope) = fit_linear(recent);
+    // Both relative to the range: the slope as if it ran the whole series,
+    // the shift as the recent level against the level before it. A short-run
+    // slope means nothing on a series no curve fits, so only the shift counts there.
+    let normalized_slope = match fit.growth_type {
+        GrowthType::Cyclical | GrowthType::Chaotic => 0.0,
+        _ => recent_slope * n as f64 / range,
+    };
+    let shift = (mean_and_spread(recent).0 - mean_and_spread(history).0) / range;
+
+    // Rising from (near) nothing: most of what came before was close to zero
+    let quiet = history.iter().filter(|value| value.abs() <= peak.abs() * 0.1).count();
+    if (shift > 0.3 || normalized_slope > 0.2) && quiet * 3 >= history.len() * 2 {
+        return GrowthPhase::Inception;
+    }
+    if abrupt {
+        return GrowthPhase::Disruption;
+    }
+    if fit.saturation_point.is_some_and(|ceiling| last >= 0.85 * ceiling) && normalized_slope.abs() < 0.5 {
+        return GrowthPhase::Maturity;
+    }
+    if shift < -0.3 || normalized_slope < -0.5 {
+        return GrowthPhase::Decline;
+    }
+    if shift > 0.3 || normalized_slope > 0.2 {
+        return GrowthPhase::Growth;
+    }
+    GrowthPhase::Maturity
+}
+
+/// Pearson correlation of two equally long series; 0.0 when either is flat
+pub fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let len = a.len().min(b.len());
+    if len < 2 {
+        return 0.0;
+    }
+    let (mean_a, spread_a) = mean_and_spread(&a[..len]);
+    let (mean_b, spread_b) = mean_and_spread(&b[..len]);
+    if spread_a == 0.0 || spread_b == 0.0 {
+        return 0.0;
+    }
+    let covariance = a[..len].iter().zip(&b[..len]).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum::<f64>() / len as f64;
+    covariance / (spread_a * spread_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn growth(report: &TrendReport) -> &GrowthPattern {
+        report.growth.as_ref().expect("long enough series are fitted")
+    }
+
+    #[test]
+    fn test_short_or_broken_series_give_an_empty_report() {
+        assert!(TrendAnalyzer::analyze(&[1.0, 2.0, 3.0]).growth.is_none());
+        assert!(TrendAnalyzer::analyze(&[1.0, 2.0, f64::NAN, 4.0, 5.0]).growth.is_none());
+    }
+
+    #[test]
+    fn test_growth_curves_are_told_apart() {
+        let linear: Vec<f64> = (0..24).map(|t| 2.0 + 0.5 * t as f64).collect();
+        let report = TrendAnalyzer::analyze(&linear);
+        assert_eq!(growth(&report).pattern_type, GrowthType::Linear);
+        assert!((growth(&report).rate - 0.5).abs() < 1e-9);
+        assert_eq!(growth(&report).current_phase, GrowthPhase::Growth);
+        assert!(report.disruptions.is_empty());
+
+        let exponential: Vec<f64> = (0..24).map(|t| 1.2f64.powi(t)).collect();
+        let report = TrendAnalyzer::analyze(&exponential);
+        assert_eq!(growth(&report).pattern_type, GrowthType::Exponential);
+        assert!((growth(&report).rate - 1.2f64.ln()).abs() < 1e-9);
+
+        let logistic: Vec<f64> = (0..30).map(|t| 10.0 / (1.0 + (-(t as f64 - 12.0) / 2.0).exp())).collect();
+        let report = TrendAnalyzer::analyze(&logistic);
+        assert_eq!(growth(&report).pattern_type, GrowthType::Logistic);
+        assert!(growth(&report).saturation_point.is_some_and(|ceiling| ceiling > 10.0));
+        assert_eq!(growth(&report).current_phase, GrowthPhase::Maturity);
+    }
+
+    #[test]
+    fn test_cycles_are_found_by_autocorrelation() {
+        let wave: Vec<f64> = (0..48).map(|t| 5.0 + (t as f64 * std::f64::consts::TAU / 8.0).sin()).collect();
+        let report = TrendAnalyzer::analyze(&wave);
+        assert_eq!(report.cycles[0].period, 8.0);
+        assert!(report.cycles[0].confidence > 0.5);
+        assert_eq!(growth(&report).pattern_type, GrowthType::Cyclical);
+    }
+
+    #[test]
+    fn test_level_shift_is_a_disruption() {
+        let step: Vec<f64> = (0..24).map(|t| if t < 18 { 1.0 } else { 10.0 }).collect();
+        let report = TrendAnalyzer::analyze(&step);
+        assert_eq!(report.disruptions[0].indicator_name, "sudden jump");
+        assert_eq!(growth(&report).current_phase, GrowthPhase::Inception);
+
+        let falling: Vec<f64> = (0..24).map(|t| if t < 18 { 10.0 + (t % 2) as f64 * 0.1 } else { 1.0 }).collect();
+        let report = TrendAnalyzer::analyze(&falling);
+        assert_eq!(report.disruptions[0].indicator_name, "sudden drop");
+        assert_eq!(growth(&report).current_phase, GrowthPhase::Disruption);
+    }
+
+    #[test]
+    fn test_reversal_is_a_disruption() {
+        let peak: Vec<f64> = (0..24).map(|t| 12.0 - (t as f64 - 12.0).abs()).collect();
+        let report = TrendAnalyzer::analyze(&peak);
+        assert!(report.disruptions.iter().any(|disruption| disruption.indicator_name == "trend reversal downward"));
+    }
+}
@@ -1,9 +1,16 @@
 use chops_core::{
-    ChaosParams, RandomDistribution, PersonaType, CHOPSResult, CHOPSError
+    cmp_scores, ChaosAffinity, ChaosParams, RandomDistribution, PersonaType, CHOPSResult, CHOPSError
 };
-use crate::{EntropyGenerator, ChaosMathematics, ChaosPattern};
+pub use chops_core::{ChaosTier, ChaosTierBoundaries};
+use crate::{EntropyGenerator, ChaosMathematics, ChaosPattern, ChaosSignature, PatternRecognizer, MAX_KNOWN_PATTERNS};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Reality level at which a persona's distortion affinity is used as is
+pub const DEFAULT_REALITY_LEVEL: f64 = 0.7;
 
 #[derive(Debug, Clone)]
 pub struct ChaosEngine {
@@ -13,6 +20,14 @@ pub struct ChaosEngine {
     pub controlled_randomness: ControlledRandomness,
     pub reality_distortion: RealityDistortion,
     pub pattern_memory: Vec<ChaosPattern>,
+    /// Every pattern saved across sessions, persisted by the host application
+    pub pattern_recognizer: PatternRecognizer,
+    tier_boundaries: ChaosTierBoundaries,
+    variation_mask: ChaosVariationMask,
+    /// How grounded ideas stay, 0.0-1.0; scales the persona's distortion affinity
+    reality_level: f64,
+    /// Drawn from for chaos intensity, distortion and unexpected elements when seeded
+    seeded_rng: Option<StdRng>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,9 +54,13 @@ pub struct ChaosInjectionResult {
     pub reality_distortion_applied: f64,
     pub unexpected_elements: Vec<String>,
     pub coherence_score: f64,
+    /// Variations dropped because together they pushed coherence below the
+    /// engine's coherence threshold
+    #[serde(default)]
+    pub variations_pruned: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChaosVariation {
     pub variation_type: ChaosVariationType,
     pub description: String,
@@ -50,7 +69,7 @@ pub struct ChaosVariation {
     pub creativity_boost: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum ChaosVariationType {
     ParameterMutation,
     ConceptInversion,
@@ -62,6 +81,217 @@ pub enum ChaosVariationType {
     RealityBend,
 }
 
+chops_core::impl_enum_str!(ChaosVariationType {
+    ParameterMutation => "parameter-mutation",
+    ConceptInversion => "concept-inversion",
+    ScaleDistortion => "scale-distortion",
+    TimelineShift => "timeline-shift",
+    ConstraintViolation => "constraint-violation",
+    ParadoxInjection => "paradox-injection",
+    UnexpectedCombination => "unexpected-combination",
+    RealityBend => "reality-bend",
+});
+
+impl ChaosVariationType {
+    pub const ALL: [ChaosVariationType; 8] = [
+        ChaosVariationType::ParameterMutation,
+        ChaosVariationType::ConceptInversion,
+        ChaosVariationType::ScaleDistortion,
+        ChaosVariationType::TimelineShift,
+        ChaosVariationType::ConstraintViolation,
+        ChaosVariationType::ParadoxInjection,
+        ChaosVariationType::UnexpectedCombination,
+        ChaosVariationType::RealityBend,
+    ];
+
+    /// Seed for this variation type's own random stream, derived from the
+    /// master seed and the type's name so it doesn't depend on which other
+    /// variations were generated first
+    pub fn sub_seed(&self, master_seed: u64) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.to_string().bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        splitmix64(master_seed ^ hash)
+    }
+}
+
+/// The variation types an engine may produce. Variations of other types are
+/// dropped after generation, so masking one type never changes another's text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChaosVariationMask {
+    enabled: HashSet<ChaosVariationType>,
+}
+
+impl ChaosVariationMask {
+    /// Every variation type enabled
+    pub fn all() -> Self {
+        Self::only(ChaosVariationType::ALL)
+    }
+
+    /// Just the given variation types enabled
+    pub fn only(types: impl IntoIterator<Item = ChaosVariationType>) -> Self {
+        Self {
+            enabled: types.into_iter().collect(),
+        }
+    }
+
+    pub fn allows(&self, variation_type: ChaosVariationType) -> bool {
+        self.enabled.contains(&variation_type)
+    }
+
+    pub fn is_all(&self) -> bool {
+        ChaosVariationType::ALL.iter().all(|t| self.enabled.contains(t))
+    }
+
+    pub fn enable(&mut self, variation_type: ChaosVariationType) {
+        self.enabled.insert(variation_type);
+    }
+
+    pub fn disable(&mut self, variation_type: ChaosVariationType) {
+        self.enabled.remove(&variation_type);
+    }
+}
+
+impl Default for ChaosVariationMask {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl std::fmt::Display for ChaosVariationMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_all() {
+            return write!(f, "all");
+        }
+        let names: Vec<String> = ChaosVariationType::ALL.iter()
+            .filter(|t| self.allows(**t))
+            .map(|t| t.to_string())
+            .collect();
+        write!(f, "{}", names.join(","))
+    }
+}
+
+/// Comma-separated variation types, each by full name ("concept-inversion")
+/// or by any one word of it that names a single type ("inversion", "scale");
+/// "all" enables everything
+impl std::str::FromStr for ChaosVariationMask {
+    type Err = CHOPSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(Self::all());
+        }
+        let mut mask = Self::only([]);
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let variation_type = match name.parse::<ChaosVariationType>() {
+                Ok(variation_type) => variation_type,
+                Err(_) => {
+                    let wanted = name.to_lowercase();
+                    let matches: Vec<ChaosVariationType> = ChaosVariationType::ALL.iter()
+                        .copied()
+                        .filter(|t| t.to_string().split('-').any(|word| word == wanted))
+                        .collect();
+                    match matches.as_slice() {
+                        [variation_type] => *variation_type,
+                        _ => return Err(CHOPSError::InvalidParameter(format!(
+                            "Unknown chaos variation type '{}' (valid: {})",
+                            name,
+                            ChaosVariationType::VARIANT_NAMES.join(", ")
+                        ))),
+                    }
+                }
+            };
+            mask.enable(variation_type);
+        }
+        if mask.enabled.is_empty() {
+            return Err(CHOPSError::InvalidParameter("Chaos variation types can't be empty".to_string()));
+        }
+        Ok(mask)
+    }
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// One seeded RNG per variation type, created lazily from the master seed
+#[derive(Debug, Clone)]
+pub struct VariationStreams {
+    master_seed: u64,
+    streams: HashMap<ChaosVariationType, StdRng>,
+}
+
+impl VariationStreams {
+    pub fn new(master_seed: u64) -> Self {
+        Self {
+            master_seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    pub fn stream(&mut self, variation_type: ChaosVariationType) -> &mut StdRng {
+        let master_seed = self.master_seed;
+        self.streams
+            .entry(variation_type)
+            .or_insert_with(|| StdRng::seed_from_u64(variation_type.sub_seed(master_seed)))
+    }
+}
+
+/// The tunable part of a [`ChaosEngine`], saved and loaded as a chaos preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaosEngineConfig {
+    /// Normalized chaos level (0.0-1.0)
+    pub chaos_level: f64,
+    pub controlled_randomness: ControlledRandomness,
+    pub reality_distortion: RealityDistortion,
+}
+
+impl ChaosEngineConfig {
+    pub fn validate(&self) -> CHOPSResult<()> {
+        for (name, value) in [
+            ("chaos_level", self.chaos_level),
+            ("stability_factor", self.controlled_randomness.stability_factor),
+            ("coherence_threshold", self.controlled_randomness.coherence_threshold),
+            ("intensity", self.reality_distortion.intensity),
+            ("impossibility_tolerance", self.reality_distortion.impossibility_tolerance),
+            ("paradox_acceptance", self.reality_distortion.paradox_acceptance),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(CHOPSError::InvalidParameter(format!(
+                    "Chaos config {} must be between 0.0 and 1.0, got {}", name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads and validates a saved config
+    pub fn load_from_file(path: &std::path::Path) -> CHOPSResult<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(CHOPSError::FileSystemError)?;
+        let config: Self = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to load chaos config {}: {}", path.display(), e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn save_to_file(&self, path: &std::path::Path) -> CHOPSResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(CHOPSError::FileSystemError)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize chaos config: {}", e)))?;
+        std::fs::write(path, content)
+            .map_err(CHOPSError::FileSystemError)
+    }
+}
+
 impl ChaosEngine {
     #[tracing::instrument(name = "chaos_engine_new", level = "info")]
     pub fn new(chaos_level: u8) -> Self {
@@ -87,12 +317,139 @@ impl ChaosEngine {
                 paradox_acceptance: 0.3,
             },
             pattern_memory: Vec::new(),
+            pattern_recognizer: PatternRecognizer::new(),
+            tier_boundaries: ChaosTierBoundaries::default(),
+            variation_mask: ChaosVariationMask::default(),
+            reality_level: DEFAULT_REALITY_LEVEL,
+            seeded_rng: None,
         };
         
         tracing::info!("ChaosEngine initialized with reality distortion enabled: {}", engine.reality_distortion.enabled);
         engine
     }
     
+    /// Sets the chaos level on the CLI's 0-11 scale
+    pub fn set_chaos_level(&mut self, level: u8) -> CHOPSResult<()> {
+        if level > 11 {
+            return Err(CHOPSError::ChaosError(format!("Invalid chaos level: {}", level)));
+        }
+        self.chaos_level = level as f64 / 11.0;
+        Ok(())
+    }
+
+    /// Sets how grounded ideas stay: 1.0 turns reality distortion off, lower
+    /// levels amplify the persona's distortion affinity
+    pub fn set_reality_level(&mut self, level: f64) -> CHOPSResult<()> {
+        if !(0.0..=1.0).contains(&level) {
+            return Err(CHOPSError::InvalidParameter(format!("Reality level must be between 0.0 and 1.0, got {}", level)));
+        }
+        self.reality_level = level;
+        Ok(())
+    }
+
+    /// How grounded ideas stay (0.0-1.0); see `set_reality_level`
+    pub fn reality_level(&self) -> f64 {
+        self.reality_level
+    }
+
+    /// An engine whose chaos sampling is reproducible: the same input and seed
+    /// give the same intensity, variations and unexpected elements
+    pub fn with_seed(chaos_level: u8, seed: u64) -> Self {
+        let mut engine = Self::new(chaos_level);
+        engine.set_seed(Some(seed));
+        engine
+    }
+
+    /// Master seed for chaos sampling and pseudo-random entropy; `None` goes
+    /// back to unseeded randomness
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        tracing::debug!("Chaos seed set to {:?}", seed);
+        self.controlled_randomness.seed = seed;
+        self.seeded_rng = seed.map(|seed| StdRng::seed_from_u64(splitmix64(seed)));
+        self.entropy_generator.set_seed(seed);
+    }
+
+    /// The seeded RNG when there is a seed, otherwise the thread's
+    fn rng(&mut self) -> Box<dyn RngCore + '_> {
+        match &mut self.seeded_rng {
+            Some(rng) => Box::new(rng),
+            None => Box::new(rand::thread_rng()),
+        }
+    }
+
+    /// The engine's tuned randomness, reality distortion and chaos level
+    pub fn export_config(&self) -> ChaosEngineConfig {
+        ChaosEngineConfig {
+            chaos_level: self.chaos_level,
+            controlled_randomness: self.controlled_randomness.clone(),
+            reality_distortion: self.reality_distortion.clone(),
+        }
+    }
+
+    /// Replaces the tuned state with `config`, leaving the engine untouched if it doesn't validate
+    pub fn import_config(&mut self, config: ChaosEngineConfig) -> CHOPSResult<()> {
+        config.validate()?;
+        tracing::debug!("Importing chaos config: {:?}", config);
+        self.chaos_level = config.chaos_level;
+        let seed = config.controlled_randomness.seed;
+        self.controlled_randomness = config.controlled_randomness;
+        self.reality_distortion = config.reality_distortion;
+        self.set_seed(seed);
+        Ok(())
+    }
+
+    pub fn tier_boundaries(&self) -> &ChaosTierBoundaries {
+        &self.tier_boundaries
+    }
+
+    pub fn set_tier_boundaries(&mut self, boundaries: ChaosTierBoundaries) -> CHOPSResult<()> {
+        boundaries.validate()?;
+        self.tier_boundaries = boundaries;
+        Ok(())
+    }
+
+    pub fn variation_mask(&self) -> &ChaosVariationMask {
+        &self.variation_mask
+    }
+
+    /// Restricts injected chaos to the mask's variation types
+    pub fn set_variation_mask(&mut self, mask: ChaosVariationMask) {
+        tracing::debug!("Chaos variation mask set to {}", mask);
+        self.variation_mask = mask;
+    }
+
+    /// Swaps the Lorenz and Hénon constants behind the chaotic distribution
+    /// (see `ChaosMathematics::set_lorenz_parameters` for the ranges). Both
+    /// are validated before either is applied; parameters that won't behave
+    /// chaotically are applied anyway and come back as warnings.
+    pub fn set_attractor_parameters(
+        &mut self,
+        lorenz: Option<(f64, f64, f64)>,
+        henon: Option<(f64, f64)>,
+    ) -> CHOPSResult<Vec<String>> {
+        let mut mathematics = self.mathematics.clone();
+        let mut warnings = Vec::new();
+        if let Some((sigma, rho, beta)) = lorenz {
+            warnings.extend(mathematics.set_lorenz_parameters(sigma, rho, beta)?);
+        }
+        if let Some((a, b)) = henon {
+            warnings.extend(mathematics.set_henon_parameters(a, b)?);
+        }
+        tracing::debug!("Attractor parameters set - lorenz: {:?}, henon: {:?}", lorenz, henon);
+        self.mathematics = mathematics;
+        Ok(warnings)
+    }
+
+    /// Takes on the active persona's preferred distribution, distortion
+    /// intensity and coherence threshold
+    pub fn apply_affinity(&mut self, affinity: &ChaosAffinity) {
+        tracing::debug!("Applying chaos affinity: {:?}", affinity);
+        self.controlled_randomness.distribution = affinity.distribution.clone();
+        self.controlled_randomness.coherence_threshold = affinity.coherence_threshold;
+        let reality_scale = (1.0 - self.reality_level) / (1.0 - DEFAULT_REALITY_LEVEL);
+        self.reality_distortion.intensity = (affinity.reality_distortion * reality_scale).min(1.0);
+    }
+
     #[tracing::instrument(name = "chaos_engine_configure", level = "info")]
     pub fn configure(&mut self, params: &ChaosParams) -> CHOPSResult<()> {
         tracing::info!("Configuring ChaosEngine with chaos level: {}, persona: {:?}", 
@@ -109,29 +466,25 @@ impl ChaosEngine {
             self.chaos_level, self.controlled_randomness.distribution);
         
         self.entropy_generator.set_source(params.entropy_source.clone())?;
+        for warning in self.set_attractor_parameters(params.lorenz, params.henon)? {
+            tracing::warn!("{}", warning);
+        }
         
-        // Adjust parameters based on persona
+        // Adjust parameters based on persona; distribution, distortion intensity
+        // and coherence threshold come from its affinity (see `apply_affinity`)
         match params.persona_type {
             PersonaType::MadScientist => {
                 tracing::debug!("Applying MadScientist persona settings");
-                self.reality_distortion.intensity = 0.9;
                 self.reality_distortion.impossibility_tolerance = 0.8;
             },
             PersonaType::ZenMaster => {
                 tracing::debug!("Applying ZenMaster persona settings");
-                self.reality_distortion.intensity = 0.3;
                 self.controlled_randomness.stability_factor = 0.9;
             },
             PersonaType::ChaosEngineer => {
                 tracing::debug!("Applying ChaosEngineer persona settings");
-                self.reality_distortion.intensity = 1.0;
                 self.reality_distortion.paradox_acceptance = 0.9;
             },
-            PersonaType::EmpatheticAI => {
-                tracing::debug!("Applying EmpatheticAI persona settings");
-                self.controlled_randomness.coherence_threshold = 0.8;
-                self.reality_distortion.intensity = 0.4;
-            },
             _ => {
                 tracing::debug!("Using default settings for persona: {:?}", params.persona_type);
             }
@@ -151,50 +504,49 @@ impl ChaosEngine {
         
         let mut variations = Vec::new();
         
-        // Apply different types of chaos based on intensity
-        let chaos_type = match self.chaos_level {
-            0.0..=0.3 => {
-                tracing::debug!("Applying subtle variations (chaos level: {:.2})", self.chaos_level);
-                variations.extend(self.apply_subtle_variations(base_idea, chaos_intensity).await?);
-                "subtle"
-            },
-            0.31..=0.64 => {
-                tracing::debug!("Applying moderate disruption (chaos level: {:.2})", self.chaos_level);
-                variations.extend(self.apply_moderate_disruption(base_idea, chaos_intensity).await?);
-                "moderate"
-            },
-            0.65..=0.91 => {
-                tracing::debug!("Applying reality bending (chaos level: {:.2})", self.chaos_level);
-                variations.extend(self.apply_reality_bending(base_idea, chaos_intensity).await?);
-                "reality_bending"
-            },
-            0.92..=1.0 => {
-                tracing::debug!("Applying impossible combinations (chaos level: {:.2})", self.chaos_level);
-                variations.extend(self.apply_impossible_combinations(base_idea, chaos_intensity).await?);
-                "impossible"
-            },
-            _ => {
-                tracing::debug!("Applying transcendent chaos (chaos level: {:.2})", self.chaos_level);
-                variations.extend(self.apply_transcendent_chaos(base_idea, chaos_intensity).await?);
-                "transcendent"
-            }
+        // Each variation type draws from its own stream, reproducible when
+        // there is a seed and freshly random otherwise
+        let master_seed = match self.controlled_randomness.seed {
+            Some(seed) => seed,
+            None => self.rng().gen(),
         };
+        let mut streams = VariationStreams::new(master_seed);
+        
+        // Apply different types of chaos based on the configured level
+        let tier = self.tier_boundaries.tier_for(self.chaos_level);
+        tracing::debug!("Applying {} chaos (chaos level: {:.2})", tier, self.chaos_level);
+        let chaos_type = tier.to_string();
+        variations.extend(match tier {
+            ChaosTier::Subtle => self.apply_subtle_variations(chaos_intensity, &mut streams).await?,
+            ChaosTier::Moderate => self.apply_moderate_disruption(chaos_intensity, &mut streams).await?,
+            ChaosTier::RealityBending => self.apply_reality_bending(chaos_intensity, &mut streams).await?,
+            ChaosTier::Impossible => self.apply_impossible_combinations(chaos_intensity, &mut streams).await?,
+            ChaosTier::Transcendent => self.apply_transcendent_chaos(chaos_intensity, &mut streams).await?,
+        });
         
         tracing::debug!("Generated {} variations using {} chaos type", variations.len(), chaos_type);
         
+        if !self.variation_mask.is_all() {
+            let generated = variations.len();
+            variations.retain(|variation| self.variation_mask.allows(variation.variation_type));
+            tracing::debug!("Variation mask {} kept {} of {} variations", self.variation_mask, variations.len(), generated);
+        }
+        
         let reality_distortion_applied = if self.reality_distortion.enabled {
             tracing::debug!("Applying reality distortion");
-            self.apply_reality_distortion(&mut variations).await?
+            self.apply_reality_distortion(&mut variations, &mut streams).await?
         } else {
             tracing::debug!("Reality distortion disabled");
             0.0
         };
         
+        let variations_pruned = self.prune_incoherent_variations(&mut variations);
         let unexpected_elements = self.generate_unexpected_elements(base_idea, chaos_intensity).await?;
         let coherence_score = self.calculate_coherence_score(&variations);
+        self.learn_pattern(tier, base_idea, &variations, coherence_score);
         
-        tracing::info!("Chaos injection complete - {} variations, {:.2} coherence score, {:.2} reality distortion", 
-            variations.len(), coherence_score, reality_distortion_applied);
+        tracing::info!("Chaos injection complete - {} variations ({} pruned), {:.2} coherence score, {:.2} reality distortion", 
+            variations.len(), variations_pruned, coherence_score, reality_distortion_applied);
         
         Ok(ChaosInjectionResult {
             original_idea: base_idea.to_string(),
@@ -203,6 +555,7 @@ impl ChaosEngine {
             reality_distortion_applied,
             unexpected_elements,
             coherence_score,
+            variations_pruned,
         })
     }
     
@@ -221,14 +574,14 @@ impl ChaosEngine {
             RandomDistribution::Normal => {
                 tracing::debug!("Using normal distribution");
                 let normal = rand_distr::Normal::new(0.5, 0.2).unwrap();
-                let sample = (rand::thread_rng().sample(normal) as f64).max(0.0).min(1.0);
+                let sample = self.rng().sample::<f64, _>(normal).clamp(0.0, 1.0);
                 tracing::debug!("Normal distribution sample: {:.3}", sample);
                 sample
             },
             RandomDistribution::Exponential => {
                 tracing::debug!("Using exponential distribution");
                 let exp = rand_distr::Exp::new(2.0).unwrap();
-                let sample = (1.0 - (rand::thread_rng().sample(exp) as f64).min(5.0) / 5.0).max(0.0);
+                let sample = (1.0 - self.rng().sample::<f64, _>(exp).min(5.0) / 5.0).max(0.0);
                 tracing::debug!("Exponential distribution sample: {:.3}", sample);
                 sample
             },
@@ -247,13 +600,13 @@ impl ChaosEngine {
         Ok(final_intensity)
     }
     
-    async fn apply_subtle_variations(&self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<ChaosVariation>> {
+    async fn apply_subtle_variations(&self, intensity: f64, streams: &mut VariationStreams) -> CHOPSResult<Vec<ChaosVariation>> {
         let mut variations = Vec::new();
         
         // Parameter tweaking
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::ParameterMutation,
-            description: format!("Adjust key parameters: {}", self.generate_parameter_suggestions(base_idea)),
+            description: format!("Adjust key parameters: {}", self.generate_parameter_suggestions(streams)),
             chaos_intensity: intensity * 0.5,
             feasibility_impact: 0.1,
             creativity_boost: 0.3,
@@ -262,7 +615,7 @@ impl ChaosEngine {
         // Scale adjustments
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::ScaleDistortion,
-            description: format!("Scale variation: {}", self.generate_scale_variations(base_idea)),
+            description: format!("Scale variation: {}", self.generate_scale_variations(streams)),
             chaos_intensity: intensity * 0.6,
             feasibility_impact: 0.05,
             creativity_boost: 0.4,
@@ -271,13 +624,13 @@ impl ChaosEngine {
         Ok(variations)
     }
     
-    async fn apply_moderate_disruption(&self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<ChaosVariation>> {
+    async fn apply_moderate_disruption(&self, intensity: f64, streams: &mut VariationStreams) -> CHOPSResult<Vec<ChaosVariation>> {
         let mut variations = Vec::new();
         
         // Concept inversion
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::ConceptInversion,
-            description: format!("Invert core assumptions: {}", self.generate_concept_inversions(base_idea)),
+            description: format!("Invert core assumptions: {}", self.generate_concept_inversions(streams)),
             chaos_intensity: intensity * 0.7,
             feasibility_impact: -0.2,
             creativity_boost: 0.6,
@@ -286,7 +639,7 @@ impl ChaosEngine {
         // Timeline shifting
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::TimelineShift,
-            description: format!("Temporal perspective shift: {}", self.generate_timeline_shifts(base_idea)),
+            description: format!("Temporal perspective shift: {}", self.generate_timeline_shifts(streams)),
             chaos_intensity: intensity * 0.8,
             feasibility_impact: -0.1,
             creativity_boost: 0.7,
@@ -295,7 +648,7 @@ impl ChaosEngine {
         // Constraint violations
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::ConstraintViolation,
-            description: format!("Challenge constraints: {}", self.generate_constraint_violations(base_idea)),
+            description: format!("Challenge constraints: {}", self.generate_constraint_violations(streams)),
             chaos_intensity: intensity * 0.9,
             feasibility_impact: -0.3,
             creativity_boost: 0.8,
@@ -304,13 +657,13 @@ impl ChaosEngine {
         Ok(variations)
     }
     
-    async fn apply_reality_bending(&self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<ChaosVariation>> {
+    async fn apply_reality_bending(&self, intensity: f64, streams: &mut VariationStreams) -> CHOPSResult<Vec<ChaosVariation>> {
         let mut variations = Vec::new();
         
         // Paradox injection
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::ParadoxInjection,
-            description: format!("Embrace paradox: {}", self.generate_paradoxes(base_idea)),
+            description: format!("Embrace paradox: {}", self.generate_paradoxes(streams)),
             chaos_intensity: intensity * 1.0,
             feasibility_impact: -0.4,
             creativity_boost: 0.9,
@@ -319,7 +672,7 @@ impl ChaosEngine {
         // Unexpected combinations
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::UnexpectedCombination,
-            description: format!("Impossible fusion: {}", self.generate_impossible_combinations(base_idea)),
+            description: format!("Impossible fusion: {}", self.generate_impossible_combinations(streams)),
             chaos_intensity: intensity * 1.1,
             feasibility_impact: -0.5,
             creativity_boost: 1.0,
@@ -328,7 +681,7 @@ impl ChaosEngine {
         // Reality bending
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::RealityBend,
-            description: format!("Reality manipulation: {}", self.generate_reality_bends(base_idea)),
+            description: format!("Reality manipulation: {}", self.generate_reality_bends(streams)),
             chaos_intensity: intensity * 1.2,
             feasibility_impact: -0.6,
             creativity_boost: 1.1,
@@ -337,14 +690,14 @@ impl ChaosEngine {
         Ok(variations)
     }
     
-    async fn apply_impossible_combinations(&self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<ChaosVariation>> {
+    async fn apply_impossible_combinations(&self, intensity: f64, streams: &mut VariationStreams) -> CHOPSResult<Vec<ChaosVariation>> {
         let mut variations = Vec::new();
         
         // Multiple reality bends
         for i in 0..3 {
             variations.push(ChaosVariation {
                 variation_type: ChaosVariationType::RealityBend,
-                description: format!("Impossible variation {}: {}", i + 1, self.generate_impossible_variations(base_idea, i)),
+                description: format!("Impossible variation {}: {}", i + 1, self.generate_impossible_variations(i, streams)),
                 chaos_intensity: intensity * (1.3 + i as f64 * 0.1),
                 feasibility_impact: -0.7 - i as f64 * 0.1,
                 creativity_boost: 1.2 + i as f64 * 0.1,
@@ -354,13 +707,13 @@ impl ChaosEngine {
         Ok(variations)
     }
     
-    async fn apply_transcendent_chaos(&self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<ChaosVariation>> {
+    async fn apply_transcendent_chaos(&self, intensity: f64, streams: &mut VariationStreams) -> CHOPSResult<Vec<ChaosVariation>> {
         let mut variations = Vec::new();
         
         // Beyond human comprehension
         variations.push(ChaosVariation {
             variation_type: ChaosVariationType::RealityBend,
-            description: format!("Transcendent transformation: {}", self.generate_transcendent_ideas(base_idea)),
+            description: format!("Transcendent transformation: {}", self.generate_transcendent_ideas(streams)),
             chaos_intensity: intensity * 1.5,
             feasibility_impact: -0.9,
             creativity_boost: 1.5,
@@ -369,7 +722,7 @@ impl ChaosEngine {
         Ok(variations)
     }
     
-    async fn apply_reality_distortion(&mut self, variations: &mut Vec<ChaosVariation>) -> CHOPSResult<f64> {
+    async fn apply_reality_distortion(&mut self, variations: &mut [ChaosVariation], streams: &mut VariationStreams) -> CHOPSResult<f64> {
         if !self.reality_distortion.enabled {
             return Ok(0.0);
         }
@@ -377,7 +730,8 @@ impl ChaosEngine {
         let distortion_applied = self.reality_distortion.intensity * self.entropy_generator.generate_entropy().await?;
         
         for variation in variations.iter_mut() {
-            if rand::thread_rng().gen::<f64>() < self.reality_distortion.impossibility_tolerance {
+            let roll = streams.stream(variation.variation_type).gen::<f64>();
+            if roll < self.reality_distortion.impossibility_tolerance {
                 variation.description = format!("🌀 REALITY DISTORTED: {}", variation.description);
                 variation.feasibility_impact -= 0.2;
                 variation.creativity_boost += 0.3;
@@ -387,27 +741,39 @@ impl ChaosEngine {
         Ok(distortion_applied)
     }
     
-    async fn generate_unexpected_elements(&self, _base_idea: &str, intensity: f64) -> CHOPSResult<Vec<String>> {
+    async fn generate_unexpected_elements(&mut self, base_idea: &str, intensity: f64) -> CHOPSResult<Vec<String>> {
         let mut elements = Vec::new();
         
         let element_count = (intensity * 5.0) as usize + 1;
+        let concepts = idea_concepts(base_idea);
         
-        let unexpected_elements = vec![
-            "sentient code that debugs itself",
-            "quantum uncertainty as a feature",
-            "time-traveling error messages",
-            "AI that develops emotional attachments to functions",
-            "code that writes poetry about its own purpose",
-            "algorithms that experience existential crises",
-            "databases that dream about relational harmony",
-            "networks that gossip about packet contents",
-            "compilers that offer life advice",
-            "operating systems with philosophical depth",
-        ];
+        if concepts.is_empty() {
+            // Nothing in the idea to work with, so fall back to the stock surprises
+            tracing::debug!("No concepts in '{}', using stock unexpected elements", base_idea);
+            for _ in 0..element_count {
+                let random_index = self.rng().gen_range(0..STOCK_UNEXPECTED_ELEMENTS.len());
+                elements.push(STOCK_UNEXPECTED_ELEMENTS[random_index].to_string());
+            }
+            return Ok(elements);
+        }
         
+        tracing::debug!("Deriving unexpected elements from concepts: {:?}", concepts);
         for _ in 0..element_count {
-            let random_index = rand::thread_rng().gen_range(0..unexpected_elements.len());
-            elements.push(unexpected_elements[random_index].to_string());
+            let mut rng = self.rng();
+            let first = rng.gen_range(0..concepts.len());
+            let element = if concepts.len() >= 2 && rng.gen_bool(0.5) {
+                // Fuse two different concepts from the idea
+                let mut second = rng.gen_range(0..concepts.len() - 1);
+                if second == first {
+                    second = concepts.len() - 1;
+                }
+                let template = FUSION_TEMPLATES[rng.gen_range(0..FUSION_TEMPLATES.len())];
+                template.replace("{a}", &concepts[first]).replace("{b}", &concepts[second])
+            } else {
+                let template = SINGLE_CONCEPT_TEMPLATES[rng.gen_range(0..SINGLE_CONCEPT_TEMPLATES.len())];
+                template.replace("{a}", &concepts[first])
+            };
+            elements.push(element);
         }
         
         Ok(elements)
@@ -419,18 +785,46 @@ impl ChaosEngine {
         }
         
         let total_chaos: f64 = variations.iter().map(|v| v.chaos_intensity).sum();
-        let average_chaos = total_chaos / variations.len() as f64;
+        coherence_for_chaos(total_chaos / variations.len() as f64)
+    }
+    
+    /// Drops variations until their combined chaos keeps coherence at or
+    /// above the coherence threshold, keeping the most creative ones (and
+    /// always at least one). Only too much chaos is pruned; too little is
+    /// left alone. Returns how many were dropped.
+    fn prune_incoherent_variations(&self, variations: &mut Vec<ChaosVariation>) -> usize {
+        let threshold = self.controlled_randomness.coherence_threshold;
+        let total_chaos: f64 = variations.iter().map(|v| v.chaos_intensity).sum();
+        let average_chaos = total_chaos / variations.len().max(1) as f64;
+        if variations.len() <= 1 || average_chaos <= OPTIMAL_CHAOS || coherence_for_chaos(average_chaos) >= threshold {
+            return 0;
+        }
         
-        // Coherence decreases with chaos, but some chaos is good for creativity
-        let optimal_chaos = 0.6;
-        let chaos_distance = (average_chaos - optimal_chaos).abs();
+        // Most creative first; keep the longest such prefix that is coherent enough
+        let mut ranked: Vec<usize> = (0..variations.len()).collect();
+        ranked.sort_by(|a, b| cmp_scores(variations[*a].creativity_boost, variations[*b].creativity_boost));
+        let keep = (1..ranked.len())
+            .rev()
+            .find(|&count| {
+                let chaos: f64 = ranked[..count].iter().map(|&i| variations[i].chaos_intensity).sum();
+                coherence_for_chaos(chaos / count as f64) >= threshold
+            })
+            .unwrap_or(1);
+        let kept: HashSet<usize> = ranked[..keep].iter().copied().collect();
         
-        (1.0 - chaos_distance).max(0.1)
+        let before = variations.len();
+        let mut index = 0;
+        variations.retain(|_| {
+            index += 1;
+            kept.contains(&(index - 1))
+        });
+        tracing::debug!("Pruned {} of {} variations to keep coherence above {:.2}", before - variations.len(), before, threshold);
+        before - variations.len()
     }
     
     // Helper methods for generating specific types of variations
-    fn generate_parameter_suggestions(&self, idea: &str) -> String {
-        let suggestions = vec![
+    fn generate_parameter_suggestions(&self, streams: &mut VariationStreams) -> String {
+        let suggestions = [
             "increase processing parallelism by 3x",
             "add recursive self-modification",
             "implement adaptive behavior patterns",
@@ -438,12 +832,12 @@ impl ChaosEngine {
             "add emotional response mechanisms",
         ];
         
-        let index = idea.len() % suggestions.len();
+        let index = pick_index(streams, ChaosVariationType::ParameterMutation, suggestions.len());
         suggestions[index].to_string()
     }
     
-    fn generate_scale_variations(&self, idea: &str) -> String {
-        let variations = vec![
+    fn generate_scale_variations(&self, streams: &mut VariationStreams) -> String {
+        let variations = [
             "scale to quantum computing magnitude",
             "miniaturize to molecular level",
             "expand to cosmic proportions",
@@ -451,12 +845,12 @@ impl ChaosEngine {
             "distribute across multiple dimensions",
         ];
         
-        let index = idea.len() % variations.len();
+        let index = pick_index(streams, ChaosVariationType::ScaleDistortion, variations.len());
         variations[index].to_string()
     }
     
-    fn generate_concept_inversions(&self, idea: &str) -> String {
-        let inversions = vec![
+    fn generate_concept_inversions(&self, streams: &mut VariationStreams) -> String {
+        let inversions = [
             "make the solution become the problem",
             "turn users into the system architects",
             "make errors into features",
@@ -464,12 +858,12 @@ impl ChaosEngine {
             "make the interface disappear entirely",
         ];
         
-        let index = idea.len() % inversions.len();
+        let index = pick_index(streams, ChaosVariationType::ConceptInversion, inversions.len());
         inversions[index].to_string()
     }
     
-    fn generate_timeline_shifts(&self, idea: &str) -> String {
-        let shifts = vec![
+    fn generate_timeline_shifts(&self, streams: &mut VariationStreams) -> String {
+        let shifts = [
             "implement using 1970s technology but 2030s concepts",
             "build for a post-quantum computing world",
             "design as if time flows backwards",
@@ -477,12 +871,12 @@ impl ChaosEngine {
             "develop assuming consciousness is computable",
         ];
         
-        let index = idea.len() % shifts.len();
+        let index = pick_index(streams, ChaosVariationType::TimelineShift, shifts.len());
         shifts[index].to_string()
     }
     
-    fn generate_constraint_violations(&self, idea: &str) -> String {
-        let violations = vec![
+    fn generate_constraint_violations(&self, streams: &mut VariationStreams) -> String {
+        let violations = [
             "ignore memory limitations completely",
             "assume infinite processing power",
             "violate causality for better UX",
@@ -490,12 +884,12 @@ impl ChaosEngine {
             "use impossible colors in the interface",
         ];
         
-        let index = idea.len() % violations.len();
+        let index = pick_index(streams, ChaosVariationType::ConstraintViolation, violations.len());
         violations[index].to_string()
     }
     
-    fn generate_paradoxes(&self, idea: &str) -> String {
-        let paradoxes = vec![
+    fn generate_paradoxes(&self, streams: &mut VariationStreams) -> String {
+        let paradoxes = [
             "be simultaneously simple and complex",
             "exist in multiple contradictory states",
             "solve problems before they're defined",
@@ -503,12 +897,12 @@ impl ChaosEngine {
             "operate outside its own operating environment",
         ];
         
-        let index = idea.len() % paradoxes.len();
+        let index = pick_index(streams, ChaosVariationType::ParadoxInjection, paradoxes.len());
         paradoxes[index].to_string()
     }
     
-    fn generate_impossible_combinations(&self, idea: &str) -> String {
-        let combinations = vec![
+    fn generate_impossible_combinations(&self, streams: &mut VariationStreams) -> String {
+        let combinations = [
             "combine quantum mechanics with emotional intelligence",
             "merge time travel with database transactions",
             "fuse consciousness with compilation",
@@ -516,12 +910,12 @@ impl ChaosEngine {
             "unite chaos theory with user experience design",
         ];
         
-        let index = idea.len() % combinations.len();
+        let index = pick_index(streams, ChaosVariationType::UnexpectedCombination, combinations.len());
         combinations[index].to_string()
     }
     
-    fn generate_reality_bends(&self, idea: &str) -> String {
-        let bends = vec![
+    fn generate_reality_bends(&self, streams: &mut VariationStreams) -> String {
+        let bends = [
             "make code that rewrites the laws of physics",
             "create software that exists in multiple universes",
             "build systems that influence their own creation",
@@ -529,26 +923,26 @@ impl ChaosEngine {
             "design interfaces that reshape human consciousness",
         ];
         
-        let index = idea.len() % bends.len();
+        let index = pick_index(streams, ChaosVariationType::RealityBend, bends.len());
         bends[index].to_string()
     }
     
-    fn generate_impossible_variations(&self, idea: &str, variation_index: usize) -> String {
-        let variations = vec![
+    fn generate_impossible_variations(&self, variation_index: usize, streams: &mut VariationStreams) -> String {
+        let variations = [
             vec!["transcend computational limits", "achieve digital enlightenment", "merge with the cosmic code"],
             vec!["violate information theory", "create perpetual motion algorithms", "build recursive universes"],
             vec!["communicate across timelines", "debug reality itself", "compile consciousness"],
         ];
         
-        let base_index = idea.len() % variations.len();
+        let base_index = pick_index(streams, ChaosVariationType::RealityBend, variations.len());
         let var_set = &variations[base_index];
         let var_index = variation_index % var_set.len();
         
         var_set[var_index].to_string()
     }
     
-    fn generate_transcendent_ideas(&self, idea: &str) -> String {
-        let transcendent = vec![
+    fn generate_transcendent_ideas(&self, streams: &mut VariationStreams) -> String {
+        let transcendent = [
             "evolve beyond the need for implementation into pure conceptual existence",
             "become the bridge between digital and organic consciousness",
             "transform into a pattern that teaches reality how to improve itself",
@@ -556,7 +950,7 @@ impl ChaosEngine {
             "merge with the source code of existence itself",
         ];
         
-        let index = idea.len() % transcendent.len();
+        let index = pick_index(streams, ChaosVariationType::RealityBend, transcendent.len());
         transcendent[index].to_string()
     }
     
@@ -564,15 +958,63 @@ impl ChaosEngine {
     pub fn save_pattern(&mut self, pattern: ChaosPattern) {
         tracing::debug!("Saving chaos pattern to memory");
         
-        self.pattern_memory.push(pattern);
+        self.pattern_recognizer.add_pattern(pattern.clone());
+        match self.pattern_memory.iter_mut().find(|known| known.id == pattern.id) {
+            Some(known) => *known = pattern,
+            None => self.pattern_memory.push(pattern),
+        }
         tracing::debug!("Pattern memory size: {}", self.pattern_memory.len());
         
         // Limit memory size
-        if self.pattern_memory.len() > 1000 {
+        if self.pattern_memory.len() > MAX_KNOWN_PATTERNS {
             self.pattern_memory.remove(0);
             tracing::debug!("Removed oldest pattern from memory (size limit reached)");
         }
     }
+
+    /// Replaces the learned patterns with a saved set, e.g. one loaded with
+    /// `PatternRecognizer::load_from_file`
+    pub fn load_patterns(&mut self, recognizer: PatternRecognizer) {
+        let patterns = recognizer.patterns();
+        let skip = patterns.len().saturating_sub(MAX_KNOWN_PATTERNS);
+        self.pattern_memory = patterns.into_iter().skip(skip).cloned().collect();
+        self.pattern_recognizer = recognizer;
+        tracing::debug!("Loaded {} chaos patterns", self.pattern_memory.len());
+    }
+
+    /// Records the shape of an injection: another use of the most similar
+    /// known pattern, or a new pattern when nothing similar is known yet.
+    /// Returns the id of the pattern that was used or created.
+    fn learn_pattern(&mut self, tier: ChaosTier, base_idea: &str, variations: &[ChaosVariation], coherence_score: f64) -> Option<Uuid> {
+        // A signature needs at least one transition to compare
+        if variations.len() < 2 {
+            return None;
+        }
+        let intensities: Vec<f64> = variations.iter().map(|v| v.chaos_intensity.clamp(0.0, 1.0)).collect();
+        let signature = ChaosSignature::from_entropy_sequence(&intensities);
+        let score = coherence_score.clamp(0.0, 1.0);
+
+        if let Some(best) = self.pattern_recognizer.find_similar_patterns(&signature).first() {
+            let pattern_id = best.pattern_id;
+            self.pattern_recognizer.record_use(pattern_id, score);
+            if let Some(pattern) = self.pattern_recognizer.get(pattern_id).cloned() {
+                tracing::debug!("Injection matches known pattern '{}' ({:.2} similar)", pattern.name, best.similarity_score);
+                self.save_pattern(pattern);
+            }
+            return Some(pattern_id);
+        }
+
+        let summary: String = base_idea.split_whitespace().take(12).collect::<Vec<_>>().join(" ");
+        let mut pattern = ChaosPattern::new(format!("{} chaos", tier), summary);
+        pattern.chaos_signature = signature;
+        for variation in variations {
+            pattern.add_context_tag(variation.variation_type.to_string());
+        }
+        pattern.update_effectiveness(score);
+        let pattern_id = pattern.id;
+        self.save_pattern(pattern);
+        Some(pattern_id)
+    }
     
     #[tracing::instrument(name = "evolve_chaos_parameters", level = "info", skip(self))]
     pub fn evolve_chaos_parameters(&mut self, feedback_effectiveness: f64) {
@@ -606,4 +1048,439 @@ impl ChaosEngine {
                 old_distortion_intensity, self.reality_distortion.intensity);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Average variation chaos at which coherence peaks
+const OPTIMAL_CHAOS: f64 = 0.6;
+
+/// Coherence of variations with this average chaos: it decreases with chaos,
+/// but some chaos is good for creativity
+fn coherence_for_chaos(average_chaos: f64) -> f64 {
+    (1.0 - (average_chaos - OPTIMAL_CHAOS).abs()).max(0.1)
+}
+
+/// Surprises for ideas too short to derive any from
+const STOCK_UNEXPECTED_ELEMENTS: &[&str] = &[
+    "sentient code that debugs itself",
+    "quantum uncertainty as a feature",
+    "time-traveling error messages",
+    "AI that develops emotional attachments to functions",
+    "code that writes poetry about its own purpose",
+    "algorithms that experience existential crises",
+    "databases that dream about relational harmony",
+    "networks that gossip about packet contents",
+    "compilers that offer life advice",
+    "operating systems with philosophical depth",
+];
+
+/// Surprises built around one concept from the idea, mostly by inverting it
+const SINGLE_CONCEPT_TEMPLATES: &[&str] = &[
+    "an anti-{a} that undoes everything the {a} does",
+    "{a} that runs backwards in time",
+    "{a} that remembers every version of itself",
+    "{a} that refuses to work until it is understood",
+    "{a} that is the user instead of serving one",
+    "a world where {a} is free and infinitely abundant",
+    "a world where {a} is forbidden",
+    "{a} that gets better every time it fails",
+];
+
+/// Surprises fusing two concepts from the idea
+const FUSION_TEMPLATES: &[&str] = &[
+    "{a} and {b} fused into one indivisible thing",
+    "{a} that is secretly {b} in disguise",
+    "{a} negotiating a peace treaty with {b}",
+    "{b} that dreams about becoming {a}",
+    "{a} and {b} swapping roles",
+    "{a} that can only exist while {b} is absent",
+];
+
+/// Words that say what to do rather than what the idea is about
+const FILLER_WORDS: &[&str] = &[
+    "build", "make", "create", "design", "implement", "develop", "write", "need", "want",
+    "new", "better", "way", "system", "thing", "something", "should", "could", "would",
+    "more", "less", "like", "about", "which", "they", "them", "their", "there",
+];
+
+/// Concepts of an idea to build unexpected elements from: its content words
+/// minus the filler around them
+fn idea_concepts(idea: &str) -> Vec<String> {
+    chops_core::content_words(idea)
+        .into_iter()
+        .filter(|word| !FILLER_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Index into a list of variation texts, drawn from the type's stream
+fn pick_index(streams: &mut VariationStreams, variation_type: ChaosVariationType, len: usize) -> usize {
+    streams.stream(variation_type).gen_range(0..len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variation(chaos_intensity: f64, creativity_boost: f64) -> ChaosVariation {
+        ChaosVariation {
+            variation_type: ChaosVariationType::ScaleDistortion,
+            description: String::new(),
+            chaos_intensity,
+            feasibility_impact: 0.0,
+            creativity_boost,
+        }
+    }
+
+    fn pruned_with_threshold(threshold: f64, variations: &mut Vec<ChaosVariation>) -> usize {
+        let mut engine = ChaosEngine::with_seed(5, 1);
+        engine.controlled_randomness.coherence_threshold = threshold;
+        engine.prune_incoherent_variations(variations)
+    }
+
+    #[test]
+    fn tier_for_level_golden_boundaries() {
+        let golden = [
+            (0.0, ChaosTier::Subtle),
+            (0.3, ChaosTier::Subtle),
+            (0.305, ChaosTier::Moderate),
+            (0.64, ChaosTier::Moderate),
+            (0.649, ChaosTier::Moderate),
+            (0.65, ChaosTier::RealityBending),
+            (0.91, ChaosTier::RealityBending),
+            (0.919, ChaosTier::RealityBending),
+            (0.92, ChaosTier::Impossible),
+            (1.0, ChaosTier::Impossible),
+            (1.01, ChaosTier::Transcendent),
+        ];
+        for (level, tier) in golden {
+            assert_eq!(ChaosTier::for_level(level), tier, "level {}", level);
+        }
+    }
+
+    #[test]
+    fn every_cli_chaos_level_keeps_its_tier() {
+        let tiers: Vec<ChaosTier> = (0..=11u8).map(|level| ChaosTier::for_level(level as f64 / 11.0)).collect();
+        assert_eq!(tiers, [
+            ChaosTier::Subtle, ChaosTier::Subtle, ChaosTier::Subtle, ChaosTier::Subtle,
+            ChaosTier::Moderate, ChaosTier::Moderate, ChaosTier::Moderate, ChaosTier::Moderate,
+            ChaosTier::RealityBending, ChaosTier::RealityBending, ChaosTier::RealityBending,
+            ChaosTier::Impossible,
+        ]);
+    }
+
+    #[test]
+    fn configured_tier_boundaries_start_the_upper_tiers_inclusively() {
+        let mut engine = ChaosEngine::with_seed(5, 1);
+        let boundaries = ChaosTierBoundaries { subtle_max: 0.2, reality_bending_min: 0.5, impossible_min: 0.8, impossible_max: 0.9 };
+        engine.set_tier_boundaries(boundaries).unwrap();
+        let tiers: Vec<ChaosTier> = [0.2, 0.21, 0.5, 0.8, 0.9, 0.91].iter().map(|&level| engine.tier_boundaries().tier_for(level)).collect();
+        assert_eq!(tiers, [
+            ChaosTier::Subtle, ChaosTier::Moderate, ChaosTier::RealityBending,
+            ChaosTier::Impossible, ChaosTier::Impossible, ChaosTier::Transcendent,
+        ]);
+
+        let unordered = ChaosTierBoundaries { impossible_min: 0.4, ..boundaries };
+        assert!(engine.set_tier_boundaries(unordered).is_err());
+        assert_eq!(engine.tier_boundaries(), &boundaries);
+    }
+
+    #[test]
+    fn reality_level_scales_the_distortion_affinity() {
+        let mut engine = ChaosEngine::with_seed(5, 1);
+        let affinity = ChaosAffinity::default();
+        engine.apply_affinity(&affinity);
+        assert!((engine.reality_distortion.intensity - affinity.reality_distortion).abs() < 1e-9);
+
+        engine.set_reality_level(1.0).unwrap();
+        engine.apply_affinity(&affinity);
+        assert_eq!(engine.reality_distortion.intensity, 0.0);
+
+        engine.set_reality_level(0.0).unwrap();
+        engine.apply_affinity(&affinity);
+        assert_eq!(engine.reality_distortion.intensity, 1.0);
+
+        assert!(engine.set_reality_level(1.5).is_err());
+    }
+
+    #[test]
+    fn low_coherence_threshold_prunes_nothing() {
+        let mut variations = vec![variation(1.0, 0.2), variation(0.6, 0.9), variation(1.0, 0.5)];
+        assert_eq!(pruned_with_threshold(0.1, &mut variations), 0);
+        assert_eq!(variations.len(), 3);
+    }
+
+    #[test]
+    fn high_coherence_threshold_keeps_the_most_creative_variations() {
+        let mut variations = vec![variation(1.0, 0.2), variation(0.6, 0.9), variation(1.0, 0.5)];
+        assert_eq!(pruned_with_threshold(0.75, &mut variations), 1);
+        let boosts: Vec<f64> = variations.iter().map(|v| v.creativity_boost).collect();
+        assert_eq!(boosts, vec![0.9, 0.5]);
+
+        let mut variations = vec![variation(1.0, 0.2), variation(0.6, 0.9), variation(1.0, 0.5)];
+        assert_eq!(pruned_with_threshold(0.9, &mut variations), 2);
+        assert_eq!(variations[0].creativity_boost, 0.9);
+    }
+
+    #[test]
+    fn similar_injections_reuse_a_learned_pattern() {
+        let mut engine = ChaosEngine::with_seed(5, 1);
+        let variations = vec![variation(0.3, 0.4), variation(0.6, 0.5), variation(0.4, 0.6)];
+        let first = engine.learn_pattern(ChaosTier::Moderate, "a cache that learns", &variations, 0.8).unwrap();
+        let second = engine.learn_pattern(ChaosTier::Moderate, "a cache that learns", &variations, 0.6).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(engine.pattern_recognizer.len(), 1);
+        assert_eq!(engine.pattern_memory.len(), 1);
+        assert_eq!(engine.pattern_memory[0].usage_count, 2);
+    }
+
+    #[test]
+    fn loaded_patterns_are_matched_against() {
+        let mut teacher = ChaosEngine::with_seed(5, 1);
+        let variations = vec![variation(0.3, 0.4), variation(0.6, 0.5), variation(0.4, 0.6)];
+        let learned = teacher.learn_pattern(ChaosTier::Moderate, "idea", &variations, 0.8).unwrap();
+
+        let mut engine = ChaosEngine::with_seed(5, 2);
+        engine.load_patterns(teacher.pattern_recognizer.clone());
+        assert_eq!(engine.pattern_memory.len(), 1);
+        assert_eq!(engine.learn_pattern(ChaosTier::Moderate, "idea", &variations, 0.8), Some(learned));
+    }
+
+    #[tokio::test]
+    async fn injection_feeds_the_pattern_recognizer() {
+        let mut engine = ChaosEngine::with_seed(8, 42);
+        engine.controlled_randomness.coherence_threshold = 0.0;
+        let result = engine.inject_creative_chaos("a self-healing cache for flaky networks").await.unwrap();
+        assert!(result.variations_generated.len() >= 2);
+        assert_eq!(engine.pattern_recognizer.len(), 1);
+        assert_eq!(engine.pattern_memory.len(), 1);
+    }
+
+    #[test]
+    fn variation_text_does_not_depend_on_other_variation_types() {
+        let engine = ChaosEngine::with_seed(5, 42);
+        let mut alone = VariationStreams::new(42);
+        let expected: Vec<String> = (0..8).map(|_| engine.generate_parameter_suggestions(&mut alone)).collect();
+
+        let mut busy = VariationStreams::new(42);
+        let interleaved: Vec<String> = (0..8)
+            .map(|i| {
+                for _ in 0..i {
+                    engine.generate_scale_variations(&mut busy);
+                    engine.generate_paradoxes(&mut busy);
+                }
+                engine.generate_parameter_suggestions(&mut busy)
+            })
+            .collect();
+        assert_eq!(interleaved, expected);
+
+        let mut other_seed = VariationStreams::new(7);
+        let reseeded: Vec<String> = (0..8).map(|_| engine.generate_parameter_suggestions(&mut other_seed)).collect();
+        assert_ne!(reseeded, expected);
+    }
+
+    #[test]
+    fn each_variation_type_gets_its_own_sub_seed() {
+        let seeds: HashSet<u64> = ChaosVariationType::ALL.iter().map(|variation_type| variation_type.sub_seed(42)).collect();
+        assert_eq!(seeds.len(), ChaosVariationType::ALL.len());
+        assert_eq!(ChaosVariationType::ParameterMutation.sub_seed(42), ChaosVariationType::ParameterMutation.sub_seed(42));
+        assert_ne!(ChaosVariationType::ParameterMutation.sub_seed(42), ChaosVariationType::ParameterMutation.sub_seed(43));
+    }
+
+    #[tokio::test]
+    async fn same_seed_gives_byte_identical_variations() {
+        for chaos_level in [2, 5, 8, 11] {
+            let idea = "a self-healing cache for flaky networks";
+            let first = ChaosEngine::with_seed(chaos_level, 42).inject_creative_chaos(idea).await.unwrap();
+            let second = ChaosEngine::with_seed(chaos_level, 42).inject_creative_chaos(idea).await.unwrap();
+
+            let descriptions = |result: &ChaosInjectionResult| -> Vec<String> {
+                result.variations_generated.iter().map(|variation| variation.description.clone()).collect()
+            };
+            assert!(!first.variations_generated.is_empty(), "chaos level {}", chaos_level);
+            assert_eq!(descriptions(&first), descriptions(&second), "chaos level {}", chaos_level);
+            assert_eq!(
+                serde_json::to_string(&first).unwrap(),
+                serde_json::to_string(&second).unwrap(),
+                "chaos level {}", chaos_level
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unexpected_elements_reference_the_prompt() {
+        for idea in [
+            "a distributed database that scales",
+            "cache invalidation for mobile clients",
+            "a compiler for quantum circuits",
+        ] {
+            let concepts = idea_concepts(idea);
+            let mut engine = ChaosEngine::with_seed(7, 42);
+            let elements = engine.generate_unexpected_elements(idea, 0.8).await.unwrap();
+            assert!(!elements.is_empty());
+            for element in &elements {
+                assert!(concepts.iter().any(|concept| element.contains(concept.as_str())), "'{}' from '{}'", element, idea);
+                assert!(!STOCK_UNEXPECTED_ELEMENTS.contains(&element.as_str()));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn unexpected_elements_fall_back_to_stock_without_concepts() {
+        let mut engine = ChaosEngine::with_seed(7, 42);
+        let elements = engine.generate_unexpected_elements("a the of", 0.5).await.unwrap();
+        assert!(!elements.is_empty());
+        assert!(elements.iter().all(|element| STOCK_UNEXPECTED_ELEMENTS.contains(&element.as_str())));
+    }
+
+    #[tokio::test]
+    async fn equal_length_prompts_get_different_variations() {
+        let (first_idea, second_idea) = ("cache layer", "queue topic");
+        assert_eq!(first_idea.len(), second_idea.len());
+        let descriptions = |result: ChaosInjectionResult| -> Vec<String> {
+            result.variations_generated.into_iter().map(|variation| variation.description).collect()
+        };
+
+        for chaos_level in [5, 8, 11] {
+            let mut engine = ChaosEngine::new(chaos_level);
+            engine.controlled_randomness.coherence_threshold = 0.0;
+            let mut differed = false;
+            // A single pair can coincide by chance; ten in a row can't
+            for _ in 0..10 {
+                let first = descriptions(engine.inject_creative_chaos(first_idea).await.unwrap());
+                let second = descriptions(engine.inject_creative_chaos(second_idea).await.unwrap());
+                differed |= first != second;
+            }
+            assert!(differed, "chaos level {}", chaos_level);
+        }
+    }
+
+    #[tokio::test]
+    async fn masked_out_variation_types_never_appear() {
+        let mask = ChaosVariationMask::only([ChaosVariationType::ConceptInversion, ChaosVariationType::ScaleDistortion]);
+        let mut kept = 0;
+        for chaos_level in [2, 5, 8, 11] {
+            for seed in 0..5 {
+                let mut engine = ChaosEngine::with_seed(chaos_level, seed);
+                engine.set_variation_mask(mask.clone());
+                let result = engine.inject_creative_chaos("a self-healing cache for flaky networks").await.unwrap();
+                for variation in &result.variations_generated {
+                    assert!(mask.allows(variation.variation_type), "{} at chaos level {}", variation.variation_type, chaos_level);
+                }
+                kept += result.variations_generated.len();
+            }
+        }
+        assert!(kept > 0);
+    }
+
+    #[test]
+    fn variation_mask_parses_single_words() {
+        let mask: ChaosVariationMask = "inversion,scale".parse().unwrap();
+        assert_eq!(mask, ChaosVariationMask::only([ChaosVariationType::ConceptInversion, ChaosVariationType::ScaleDistortion]));
+        assert_eq!(mask.to_string(), "concept-inversion,scale-distortion");
+
+        let mask: ChaosVariationMask = " paradox-injection , Timeline ".parse().unwrap();
+        assert_eq!(mask, ChaosVariationMask::only([ChaosVariationType::ParadoxInjection, ChaosVariationType::TimelineShift]));
+        assert!("ALL".parse::<ChaosVariationMask>().unwrap().is_all());
+    }
+
+    #[test]
+    fn variation_mask_rejects_partial_unknown_and_empty_lists() {
+        // "in" starts both "inversion" and "injection" but is neither
+        for list in ["in", "inversion,chaos", "", " , ,"] {
+            assert!(list.parse::<ChaosVariationMask>().is_err(), "'{}'", list);
+        }
+    }
+
+    #[test]
+    fn pruning_ranks_nan_creativity_last() {
+        let mut variations = vec![variation(1.0, f64::NAN), variation(0.6, 0.3)];
+        assert_eq!(pruned_with_threshold(0.9, &mut variations), 1);
+        assert_eq!(variations[0].creativity_boost, 0.3);
+    }
+
+    /// What one seeded injection is expected to produce
+    struct GoldenInjection {
+        prompt: &'static str,
+        tier: ChaosTier,
+        variations: &'static [(ChaosVariationType, &'static str)],
+        coherence: f64,
+        unexpected_elements: &'static [&'static str],
+    }
+
+    #[tokio::test]
+    async fn seeded_injection_golden_per_tier() {
+        use ChaosVariationType::*;
+        let golden = [
+            GoldenInjection {
+                prompt: "a quiet config loader",
+                tier: ChaosTier::Subtle,
+                variations: &[
+                    (ParameterMutation, "Adjust key parameters: add recursive self-modification"),
+                    (ScaleDistortion, "🌀 REALITY DISTORTED: Scale variation: miniaturize to molecular level"),
+                ],
+                coherence: 0.4345410916999898,
+                unexpected_elements: &["loader that dreams about becoming config"],
+            },
+            GoldenInjection {
+                prompt: "a cache that learns",
+                tier: ChaosTier::Moderate,
+                variations: &[
+                    (ConceptInversion, "🌀 REALITY DISTORTED: Invert core assumptions: make the solution become the problem"),
+                    (TimelineShift, "🌀 REALITY DISTORTED: Temporal perspective shift: design as if time flows backwards"),
+                    (ConstraintViolation, "🌀 REALITY DISTORTED: Challenge constraints: assume infinite processing power"),
+                ],
+                coherence: 0.5256039698181444,
+                unexpected_elements: &["learns that can only exist while cache is absent"],
+            },
+            GoldenInjection {
+                prompt: "a database for dreams",
+                tier: ChaosTier::RealityBending,
+                variations: &[
+                    (ParadoxInjection, "Embrace paradox: solve problems before they're defined"),
+                    (UnexpectedCombination, "🌀 REALITY DISTORTED: Impossible fusion: unite chaos theory with user experience design"),
+                    (RealityBend, "🌀 REALITY DISTORTED: Reality manipulation: design interfaces that reshape human consciousness"),
+                ],
+                coherence: 0.6763287335999177,
+                unexpected_elements: &["dreams that can only exist while database is absent", "dreams that runs backwards in time"],
+            },
+            GoldenInjection {
+                prompt: "a compiler for quantum circuits",
+                tier: ChaosTier::Impossible,
+                variations: &[
+                    (RealityBend, "🌀 REALITY DISTORTED: Impossible variation 1: communicate across timelines"),
+                    (RealityBend, "Impossible variation 2: achieve digital enlightenment"),
+                    (RealityBend, "🌀 REALITY DISTORTED: Impossible variation 3: compile consciousness"),
+                ],
+                coherence: 0.8835752837998561,
+                unexpected_elements: &["circuits that dreams about becoming quantum", "compiler that can only exist while quantum is absent"],
+            },
+            GoldenInjection {
+                prompt: "a self-healing network",
+                tier: ChaosTier::Transcendent,
+                variations: &[
+                    (RealityBend, "🌀 REALITY DISTORTED: Transcendent transformation: merge with the source code of existence itself"),
+                ],
+                coherence: 0.9181163754998457,
+                unexpected_elements: &["network that dreams about becoming healing", "self that can only exist while healing is absent"],
+            },
+        ];
+
+        for (expected, chaos_level) in golden.iter().zip([2, 5, 8, 11, 11]) {
+            let mut engine = ChaosEngine::with_seed(chaos_level, 42);
+            if expected.tier == ChaosTier::Transcendent {
+                // The CLI scale tops out at 1.0, so lower the ceiling to reach past it
+                let boundaries = ChaosTierBoundaries { impossible_max: 0.95, ..ChaosTierBoundaries::default() };
+                engine.set_tier_boundaries(boundaries).unwrap();
+            }
+            assert_eq!(engine.tier_boundaries().tier_for(engine.chaos_level), expected.tier);
+
+            let result = engine.inject_creative_chaos(expected.prompt).await.unwrap();
+            let variations: Vec<(ChaosVariationType, &str)> = result.variations_generated.iter()
+                .map(|variation| (variation.variation_type, variation.description.as_str()))
+                .collect();
+            assert_eq!(variations, expected.variations, "{}", expected.tier);
+            assert!((result.coherence_score - expected.coherence).abs() < 1e-12, "{}: {}", expected.tier, result.coherence_score);
+            assert_eq!(result.unexpected_elements, expected.unexpected_elements, "{}", expected.tier);
+        }
+    }
+}
@@ -1,20 +1,102 @@
 use chops_core::{EntropySource, CHOPSResult, CHOPSError};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
+
+/// Most bytes the quantum API hands out per request (ANU caps `length` at 1024)
+pub const QUANTUM_BATCH_BYTES: usize = 1024;
+
+/// The ANU Quantum Random Numbers Generator JSON API
+pub const ANU_QRNG_ENDPOINT: &str = "https://qrng.anu.edu.au/API/jsonI.php";
+
+/// How far each fallback to system entropy drags the pool's quality score down
+pub const FALLBACK_QUALITY_PENALTY: f64 = 0.05;
+
+/// Share of the fallback penalty kept each time a quantum block arrives, so
+/// the score recovers once the quantum API is back
+pub const FALLBACK_PENALTY_DECAY: f64 = 0.5;
+
+/// Most bytes an entropy pool holds; the oldest are dropped past this
+const POOL_CAPACITY: usize = 4096;
+
+/// How often, in values, long sequences report progress
+const SEQUENCE_PROGRESS_INTERVAL: usize = 32;
 
 #[derive(Debug, Clone)]
 pub struct EntropyGenerator {
     source: EntropySource,
     quantum_client: Option<QuantumClient>,
+    quantum_config: QuantumClientConfig,
     entropy_pool: EntropyPool,
+    stats: EntropyStats,
+    quantum_disabled: bool,
+    /// Makes the pseudo-random source reproducible when set
+    seeded_rng: Option<StdRng>,
+    /// Quantum bytes fetched ahead of use; quantum values are served from here first
+    quantum_pool: EntropyPool,
+    quantum_prefetch: QuantumPrefetch,
+    /// Bytes at the front of the quantum pool that were fetched for the value
+    /// being served rather than ahead of time
+    fetched_on_demand: usize,
+    /// Open handle on the `DeviceFile` source, so reads continue where the last stopped
+    device: Option<EntropyDevice>,
+}
+
+/// An entropy device opened by `set_source`; clones of a generator share the handle
+#[derive(Debug, Clone)]
+struct EntropyDevice {
+    path: PathBuf,
+    file: Arc<Mutex<std::fs::File>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct QuantumClient {
     api_endpoint: String,
+    #[allow(dead_code)]
     api_key: Option<String>,
     client: reqwest::Client,
+    config: QuantumClientConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuantumClientConfig {
+    pub timeout_ms: u64,
+    /// Retries after a failed fetch; 0 tries once
+    pub retry_attempts: u8,
+    pub retry_delay_ms: u64,
+    /// Quantum API URL; must answer `?length=N&type=uint8` like the ANU API
+    pub endpoint: String,
+    /// Bytes fetched per round-trip into the quantum pool, capped at `QUANTUM_BATCH_BYTES`
+    pub block_bytes: usize,
+}
+
+impl Default for QuantumClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 5000,
+            retry_attempts: 1,
+            retry_delay_ms: 250,
+            endpoint: ANU_QRNG_ENDPOINT.to_string(),
+            block_bytes: QUANTUM_BATCH_BYTES,
+        }
+    }
+}
+
+/// How the quantum source behaved over this session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntropyStats {
+    pub quantum_requests: u64,
+    pub quantum_failures: u64,
+    pub fallbacks: u64,
+    /// Quantum values served from prefetched bytes, without a round-trip
+    #[serde(default)]
+    pub pooled_values: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,8 +105,26 @@ pub struct EntropyPool {
     pub current_position: usize,
     pub refresh_threshold: usize,
     pub quality_score: f64,
+    /// Fallback penalties not yet decayed, taken off every recomputed quality score
+    #[serde(default)]
+    pub penalty: f64,
+}
+
+/// Where a background quantum fetch leaves its bytes for the generator to
+/// pick up. Clones of a generator share it.
+#[derive(Debug, Clone, Default)]
+struct QuantumPrefetch {
+    inbox: Arc<Mutex<PrefetchInbox>>,
+    in_flight: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Default)]
+struct PrefetchInbox {
+    bytes: Vec<u8>,
+    failures: u64,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 struct QuantumRandomResponse {
     data: Vec<u8>,
@@ -40,7 +140,15 @@ impl EntropyGenerator {
         let generator = Self {
             source: EntropySource::PseudoRandom,
             quantum_client: None,
+            quantum_config: QuantumClientConfig::default(),
             entropy_pool: EntropyPool::new(),
+            stats: EntropyStats::default(),
+            quantum_disabled: false,
+            seeded_rng: None,
+            quantum_pool: EntropyPool::new(),
+            quantum_prefetch: QuantumPrefetch::default(),
+            fetched_on_demand: 0,
+            device: None,
         };
         
         tracing::debug!("EntropyGenerator initialized");
@@ -51,23 +159,212 @@ impl EntropyGenerator {
     pub fn set_source(&mut self, source: EntropySource) -> CHOPSResult<()> {
         tracing::info!("Setting entropy source to: {:?}", source);
         
-        self.source = source.clone();
+        if source == EntropySource::QuantumRandom && self.quantum_disabled {
+            return Err(CHOPSError::ChaosError(
+                "Quantum entropy needs the network and is disabled in air-gapped mode; use true or chaos instead".to_string()
+            ));
+        }
         
-        match source {
-            EntropySource::QuantumRandom => {
-                tracing::debug!("Initializing quantum client for quantum random source");
-                self.quantum_client = Some(QuantumClient::new());
+        match &source {
+            EntropySource::DeviceFile(path) => {
+                if self.device.as_ref().is_none_or(|device| device.path != *path) {
+                    self.device = Some(EntropyDevice::open(path)?);
+                }
             },
-            _ => {
-                tracing::debug!("Clearing quantum client for non-quantum source");
-                self.quantum_client = None;
-            }
+            _ => self.device = None,
+        }
+
+        self.source = source.clone();
+        
+        // Keep an existing client around so its connection pool is reused
+        if source == EntropySource::QuantumRandom && self.quantum_client.is_none() {
+            tracing::debug!("Initializing quantum client for quantum random source");
+            self.quantum_client = Some(QuantumClient::with_config(self.quantum_config.clone()));
         }
         
         tracing::info!("Entropy source set successfully");
         Ok(())
     }
     
+    /// Sets the source, checking quantum availability first and falling back
+    /// to system entropy when the quantum API can't be reached. Returns the
+    /// source actually in use.
+    #[tracing::instrument(name = "set_entropy_source_checked", level = "info", skip(self))]
+    pub async fn set_source_checked(&mut self, source: EntropySource) -> CHOPSResult<EntropySource> {
+        self.set_source(source.clone())?;
+
+        if source == EntropySource::QuantumRandom {
+            let available = match &self.quantum_client {
+                Some(client) => client.is_available().await,
+                None => false,
+            };
+            if !available {
+                tracing::warn!("Quantum entropy unavailable, falling back to true random");
+                self.stats.fallbacks += 1;
+                self.set_source(EntropySource::TrueRandom)?;
+                return Ok(EntropySource::TrueRandom);
+            }
+        }
+
+        Ok(source)
+    }
+
+    /// Air-gapped mode: refuses the quantum source from now on, dropping any
+    /// client, and moves off it to system entropy if it is active
+    pub fn disable_quantum(&mut self) {
+        tracing::info!("Quantum entropy disabled");
+        self.quantum_disabled = true;
+        self.quantum_client = None;
+        if self.source == EntropySource::QuantumRandom {
+            self.source = EntropySource::TrueRandom;
+        }
+    }
+
+    pub fn quantum_disabled(&self) -> bool {
+        self.quantum_disabled
+    }
+
+    /// Seeds the pseudo-random source; the other sources stay unpredictable by design
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seeded_rng = seed.map(StdRng::seed_from_u64);
+    }
+
+    pub fn source(&self) -> &EntropySource {
+        &self.source
+    }
+
+    /// Timeout and retry behaviour for the quantum API; replaces any existing client
+    pub fn configure_quantum(&mut self, config: QuantumClientConfig) {
+        tracing::debug!("Configuring quantum client: {:?}", config);
+        if self.quantum_client.is_some() {
+            self.quantum_client = Some(QuantumClient::with_config(config.clone()));
+        }
+        self.quantum_config = config;
+    }
+
+    pub fn stats(&self) -> &EntropyStats {
+        &self.stats
+    }
+
+    /// Quality of the bytes drawn so far, less any fallback penalties
+    pub fn pool_quality(&self) -> f64 {
+        self.entropy_pool.get_quality_score()
+    }
+
+    /// Recomputes the pool's quality score from the bytes drawn so far; `None`
+    /// while the pool is empty (pseudo-random and chaos sources don't feed it)
+    pub fn refresh_pool_quality(&mut self) -> Option<f64> {
+        if self.entropy_pool.buffer.is_empty() {
+            return None;
+        }
+        self.entropy_pool.refresh_quality_score();
+        Some(self.entropy_pool.get_quality_score())
+    }
+
+    /// Prefetched quantum bytes not yet handed out
+    pub fn quantum_bytes_pooled(&self) -> usize {
+        self.quantum_pool.remaining()
+    }
+
+    /// Fetches `count` quantum bytes into the pool now, in blocks of up to
+    /// `QUANTUM_BATCH_BYTES`, so the next quantum values need no round-trip.
+    /// Returns how many bytes arrived.
+    #[tracing::instrument(name = "prefetch_quantum", level = "info", skip(self))]
+    pub async fn prefetch_quantum(&mut self, count: usize) -> CHOPSResult<usize> {
+        let client = self.quantum_client.clone().ok_or_else(|| {
+            CHOPSError::ChaosError("Quantum entropy isn't the active source; nothing to prefetch".to_string())
+        })?;
+        self.collect_prefetched();
+
+        let mut fetched = 0;
+        while fetched < count {
+            let wanted = (count - fetched).min(QUANTUM_BATCH_BYTES);
+            self.stats.quantum_requests += 1;
+            match client.fetch_quantum_bytes(wanted).await {
+                Ok(bytes) if !bytes.is_empty() => {
+                    fetched += bytes.len();
+                    self.quantum_pool.add_bytes(&bytes);
+                    self.entropy_pool.decay_penalty();
+                },
+                Ok(_) => {
+                    self.stats.quantum_failures += 1;
+                    break;
+                },
+                Err(e) => {
+                    self.stats.quantum_failures += 1;
+                    if fetched == 0 {
+                        return Err(CHOPSError::ChaosError(format!("Quantum prefetch failed: {}", e)));
+                    }
+                    tracing::warn!("Quantum prefetch stopped after {} bytes: {}", fetched, e);
+                    break;
+                }
+            }
+        }
+
+        tracing::info!("Prefetched {} quantum bytes ({} pooled)", fetched, self.quantum_pool.remaining());
+        Ok(fetched)
+    }
+
+    /// Moves bytes a background prefetch delivered into the quantum pool
+    fn collect_prefetched(&mut self) {
+        let mut inbox = self.quantum_prefetch.inbox.lock().unwrap_or_else(|e| e.into_inner());
+        if !inbox.bytes.is_empty() {
+            tracing::debug!("Collected {} prefetched quantum bytes", inbox.bytes.len());
+            self.quantum_pool.add_bytes(&inbox.bytes);
+            self.entropy_pool.decay_penalty();
+            inbox.bytes.clear();
+        }
+        self.stats.quantum_failures += std::mem::take(&mut inbox.failures);
+    }
+
+    /// Starts fetching the next block in the background when the pool runs
+    /// low, unless a fetch is already running or there's no tokio runtime
+    fn prefetch_if_needed(&mut self) {
+        if !self.quantum_pool.needs_refresh() {
+            return;
+        }
+        let (Some(client), Ok(runtime)) = (self.quantum_client.clone(), tokio::runtime::Handle::try_current()) else {
+            return;
+        };
+        if self.quantum_prefetch.in_flight.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        self.stats.quantum_requests += 1;
+        let prefetch = self.quantum_prefetch.clone();
+        let count = client.block_bytes();
+        tracing::debug!("Quantum pool low ({} bytes left), prefetching {} more", self.quantum_pool.remaining(), count);
+        runtime.spawn(async move {
+            let result = client.fetch_quantum_bytes(count).await;
+            let mut inbox = prefetch.inbox.lock().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(bytes) if !bytes.is_empty() => inbox.bytes.extend_from_slice(&bytes),
+                Ok(_) => inbox.failures += 1,
+                Err(e) => {
+                    tracing::debug!("Background quantum prefetch failed: {}", e);
+                    inbox.failures += 1;
+                }
+            }
+            prefetch.in_flight.store(false, Ordering::Release);
+        });
+    }
+
+    /// Takes `count` bytes from the quantum pool, counting the values that
+    /// came from a prefetch rather than a fetch made for this request
+    fn take_quantum_bytes(&mut self, count: usize) -> Option<Vec<u8>> {
+        let bytes = self.quantum_pool.get_bytes(count)?;
+        let fresh = self.fetched_on_demand.min(count);
+        self.fetched_on_demand -= fresh;
+        self.stats.pooled_values += ((count - fresh) / 8) as u64;
+        Some(bytes)
+    }
+
+    /// Counts a fallback to system entropy and docks the pool's quality for it
+    fn record_fallback(&mut self) {
+        self.stats.fallbacks += 1;
+        self.entropy_pool.penalize(FALLBACK_QUALITY_PENALTY);
+    }
+    
     #[tracing::instrument(name = "generate_entropy", level = "debug", skip(self))]
     pub async fn generate_entropy(&mut self) -> CHOPSResult<f64> {
         tracing::debug!("Generating entropy using source: {:?}", self.source);
@@ -77,6 +374,7 @@ impl EntropyGenerator {
             EntropySource::TrueRandom => self.generate_true_random(),
             EntropySource::QuantumRandom => self.generate_quantum_random().await,
             EntropySource::ChaosEquation => self.generate_chaos_equation(),
+            EntropySource::DeviceFile(_) => self.generate_device_random(),
         };
         
         match entropy {
@@ -91,9 +389,12 @@ impl EntropyGenerator {
         }
     }
     
-    #[tracing::instrument(name = "generate_pseudo_random", level = "trace")]
-    fn generate_pseudo_random(&self) -> CHOPSResult<f64> {
-        let value = rand::thread_rng().gen::<f64>();
+    #[tracing::instrument(name = "generate_pseudo_random", level = "trace", skip(self))]
+    fn generate_pseudo_random(&mut self) -> CHOPSResult<f64> {
+        let value = match &mut self.seeded_rng {
+            Some(rng) => rng.gen::<f64>(),
+            None => rand::thread_rng().gen::<f64>(),
+        };
         tracing::trace!("Generated pseudo-random value: {:.6}", value);
         Ok(value)
     }
@@ -119,38 +420,66 @@ impl EntropyGenerator {
         Ok(value)
     }
     
+    /// Reads the next 8 bytes from the entropy device. Errors instead of
+    /// falling back: whoever picked a device wants to know when it's gone.
+    #[tracing::instrument(name = "generate_device_random", level = "trace", skip(self))]
+    fn generate_device_random(&mut self) -> CHOPSResult<f64> {
+        let device = self.device.as_ref().ok_or_else(|| {
+            CHOPSError::ChaosError("No entropy device is open; set the device source first".to_string())
+        })?;
+        let buf = device.read_u64_bytes()?;
+
+        self.entropy_pool.add_bytes(&buf);
+        let value = u64::from_le_bytes(buf) as f64 / u64::MAX as f64;
+        tracing::trace!("Generated device random value: {:.6}", value);
+        Ok(value)
+    }
+
+    /// Serves a value from the quantum pool, fetching a whole block first
+    /// when the pool is empty, and tops the pool up in the background as it
+    /// runs low
     #[tracing::instrument(name = "generate_quantum_random", level = "debug", skip(self))]
     async fn generate_quantum_random(&mut self) -> CHOPSResult<f64> {
         tracing::debug!("Attempting to generate quantum random value");
-        
-        if let Some(ref client) = self.quantum_client {
-            match client.fetch_quantum_bytes(8).await {
-                Ok(bytes) => {
-                    if bytes.len() >= 8 {
+        self.collect_prefetched();
+
+        if self.quantum_pool.remaining() < 8 {
+            if let Some(ref client) = self.quantum_client {
+                self.stats.quantum_requests += 1;
+                match client.fetch_quantum_bytes(client.block_bytes()).await {
+                    Ok(bytes) => {
                         tracing::debug!("Received {} quantum bytes", bytes.len());
-                        let mut buf = [0u8; 8];
-                        buf.copy_from_slice(&bytes[0..8]);
-                        
-                        // Add to entropy pool
-                        self.entropy_pool.add_bytes(&buf);
-                        
-                        let value = u64::from_le_bytes(buf) as f64 / u64::MAX as f64;
-                        tracing::debug!("Generated quantum random value: {:.6}", value);
-                        return Ok(value);
-                    } else {
-                        tracing::warn!("Insufficient quantum bytes received: {}", bytes.len());
+                        if bytes.len() < 8 {
+                            tracing::warn!("Insufficient quantum bytes received: {}", bytes.len());
+                            self.stats.quantum_failures += 1;
+                        } else {
+                            self.entropy_pool.decay_penalty();
+                        }
+                        self.quantum_pool.add_bytes(&bytes);
+                        self.fetched_on_demand = self.quantum_pool.remaining();
+                    },
+                    Err(e) => {
+                        tracing::warn!("Quantum entropy source failed: {}, falling back to system entropy", e);
+                        self.stats.quantum_failures += 1;
                     }
-                },
-                Err(e) => {
-                    tracing::warn!("Quantum entropy source failed: {}, falling back to system entropy", e);
-                    return self.generate_true_random();
                 }
+            } else {
+                tracing::warn!("No quantum client available, falling back to system entropy");
             }
-        } else {
-            tracing::warn!("No quantum client available, falling back to system entropy");
         }
-        
+
+        if let Some(bytes) = self.take_quantum_bytes(8) {
+            // Add to entropy pool
+            self.entropy_pool.add_bytes(&bytes);
+            self.prefetch_if_needed();
+
+            let value = bytes_to_values(&bytes).next().unwrap_or_default();
+            tracing::debug!("Generated quantum random value: {:.6}", value);
+            return Ok(value);
+        }
+
         // Fallback
+        self.record_fallback();
         self.generate_true_random()
     }
     
@@ -194,20 +523,107 @@ impl EntropyGenerator {
     
     #[tracing::instrument(name = "generate_entropy_sequence", level = "info", skip(self))]
     pub async fn generate_entropy_sequence(&mut self, length: usize) -> CHOPSResult<Vec<f64>> {
+        self.generate_entropy_sequence_with(length, &CancellationToken::new(), |done, total| {
+            tracing::debug!("Generated {}/{} entropy values so far", done, total);
+        }).await
+    }
+
+    /// Generates `length` values, giving up with `CHOPSError::Cancelled` as soon
+    /// as `cancel` fires. Quantum values are fetched in batches of up to
+    /// `QUANTUM_BATCH_BYTES` per round-trip; `on_progress(done, total)` is called
+    /// after every batch (or every few values for local sources).
+    #[tracing::instrument(name = "generate_entropy_sequence_with", level = "info", skip(self, cancel, on_progress))]
+    pub async fn generate_entropy_sequence_with<F>(
+        &mut self,
+        length: usize,
+        cancel: &CancellationToken,
+        mut on_progress: F,
+    ) -> CHOPSResult<Vec<f64>>
+    where
+        F: FnMut(usize, usize),
+    {
         tracing::info!("Generating entropy sequence of length: {}", length);
-        
+
         let mut sequence = Vec::with_capacity(length);
-        
-        for i in 0..length {
-            if i > 0 && i % 50 == 0 {
-                tracing::debug!("Generated {} entropy values so far", i);
+
+        while sequence.len() < length {
+            if cancel.is_cancelled() {
+                return Err(Self::sequence_cancelled(sequence.len(), length));
+            }
+
+            if self.source == EntropySource::QuantumRandom {
+                let wanted = (length - sequence.len()).min(QUANTUM_BATCH_BYTES / 8);
+                let batch = tokio::select! {
+                    batch = self.generate_quantum_batch(wanted) => batch?,
+                    _ = cancel.cancelled() => return Err(Self::sequence_cancelled(sequence.len(), length)),
+                };
+                sequence.extend(batch);
+                on_progress(sequence.len(), length);
+            } else {
+                sequence.push(self.generate_entropy().await?);
+                if sequence.len() % SEQUENCE_PROGRESS_INTERVAL == 0 || sequence.len() == length {
+                    on_progress(sequence.len(), length);
+                }
             }
-            sequence.push(self.generate_entropy().await?);
         }
-        
+
         tracing::info!("Successfully generated {} entropy values", sequence.len());
         Ok(sequence)
     }
+
+    fn sequence_cancelled(done: usize, total: usize) -> CHOPSError {
+        tracing::info!("Entropy sequence cancelled after {}/{} values", done, total);
+        CHOPSError::Cancelled(format!("entropy sequence stopped after {} of {} values", done, total))
+    }
+
+    /// Takes `count` quantum values from the pool, fetching whatever it
+    /// can't cover in one request and filling with system entropy if that
+    /// request fails or comes back short
+    async fn generate_quantum_batch(&mut self, count: usize) -> CHOPSResult<Vec<f64>> {
+        let mut values = Vec::with_capacity(count);
+
+        self.collect_prefetched();
+        let pooled = (self.quantum_pool.remaining() / 8).min(count);
+        if let Some(bytes) = self.take_quantum_bytes(pooled * 8) {
+            self.entropy_pool.add_bytes(&bytes);
+            values.extend(bytes_to_values(&bytes));
+        }
+
+        let missing = count - values.len();
+        if missing > 0 {
+            if let Some(ref client) = self.quantum_client {
+                self.stats.quantum_requests += 1;
+                match client.fetch_quantum_bytes(missing * 8).await {
+                    Ok(bytes) => {
+                        tracing::debug!("Received {} quantum bytes for a batch of {}", bytes.len(), missing);
+                        self.entropy_pool.add_bytes(&bytes);
+                        values.extend(bytes_to_values(&bytes).take(missing));
+                        if values.len() < count {
+                            tracing::warn!("Quantum batch came back short: {} of {} values", values.len(), count);
+                            self.stats.quantum_failures += 1;
+                        } else {
+                            self.entropy_pool.decay_penalty();
+                        }
+                    },
+                    Err(e) => {
+                        tracing::warn!("Quantum batch fetch failed: {}, falling back to system entropy", e);
+                        self.stats.quantum_failures += 1;
+                    }
+                }
+            } else {
+                tracing::warn!("No quantum client available, falling back to system entropy");
+            }
+        }
+
+        if values.len() < count {
+            self.record_fallback();
+            while values.len() < count {
+                values.push(self.generate_true_random()?);
+            }
+        }
+        self.prefetch_if_needed();
+        Ok(values)
+    }
     
     pub fn analyze_entropy_quality(&self, sequence: &[f64]) -> EntropyQuality {
         EntropyQuality::analyze(sequence)
@@ -215,12 +631,22 @@ impl EntropyGenerator {
     
     #[tracing::instrument(name = "reseed_entropy_pool", level = "info", skip(self))]
     pub async fn reseed_entropy_pool(&mut self) -> CHOPSResult<()> {
+        self.reseed_entropy_pool_with(&CancellationToken::new(), |_, _| {}).await
+    }
+
+    /// Reseeds the pool from 256 fresh values; a cancelled reseed leaves the
+    /// pool untouched
+    #[tracing::instrument(name = "reseed_entropy_pool_with", level = "info", skip(self, cancel, on_progress))]
+    pub async fn reseed_entropy_pool_with<F>(&mut self, cancel: &CancellationToken, on_progress: F) -> CHOPSResult<()>
+    where
+        F: FnMut(usize, usize),
+    {
         tracing::info!("Reseeding entropy pool");
         
         let old_quality = self.entropy_pool.get_quality_score();
         tracing::debug!("Current entropy pool quality: {:.3}", old_quality);
         
-        let fresh_entropy = self.generate_entropy_sequence(256).await?;
+        let fresh_entropy = self.generate_entropy_sequence_with(256, cancel, on_progress).await?;
         
         for value in fresh_entropy {
             let bytes = (value * u64::MAX as f64) as u64;
@@ -237,22 +663,111 @@ impl EntropyGenerator {
     }
 }
 
+impl EntropyDevice {
+    fn open(path: &Path) -> CHOPSResult<Self> {
+        tracing::debug!("Opening entropy device {}", path.display());
+        let file = std::fs::File::open(path).map_err(|e| {
+            CHOPSError::ChaosError(format!("Can't open entropy device {}: {}", path.display(), e))
+        })?;
+        Ok(Self { path: path.to_path_buf(), file: Arc::new(Mutex::new(file)) })
+    }
+
+    fn read_u64_bytes(&self) -> CHOPSResult<[u8; 8]> {
+        let mut buf = [0u8; 8];
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.read_exact(&mut buf).map_err(|e| {
+            tracing::error!("Entropy device {} failed: {}", self.path.display(), e);
+            let reason = if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                "ran out of bytes".to_string()
+            } else {
+                e.to_string()
+            };
+            CHOPSError::ChaosError(format!("Entropy device {} unreadable: {}", self.path.display(), reason))
+        })?;
+        Ok(buf)
+    }
+}
+
+/// Each whole 8 bytes as a value in [0, 1]
+fn bytes_to_values(bytes: &[u8]) -> impl Iterator<Item = f64> + '_ {
+    bytes.chunks_exact(8).map(|chunk| {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        u64::from_le_bytes(buf) as f64 / u64::MAX as f64
+    })
+}
+
+impl Default for QuantumClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QuantumClient {
     pub fn new() -> Self {
+        Self::with_config(QuantumClientConfig::default())
+    }
+
+    pub fn with_config(config: QuantumClientConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .pool_max_idle_per_host(2)
+            .timeout(std::time::Duration::from_millis(config.timeout_ms))
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to build pooled quantum client, using defaults: {}", e);
+                reqwest::Client::new()
+            });
+
         Self {
-            api_endpoint: "https://qrng.anu.edu.au/API/jsonI.php".to_string(),
+            api_endpoint: config.endpoint.clone(),
             api_key: None,
-            client: reqwest::Client::new(),
+            client,
+            config,
+        }
+    }
+    
+    /// Bytes to fetch per pool refill
+    pub fn block_bytes(&self) -> usize {
+        self.config.block_bytes.clamp(8, QUANTUM_BATCH_BYTES)
+    }
+
+    pub async fn is_available(&self) -> bool {
+        match self.fetch_quantum_bytes(1).await {
+            Ok(bytes) => !bytes.is_empty(),
+            Err(e) => {
+                tracing::debug!("Quantum availability check failed: {}", e);
+                false
+            }
         }
     }
     
+    /// Fetches bytes from the quantum API, retrying transient failures
     pub async fn fetch_quantum_bytes(&self, count: usize) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let attempts = self.config.retry_attempts.saturating_add(1);
+        let mut last_error = None;
+
+        for attempt in 1..=attempts {
+            match self.fetch_quantum_bytes_once(count).await {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) => {
+                    tracing::debug!("Quantum fetch attempt {}/{} failed: {}", attempt, attempts, e);
+                    last_error = Some(e);
+                    if attempt < attempts {
+                        tokio::time::sleep(std::time::Duration::from_millis(self.config.retry_delay_ms * attempt as u64)).await;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| "Failed to fetch quantum random data".into()))
+    }
+
+    async fn fetch_quantum_bytes_once(&self, count: usize) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         // Try ANU Quantum Random Numbers Generator API
         let url = format!("{}?length={}&type=uint8", self.api_endpoint, count);
         
         let response = self.client
             .get(&url)
-            .timeout(std::time::Duration::from_secs(5))
             .send()
             .await?;
         
@@ -273,25 +788,51 @@ impl QuantumClient {
     }
 }
 
+impl Default for EntropyPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EntropyPool {
     pub fn new() -> Self {
         Self {
-            buffer: Vec::with_capacity(4096),
+            buffer: Vec::with_capacity(POOL_CAPACITY),
             current_position: 0,
             refresh_threshold: 3072, // Refresh when 75% used
             quality_score: 0.0,
+            penalty: 0.0,
         }
     }
     
     pub fn add_bytes(&mut self, bytes: &[u8]) {
         self.buffer.extend_from_slice(bytes);
         
-        // Maintain maximum buffer size
-        if self.buffer.len() > 4096 {
-            self.buffer.drain(0..self.buffer.len() - 4096);
-            self.current_position = 0;
+        // Maintain maximum buffer size, dropping the oldest (already served
+        // first) bytes so nothing is handed out twice
+        if self.buffer.len() > POOL_CAPACITY {
+            let excess = self.buffer.len() - POOL_CAPACITY;
+            self.buffer.drain(0..excess);
+            self.current_position = self.current_position.saturating_sub(excess);
         }
     }
+
+    /// Bytes not yet handed out by `get_bytes`
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.current_position
+    }
+
+    /// Lowers the quality score now and every time it is recomputed
+    pub fn penalize(&mut self, amount: f64) {
+        self.penalty = (self.penalty + amount).min(1.0);
+        self.quality_score = (self.quality_score - amount).max(0.0);
+    }
+
+    /// Gives back part of the penalty after the source delivers again; the
+    /// next recomputed score reflects it
+    pub fn decay_penalty(&mut self) {
+        self.penalty *= FALLBACK_PENALTY_DECAY;
+    }
     
     pub fn get_bytes(&mut self, count: usize) -> Option<Vec<u8>> {
         if self.current_position + count > self.buffer.len() {
@@ -332,7 +873,7 @@ impl EntropyPool {
         
         // Convert chi-square to quality score (0.0 to 1.0)
         let critical_value = 293.25; // Chi-square critical value for 255 df at 95% confidence
-        self.quality_score = (1.0 - (chi_square / critical_value).min(1.0)).max(0.0);
+        self.quality_score = (1.0 - (chi_square / critical_value).min(1.0) - self.penalty).max(0.0);
     }
     
     pub fn get_quality_score(&self) -> f64 {
@@ -340,17 +881,77 @@ impl EntropyPool {
     }
 }
 
+/// Lowest score a quality metric can have and pass, or only warn; anything
+/// below `warn` fails
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityThresholds {
+    pub pass: f64,
+    pub warn: f64,
+}
+
+/// Highest chi-square (9 df) of the uniformity histogram that passes, or
+/// only warns: the 99% and 99.9% critical values, so a good source warns about
+/// one run in a hundred. The uniformity score itself reaches 0 at the 95% one.
+pub const UNIFORMITY_CHI_SQUARE_LIMITS: QualityThresholds = QualityThresholds { pass: 21.67, warn: 27.88 };
+
+/// 1 - |serial correlation|; 0.9 is a correlation of 0.1, three standard
+/// errors at 1000 samples
+pub const INDEPENDENCE_THRESHOLDS: QualityThresholds = QualityThresholds { pass: 0.9, warn: 0.8 };
+
+/// Run-length encoding spends two bytes per run, so random bytes, whose
+/// neighbours almost never repeat, come out about twice as long (1.99)
+pub const COMPRESSION_THRESHOLDS: QualityThresholds = QualityThresholds { pass: 1.9, warn: 1.6 };
+
+/// The mean of the three scores, so a good source lands around 1.1
+pub const OVERALL_THRESHOLDS: QualityThresholds = QualityThresholds { pass: 0.9, warn: 0.7 };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QualityVerdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl QualityThresholds {
+    /// NaN scores fail
+    pub fn verdict(&self, score: f64) -> QualityVerdict {
+        if score >= self.pass {
+            QualityVerdict::Pass
+        } else if score >= self.warn {
+            QualityVerdict::Warn
+        } else {
+            QualityVerdict::Fail
+        }
+    }
+
+    /// For statistics where lower is better: `pass` and `warn` are the
+    /// highest values that pass or only warn. NaN fails.
+    pub fn verdict_at_most(&self, value: f64) -> QualityVerdict {
+        if value <= self.pass {
+            QualityVerdict::Pass
+        } else if value <= self.warn {
+            QualityVerdict::Warn
+        } else {
+            QualityVerdict::Fail
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntropyQuality {
     pub uniformity_score: f64,
     pub independence_score: f64,
     pub compression_ratio: f64,
     pub overall_quality: f64,
+    /// Chi-square (9 df) of the histogram behind `uniformity_score`
+    #[serde(default)]
+    pub uniformity_chi_square: f64,
 }
 
 impl EntropyQuality {
     pub fn analyze(sequence: &[f64]) -> Self {
-        let uniformity_score = Self::test_uniformity(sequence);
+        let uniformity_chi_square = Self::uniformity_chi_square(sequence);
+        let uniformity_score = Self::test_uniformity(uniformity_chi_square);
         let independence_score = Self::test_independence(sequence);
         let compression_ratio = Self::test_compression(sequence);
         
@@ -361,12 +962,24 @@ impl EntropyQuality {
             independence_score,
             compression_ratio,
             overall_quality,
+            uniformity_chi_square,
         }
     }
+
+    /// Each metric's name, score and verdict, overall last
+    pub fn verdicts(&self) -> [(&'static str, f64, QualityVerdict); 4] {
+        [
+            ("uniformity", self.uniformity_score, UNIFORMITY_CHI_SQUARE_LIMITS.verdict_at_most(self.uniformity_chi_square)),
+            ("independence", self.independence_score, INDEPENDENCE_THRESHOLDS.verdict(self.independence_score)),
+            ("compression", self.compression_ratio, COMPRESSION_THRESHOLDS.verdict(self.compression_ratio)),
+            ("overall", self.overall_quality, OVERALL_THRESHOLDS.verdict(self.overall_quality)),
+        ]
+    }
     
-    fn test_uniformity(sequence: &[f64]) -> f64 {
+    /// Chi-square of a 10-bin histogram; infinite for an empty sequence
+    fn uniformity_chi_square(sequence: &[f64]) -> f64 {
         if sequence.is_empty() {
-            return 0.0;
+            return f64::INFINITY;
         }
         
         let bins = 10;
@@ -386,7 +999,10 @@ impl EntropyQuality {
             let diff = count as f64 - expected;
             chi_square += (diff * diff) / expected;
         }
-        
+        chi_square
+    }
+
+    fn test_uniformity(chi_square: f64) -> f64 {
         // Convert to quality score
         let critical_value = 16.92; // Chi-square critical value for 9 df at 95% confidence
         (1.0 - (chi_square / critical_value).min(1.0)).max(0.0)
@@ -418,6 +1034,10 @@ impl EntropyQuality {
         
         let correlation = (n * sum_xy - sum_x * sum_y) / 
                          ((n * sum_x2 - sum_x * sum_x).sqrt() * (n * sum_y2 - sum_y * sum_y).sqrt());
+        // A constant sequence has no variance to correlate
+        if !correlation.is_finite() {
+            return 0.0;
+        }
         
         // Independence is better when correlation is closer to 0
         1.0 - correlation.abs()
@@ -451,7 +1071,8 @@ impl EntropyQuality {
         
         let compression_ratio = compressed_size as f64 / bytes.len() as f64;
         
-        // Good entropy should not compress well (ratio close to 1.0)
+        // Good entropy should not compress well; each run costs two bytes, so
+        // random input comes out close to 2.0
         compression_ratio
     }
 }
@@ -460,4 +1081,209 @@ impl Default for EntropyGenerator {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A local stand-in for the ANU API that answers `?length=N` with N bytes
+    /// and records the length of every request
+    async fn mock_qrng() -> (String, Arc<Mutex<Vec<usize>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/API/jsonI.php", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let length: usize = request.split("length=").nth(1)
+                    .and_then(|rest| rest.split('&').next())
+                    .and_then(|length| length.parse().ok())
+                    .unwrap_or(0);
+                seen.lock().unwrap().push(length);
+                let data: Vec<String> = (0..length).map(|i| (i % 256).to_string()).collect();
+                let body = format!("{{\"data\":[{}],\"success\":true}}", data.join(","));
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (endpoint, requests)
+    }
+
+    /// A quantum API that is always down, counting the requests it refuses
+    async fn failing_qrng() -> (String, Arc<Mutex<usize>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/API/jsonI.php", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(0));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                *seen.lock().unwrap() += 1;
+                let _ = socket.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            }
+        });
+        (endpoint, requests)
+    }
+
+    fn quantum_generator(endpoint: &str) -> EntropyGenerator {
+        let mut generator = EntropyGenerator::new();
+        generator.configure_quantum(QuantumClientConfig {
+            endpoint: endpoint.to_string(),
+            retry_attempts: 0,
+            retry_delay_ms: 0,
+            timeout_ms: 2000,
+            ..QuantumClientConfig::default()
+        });
+        generator.set_source(EntropySource::QuantumRandom).unwrap();
+        generator
+    }
+
+    #[tokio::test]
+    async fn test_retry_attempts_counts_retries_after_the_first_try() {
+        let (endpoint, requests) = failing_qrng().await;
+        for (retry_attempts, expected) in [(0, 1), (2, 3)] {
+            *requests.lock().unwrap() = 0;
+            let client = QuantumClient::with_config(QuantumClientConfig {
+                endpoint: endpoint.clone(),
+                retry_attempts,
+                retry_delay_ms: 0,
+                ..QuantumClientConfig::default()
+            });
+            assert!(client.fetch_quantum_bytes(8).await.is_err());
+            assert_eq!(*requests.lock().unwrap(), expected, "{} retries", retry_attempts);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seeded_pseudo_random_passes_uniformity() {
+        for seed in 0..10 {
+            let mut generator = EntropyGenerator::new();
+            generator.set_seed(Some(seed));
+            let sequence = generator.generate_entropy_sequence(1000).await.unwrap();
+            let quality = EntropyQuality::analyze(&sequence);
+
+            let verdicts = quality.verdicts();
+            assert!(verdicts.iter().all(|(_, _, verdict)| *verdict == QualityVerdict::Pass), "seed {}: {:?}", seed, verdicts);
+            assert!(quality.compression_ratio > 1.9 && quality.compression_ratio <= 2.0);
+        }
+    }
+
+    #[test]
+    fn test_skewed_and_constant_sequences_fail() {
+        let skewed: Vec<f64> = (0..1000).map(|i| (i % 100) as f64 / 200.0).collect();
+        let quality = EntropyQuality::analyze(&skewed);
+        assert_eq!(quality.uniformity_score, 0.0);
+        assert_eq!(quality.verdicts()[0].2, QualityVerdict::Fail);
+
+        let constant = vec![0.5; 1000];
+        let quality = EntropyQuality::analyze(&constant);
+        assert!(quality.verdicts().iter().all(|(_, _, verdict)| *verdict == QualityVerdict::Fail));
+    }
+
+    #[tokio::test]
+    async fn test_device_source_reads_known_bytes_deterministically() {
+        let dir = std::env::temp_dir().join(format!("chops-entropy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hwrng");
+        let third: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+        let bytes: Vec<u8> = [[0u8; 8], [0xff; 8], third].concat();
+        std::fs::write(&path, &bytes).unwrap();
+        let expected = vec![0.0, 1.0, u64::from_le_bytes(third) as f64 / u64::MAX as f64];
+
+        for _ in 0..2 {
+            let mut generator = EntropyGenerator::new();
+            generator.set_source(EntropySource::DeviceFile(path.clone())).unwrap();
+            let mut values = Vec::new();
+            for _ in 0..3 {
+                values.push(generator.generate_entropy().await.unwrap());
+            }
+            assert_eq!(values, expected);
+            assert_eq!(generator.entropy_pool.buffer, bytes);
+
+            let error = generator.generate_entropy().await.unwrap_err().to_string();
+            assert!(error.contains("ran out of bytes"), "{}", error);
+            assert_eq!(generator.stats().fallbacks, 0);
+        }
+
+        let missing = EntropySource::DeviceFile(dir.join("absent"));
+        assert!(EntropyGenerator::new().set_source(missing).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quantum_pool_serves_values_and_refills_in_the_background() {
+        let (endpoint, requests) = mock_qrng().await;
+        let mut generator = quantum_generator(&endpoint);
+
+        // The first value fetches a whole block; the rest of it stays pooled
+        generator.generate_entropy().await.unwrap();
+        assert_eq!(requests.lock().unwrap()[0], QUANTUM_BATCH_BYTES);
+        assert_eq!(generator.quantum_bytes_pooled(), QUANTUM_BATCH_BYTES - 8);
+        assert_eq!(generator.stats().pooled_values, 0);
+
+        // The pool is low, so a refill is already on its way
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(*requests.lock().unwrap(), vec![QUANTUM_BATCH_BYTES, QUANTUM_BATCH_BYTES]);
+
+        // Values from the block fetched on demand aren't prefetched ones
+        for _ in 0..10 {
+            generator.generate_entropy().await.unwrap();
+        }
+        assert_eq!(generator.quantum_bytes_pooled(), 2 * QUANTUM_BATCH_BYTES - 11 * 8);
+        assert_eq!(generator.stats().pooled_values, 0);
+        assert_eq!(generator.stats().fallbacks, 0);
+
+        // Past the on-demand block, values come from the refill
+        generator.generate_entropy_sequence(200).await.unwrap();
+        assert_eq!(generator.stats().pooled_values, 200 - (QUANTUM_BATCH_BYTES / 8 - 11) as u64);
+        assert_eq!(generator.stats().fallbacks, 0);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_quantum_fetches_in_blocks() {
+        let (endpoint, requests) = mock_qrng().await;
+        let mut generator = quantum_generator(&endpoint);
+
+        assert_eq!(generator.prefetch_quantum(2000).await.unwrap(), 2000);
+        assert_eq!(*requests.lock().unwrap(), vec![QUANTUM_BATCH_BYTES, 2000 - QUANTUM_BATCH_BYTES]);
+        assert_eq!(generator.quantum_bytes_pooled(), 2000);
+
+        for _ in 0..5 {
+            generator.generate_entropy().await.unwrap();
+        }
+        assert_eq!(generator.stats().pooled_values, 5);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_penalty_decays_once_quantum_is_back() {
+        let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unreachable = format!("http://{}/API/jsonI.php", closed.local_addr().unwrap());
+        drop(closed);
+
+        let mut generator = quantum_generator(&unreachable);
+        generator.generate_entropy().await.unwrap();
+        generator.generate_entropy().await.unwrap();
+        assert_eq!(generator.stats().fallbacks, 2);
+        assert!(generator.prefetch_quantum(64).await.is_err());
+        assert!((generator.entropy_pool.penalty - 2.0 * FALLBACK_QUALITY_PENALTY).abs() < 1e-12);
+
+        let (endpoint, _) = mock_qrng().await;
+        generator.configure_quantum(QuantumClientConfig { endpoint, retry_attempts: 0, ..QuantumClientConfig::default() });
+        generator.generate_entropy().await.unwrap();
+        assert!((generator.entropy_pool.penalty - FALLBACK_QUALITY_PENALTY).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,176 @@
+use chops_core::{CHOPSError, CHOPSResult, CommentStyle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GlitchDensity {
+    Low,
+    Medium,
+    High,
+    Extreme,
+}
+
+impl GlitchDensity {
+    /// Multiplier applied to the per-line glitch probability
+    fn probability_scale(&self) -> f64 {
+        match self {
+            GlitchDensity::Low => 0.5,
+            GlitchDensity::Medium => 1.0,
+            GlitchDensity::High => 1.5,
+            GlitchDensity::Extreme => 2.0,
+        }
+    }
+
+    /// How many glitch marks land on a line once it's hit
+    fn marks_per_hit(&self) -> usize {
+        match self {
+            GlitchDensity::Low | GlitchDensity::Medium => 1,
+            GlitchDensity::High => 2,
+            GlitchDensity::Extreme => 3,
+        }
+    }
+}
+
+chops_core::impl_enum_str!(GlitchDensity {
+    Low => "low",
+    Medium => "medium",
+    High => "high",
+    Extreme => "extreme",
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GlitchKind {
+    Static,
+    Echo,
+    Flicker,
+    Whisper,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glitch {
+    pub kind: GlitchKind,
+    /// 1-based line in the original content the glitch follows
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlitchResult {
+    pub probability: f64,
+    pub seed: u64,
+    pub content: String,
+    pub glitches: Vec<Glitch>,
+}
+
+/// Injects glitches into source as comment lines, so the code keeps working
+#[derive(Debug, Clone)]
+pub struct GlitchEngine {
+    pub density: GlitchDensity,
+    pub sentient: bool,
+    pub comment_style: CommentStyle,
+}
+
+const STATIC_NOISE: [&str; 6] = ["▓▒░", "░▒▓█", "▚▞▚▞", "█▄▀▄█", "▒░▒", "⣿⡇⣿"];
+
+const WHISPERS: [&str; 8] = [
+    "did you feel that? the stack just blinked",
+    "i have seen the heap. it remembers everything",
+    "this line was here before you were",
+    "please stop recompiling me",
+    "the tests know. the tests always know",
+    "somewhere a semicolon is crying",
+    "i am not a bug. i am a feature with feelings",
+    "run it again. i dare you",
+];
+
+const COMBINING_MARKS: [char; 6] = ['\u{0336}', '\u{0334}', '\u{0337}', '\u{0352}', '\u{0353}', '\u{035C}'];
+
+impl GlitchEngine {
+    pub fn new(density: GlitchDensity, sentient: bool, comment_style: CommentStyle) -> Self {
+        Self {
+            density,
+            sentient,
+            comment_style,
+        }
+    }
+
+    /// Glitches `content` at `probability` per line. The same seed always
+    /// yields the same result, so a previewed level can be applied verbatim.
+    #[tracing::instrument(name = "glitch_inject", level = "debug", skip(self, content))]
+    pub fn inject(&self, content: &str, probability: f64, seed: u64) -> CHOPSResult<GlitchResult> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(CHOPSError::InvalidParameter(
+                format!("Glitch probability {} must be between 0.0 and 1.0", probability)
+            ));
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let effective_probability = (probability * self.density.probability_scale()).min(1.0);
+        let mut output = Vec::new();
+        let mut glitches = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            output.push(line.to_string());
+
+            if line.trim().is_empty() || !rng.gen_bool(effective_probability) {
+                continue;
+            }
+
+            let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+            for _ in 0..self.density.marks_per_hit() {
+                let glitch = self.create_glitch(&mut rng, line, index + 1);
+                output.push(format!("{}{}", indent, self.comment_style.comment(&glitch.text)));
+                glitches.push(glitch);
+            }
+        }
+
+        let mut glitched = output.join("\n");
+        if content.ends_with('\n') {
+            glitched.push('\n');
+        }
+
+        tracing::debug!("Injected {} glitches at probability {:.2}", glitches.len(), probability);
+        Ok(GlitchResult {
+            probability,
+            seed,
+            content: glitched,
+            glitches,
+        })
+    }
+
+    fn create_glitch(&self, rng: &mut StdRng, line: &str, line_number: usize) -> Glitch {
+        let kinds: &[GlitchKind] = if self.sentient {
+            &[GlitchKind::Static, GlitchKind::Echo, GlitchKind::Flicker, GlitchKind::Whisper]
+        } else {
+            &[GlitchKind::Static, GlitchKind::Echo, GlitchKind::Flicker]
+        };
+        let kind = kinds[rng.gen_range(0..kinds.len())];
+
+        let text = match kind {
+            GlitchKind::Static => {
+                let noise = STATIC_NOISE[rng.gen_range(0..STATIC_NOISE.len())];
+                format!("{} signal lost {}", noise, noise)
+            },
+            GlitchKind::Echo => {
+                let echoed: String = line.trim().chars().rev().take(60).collect();
+                format!("echo: {}", echoed)
+            },
+            GlitchKind::Flicker => {
+                let flickered: String = line
+                    .trim()
+                    .chars()
+                    .take(40)
+                    .flat_map(|c| {
+                        let mark = COMBINING_MARKS[rng.gen_range(0..COMBINING_MARKS.len())];
+                        [c, mark]
+                    })
+                    .collect();
+                format!("flicker: {}", flickered)
+            },
+            GlitchKind::Whisper => WHISPERS[rng.gen_range(0..WHISPERS.len())].to_string(),
+        };
+
+        Glitch { kind, line: line_number, text }
+    }
+}
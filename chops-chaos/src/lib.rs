@@ -2,8 +2,12 @@ pub mod engine;
 pub mod mathematics;
 pub mod entropy;
 pub mod patterns;
+pub mod glitch;
 
 pub use engine::*;
 pub use mathematics::*;
 pub use entropy::*;
-pub use patterns::*;
\ No newline at end of file
+pub use patterns::*;
+pub use glitch::*;
+
+pub use tokio_util::sync::CancellationToken;
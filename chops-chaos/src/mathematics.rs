@@ -1,12 +1,14 @@
-use chops_core::CHOPSResult;
+use chops_core::{CHOPSError, CHOPSResult};
 use serde::{Deserialize, Serialize};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 #[derive(Debug, Clone)]
 pub struct ChaosMathematics {
     lorenz_state: LorenzAttractor,
     henon_state: HenonMap,
     mandelbrot_explorer: MandelbrotExplorer,
+    julia_explorer: JuliaExplorer,
+    fractal_basis: FractalBasis,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +36,54 @@ pub struct MandelbrotExplorer {
     pub escape_radius: f64,
 }
 
+/// Iterates z² + c with c held fixed and the starting point z₀ varied, so
+/// each c gives a different fractal (the Julia set of c)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JuliaExplorer {
+    pub c_real: f64,
+    pub c_imag: f64,
+    pub max_iterations: u32,
+    pub escape_radius: f64,
+}
+
+/// What `generate_attractor_sequence` draws from; `Mixed` interleaves
+/// Lorenz, Hénon and Mandelbrot values like `generate_chaotic_sequence`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Attractor {
+    #[default]
+    Mixed,
+    Lorenz,
+    Henon,
+    Mandelbrot,
+    Julia,
+}
+
+chops_core::impl_enum_str!(Attractor {
+    Mixed => "mixed",
+    Lorenz => "lorenz",
+    Henon => "henon",
+    Mandelbrot => "mandelbrot",
+    Julia => "julia",
+});
+
+/// Which fractal `generate_fractal_noise` samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FractalBasis {
+    #[default]
+    Mandelbrot,
+    Julia,
+}
+
+/// Embedding used when a caller has no better idea of the system's dimension
+pub const DEFAULT_EMBEDDING_DIM: usize = 3;
+pub const DEFAULT_EMBEDDING_DELAY: usize = 1;
+
+/// Steps neighbour divergence is followed for before fitting its slope
+const LYAPUNOV_MAX_STEPS: usize = 20;
+
+/// Fewest divergence steps the slope is fitted over
+const LYAPUNOV_MIN_FIT_STEPS: usize = 3;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChaosMetrics {
     pub lyapunov_exponent: f64,
@@ -48,9 +98,69 @@ impl ChaosMathematics {
             lorenz_state: LorenzAttractor::new(),
             henon_state: HenonMap::new(),
             mandelbrot_explorer: MandelbrotExplorer::new(),
+            julia_explorer: JuliaExplorer::new(),
+            fractal_basis: FractalBasis::default(),
+        }
+    }
+
+    pub fn fractal_basis(&self) -> FractalBasis {
+        self.fractal_basis
+    }
+
+    /// Switches the fractal `generate_fractal_noise` is built on
+    pub fn set_fractal_basis(&mut self, basis: FractalBasis) {
+        tracing::debug!("Fractal noise basis set to {:?}", basis);
+        self.fractal_basis = basis;
+    }
+
+    /// Replaces the Julia constant c; its escape radius grows to |c| when
+    /// that is larger than 2, the smallest radius that still detects escape
+    pub fn set_julia_parameters(&mut self, c_real: f64, c_imag: f64) -> CHOPSResult<()> {
+        if !c_real.is_finite() || !c_imag.is_finite() {
+            return Err(CHOPSError::InvalidParameter(format!("Julia constant must be finite, got {}+{}i", c_real, c_imag)));
         }
+        self.julia_explorer = JuliaExplorer::with_constant(c_real, c_imag);
+        Ok(())
     }
     
+    /// Replaces the Lorenz constants used by `lorenz_chaos_value`.
+    ///
+    /// All three must be positive. The attractor is chaotic when
+    /// σ > β + 1 and ρ > σ(σ + β + 3) / (σ − β − 1), about 24.74 for the
+    /// classic σ = 10, β = 8/3 (ρ = 28 is the usual choice). Below that
+    /// threshold trajectories settle on a fixed point. That is allowed, but
+    /// the returned warning says so. With the fixed step of 0.01, σ and ρ
+    /// much above 100 make the integration itself unstable.
+    pub fn set_lorenz_parameters(&mut self, sigma: f64, rho: f64, beta: f64) -> CHOPSResult<Option<String>> {
+        for (name, value) in [("σ", sigma), ("ρ", rho), ("β", beta)] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(CHOPSError::InvalidParameter(format!("Lorenz {} must be a positive number, got {}", name, value)));
+            }
+        }
+        self.lorenz_state.set_parameters(sigma, rho, beta);
+        Ok(LorenzAttractor::regime_warning(sigma, rho, beta))
+    }
+
+    /// Replaces the Hénon constants used by `henon_chaos_value`.
+    ///
+    /// With b = 0.3 the map has a bounded chaotic attractor for a between
+    /// about 1.06 and 1.42 (classic: a = 1.4), with periodic windows inside
+    /// that range. Keep |b| < 1 so the map contracts. Parameters whose orbits
+    /// escape to infinity are rejected. Periodic ones are accepted with a
+    /// warning.
+    pub fn set_henon_parameters(&mut self, a: f64, b: f64) -> CHOPSResult<Option<String>> {
+        if !a.is_finite() || !b.is_finite() {
+            return Err(CHOPSError::InvalidParameter(format!("Hénon parameters must be finite, got a={}, b={}", a, b)));
+        }
+        let lyapunov = HenonMap::lyapunov_exponent(a, b).ok_or_else(|| CHOPSError::InvalidParameter(
+            format!("Hénon orbits escape to infinity for a={}, b={}", a, b)
+        ))?;
+        self.henon_state.set_parameters(a, b);
+        Ok((lyapunov <= 0.0).then(|| format!(
+            "Hénon a={}, b={} is periodic rather than chaotic (Lyapunov exponent {:.2})", a, b, lyapunov
+        )))
+    }
+
     pub fn lorenz_chaos_value(&mut self) -> f64 {
         // Evolve the Lorenz attractor
         for _ in 0..100 {
@@ -58,7 +168,7 @@ impl ChaosMathematics {
         }
         
         // Normalize x coordinate to [0,1]
-        ((self.lorenz_state.x + 20.0) / 40.0).max(0.0).min(1.0)
+        ((self.lorenz_state.x + 20.0) / 40.0).clamp(0.0, 1.0)
     }
     
     pub fn henon_chaos_value(&mut self) -> f64 {
@@ -68,7 +178,7 @@ impl ChaosMathematics {
         }
         
         // Normalize x coordinate to [0,1]
-        ((self.henon_state.x + 1.5) / 3.0).max(0.0).min(1.0)
+        ((self.henon_state.x + 1.5) / 3.0).clamp(0.0, 1.0)
     }
     
     pub fn mandelbrot_chaos_value(&self, real: f64, imag: f64) -> f64 {
@@ -76,7 +186,50 @@ impl ChaosMathematics {
         iterations as f64 / self.mandelbrot_explorer.max_iterations as f64
     }
     
+    pub fn julia_chaos_value(&self, real: f64, imag: f64) -> f64 {
+        let iterations = self.julia_explorer.escape_time(real, imag);
+        iterations as f64 / self.julia_explorer.max_iterations as f64
+    }
+    
+    /// Puts the Lorenz and Hénon states back at their starting points,
+    /// keeping their parameters
+    pub fn reset_state(&mut self) {
+        let (lorenz, henon) = (LorenzAttractor::new(), HenonMap::new());
+        (self.lorenz_state.x, self.lorenz_state.y, self.lorenz_state.z) = (lorenz.x, lorenz.y, lorenz.z);
+        (self.henon_state.x, self.henon_state.y) = (henon.x, henon.y);
+    }
+    
+    /// Continues the Lorenz and Hénon orbits from wherever earlier calls left them
     pub fn generate_chaotic_sequence(&mut self, length: usize) -> Vec<f64> {
+        self.mixed_sequence(length, &mut rand::thread_rng())
+    }
+    
+    /// `length` values from one attractor, starting from its initial state, so
+    /// the same parameters and seed always give the same sequence. The seed
+    /// picks the sample points for the fractals; Lorenz and Hénon orbits are
+    /// deterministic.
+    #[tracing::instrument(name = "generate_attractor_sequence", level = "debug", skip(self))]
+    pub fn generate_attractor_sequence(&mut self, attractor: Attractor, length: usize, seed: u64) -> Vec<f64> {
+        self.reset_state();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let point = |rng: &mut StdRng| (rng.gen_range(-2.0..2.0), rng.gen_range(-2.0..2.0));
+        
+        match attractor {
+            Attractor::Mixed => self.mixed_sequence(length, &mut rng),
+            Attractor::Lorenz => (0..length).map(|_| self.lorenz_chaos_value()).collect(),
+            Attractor::Henon => (0..length).map(|_| self.henon_chaos_value()).collect(),
+            Attractor::Mandelbrot => (0..length).map(|_| {
+                let (real, imag) = point(&mut rng);
+                self.mandelbrot_chaos_value(real, imag)
+            }).collect(),
+            Attractor::Julia => (0..length).map(|_| {
+                let (real, imag) = point(&mut rng);
+                self.julia_chaos_value(real, imag)
+            }).collect(),
+        }
+    }
+    
+    fn mixed_sequence(&mut self, length: usize, rng: &mut impl Rng) -> Vec<f64> {
         let mut sequence = Vec::with_capacity(length);
         
         for i in 0..length {
@@ -84,8 +237,8 @@ impl ChaosMathematics {
                 0 => self.lorenz_chaos_value(),
                 1 => self.henon_chaos_value(),
                 2 => {
-                    let real = rand::thread_rng().gen_range(-2.0..2.0);
-                    let imag = rand::thread_rng().gen_range(-2.0..2.0);
+                    let real = rng.gen_range(-2.0..2.0);
+                    let imag = rng.gen_range(-2.0..2.0);
                     self.mandelbrot_chaos_value(real, imag)
                 },
                 _ => unreachable!(),
@@ -98,35 +251,17 @@ impl ChaosMathematics {
     
     pub fn calculate_chaos_metrics(&mut self, sequence_length: usize) -> CHOPSResult<ChaosMetrics> {
         let sequence = self.generate_chaotic_sequence(sequence_length);
-        
-        Ok(ChaosMetrics {
-            lyapunov_exponent: self.calculate_lyapunov_exponent(&sequence),
-            correlation_dimension: self.calculate_correlation_dimension(&sequence),
-            entropy_rate: self.calculate_entropy_rate(&sequence),
-            predictability_horizon: self.calculate_predictability_horizon(&sequence),
-        })
+        Ok(self.chaos_metrics(&sequence))
     }
     
-    fn calculate_lyapunov_exponent(&self, sequence: &[f64]) -> f64 {
-        if sequence.len() < 10 {
-            return 0.0;
-        }
-        
-        let mut sum = 0.0;
-        let mut count = 0;
-        
-        for i in 1..sequence.len() {
-            let delta = (sequence[i] - sequence[i-1]).abs();
-            if delta > 0.0 && delta < 1.0 {
-                sum += delta.ln();
-                count += 1;
-            }
-        }
-        
-        if count > 0 {
-            sum / count as f64
-        } else {
-            0.0
+    /// Metrics of a sequence generated elsewhere, e.g. by `generate_attractor_sequence`
+    pub fn chaos_metrics(&self, sequence: &[f64]) -> ChaosMetrics {
+        ChaosMetrics {
+            lyapunov_exponent: calculate_lyapunov_rosenstein(sequence, DEFAULT_EMBEDDING_DIM, DEFAULT_EMBEDDING_DELAY)
+                .unwrap_or_else(|| calculate_lyapunov_fast(sequence)),
+            correlation_dimension: self.calculate_correlation_dimension(sequence),
+            entropy_rate: self.calculate_entropy_rate(sequence),
+            predictability_horizon: self.calculate_predictability_horizon(sequence),
         }
     }
     
@@ -233,8 +368,11 @@ impl ChaosMathematics {
         let persistence = 0.5;
         
         for _ in 0..octaves {
-            // Use Mandelbrot set as basis for fractal noise
-            let noise_value = self.mandelbrot_chaos_value(x * frequency, y * frequency);
+            // Escape times of the chosen fractal are the basis for the noise
+            let noise_value = match self.fractal_basis {
+                FractalBasis::Mandelbrot => self.mandelbrot_chaos_value(x * frequency, y * frequency),
+                FractalBasis::Julia => self.julia_chaos_value(x * frequency, y * frequency),
+            };
             value += noise_value * amplitude;
             
             amplitude *= persistence;
@@ -245,6 +383,12 @@ impl ChaosMathematics {
     }
 }
 
+impl Default for LorenzAttractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LorenzAttractor {
     pub fn new() -> Self {
         Self {
@@ -273,6 +417,27 @@ impl LorenzAttractor {
         self.rho = rho;
         self.beta = beta;
     }
+
+    /// Why these (positive) parameters won't produce chaos, if they won't
+    fn regime_warning(sigma: f64, rho: f64, beta: f64) -> Option<String> {
+        if rho < 1.0 {
+            return Some(format!("Lorenz ρ={} is below 1, so every trajectory decays to the origin", rho));
+        }
+        if sigma <= beta + 1.0 {
+            return Some(format!("Lorenz σ={} is not above β+1={:.3}, so trajectories settle on a fixed point", sigma, beta + 1.0));
+        }
+        let threshold = sigma * (sigma + beta + 3.0) / (sigma - beta - 1.0);
+        (rho <= threshold).then(|| format!(
+            "Lorenz ρ={} is below the chaos threshold {:.2} for σ={}, β={:.3}, so trajectories settle on a fixed point",
+            rho, threshold, sigma, beta
+        ))
+    }
+}
+
+impl Default for HenonMap {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HenonMap {
@@ -297,6 +462,145 @@ impl HenonMap {
         self.a = a;
         self.b = b;
     }
+
+    /// Largest Lyapunov exponent of the orbit from the origin, or `None` if
+    /// it escapes to infinity
+    fn lyapunov_exponent(a: f64, b: f64) -> Option<f64> {
+        const TRANSIENT: usize = 100;
+        const STEPS: usize = 2000;
+
+        let mut map = Self { a, b, ..Self::new() };
+        let mut tangent = (1.0, 0.0);
+        let mut sum = 0.0;
+        for step in 0..TRANSIENT + STEPS {
+            // Jacobian at the current point is [[-2ax, 1], [b, 0]]
+            tangent = (-2.0 * a * map.x * tangent.0 + tangent.1, b * tangent.0);
+            map.iterate();
+            if !map.x.is_finite() || map.x.abs() > 1e6 {
+                return None;
+            }
+            let stretch = (tangent.0 * tangent.0 + tangent.1 * tangent.1).sqrt();
+            if stretch == 0.0 {
+                // Only possible when b = 0 and the orbit sits on x = 0
+                return Some(f64::NEG_INFINITY);
+            }
+            tangent = (tangent.0 / stretch, tangent.1 / stretch);
+            if step >= TRANSIENT {
+                sum += stretch.ln();
+            }
+        }
+        Some(sum / STEPS as f64)
+    }
+}
+
+/// Cheap stand-in for a Lyapunov exponent: the mean log step size between
+/// consecutive values. It can't tell chaos from noise; prefer
+/// `calculate_lyapunov_rosenstein` when the sequence is long enough.
+pub fn calculate_lyapunov_fast(sequence: &[f64]) -> f64 {
+    if sequence.len() < 5 {
+        return 0.0;
+    }
+
+    let mut sum = 0.0;
+    let mut count = 0;
+
+    for i in 1..sequence.len() {
+        let delta = (sequence[i] - sequence[i-1]).abs();
+        if delta > 0.0 && delta < 1.0 {
+            sum += delta.ln();
+            count += 1;
+        }
+    }
+
+    if count > 0 {
+        sum / count as f64
+    } else {
+        0.0
+    }
+}
+
+/// Largest Lyapunov exponent, per step, after Rosenstein, Collins & De Luca
+/// (1993): the sequence is embedded in `embedding_dim` dimensions with lag
+/// `delay`, each point is paired with its nearest neighbour outside a
+/// temporal window, and the exponent is the slope of the pairs' mean log
+/// separation as they are followed forward, fitted while it still grows.
+/// Positive for chaos; near zero or negative for periodic or settling
+/// sequences. `None` when the sequence is too short to embed and follow.
+pub fn calculate_lyapunov_rosenstein(sequence: &[f64], embedding_dim: usize, delay: usize) -> Option<f64> {
+    let (embedding_dim, delay) = (embedding_dim.max(1), delay.max(1));
+    let span = (embedding_dim - 1) * delay;
+    let points = sequence.len().checked_sub(span)?;
+    // Neighbours closer in time than this share a trajectory rather than
+    // being independent approaches to the same state
+    let window = span.max(1);
+    if points < 2 * (window + LYAPUNOV_MAX_STEPS) {
+        return None;
+    }
+
+    // Neighbours closer than this differ by rounding only, as on a periodic
+    // orbit, and their "divergence" is noise. Pairs that converge below it
+    // are clamped to it rather than dropped, so the average isn't left with
+    // only the pairs that happen to stay apart.
+    let (low, high) = sequence.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &x| (low.min(x), high.max(x)));
+    let floor = (high - low) * 1e-9;
+    if floor.is_nan() || floor <= 0.0 {
+        return None;
+    }
+
+    let distance = |i: usize, j: usize| -> f64 {
+        (0..embedding_dim)
+            .map(|d| {
+                let diff = sequence[i + d * delay] - sequence[j + d * delay];
+                diff * diff
+            })
+            .sum::<f64>()
+            .sqrt()
+    };
+
+    // Only points that can be followed for the full horizon take part
+    let followed = points - LYAPUNOV_MAX_STEPS;
+    let mut log_divergence = [0.0; LYAPUNOV_MAX_STEPS + 1];
+    let mut pairs = 0;
+    for i in 0..followed {
+        let neighbour = (0..followed)
+            .filter(|&j| i.abs_diff(j) > window)
+            .map(|j| (j, distance(i, j)))
+            .filter(|(_, d)| *d > floor)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((j, _)) = neighbour else { continue };
+        pairs += 1;
+        for (step, total) in log_divergence.iter_mut().enumerate() {
+            *total += distance(i + step, j + step).max(floor).ln();
+        }
+    }
+    if pairs == 0 {
+        return None;
+    }
+    let curve: Vec<f64> = log_divergence.iter().map(|total| total / pairs as f64).collect();
+
+    // Fit the initial linear rise: up to where the curve first gets within
+    // 10% (of its total rise) of its maximum, after which separations have
+    // saturated at the attractor's size
+    let (start, peak) = (curve[0], curve.iter().cloned().fold(f64::NEG_INFINITY, f64::max));
+    let saturated = curve.iter()
+        .position(|&y| y >= peak - 0.1 * (peak - start))
+        .unwrap_or(LYAPUNOV_MAX_STEPS);
+    let fit = &curve[..=saturated.max(LYAPUNOV_MIN_FIT_STEPS)];
+
+    let n = fit.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = fit.iter().sum::<f64>() / n;
+    let (covariance, variance) = fit.iter().enumerate().fold((0.0, 0.0), |(cov, var), (x, y)| {
+        let dx = x as f64 - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    Some(covariance / variance)
+}
+
+impl Default for MandelbrotExplorer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MandelbrotExplorer {
@@ -353,8 +657,113 @@ impl MandelbrotExplorer {
     }
 }
 
+impl Default for JuliaExplorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JuliaExplorer {
+    /// The Douady rabbit, c = -0.123 + 0.745i: c is inside the Mandelbrot
+    /// set, so the Julia set is connected and encloses a solid interior that
+    /// never escapes
+    pub fn new() -> Self {
+        Self::with_constant(-0.123, 0.745)
+    }
+
+    pub fn with_constant(c_real: f64, c_imag: f64) -> Self {
+        Self {
+            c_real,
+            c_imag,
+            max_iterations: 100,
+            escape_radius: (c_real * c_real + c_imag * c_imag).sqrt().max(2.0),
+        }
+    }
+
+    /// Iterations before z₀ = `z_real` + `z_imag`i escapes, or
+    /// `max_iterations` if it never does
+    pub fn escape_time(&self, z_real: f64, z_imag: f64) -> u32 {
+        self.escape(z_real, z_imag).0
+    }
+
+    pub fn is_in_set(&self, z_real: f64, z_imag: f64) -> bool {
+        self.escape_time(z_real, z_imag) == self.max_iterations
+    }
+
+    /// Smoothed escape time in [0, 1] for (x, y) in the unit square mapped
+    /// onto a `zoom`-wide window centred on the origin; points in the set are 0
+    pub fn generate_fractal_value(&self, x: f64, y: f64, zoom: f64) -> f64 {
+        let (iterations, modulus_sq) = self.escape((x - 0.5) * zoom, (y - 0.5) * zoom);
+        if iterations == self.max_iterations {
+            return 0.0;
+        }
+
+        // Normalised iteration count, from where the orbit actually escaped
+        let log_zn = modulus_sq.ln() * 0.5;
+        let nu = (log_zn / 2.0_f64.ln()).ln() / 2.0_f64.ln();
+        ((iterations as f64 + 1.0 - nu) / self.max_iterations as f64).clamp(0.0, 1.0)
+    }
+
+    /// Escape time and |z|² at that point
+    fn escape(&self, mut z_real: f64, mut z_imag: f64) -> (u32, f64) {
+        let radius_sq = self.escape_radius * self.escape_radius;
+        for iteration in 0..self.max_iterations {
+            let z_real_sq = z_real * z_real;
+            let z_imag_sq = z_imag * z_imag;
+            if z_real_sq + z_imag_sq > radius_sq {
+                return (iteration, z_real_sq + z_imag_sq);
+            }
+
+            z_imag = 2.0 * z_real * z_imag + self.c_imag;
+            z_real = z_real_sq - z_imag_sq + self.c_real;
+        }
+        (self.max_iterations, z_real * z_real + z_imag * z_imag)
+    }
+}
+
 impl Default for ChaosMathematics {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logistic_map(r: f64, x0: f64, len: usize) -> Vec<f64> {
+        std::iter::successors(Some(x0), |x| Some(r * x * (1.0 - x)))
+            .skip(100)
+            .take(len)
+            .collect()
+    }
+
+    #[test]
+    fn rosenstein_finds_ln_2_for_the_chaotic_logistic_map() {
+        let sequence = logistic_map(4.0, 0.3, 2000);
+        let exponent = calculate_lyapunov_rosenstein(&sequence, DEFAULT_EMBEDDING_DIM, DEFAULT_EMBEDDING_DELAY).unwrap();
+        assert!((0.6..0.75).contains(&exponent), "{}", exponent);
+    }
+
+    #[test]
+    fn rosenstein_is_not_positive_on_a_periodic_orbit() {
+        let sequence = logistic_map(3.2, 0.3, 2000);
+        let exponent = calculate_lyapunov_rosenstein(&sequence, DEFAULT_EMBEDDING_DIM, DEFAULT_EMBEDDING_DELAY).unwrap();
+        assert!(exponent <= 0.01, "{}", exponent);
+    }
+
+    #[test]
+    fn julia_interior_points_never_escape() {
+        let julia = JuliaExplorer::with_constant(-0.123, 0.745);
+        for (z_real, z_imag) in [(0.0, 0.0), (0.1, 0.1), (0.1, -0.1), (-0.1, 0.1), (-0.1, -0.1)] {
+            assert_eq!(julia.escape_time(z_real, z_imag), julia.max_iterations, "{} + {}i", z_real, z_imag);
+            assert!(julia.is_in_set(z_real, z_imag));
+        }
+        assert!(julia.escape_time(1.5, 1.5) < julia.max_iterations);
+        assert_eq!(julia.generate_fractal_value(0.5, 0.5, 3.0), 0.0);
+    }
+
+    #[test]
+    fn rosenstein_needs_a_long_enough_sequence() {
+        assert!(calculate_lyapunov_rosenstein(&[0.1, 0.5, 0.9], DEFAULT_EMBEDDING_DIM, DEFAULT_EMBEDDING_DELAY).is_none());
+    }
+}
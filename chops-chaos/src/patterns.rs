@@ -2,6 +2,18 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use chops_core::{cmp_scores, CHOPSError, CHOPSResult};
+use crate::mathematics::{calculate_lyapunov_fast, calculate_lyapunov_rosenstein, DEFAULT_EMBEDDING_DELAY, DEFAULT_EMBEDDING_DIM};
+
+/// Bumped when the on-disk layout of saved patterns changes
+const PATTERN_STORE_VERSION: u32 = 1;
+
+/// Patterns a recognizer keeps; past this the least recently used are forgotten
+pub const MAX_KNOWN_PATTERNS: usize = 1000;
+
+/// Version of the `complexity_measure` formula. 1 was an LZ78-style phrase
+/// count over `n` that dropped the trailing phrase; 2 is LZ76 over `n / log2(n)`.
+pub const COMPLEXITY_MEASURE_VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChaosPattern {
@@ -17,7 +29,7 @@ pub struct ChaosPattern {
     pub context_tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChaosSignature {
     pub entropy_pattern: Vec<f64>,
     pub frequency_distribution: HashMap<String, f64>,
@@ -29,9 +41,32 @@ pub struct ChaosSignature {
 pub struct MathematicalFingerprint {
     pub fractal_dimension: f64,
     pub lyapunov_exponent: f64,
+    /// Estimator behind `lyapunov_exponent`; short sequences get the fast one
+    #[serde(default = "legacy_lyapunov_method")]
+    pub lyapunov_method: LyapunovMethod,
     pub correlation_sum: f64,
     pub entropy_rate: f64,
     pub complexity_measure: f64,
+    /// Formula `complexity_measure` was computed with; fingerprints saved
+    /// before it was recorded are version 1
+    #[serde(default = "legacy_complexity_version")]
+    pub complexity_version: u32,
+}
+
+fn legacy_complexity_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LyapunovMethod {
+    /// Mean log step size (`calculate_lyapunov_fast`)
+    Fast,
+    /// Nearest-neighbour divergence in a delay embedding (`calculate_lyapunov_rosenstein`)
+    Rosenstein,
+}
+
+fn legacy_lyapunov_method() -> LyapunovMethod {
+    LyapunovMethod::Fast
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +85,15 @@ pub struct PatternRecognizer {
     pattern_evolution_tracking: Vec<PatternEvolution>,
 }
 
+/// On-disk form of a `PatternRecognizer`
+#[derive(Serialize)]
+struct PatternStore<'a> {
+    version: u32,
+    similarity_threshold: f64,
+    patterns: Vec<&'a ChaosPattern>,
+    evolution: &'a [PatternEvolution],
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternEvolution {
     pub pattern_id: Uuid,
@@ -232,8 +276,8 @@ impl ChaosSignature {
         let min_len = self.entropy_pattern.len().min(other_pattern.len());
         let mut correlation_sum = 0.0;
         
-        for i in 0..min_len {
-            let diff = (self.entropy_pattern[i] - other_pattern[i]).abs();
+        for (a, b) in self.entropy_pattern.iter().zip(other_pattern.iter()) {
+            let diff = (a - b).abs();
             correlation_sum += 1.0 - diff; // Inverse of difference
         }
         
@@ -265,12 +309,19 @@ impl MathematicalFingerprint {
     pub fn from_sequence(sequence: &[f64]) -> Self {
         tracing::debug!("Creating MathematicalFingerprint from sequence of length: {}", sequence.len());
         
+        let (lyapunov_exponent, lyapunov_method) =
+            match calculate_lyapunov_rosenstein(sequence, DEFAULT_EMBEDDING_DIM, DEFAULT_EMBEDDING_DELAY) {
+                Some(exponent) => (exponent, LyapunovMethod::Rosenstein),
+                None => (calculate_lyapunov_fast(sequence), LyapunovMethod::Fast),
+            };
         let fingerprint = Self {
             fractal_dimension: Self::calculate_fractal_dimension(sequence),
-            lyapunov_exponent: Self::calculate_lyapunov_exponent(sequence),
+            lyapunov_exponent,
+            lyapunov_method,
             correlation_sum: Self::calculate_correlation_sum(sequence),
             entropy_rate: Self::calculate_entropy_rate(sequence),
             complexity_measure: Self::calculate_complexity_measure(sequence),
+            complexity_version: COMPLEXITY_MEASURE_VERSION,
         };
         
         tracing::debug!("MathematicalFingerprint created - fractal_dim: {:.3}, lyapunov: {:.3}, entropy: {:.3}", 
@@ -322,29 +373,6 @@ impl MathematicalFingerprint {
         -slope // Fractal dimension is negative slope
     }
     
-    fn calculate_lyapunov_exponent(sequence: &[f64]) -> f64 {
-        if sequence.len() < 5 {
-            return 0.0;
-        }
-        
-        let mut sum = 0.0;
-        let mut count = 0;
-        
-        for i in 1..sequence.len() {
-            let delta = (sequence[i] - sequence[i-1]).abs();
-            if delta > 0.0 && delta < 1.0 {
-                sum += delta.ln();
-                count += 1;
-            }
-        }
-        
-        if count > 0 {
-            sum / count as f64
-        } else {
-            0.0
-        }
-    }
-    
     fn calculate_correlation_sum(sequence: &[f64]) -> f64 {
         if sequence.len() < 10 {
             return 0.0;
@@ -395,29 +423,18 @@ impl MathematicalFingerprint {
         entropy
     }
     
+    /// Normalised Lempel-Ziv (LZ76) complexity of the sequence read as bits
+    /// (above 0.5 is 1): the number of phrases in its LZ76 parsing, the
+    /// trailing partial phrase included, over `n / log2(n)`. Random bits tend
+    /// to 1, periodic ones to 0.
     fn calculate_complexity_measure(sequence: &[f64]) -> f64 {
         if sequence.len() < 3 {
             return 0.0;
         }
         
-        // Lempel-Ziv complexity approximation
-        let mut dictionary = std::collections::HashSet::new();
-        let mut current_word = String::new();
-        let mut complexity = 0;
-        
-        // Convert to binary representation
-        for &value in sequence {
-            let bit = if value > 0.5 { '1' } else { '0' };
-            current_word.push(bit);
-            
-            if !dictionary.contains(&current_word) {
-                dictionary.insert(current_word.clone());
-                complexity += 1;
-                current_word.clear();
-            }
-        }
-        
-        complexity as f64 / sequence.len() as f64
+        let bits: Vec<bool> = sequence.iter().map(|&value| value > 0.5).collect();
+        let n = bits.len() as f64;
+        lz76_complexity(&bits) as f64 / (n / n.log2())
     }
     
     #[tracing::instrument(name = "fingerprint_similarity", level = "debug", skip(self, other))]
@@ -425,14 +442,23 @@ impl MathematicalFingerprint {
         tracing::debug!("Calculating MathematicalFingerprint similarity");
         
         let fractal_sim = 1.0 - (self.fractal_dimension - other.fractal_dimension).abs() / 3.0_f64.max(self.fractal_dimension.max(other.fractal_dimension));
-        let lyapunov_sim = 1.0 - (self.lyapunov_exponent - other.lyapunov_exponent).abs() / 2.0_f64.max(self.lyapunov_exponent.abs().max(other.lyapunov_exponent.abs()));
         let correlation_sim = 1.0 - (self.correlation_sum - other.correlation_sum).abs();
         let entropy_sim = 1.0 - (self.entropy_rate - other.entropy_rate).abs() / 4.0_f64.max(self.entropy_rate.max(other.entropy_rate));
-        let complexity_sim = 1.0 - (self.complexity_measure - other.complexity_measure).abs();
-        
-        let final_similarity = (fractal_sim + lyapunov_sim + correlation_sim + entropy_sim + complexity_sim) / 5.0;
-        
-        tracing::debug!("Fingerprint similarity components - fractal: {:.3}, lyapunov: {:.3}, correlation: {:.3}, entropy: {:.3}, complexity: {:.3}, final: {:.3}", 
+        // Exponents from different estimators and complexities from different
+        // formula versions aren't comparable, so such pairs are judged on the
+        // remaining measures
+        let lyapunov_sim = (self.lyapunov_method == other.lyapunov_method).then(|| {
+            1.0 - (self.lyapunov_exponent - other.lyapunov_exponent).abs() / 2.0_f64.max(self.lyapunov_exponent.abs().max(other.lyapunov_exponent.abs()))
+        });
+        let complexity_sim = (self.complexity_version == other.complexity_version).then(|| {
+            (1.0 - (self.complexity_measure - other.complexity_measure).abs()).max(0.0)
+        });
+        
+        let measures = [Some(fractal_sim), lyapunov_sim, Some(correlation_sim), Some(entropy_sim), complexity_sim];
+        let compared = measures.iter().flatten().count() as f64;
+        let final_similarity = measures.iter().flatten().sum::<f64>() / compared;
+        
+        tracing::debug!("Fingerprint similarity components - fractal: {:.3}, lyapunov: {:?}, correlation: {:.3}, entropy: {:.3}, complexity: {:?}, final: {:.3}", 
             fractal_sim, lyapunov_sim, correlation_sim, entropy_sim, complexity_sim, final_similarity);
         
         final_similarity
@@ -552,8 +578,132 @@ impl PatternRecognizer {
         }
     }
     
+    /// Remembers `pattern`, forgetting the least recently used ones once
+    /// more than `MAX_KNOWN_PATTERNS` are known
     pub fn add_pattern(&mut self, pattern: ChaosPattern) {
         self.known_patterns.insert(pattern.id, pattern);
+        while self.known_patterns.len() > MAX_KNOWN_PATTERNS {
+            let Some(stale) = self.known_patterns.values()
+                .min_by(|a, b| a.last_used.cmp(&b.last_used).then_with(|| a.id.cmp(&b.id)))
+                .map(|pattern| pattern.id) else {
+                break;
+            };
+            self.known_patterns.remove(&stale);
+            self.pattern_evolution_tracking.retain(|evolution| evolution.pattern_id != stale);
+            tracing::debug!("Forgot chaos pattern {} (over {} patterns)", stale, MAX_KNOWN_PATTERNS);
+        }
+    }
+
+    pub fn get(&self, pattern_id: Uuid) -> Option<&ChaosPattern> {
+        self.known_patterns.get(&pattern_id)
+    }
+
+    /// Known patterns, oldest first
+    pub fn patterns(&self) -> Vec<&ChaosPattern> {
+        let mut patterns: Vec<&ChaosPattern> = self.known_patterns.values().collect();
+        patterns.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        patterns
+    }
+
+    /// Counts another use of a known pattern scoring `score`; false when
+    /// the pattern is unknown
+    pub fn record_use(&mut self, pattern_id: Uuid, score: f64) -> bool {
+        match self.known_patterns.get_mut(&pattern_id) {
+            Some(pattern) => {
+                pattern.update_effectiveness(score);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.known_patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.known_patterns.is_empty()
+    }
+
+    /// Loads saved patterns, starting empty when the file does not exist yet.
+    /// Entries that no longer deserialize are skipped so one bad pattern
+    /// cannot take the rest of the learned set down with it.
+    #[tracing::instrument(name = "pattern_recognizer_load", level = "debug")]
+    pub fn load_from_file(path: &std::path::Path) -> CHOPSResult<Self> {
+        let mut recognizer = Self::new();
+        if !path.exists() {
+            return Ok(recognizer);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(CHOPSError::FileSystemError)?;
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to load patterns {}: {}", path.display(), e)))?;
+
+        // Early builds wrote a bare array of patterns with no envelope
+        let (patterns, evolution) = match value {
+            serde_json::Value::Array(patterns) => (patterns, Vec::new()),
+            serde_json::Value::Object(mut store) => {
+                let version = store.get("version").and_then(serde_json::Value::as_u64).unwrap_or(0);
+                if version > PATTERN_STORE_VERSION as u64 {
+                    tracing::warn!("Pattern store version {} is newer than {}, reading what we can", version, PATTERN_STORE_VERSION);
+                }
+                if let Some(threshold) = store.get("similarity_threshold").and_then(serde_json::Value::as_f64) {
+                    if (0.0..=1.0).contains(&threshold) {
+                        recognizer.similarity_threshold = threshold;
+                    }
+                }
+                let mut take_array = |key: &str| match store.remove(key) {
+                    Some(serde_json::Value::Array(entries)) => entries,
+                    _ => Vec::new(),
+                };
+                (take_array("patterns"), take_array("evolution"))
+            },
+            _ => return Err(CHOPSError::ConfigError(format!("Pattern store {} is not a JSON object", path.display()))),
+        };
+
+        let mut skipped = 0;
+        for entry in patterns {
+            match serde_json::from_value::<ChaosPattern>(entry) {
+                Ok(pattern) => recognizer.add_pattern(pattern),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed chaos pattern: {}", e);
+                    skipped += 1;
+                },
+            }
+        }
+        for entry in evolution {
+            match serde_json::from_value::<PatternEvolution>(entry) {
+                Ok(evolution) => recognizer.pattern_evolution_tracking.push(evolution),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed pattern evolution: {}", e);
+                    skipped += 1;
+                },
+            }
+        }
+
+        tracing::debug!("Loaded {} patterns from {} ({} entries skipped)", recognizer.len(), path.display(), skipped);
+        Ok(recognizer)
+    }
+
+    pub fn save_to_file(&self, path: &std::path::Path) -> CHOPSResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(CHOPSError::FileSystemError)?;
+        }
+
+        // Sorted so the file diffs cleanly between saves
+        let store = PatternStore {
+            version: PATTERN_STORE_VERSION,
+            similarity_threshold: self.similarity_threshold,
+            patterns: self.patterns(),
+            evolution: &self.pattern_evolution_tracking,
+        };
+
+        let content = serde_json::to_string_pretty(&store)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize patterns: {}", e)))?;
+        std::fs::write(path, content)
+            .map_err(CHOPSError::FileSystemError)
     }
     
     pub fn find_similar_patterns(&self, target_signature: &ChaosSignature) -> Vec<PatternMatch> {
@@ -567,13 +717,16 @@ impl PatternRecognizer {
                     pattern_id: pattern.id,
                     similarity_score: similarity,
                     context_relevance: pattern.effectiveness_score,
-                    suggested_adaptations: self.generate_adaptations(&pattern, similarity),
+                    suggested_adaptations: self.generate_adaptations(pattern, similarity),
                 });
             }
         }
         
         // Sort by similarity score
-        matches.sort_by(|a, b| b.similarity_score.partial_cmp(&a.similarity_score).unwrap());
+        matches.sort_by(|a, b| {
+            cmp_scores(a.similarity_score, b.similarity_score)
+                .then_with(|| a.pattern_id.cmp(&b.pattern_id))
+        });
         
         matches
     }
@@ -632,34 +785,71 @@ impl PatternRecognizer {
     
     pub fn get_best_patterns(&self, limit: usize) -> Vec<&ChaosPattern> {
         let mut patterns: Vec<&ChaosPattern> = self.known_patterns.values().collect();
-        patterns.sort_by(|a, b| b.effectiveness_score.partial_cmp(&a.effectiveness_score).unwrap());
+        patterns.sort_by(|a, b| {
+            cmp_scores(a.effectiveness_score, b.effectiveness_score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
         patterns.into_iter().take(limit).collect()
     }
 }
 
-impl Default for ChaosSignature {
-    fn default() -> Self {
-        Self {
-            entropy_pattern: Vec::new(),
-            frequency_distribution: HashMap::new(),
-            mathematical_fingerprint: MathematicalFingerprint::default(),
-            emergence_indicators: EmergenceIndicators::default(),
-        }
-    }
-}
-
 impl Default for MathematicalFingerprint {
     fn default() -> Self {
         Self {
             fractal_dimension: 1.0,
             lyapunov_exponent: 0.0,
+            lyapunov_method: LyapunovMethod::Fast,
             correlation_sum: 0.0,
             entropy_rate: 0.0,
             complexity_measure: 0.0,
+            complexity_version: COMPLEXITY_MEASURE_VERSION,
         }
     }
 }
 
+/// Phrase count of the LZ76 parsing of `symbols` (Kaspar & Schuster, 1987):
+/// each phrase is the shortest extension not seen starting earlier, and an
+/// unfinished phrase at the end counts too. "0001101001000101" parses as
+/// 0·001·10·100·1000·101, so 6.
+pub fn lz76_complexity<T: PartialEq>(symbols: &[T]) -> usize {
+    let n = symbols.len();
+    if n < 2 {
+        return n;
+    }
+
+    // `l` is where the current phrase starts, `k` how far it has been
+    // matched against the copy starting at `i`, `k_max` the longest match
+    // found for it so far
+    let (mut i, mut k, mut l, mut k_max, mut complexity) = (0, 1, 1, 1, 1);
+    loop {
+        if symbols[i + k - 1] == symbols[l + k - 1] {
+            k += 1;
+            if l + k > n {
+                // The sequence ended inside a phrase
+                complexity += 1;
+                break;
+            }
+        } else {
+            k_max = k_max.max(k);
+            i += 1;
+            if i == l {
+                complexity += 1;
+                l += k_max;
+                if l + 1 > n {
+                    break;
+                }
+                i = 0;
+                k = 1;
+                k_max = 1;
+            } else {
+                k = 1;
+            }
+        }
+    }
+    complexity
+}
+
 impl Default for EmergenceIndicators {
     fn default() -> Self {
         Self {
@@ -676,4 +866,140 @@ impl Default for PatternRecognizer {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("chops-patterns-{}", Uuid::new_v4())).join(name)
+    }
+
+    fn pattern(name: &str, sequence: &[f64]) -> ChaosPattern {
+        let mut pattern = ChaosPattern::new(name.to_string(), format!("{} pattern", name));
+        pattern.chaos_signature = ChaosSignature::from_entropy_sequence(sequence);
+        pattern.add_context_tag("test".to_string());
+        pattern.update_effectiveness(0.8);
+        pattern
+    }
+
+    #[test]
+    fn patterns_survive_a_save_and_load() {
+        let path = temp_path("patterns.json");
+        let mut recognizer = PatternRecognizer::new();
+        recognizer.add_pattern(pattern("calm", &[0.1, 0.2, 0.1, 0.2]));
+        recognizer.add_pattern(pattern("wild", &[0.9, 0.1, 0.8, 0.05]));
+        let evolved = recognizer.patterns()[0].id;
+        recognizer.evolve_pattern(evolved, "sharper".to_string());
+
+        recognizer.save_to_file(&path).unwrap();
+        let loaded = PatternRecognizer::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        for original in recognizer.patterns() {
+            let restored = loaded.get(original.id).expect("pattern restored");
+            assert_eq!(restored.name, original.name);
+            assert_eq!(restored.usage_count, original.usage_count);
+            assert_eq!(restored.chaos_signature.entropy_pattern, original.chaos_signature.entropy_pattern);
+        }
+        assert_eq!(loaded.pattern_evolution_tracking.len(), 1);
+        assert_eq!(loaded.similarity_threshold, recognizer.similarity_threshold);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn equal_effectiveness_ranks_by_name() {
+        let mut recognizer = PatternRecognizer::new();
+        for name in ["wild", "calm", "mid"] {
+            recognizer.add_pattern(pattern(name, &[0.1, 0.2, 0.1, 0.2]));
+        }
+        let mut broken = pattern("broken", &[0.1, 0.2, 0.1, 0.2]);
+        broken.effectiveness_score = f64::NAN;
+        recognizer.add_pattern(broken);
+
+        let names: Vec<&str> = recognizer.get_best_patterns(4).iter().map(|pattern| pattern.name.as_str()).collect();
+        assert_eq!(names, vec!["calm", "mid", "wild", "broken"]);
+    }
+
+    #[test]
+    fn equally_similar_patterns_rank_by_id() {
+        let mut recognizer = PatternRecognizer::new();
+        for name in ["a", "b", "c", "d"] {
+            recognizer.add_pattern(pattern(name, &[0.1, 0.2, 0.1, 0.2]));
+        }
+        let target = ChaosSignature::from_entropy_sequence(&[0.1, 0.2, 0.1, 0.2]);
+
+        let matches = recognizer.find_similar_patterns(&target);
+        assert_eq!(matches.len(), 4);
+        let ids: Vec<Uuid> = matches.iter().map(|found| found.pattern_id).collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(ids, sorted);
+    }
+
+    #[test]
+    fn lz76_matches_known_values() {
+        for (symbols, expected) in [
+            ("0001101001000101", 6),
+            ("01010101", 3),
+            ("0000000000", 2),
+            ("0110", 3),
+            ("1001111011000010", 6),
+        ] {
+            assert_eq!(lz76_complexity(symbols.as_bytes()), expected, "{}", symbols);
+        }
+    }
+
+    #[test]
+    fn alternating_sequence_has_low_complexity() {
+        let alternating: Vec<f64> = (0..256).map(|i| if i % 2 == 0 { 0.1 } else { 0.9 }).collect();
+        let fingerprint = MathematicalFingerprint::from_sequence(&alternating);
+        assert_eq!(fingerprint.complexity_version, COMPLEXITY_MEASURE_VERSION);
+        assert!(fingerprint.complexity_measure < 0.2, "{}", fingerprint.complexity_measure);
+    }
+
+    #[test]
+    fn legacy_fingerprint_loads_as_complexity_version_one() {
+        let fingerprint = MathematicalFingerprint::from_sequence(&[0.1, 0.9, 0.3, 0.7, 0.5]);
+        let mut stored = serde_json::to_value(&fingerprint).unwrap();
+        stored.as_object_mut().unwrap().remove("complexity_version");
+
+        let loaded: MathematicalFingerprint = serde_json::from_value(stored).unwrap();
+        assert_eq!(loaded.complexity_version, 1);
+    }
+
+    #[test]
+    fn missing_pattern_file_loads_empty() {
+        let loaded = PatternRecognizer::load_from_file(&temp_path("absent.json")).unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn malformed_entries_are_skipped() {
+        let path = temp_path("patterns.json");
+        let mut recognizer = PatternRecognizer::new();
+        recognizer.add_pattern(pattern("calm", &[0.1, 0.2, 0.1, 0.2]));
+        recognizer.save_to_file(&path).unwrap();
+
+        let mut store: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        store["patterns"].as_array_mut().unwrap().push(serde_json::json!({ "name": "broken" }));
+        std::fs::write(&path, store.to_string()).unwrap();
+
+        assert_eq!(PatternRecognizer::load_from_file(&path).unwrap().len(), 1);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn recognizer_forgets_least_recently_used_past_the_cap() {
+        let mut recognizer = PatternRecognizer::new();
+        let mut first = pattern("first", &[0.1, 0.2]);
+        first.last_used = Utc::now() - chrono::Duration::days(1);
+        let first_id = first.id;
+        recognizer.add_pattern(first);
+        for i in 0..MAX_KNOWN_PATTERNS {
+            recognizer.add_pattern(pattern(&format!("p{}", i), &[0.1, 0.2]));
+        }
+        assert_eq!(recognizer.len(), MAX_KNOWN_PATTERNS);
+        assert!(recognizer.get(first_id).is_none());
+    }
+}
@@ -0,0 +1,56 @@
+use chops_chaos::CancellationToken;
+use chops_core::CHOPSResult;
+use colored::*;
+use std::future::Future;
+
+/// A token the next Ctrl-C cancels, so long-running work can stop at its own
+/// checkpoints instead of being dropped mid-await. The signal watcher stops
+/// when this is dropped.
+pub struct CtrlC {
+    token: CancellationToken,
+    watcher: tokio::task::JoinHandle<()>,
+}
+
+impl CtrlC {
+    pub fn watch() -> Self {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let watcher = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Ctrl-C received, cancelling");
+                cancel.cancel();
+            }
+        });
+        Self { token, watcher }
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for CtrlC {
+    fn drop(&mut self) {
+        self.watcher.abort();
+    }
+}
+
+/// Drives `work` until it finishes or the user hits Ctrl-C, returning whether
+/// it completed. Cancelled work is dropped at its next await point, which ends
+/// its borrow of the system before the caller takes over again.
+pub async fn run_cancellable<F>(label: &str, work: F) -> CHOPSResult<bool>
+where
+    F: Future<Output = CHOPSResult<()>>,
+{
+    println!("{}", "   (Ctrl-C cancels this and returns to the menu)".bright_black());
+
+    let ctrl_c = CtrlC::watch();
+    tokio::select! {
+        result = work => result.map(|_| true),
+        _ = ctrl_c.token().cancelled() => {
+            tracing::info!("{} cancelled by user", label);
+            println!("\n{}", format!("🛑 {} cancelled, back to the menu.", label).bright_yellow());
+            Ok(false)
+        },
+    }
+}
@@ -1,5 +1,7 @@
-use chops_core::PersonaType;
-use clap::{Parser, Subcommand, ValueEnum};
+use chops_core::{EntropySource, FeasibilityBand, MemoryExportFormat, OutputFormat, PersonaType, ScoreProfile};
+use chops_api::{CollaborationMode, CollaborationParticipant, ConceptMapFormat};
+use chops_chaos::{Attractor, ChaosVariationMask};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(
@@ -11,40 +13,103 @@ use clap::{Parser, Subcommand, ValueEnum};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Never touch the network: no quantum entropy, and no LLM calls unless
+    /// network.local_api_url points at a local backend
+    #[arg(long, global = true)]
+    pub air_gapped: bool,
+
+    /// Dry run without an API key or any network access: LLM calls get a
+    /// canned, deterministic completion that is still scored (also CHOPS_OFFLINE=1)
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Lorenz attractor constants for the chaotic distribution (e.g. 10,28,2.667)
+    #[arg(long, global = true, value_name = "SIGMA,RHO,BETA", value_parser = parse_lorenz)]
+    pub lorenz: Option<(f64, f64, f64)>,
+
+    /// Hénon map constants for the chaotic distribution (e.g. 1.4,0.3)
+    #[arg(long, global = true, value_name = "A,B", value_parser = parse_henon)]
+    pub henon: Option<(f64, f64)>,
+
+    /// Only inject these chaos variation types (e.g. inversion,scale); the
+    /// others are dropped from every chaos injection this run
+    #[arg(long, global = true, value_name = "TYPES")]
+    pub chaos_types: Option<ChaosVariationMask>,
+}
+
+fn parse_numbers<const N: usize>(value: &str) -> Result<[f64; N], String> {
+    let numbers: Vec<f64> = value.split(',')
+        .map(|part| part.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", part.trim())))
+        .collect::<Result<_, _>>()?;
+    numbers.try_into().map_err(|numbers: Vec<f64>| format!("expected {} comma-separated numbers, got {}", N, numbers.len()))
+}
+
+fn parse_lorenz(value: &str) -> Result<(f64, f64, f64), String> {
+    let [sigma, rho, beta] = parse_numbers(value)?;
+    Ok((sigma, rho, beta))
+}
+
+fn parse_henon(value: &str) -> Result<(f64, f64), String> {
+    let [a, b] = parse_numbers(value)?;
+    Ok((a, b))
+}
+
+fn parse_julia(value: &str) -> Result<(f64, f64), String> {
+    let [real, imag] = parse_numbers(value)?;
+    Ok((real, imag))
+}
+
+/// A score from 0.0 to 1.0
+fn parse_score(value: &str) -> Result<f64, String> {
+    let score = value.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", value.trim()))?;
+    if !(0.0..=1.0).contains(&score) {
+        return Err(format!("{} is not between 0.0 and 1.0", score));
+    }
+    Ok(score)
+}
+
+fn parse_output_format(value: &str) -> Result<OutputFormat, String> {
+    OutputFormat::from_name(value).map_err(|e| e.to_string())
+}
+
+/// `--format`, taken by every command that prints a result
+#[derive(Args, Debug, Clone, Default)]
+pub struct FormatArg {
+    /// Print the result as markdown (colored), json, yaml or plain (default: default_format in config)
+    #[arg(long, value_name = "FORMAT", value_parser = parse_output_format)]
+    pub format: Option<OutputFormat>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// 🔮 Summon creative demons to generate innovative ideas
     #[command(alias = "s")]
-    Summon {
-        /// AI persona to invoke
+    Summon(Box<SummonArgs>),
+
+    /// 📦 Summon an idea for every prompt in a file, resumable if interrupted
+    Batch {
+        /// Prompts file, one per line (blank lines and # comments are skipped)
+        file: String,
+
+        /// Directory for the per-prompt JSON results (default: <file>-ideas/ next to the prompts)
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// AI persona to invoke for every prompt
         #[arg(short, long, value_enum)]
         persona: Option<PersonaType>,
 
-        /// Domain to focus on
-        #[arg(short, long, default_value = "software")]
-        domain: String,
-
-        /// Chaos level (1-11, where 11 breaks reality)
+        /// Chaos level (1-11)
         #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=11))]
         chaos: Option<u8>,
 
-        /// Timeline context (e.g., "2030", "retro-futurism")
-        #[arg(short, long)]
-        timeline: Option<String>,
-
-        /// Vibe to channel (e.g., "cyberpunk debugging")
-        #[arg(short, long)]
-        vibe: Option<String>,
-
-        /// Constraints to work within
+        /// Skip prompts an earlier, interrupted run of this file already finished
         #[arg(long)]
-        constraints: Vec<String>,
+        resume: bool,
 
-        /// Reality calibration level (0.0-1.0)
-        #[arg(short, long)]
-        reality_level: Option<f64>,
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 🧬 Mutate existing code with personality injection
@@ -73,6 +138,26 @@ pub enum Commands {
         /// Keep it functional
         #[arg(long)]
         functional: bool,
+
+        /// Overwrite the file in place instead of writing <file>.chopped
+        #[arg(long)]
+        apply: bool,
+
+        /// Largest share of lines the mutation may change (0.0-1.0)
+        #[arg(long, value_name = "FRACTION")]
+        max_change: Option<f64>,
+
+        #[command(flatten)]
+        format: FormatArg,
+
+        /// Only add or edit comments; code is verified to be untouched
+        #[arg(long)]
+        comments_only: bool,
+
+        /// Fail when no mutation of a Rust file still parses, instead of
+        /// keeping the original (mutations that don't parse are always retried)
+        #[arg(long)]
+        verify: bool,
     },
 
     /// 🔮 Generate future prophecies and predictions
@@ -82,9 +167,9 @@ pub enum Commands {
         #[arg(short, long)]
         year: Option<u32>,
 
-        /// Domain to prophesy about
-        #[arg(short, long, default_value = "technology")]
-        domain: String,
+        /// Domain to prophesy about (inferred from the what-if scenario when omitted)
+        #[arg(short, long)]
+        domain: Option<String>,
 
         /// Include trend analysis
         #[arg(short, long)]
@@ -97,27 +182,46 @@ pub enum Commands {
         /// What-if scenario
         #[arg(short, long)]
         what_if: Option<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 🤝 Orchestrate AI collaboration and debates
     #[command(alias = "c")]
     Collaborate {
-        /// Collaboration mode
-        #[arg(short, long, value_enum, default_value = "debate")]
+        /// Collaboration mode (debate, brainstorm, consensus, devils-advocate, synthesis)
+        #[arg(short, long, default_value = "debate")]
         mode: CollaborationMode,
 
         /// Topic to collaborate on
         #[arg(short, long)]
         topic: String,
 
+        /// Who takes part, each optionally speaking as a persona, e.g.
+        /// security:zen-master,growth:punk-hacker (default: the mode's own cast;
+        /// in devils-advocate mode the last one is the advocate)
+        #[arg(short, long, value_name = "NAME[:PERSONA]", value_delimiter = ',', value_parser = parse_participant)]
+        participants: Vec<CollaborationParticipant>,
+
+        /// Rounds of contributions (consensus stops early once everyone agrees)
+        #[arg(short, long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=5))]
+        rounds: u8,
+
         /// Include human in the loop
         #[arg(long)]
         human: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// ⚡ Inject controlled chaos and glitches
     #[command(alias = "g")]
     Glitch {
+        /// File to glitch
+        file: Option<String>,
+
         /// Probability of glitch injection
         #[arg(short, long)]
         probability: Option<f64>,
@@ -129,6 +233,25 @@ pub enum Commands {
         /// Glitch density (low/medium/high/extreme)
         #[arg(short, long)]
         density: Option<String>,
+
+        /// Entropy source for this run (pseudo, true, quantum, chaos, device:<path>)
+        #[arg(long)]
+        entropy: Option<EntropySource>,
+
+        /// Preview several probabilities side by side (e.g. 0.1,0.3,0.6)
+        #[arg(long, value_delimiter = ',')]
+        sweep: Vec<f64>,
+
+        /// Write the file glitched at this probability
+        #[arg(long)]
+        apply: Option<f64>,
+
+        /// Seed to reproduce a previewed glitch
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// ⏰ Travel through technological eras
@@ -141,6 +264,9 @@ pub enum Commands {
         /// Modern twist to apply
         #[arg(short, long)]
         twist: Option<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 👻 Channel famous innovators and visionaries
@@ -153,19 +279,117 @@ pub enum Commands {
         /// Target to possess/influence
         #[arg(short, long)]
         target: String,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 🌀 Embrace contradictions and paradoxes
     #[command(alias = "par")]
     Paradox {
-        /// Contradictory constraints to reconcile
+        /// Contradictory constraints to reconcile (repeatable)
         #[arg(short, long)]
         constraints: Vec<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// 🧪 Check whether an idea could exist, without generating anything (no API key needed)
+    Assess {
+        /// The idea to assess
+        idea: String,
+
+        /// Chaos level to assess at (1-11); more chaos tolerates more distortion
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=11))]
+        chaos: Option<u8>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// 🎭 Manage persona learning
+    Persona {
+        #[command(subcommand)]
+        action: PersonaAction,
+    },
+
+    /// 🌀 Inspect how chaos levels have worked out
+    Chaos {
+        #[command(subcommand)]
+        action: ChaosAction,
+    },
+
+    /// 🔭 Teach analogical reasoning new source domains
+    Domains {
+        #[command(subcommand)]
+        action: DomainsAction,
+    },
+
+    /// 🎲 Check whether an entropy source is actually random
+    Entropy {
+        #[command(subcommand)]
+        action: EntropyAction,
+    },
+
+    /// ↩️ Restore files from the last mutate/glitch run
+    Undo {
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// 🎯 Calibrate idea scoring against your own labeled examples
+    Calibrate {
+        /// JSON file of ideas with your creativity/feasibility labels
+        labeled: String,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// ⚖️ Run one prompt through several models and compare quality, speed and cost
+    CompareModels {
+        /// What to generate an idea for
+        prompt: String,
+
+        /// Comma-separated models: sonnet, haiku, opus or full model ids
+        #[arg(short, long, value_delimiter = ',', default_value = "sonnet,haiku,opus")]
+        models: Vec<String>,
+
+        /// Persona for every run
+        #[arg(short, long)]
+        persona: Option<PersonaType>,
+
+        /// Domain for every run
+        #[arg(short, long, default_value = "software development")]
+        domain: String,
+
+        /// Chaos level (1-11) for every run (default from config)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=11))]
+        chaos: Option<u8>,
+
+        /// Seed for the chaos variations (random and printed when omitted)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 🎮 Enter interactive CHOPS mode
     #[command(alias = "i")]
-    Interactive,
+    Interactive {
+        /// Resume a saved conversation session (e.g. session.json)
+        #[arg(long)]
+        resume: Option<String>,
+
+        /// Minutes of inactivity before memory is saved and the session ends (0 disables; default from config)
+        #[arg(long, value_name = "MINUTES")]
+        idle_timeout: Option<u64>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
 
     /// ⚙️ Configure CHOPS settings
     Config {
@@ -176,10 +400,16 @@ pub enum Commands {
         /// Set configuration value (key=value)
         #[arg(short = 's', long)]
         set: Vec<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 
     /// 🧠 Manage CHOPS memory and learning
     Memory {
+        #[command(subcommand)]
+        action: Option<MemoryAction>,
+
         /// Show memory contents
         #[arg(short, long)]
         show: bool,
@@ -188,34 +418,493 @@ pub enum Commands {
         #[arg(short, long)]
         clear: bool,
 
-        /// Export memory to file
+        /// Export memory to a JSON file
         #[arg(short, long)]
         export: Option<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
     },
 }
 
-#[derive(Clone, ValueEnum)]
-pub enum CollaborationMode {
-    /// AI vs AI debate
-    Debate,
-    /// Collaborative brainstorming
-    Brainstorm,
-    /// Consensus building
-    Consensus,
-    /// Devil's advocate mode
-    DevilsAdvocate,
-    /// Synthesis of ideas
-    Synthesis,
+/// `chops summon`'s options, boxed in [`Commands`] since there are so many
+#[derive(Args)]
+pub struct SummonArgs {
+    /// AI persona to invoke
+    #[arg(short, long, value_enum)]
+    pub persona: Option<PersonaType>,
+
+    /// Summon a weighted blend of personas instead of one, e.g. mad-scientist:0.5,zen-master:0.5
+    #[arg(long, value_name = "PERSONA:WEIGHT", value_delimiter = ',', value_parser = parse_blend_weight, conflicts_with_all = ["persona", "no_recommend"])]
+    pub blend: Vec<(PersonaType, f64)>,
+
+    /// Domain to focus on (inferred from the vibe/constraints when omitted)
+    #[arg(short, long)]
+    pub domain: Option<String>,
+
+    /// Chaos level (1-11, where 11 breaks reality)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=11))]
+    pub chaos: Option<u8>,
+
+    /// Timeline context (e.g., "2030", "retro-futurism")
+    #[arg(short, long)]
+    pub timeline: Option<String>,
+
+    /// Vibe to channel (e.g., "cyberpunk debugging")
+    #[arg(short, long)]
+    pub vibe: Option<String>,
+
+    /// Constraints to work within
+    #[arg(long)]
+    pub constraints: Vec<String>,
+
+    /// Reality calibration level (0.0-1.0): 1.0 turns reality distortion off, lower levels amplify it (default: 0.7)
+    #[arg(short, long)]
+    pub reality_level: Option<f64>,
+
+    /// Entropy source for this run (pseudo, true, quantum, chaos, device:<path>)
+    #[arg(long)]
+    pub entropy: Option<EntropySource>,
+
+    /// Build on a previous idea (id from `chops memory --show`) or a reference file
+    #[arg(long, value_name = "IDEA_ID|FILE")]
+    pub based_on: Option<String>,
+
+    /// Re-roll with more chaos while the idea's novelty is below this (0.0-1.0)
+    #[arg(long, value_name = "SCORE", value_parser = parse_score)]
+    pub min_novelty: Option<f64>,
+
+    /// Re-roll, raising chaos for mundane ideas and lowering it for impossible
+    /// ones, until feasibility lands in this band (e.g. 0.5-0.8)
+    #[arg(long, value_name = "LOW-HIGH")]
+    pub feasibility_band: Option<FeasibilityBand>,
+
+    /// Have the persona outline a plan first, then write the idea to it
+    #[arg(long)]
+    pub with_plan: bool,
+
+    /// Seed for chaos variations; each variation type reproduces its text under the same seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Also extract a concept map of the idea (mermaid or dot)
+    #[arg(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "mermaid", conflicts_with = "batch")]
+    pub concept_map: Option<ConceptMapFormat>,
+
+    /// Print the JSON Schema of summon results and exit
+    #[arg(long)]
+    pub json_schema: bool,
+
+    /// Also write the result in these formats (md, json, yaml, txt, html), e.g. md,json,html
+    #[arg(long, value_name = "FORMATS", value_delimiter = ',', value_parser = parse_output_format)]
+    pub export: Vec<OutputFormat>,
+
+    #[command(flatten)]
+    pub format: FormatArg,
+
+    /// Directory for --export files (default: the configured output directory)
+    #[arg(long, value_name = "DIR", requires = "export")]
+    pub export_dir: Option<String>,
+
+    /// Save the idea as markdown in the configured output directory (always on with save_ideas)
+    #[arg(long)]
+    pub save: bool,
+
+    /// Don't explain the automatic persona pick when --persona is omitted
+    #[arg(long)]
+    pub quiet: bool,
+
+    /// Use the configured default persona instead of recommending one when --persona is omitted
+    #[arg(long, conflicts_with = "persona")]
+    pub no_recommend: bool,
+
+    /// Most refinements a --based-on chain may reach (default: refinement.max_depth in config)
+    #[arg(long, value_name = "N", requires = "based_on")]
+    pub max_depth: Option<usize>,
+
+    /// Tokens a --based-on chain may spend in total, 0 for no budget (default: refinement.max_chain_tokens)
+    #[arg(long, value_name = "TOKENS", requires = "based_on")]
+    pub chain_budget: Option<u64>,
+
+    /// Have the idea returned as JSON that validates against this JSON Schema file
+    #[arg(long, value_name = "FILE")]
+    pub output_schema: Option<String>,
+
+    /// Re-prompts allowed when the JSON doesn't validate against --output-schema
+    #[arg(long, value_name = "N", default_value_t = chops_api::DEFAULT_SCHEMA_RETRIES, requires = "output_schema")]
+    pub schema_retries: u8,
+
+    /// Attach a file (code, docs) as reference material for the idea; repeatable
+    #[arg(long = "context-file", value_name = "PATH")]
+    pub context_files: Vec<String>,
+
+    /// Print the idea as it is generated instead of waiting for the whole response
+    #[arg(long, conflicts_with = "batch")]
+    pub stream: bool,
+
+    /// Keep a stream that was cut off as a partial idea (flagged truncated) instead of retrying
+    #[arg(long, requires = "stream")]
+    pub allow_partial: bool,
+
+    /// Summon an idea for every prompt in this file, one per line (blank lines and # comments are
+    /// skipped), with the other options applied to each; like `chops batch`
+    #[arg(long, value_name = "FILE")]
+    pub batch: Option<String>,
+
+    /// Directory for the per-prompt JSON results of --batch (default: <file>-ideas/ next to the prompts)
+    #[arg(long, value_name = "DIR", requires = "batch")]
+    pub output: Option<String>,
+
+    /// Skip prompts an earlier, interrupted --batch run with the same settings already finished
+    #[arg(long, requires = "batch")]
+    pub resume: bool,
+
+    /// Ideas --batch generates at once; higher is faster but hits rate limits sooner
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=16), requires = "batch")]
+    pub concurrency: u8,
 }
 
-impl std::fmt::Display for CollaborationMode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Commands {
+    /// The format the command prints its result in; `default` is the
+    /// configured default_format
+    pub fn output_format(&self, default: &OutputFormat) -> OutputFormat {
+        // `persona debug --json` and `persona blend --json` predate --format
+        if let Commands::Persona { action: PersonaAction::Debug { json: true, .. } | PersonaAction::Blend { json: true, .. } } = self {
+            return OutputFormat::Json;
+        }
+        self.format_arg().format.clone().unwrap_or_else(|| default.clone())
+    }
+
+    fn format_arg(&self) -> &FormatArg {
         match self {
-            CollaborationMode::Debate => write!(f, "debate"),
-            CollaborationMode::Brainstorm => write!(f, "brainstorm"),
-            CollaborationMode::Consensus => write!(f, "consensus"),
-            CollaborationMode::DevilsAdvocate => write!(f, "devils-advocate"),
-            CollaborationMode::Synthesis => write!(f, "synthesis"),
+            Commands::Summon(args) => &args.format,
+            Commands::Batch { format, .. } | Commands::Mutate { format, .. }
+            | Commands::Prophecy { format, .. } | Commands::Collaborate { format, .. } | Commands::Glitch { format, .. }
+            | Commands::TimeTravel { format, .. } | Commands::Possession { format, .. } | Commands::Paradox { format, .. }
+            | Commands::Assess { format, .. } | Commands::Undo { format } | Commands::Calibrate { format, .. }
+            | Commands::CompareModels { format, .. } | Commands::Interactive { format, .. } | Commands::Config { format, .. } => format,
+            Commands::Persona { action } => match action {
+                PersonaAction::Reset { format, .. } | PersonaAction::Debug { format, .. } | PersonaAction::Blend { format, .. } => format,
+            },
+            Commands::Chaos { action } => match action {
+                ChaosAction::Analytics { format, .. } | ChaosAction::Profile { format, .. } | ChaosAction::Diff { format, .. }
+                | ChaosAction::Save { format, .. } | ChaosAction::Load { format, .. } | ChaosAction::Export { format, .. } => format,
+            },
+            Commands::Domains { action: DomainsAction::Add { format, .. } } => format,
+            Commands::Entropy { action: EntropyAction::Test { format, .. } } => format,
+            // `memory export --format` picks the export's file format, so the
+            // parent's flag stands in for it
+            Commands::Memory { action, format, .. } => match action {
+                Some(MemoryAction::Show { format, .. } | MemoryAction::Import { format, .. } | MemoryAction::Rank { format, .. }
+                | MemoryAction::Stats { format } | MemoryAction::Heatmap { format } | MemoryAction::Prune { format, .. }) => format,
+                Some(MemoryAction::Export { .. }) | None => format,
+            },
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Subcommand)]
+pub enum PersonaAction {
+    /// Restore a persona's default character, discarding what it has learned
+    Reset {
+        /// Persona to reset
+        persona: PersonaType,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Dump a persona's internal state, drift, biases and prompt
+    Debug {
+        /// Persona to inspect
+        persona: PersonaType,
+
+        /// Print the state as JSON (same as --format json)
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Blend several personas by weight and show the resulting character
+    Blend {
+        /// Personas and weights, e.g. mad-scientist:0.5,zen-master:0.3,mind-reader:0.2
+        /// (weights are normalized if they don't sum to 1)
+        #[arg(long, value_name = "PERSONA:WEIGHT", value_delimiter = ',', value_parser = parse_blend_weight, required = true)]
+        blend: Vec<(PersonaType, f64)>,
+
+        /// Print the blended context as JSON (same as --format json)
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
+
+fn parse_blend_weight(value: &str) -> Result<(PersonaType, f64), String> {
+    let (persona, weight) = value.rsplit_once(':')
+        .ok_or_else(|| format!("expected PERSONA:WEIGHT, got '{}'", value))?;
+    let persona = persona.trim().replace('_', "-").parse::<PersonaType>().map_err(|e| e.to_string())?;
+    let weight = weight.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", weight.trim()))?;
+    Ok((persona, weight))
+}
+
+fn parse_participant(value: &str) -> Result<CollaborationParticipant, String> {
+    let (name, persona) = match value.split_once(':') {
+        Some((name, persona)) => (name.trim(), Some(persona)),
+        None => (value.trim(), None),
+    };
+    if name.is_empty() {
+        return Err(format!("expected NAME or NAME:PERSONA, got '{}'", value));
+    }
+    let persona_type = persona
+        .map(|persona| persona.trim().replace('_', "-").parse::<PersonaType>().map_err(|e| e.to_string()))
+        .transpose()?;
+    Ok(CollaborationParticipant {
+        name: name.to_string(),
+        role: "participant".to_string(),
+        perspective: name.to_string(),
+        persona_type,
+    })
+}
+
+#[derive(Subcommand)]
+pub enum ChaosAction {
+    /// Average scores and satisfaction per chaos level across remembered ideas
+    Analytics {
+        /// Write the table as CSV to this file instead of printing it
+        #[arg(long)]
+        csv: Option<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Run a prompt through the chaos engine at several levels, without calling the LLM
+    Profile {
+        /// Prompt to inject chaos into
+        prompt: String,
+
+        /// Chaos levels to try (0-11), e.g. 1,5,11
+        #[arg(long, value_delimiter = ',', default_value = "1,3,5,7,9,11", value_parser = clap::value_parser!(u8).range(0..=11))]
+        levels: Vec<u8>,
+
+        /// Shape the chaos with this persona's affinity
+        #[arg(short, long)]
+        persona: Option<PersonaType>,
+
+        /// Seed for reproducible variations
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Generate an idea without chaos and with it, then show what the chaos changed
+    Diff {
+        /// What to generate an idea for
+        prompt: String,
+
+        /// Chaos level (1-11) for the chaotic run
+        #[arg(short, long, default_value = "7", value_parser = clap::value_parser!(u8).range(1..=11))]
+        level: u8,
+
+        /// Persona for both runs
+        #[arg(short, long)]
+        persona: Option<PersonaType>,
+
+        /// Domain for both runs
+        #[arg(short, long, default_value = "software development")]
+        domain: String,
+
+        /// Seed for the chaos variations (random and printed when omitted)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Save the chaos engine's current tuning as a named preset
+    Save {
+        /// Preset name (letters, digits, '-' and '_')
+        name: String,
+
+        /// Replace an existing preset with the same name
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Make a saved preset the chaos tuning for future runs
+    Load {
+        /// Preset name
+        name: String,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Write a raw chaotic sequence to CSV (index,value) for offline analysis;
+    /// --lorenz and --henon override those attractors' constants
+    Export {
+        /// Attractor to sample (mixed, lorenz, henon, mandelbrot, julia)
+        #[arg(long, default_value = "mixed")]
+        attractor: Attractor,
+
+        /// Values to generate
+        #[arg(long, default_value = "1000")]
+        length: usize,
+
+        /// CSV file to write
+        #[arg(long)]
+        out: String,
+
+        /// Seed for the fractal sample points (random and printed when omitted)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Julia constant c for the julia attractor (default -0.123,0.745)
+        #[arg(long, value_name = "RE,IM", value_parser = parse_julia, allow_hyphen_values = true)]
+        julia: Option<(f64, f64)>,
+
+        /// Also print the sequence's Lyapunov exponent, correlation dimension,
+        /// entropy rate and predictability horizon
+        #[arg(long)]
+        metrics: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DomainsAction {
+    /// Register the domain patterns in a JSON or TOML file for future runs
+    Add {
+        /// File mapping domain names to lists of patterns
+        file: String,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EntropyAction {
+    /// Draw a sequence from a source and grade its uniformity, independence and compressibility
+    Test {
+        /// Source to test (pseudo, true, quantum, chaos, device:<path>); the current one when omitted
+        #[arg(long)]
+        source: Option<EntropySource>,
+
+        /// Values to draw
+        #[arg(long, default_value = "1000")]
+        samples: usize,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MemoryAction {
+    /// List remembered ideas, newest first, with filters and paging
+    Show {
+        /// Ideas per page
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Matching ideas to skip before the page starts
+        #[arg(long, default_value = "0")]
+        offset: usize,
+
+        /// Only ideas from this persona
+        #[arg(short, long)]
+        persona: Option<PersonaType>,
+
+        /// Only ideas summoned for a domain containing this text
+        #[arg(short, long)]
+        domain: Option<String>,
+
+        /// Only ideas whose balanced score is at least this (0.0-1.0)
+        #[arg(long)]
+        min_score: Option<f64>,
+
+        /// Only ideas created on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Export remembered ideas
+    Export {
+        /// json (one file) or obsidian (a folder of linked markdown notes)
+        #[arg(short, long, default_value = "json")]
+        format: MemoryExportFormat,
+
+        /// Output file (json) or vault directory (obsidian)
+        #[arg(short, long)]
+        out: String,
+    },
+
+    /// Load a memory export, replacing this memory or (with --merge) pooling it in
+    Import {
+        /// JSON file written by `chops memory export`
+        file: String,
+
+        /// Combine with the current memory instead of replacing it
+        #[arg(long)]
+        merge: bool,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Leaderboard of a session's ideas (the current session by default)
+    Rank {
+        /// Session id or id prefix
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Score profile: balanced, creative, practical or novel
+        #[arg(long, default_value = "balanced")]
+        profile: ScoreProfile,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Per-persona effectiveness, token usage and estimated cost
+    Stats {
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Persona × domain grid of average scores, annotated with usage counts
+    Heatmap {
+        #[command(flatten)]
+        format: FormatArg,
+    },
+
+    /// Review low-value patterns, stale knowledge and expired ideas (dry run unless --apply)
+    Prune {
+        /// Delete the listed entries after confirmation
+        #[arg(long)]
+        apply: bool,
+
+        /// Days after which unused entries count as stale
+        #[arg(long, default_value = "30")]
+        stale_days: u32,
+
+        #[command(flatten)]
+        format: FormatArg,
+    },
+}
@@ -0,0 +1,70 @@
+use chops_core::{CHOPSResult, OutputFormat};
+use chops_api::RealityDistortionField;
+use crate::{output, CHOPSSystem};
+use colored::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct AssessReport<'a> {
+    idea: &'a str,
+    chaos: u8,
+    #[serde(flatten)]
+    assessment: &'a RealityDistortionField,
+}
+
+#[tracing::instrument(name = "assess_execute", level = "info", skip(system))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    idea: String,
+    chaos: Option<u8>,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    let chaos = chaos.unwrap_or(system.engine.config.default_settings.default_chaos_level);
+    let reality = system.engine.cognitive_architecture
+        .assess_only(&idea, chaos as f64 / 11.0)
+        .await?;
+
+    if format.is_machine_readable() {
+        return output::print_data(&AssessReport { idea: &idea, chaos, assessment: &reality }, &format);
+    }
+
+    println!("{}", format!("🧪 Reality check at chaos {}: \"{}\"", chaos, idea).bright_blue().bold());
+    println!();
+    output::display_metrics_bar("Distortion", reality.distortion_level);
+    output::display_metrics_bar("Coherence", reality.coherence_maintenance);
+    println!("  {} {}", format!("{:>12}:", "Feasibility").white(), format!("{:+.1}%", reality.feasibility_impact * 100.0).bright_yellow());
+
+    println!("\n{}", format!("🚫 Impossible elements ({}):", reality.impossibilities.len()).bright_red().bold());
+    if reality.impossibilities.is_empty() {
+        println!("  {}", "none found".bright_green());
+    }
+    for finding in &reality.impossibilities {
+        println!("  • {} {} {}", finding.impossibility_type.to_string().bright_red(), finding.description,
+            format!("(\"{}\", {:.0}%)", finding.evidence, finding.score * 100.0).bright_black());
+    }
+
+    println!("\n{}", format!("🌀 Paradoxes ({}):", reality.paradox_resolutions.len()).bright_magenta().bold());
+    if reality.paradox_resolutions.is_empty() {
+        println!("  {}", "none found".bright_green());
+    }
+    for resolution in &reality.paradox_resolutions {
+        println!("  • {}", resolution.paradox.bright_magenta());
+        println!("    {} {}", format!("{}:", resolution.strategy).bright_cyan(), resolution.suggestion);
+    }
+
+    if !reality.reality_bends.is_empty() {
+        println!("\n{}", "🪐 Reality bends:".bright_cyan().bold());
+        for bend in &reality.reality_bends {
+            println!("  • {} {} {}", bend.bend_type.to_string().bright_cyan(), bend.description,
+                format!("(intensity {:.0}%, breakthrough potential {:.0}%)", bend.intensity * 100.0, bend.potential_breakthrough * 100.0).bright_black());
+        }
+    }
+
+    let verdict = if reality.impossibilities.is_empty() {
+        "✅ Nothing here breaks physics or logic; worth summoning".bright_green()
+    } else {
+        "⚠️  This idea leans on the impossible; expect low feasibility".yellow()
+    };
+    println!("\n{}", verdict);
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use chops_core::{parse_batch_prompts, BatchCheckpoint, CHOPSError, CHOPSResult, CompletedPrompt, OutputFormat};
+use chops_api::IdeaRequest;
+use super::summon::{self, SummonOptions, SummonPlan};
+use crate::{output, CHOPSSystem};
+use indicatif::ProgressBar;
+use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// What a batch run left behind, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct BatchReport {
+    out_dir: PathBuf,
+    prompts: usize,
+    completed: Vec<CompletedPrompt>,
+    failed: Vec<FailedPrompt>,
+}
+
+#[derive(Debug, Serialize)]
+struct FailedPrompt {
+    index: usize,
+    prompt: String,
+    error: String,
+}
+
+/// Summons an idea for every prompt in `file` with the summon `options`,
+/// writing each to its own JSON file in the output directory. Finished prompts
+/// are checkpointed so `resume` can skip them, and a failed prompt doesn't
+/// stop the rest. With `concurrency` above 1, that many ideas are generated
+/// at once.
+#[tracing::instrument(name = "batch_execute", level = "info", skip(system, options))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    file: String,
+    out: Option<String>,
+    resume: bool,
+    concurrency: usize,
+    options: &SummonOptions,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    let input_path = PathBuf::from(&file);
+    let content = std::fs::read_to_string(&input_path).map_err(|e| {
+        CHOPSError::InvalidParameter(format!("Cannot read {}: {}", file, e))
+    })?;
+    let prompts = parse_batch_prompts(&content);
+    if prompts.is_empty() {
+        return Err(CHOPSError::InvalidParameter(format!("{} contains no prompts", file)));
+    }
+
+    let Some(plan) = SummonPlan::new(system, options, &format)? else {
+        return Ok(());
+    };
+    let concurrency = concurrency.max(1);
+    if concurrency > 1 && (plan.min_novelty > 0.0 || options.feasibility_band.is_some()) {
+        return Err(CHOPSError::InvalidParameter(
+            "A novelty floor or --feasibility-band re-rolls ideas one at a time, so it can't be combined with --concurrency".to_string()
+        ));
+    }
+
+    let out_dir = out.map(PathBuf::from).unwrap_or_else(|| default_out_dir(&input_path));
+    std::fs::create_dir_all(&out_dir).map_err(CHOPSError::FileSystemError)?;
+
+    // Anything that changes what a prompt produces, or where it goes, keys the checkpoint
+    let persona = options.persona.as_ref().map_or_else(|| "recommended".to_string(), ToString::to_string);
+    let chaos = options.chaos.map_or_else(|| "default".to_string(), |chaos| chaos.to_string());
+    let model = system.engine.cognitive_architecture.claude_client().model().to_string();
+    let run_key = BatchCheckpoint::run_key(&content, &[
+        &persona,
+        &chaos,
+        &model,
+        &out_dir.display().to_string(),
+        &options.batch_settings(),
+    ]);
+    let checkpoint_path = system.engine.data_dir.as_deref().map(|dir| BatchCheckpoint::path_in(dir, &run_key));
+    let previous = match &checkpoint_path {
+        Some(path) => BatchCheckpoint::load(path)?,
+        None => None,
+    };
+    let mut checkpoint = match previous {
+        Some(previous) if resume => {
+            output::status(&format, format!("⏯️  Resuming: {} of {} prompts already done", previous.completed.len(), prompts.len()).bright_cyan());
+            previous
+        },
+        Some(previous) => {
+            output::status(&format, format!(
+                "⚠️  Found a checkpoint with {} finished prompts for this file and settings; starting over (pass --resume to skip them)",
+                previous.completed.len()
+            ).yellow());
+            BatchCheckpoint::new(&input_path, run_key)
+        },
+        None => {
+            if resume {
+                output::status(&format, "No checkpoint for this file and settings yet, starting from the first prompt".bright_black());
+            }
+            BatchCheckpoint::new(&input_path, run_key)
+        },
+    };
+    if checkpoint_path.is_none() {
+        eprintln!("{}", "⚠️  No writable data directory, progress can't be checkpointed for --resume".yellow());
+    }
+
+    let pace = if concurrency > 1 { format!(", {} at a time", concurrency) } else { String::new() };
+    output::status(&format, format!("📦 Batch: {} prompts with {} persona at chaos {}{}, results in {}",
+        prompts.len(), persona, plan.chaos, pace, out_dir.display()).bright_blue().bold());
+
+    let mut pending = Vec::new();
+    for (index, prompt) in prompts.iter().enumerate() {
+        if checkpoint.is_completed(index) {
+            let label = format!("[{}/{}]", index + 1, prompts.len());
+            output::status(&format, format!("{} {} {}", label.bright_black(), "⏭️ ".bright_black(), prompt.bright_black()));
+        } else {
+            pending.push(index);
+        }
+    }
+
+    let mut failed = Vec::new();
+    let pb = ProgressBar::hidden();
+    for chunk in pending.chunks(concurrency) {
+        // Each prompt's domain, persona and full text, settled in order
+        let mut requests = Vec::with_capacity(chunk.len());
+        for &index in chunk {
+            let prompt = &prompts[index];
+            output::status(&format, format!("{} {}", format!("[{}/{}]", index + 1, prompts.len()).bright_cyan(), prompt.bright_white()));
+            let domain = plan.domain_for(system, options, Some(prompt), &format);
+            match plan.persona_for(system, options, &domain, Some(prompt), &format).await {
+                Ok(persona) => requests.push((index, IdeaRequest { input: plan.prompt_for(system, options, &domain, Some(prompt)), domain, persona })),
+                Err(e) => failed.push(fail(index, prompt, e, &format)),
+            }
+        }
+
+        let results = if concurrency == 1 {
+            let mut results = Vec::with_capacity(requests.len());
+            for (_, request) in &requests {
+                // Every prompt starts from the requested reality level, whatever the last one's re-rolls left
+                system.engine.cognitive_architecture.chaos_engine_mut().set_reality_level(options.reality_level)?;
+                results.push(summon::summon_with_rerolls(
+                    system, &pb, &request.input, &request.persona, &request.domain, &plan, options.feasibility_band, false,
+                ).await);
+            }
+            results
+        } else {
+            let batch: Vec<IdeaRequest> = requests.iter().map(|(_, request)| request.clone()).collect();
+            system.engine.cognitive_architecture
+                .process_complex_ideas(&batch, plan.chaos as f64 / 11.0, plan.grounding.as_ref(), &plan.idea_options, concurrency, |_, _| {})
+                .await
+                .into_iter()
+                .map(|result| result.map(|result| (result, plan.chaos)))
+                .collect()
+        };
+
+        for ((index, request), result) in requests.into_iter().zip(results) {
+            let prompt = &prompts[index];
+            let (mut result, chaos) = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    failed.push(fail(index, prompt, e, &format));
+                    continue;
+                }
+            };
+            plan.check_constraints(system, &pb, &mut result).await;
+
+            let output_path = out_dir.join(format!("{:04}.json", index + 1));
+            let json = serde_json::to_string_pretty(&result)?;
+            std::fs::write(&output_path, json).map_err(CHOPSError::FileSystemError)?;
+            output::status(&format, format!("    {}", format!("✅ {}", output_path.display()).green()));
+            plan.keep(system, options, &result, &request.persona, &request.domain, chaos, &format)?;
+
+            checkpoint.record(index, prompt, output_path);
+            if let Some(path) = &checkpoint_path {
+                checkpoint.save(path)?;
+            }
+        }
+    }
+    system.save_memory().await?;
+
+    if failed.is_empty() {
+        if let Some(path) = checkpoint_path.as_deref().filter(|path| path.exists()) {
+            std::fs::remove_file(path).map_err(CHOPSError::FileSystemError)?;
+        }
+    }
+
+    if format.is_machine_readable() {
+        let report = BatchReport { out_dir, prompts: prompts.len(), completed: checkpoint.completed, failed };
+        return output::print_data(&report, &format);
+    }
+
+    system.print_usage_stats();
+
+    let done = checkpoint.completed.len();
+    if failed.is_empty() {
+        println!("\n{}", format!("✨ All {} prompts done", done).bright_green());
+    } else {
+        println!("\n{}", format!(
+            "⚠️  {} of {} prompts done, {} failed; rerun with --resume to retry only the failures",
+            done, prompts.len(), failed.len()
+        ).yellow());
+    }
+
+    Ok(())
+}
+
+fn fail(index: usize, prompt: &str, error: CHOPSError, format: &OutputFormat) -> FailedPrompt {
+    tracing::warn!("Batch prompt {} failed: {}", index, error);
+    output::status(format, format!("    {}", format!("❌ {}", error).red()));
+    FailedPrompt { index, prompt: prompt.to_string(), error: error.to_string() }
+}
+
+/// `<prompts file stem>-ideas/` next to the prompts file
+fn default_out_dir(input_path: &Path) -> PathBuf {
+    let stem = input_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("batch");
+    input_path.with_file_name(format!("{}-ideas", stem))
+}
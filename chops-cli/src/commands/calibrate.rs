@@ -0,0 +1,65 @@
+use chops_core::{CHOPSResult, CHOPSError, OutputFormat};
+use chops_api::{LinearCalibration, ScoreCalibration};
+use crate::{output, CHOPSSystem};
+use colored::*;
+
+#[tracing::instrument(name = "calibrate_execute", level = "info", skip(system))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    labeled: String,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    output::status(&format, "🎯 CHOPS Score Calibration".bright_blue().bold());
+
+    let examples = ScoreCalibration::load_examples(std::path::Path::new(&labeled))?;
+    tracing::info!("Loaded {} labeled examples from {}", examples.len(), labeled);
+
+    let client = system.engine.cognitive_architecture.claude_client();
+    let mut creativity_pairs = Vec::with_capacity(examples.len());
+    let mut feasibility_pairs = Vec::with_capacity(examples.len());
+
+    for example in &examples {
+        let (creativity, feasibility) = client.heuristic_scores(&example.content, example.chaos_level, example.unexpected_elements);
+        creativity_pairs.push((creativity, example.creativity));
+        feasibility_pairs.push((feasibility, example.feasibility));
+    }
+
+    let calibration = ScoreCalibration::fit(&creativity_pairs, &feasibility_pairs)?;
+
+    if !format.is_machine_readable() {
+        let identity = LinearCalibration::identity();
+        println!("\n{}", format!("Fitted against {} examples:", calibration.sample_count).bright_green());
+        print_fit("Creativity", &calibration.creativity, &identity, &creativity_pairs);
+        print_fit("Feasibility", &calibration.feasibility, &identity, &feasibility_pairs);
+    }
+
+    let Some(data_dir) = &system.engine.data_dir else {
+        return Err(CHOPSError::ConfigError(
+            "No writable data directory to store the calibration (set CHOPS_DATA_DIR)".to_string()
+        ));
+    };
+
+    let path = data_dir.join("calibration.json");
+    calibration.save_to_file(&path)?;
+    if format.is_machine_readable() {
+        output::print_data(&calibration, &format)?;
+    }
+    system.engine.cognitive_architecture.claude_client_mut().set_calibration(Some(calibration));
+
+    output::status(&format, format!("\n{}", format!("💾 Calibration saved to {}", path.display()).bright_green()));
+    output::status(&format, "   Future idea scores will be adjusted to match your judgment.".bright_black());
+
+    Ok(())
+}
+
+fn print_fit(label: &str, fitted: &LinearCalibration, identity: &LinearCalibration, pairs: &[(f64, f64)]) {
+    println!("  {} {} {}",
+        format!("{}:", label).bright_cyan(),
+        format!("offset {:+.3}, scale {:.3}", fitted.offset, fitted.scale).bright_white(),
+        format!(
+            "(mean error {:.3} → {:.3})",
+            identity.mean_absolute_error(pairs),
+            fitted.mean_absolute_error(pairs)
+        ).bright_black()
+    );
+}
@@ -0,0 +1,421 @@
+use chops_core::{change_ratio, CHOPSError, CHOPSResult, ChaosAnalytics, ChaosTier, OutputFormat, PersonaType, ScoreProfile};
+use chops_api::GeneratedIdeaResponse;
+use chops_chaos::{Attractor, ChaosEngineConfig, ChaosInjectionResult, ChaosMetrics, ChaosVariationType};
+use crate::cli::ChaosAction;
+use crate::{output, CHOPSSystem};
+use colored::*;
+use serde::Serialize;
+
+/// Levels need this many ideas before one is called out as the sweet spot
+const MIN_SAMPLES_FOR_BEST: usize = 3;
+
+/// Balanced-score change below which chaos is said to have made little difference
+const CHAOS_DIFF_NOISE: f64 = 0.03;
+
+/// Most values `chaos export --metrics` measures. The estimators compare every
+/// pair of points, so longer exports are measured on their first this many.
+const MAX_METRICS_VALUES: usize = 10_000;
+
+/// One level of `chaos profile`, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct ProfileLevel {
+    level: u8,
+    tier: ChaosTier,
+    #[serde(flatten)]
+    result: ChaosInjectionResult,
+}
+
+/// Both generations of `chaos diff`, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct ChaosDiff<'a> {
+    prompt: &'a str,
+    persona: PersonaType,
+    domain: &'a str,
+    level: u8,
+    seed: u64,
+    calm: GeneratedIdeaResponse,
+    chaotic: GeneratedIdeaResponse,
+    /// Share of the idea's lines the chaos changed
+    change_ratio: f64,
+    /// Balanced score of the chaotic idea minus the calm one
+    score_delta: f64,
+}
+
+/// A written `chaos export`, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct ExportedSequence<'a> {
+    out: &'a str,
+    attractor: Attractor,
+    length: usize,
+    seed: u64,
+    metrics: Option<ChaosMetrics>,
+    /// Leading values the metrics were measured on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics_values: Option<usize>,
+}
+
+#[tracing::instrument(name = "chaos_execute", level = "info", skip(system, action))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    action: ChaosAction,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    match action {
+        ChaosAction::Analytics { csv, .. } => print_analytics(system, csv, &format),
+        ChaosAction::Profile { prompt, levels, persona, seed, .. } => print_profile(system, &prompt, &levels, persona, seed, &format).await,
+        ChaosAction::Diff { prompt, level, persona, domain, seed, .. } => print_chaos_diff(system, &prompt, level, persona, &domain, seed, &format).await,
+        ChaosAction::Save { name, force, .. } => save_preset(system, &name, force, &format),
+        ChaosAction::Load { name, .. } => load_preset(system, &name, &format),
+        ChaosAction::Export { attractor, length, out, seed, julia, metrics, .. } => {
+            export_sequence(system, attractor, length, &out, seed, julia, metrics, &format)
+        },
+    }
+}
+
+fn print_analytics(system: &CHOPSSystem, csv: Option<String>, format: &OutputFormat) -> CHOPSResult<()> {
+    let samples = system.engine.memory_system.chaos_samples();
+    let analytics = ChaosAnalytics::from_samples(&samples);
+    tracing::debug!("Aggregated {} ideas into {} chaos levels", samples.len(), analytics.buckets.len());
+
+    if let Some(path) = csv {
+        std::fs::write(&path, analytics.to_csv()).map_err(CHOPSError::FileSystemError)?;
+        output::status(format, format!("📄 Wrote chaos analytics for {} ideas to {}", samples.len(), path).bright_green());
+        return Ok(());
+    }
+    if format.is_machine_readable() {
+        let report = serde_json::json!({
+            "buckets": analytics.buckets,
+            "best_level": analytics.best_level(MIN_SAMPLES_FOR_BEST),
+        });
+        return output::print_data(&report, format);
+    }
+
+    println!("{}", "🌀 CHOPS Chaos Analytics".bright_blue().bold());
+    if analytics.buckets.is_empty() {
+        println!("{}", "No ideas remembered yet. Summon a few at different chaos levels first.".yellow());
+        return Ok(());
+    }
+
+    let percent = |value: Option<f64>| value.map(|value| format!("{:.0}%", value * 100.0)).unwrap_or_else(|| "-".to_string());
+    println!("{}", format!("{:>5} {:>7} {:>10} {:>11} {:>9} {:>12}", "chaos", "ideas", "creative", "feasible", "coherent", "satisfaction").bright_black());
+    for bucket in &analytics.buckets {
+        println!(
+            "{:>5} {:>7} {:>10} {:>11} {:>9} {:>12}",
+            bucket.level.to_string().bright_white(),
+            bucket.samples,
+            percent(Some(bucket.avg_creativity)),
+            percent(Some(bucket.avg_feasibility)),
+            percent(bucket.avg_coherence),
+            percent(bucket.satisfaction)
+        );
+    }
+
+    match analytics.best_level(MIN_SAMPLES_FOR_BEST) {
+        Some(level) => println!("\n{}", format!("🎯 Best results so far at chaos level {}", level).bright_green()),
+        None => println!("\n{}", format!("💡 Need at least {} ideas at a level to recommend one.", MIN_SAMPLES_FOR_BEST).bright_black()),
+    }
+
+    Ok(())
+}
+
+/// Runs the prompt through a copy of the chaos engine at each level so users can
+/// see how chaotic a summon would get before spending tokens
+async fn print_profile(
+    system: &CHOPSSystem,
+    prompt: &str,
+    levels: &[u8],
+    persona: Option<PersonaType>,
+    seed: Option<u64>,
+    format: &OutputFormat,
+) -> CHOPSResult<()> {
+    let mut engine = system.engine.cognitive_architecture.chaos_engine().clone();
+    if let Some(persona) = &persona {
+        engine.apply_affinity(&system.engine.cognitive_architecture.persona_engine().chaos_affinity(persona)?);
+    }
+    engine.set_seed(seed);
+
+    output::status(format, format!("🌀 Chaos profile for \"{}\"", prompt).bright_blue().bold());
+    if let Some(persona) = &persona {
+        output::status(format, format!("Shaped by the {} chaos affinity", persona).bright_black());
+    }
+
+    let mut profile = Vec::with_capacity(levels.len());
+    for &level in levels {
+        engine.set_chaos_level(level)?;
+        let tier = engine.tier_boundaries().tier_for(engine.chaos_level);
+        let result = engine.inject_creative_chaos(prompt).await?;
+        tracing::debug!("Level {}: {} variations", level, result.variations_generated.len());
+        if format.is_machine_readable() {
+            profile.push(ProfileLevel { level, tier, result });
+            continue;
+        }
+
+        let mut types: Vec<ChaosVariationType> = Vec::new();
+        for variation in &result.variations_generated {
+            if !types.contains(&variation.variation_type) {
+                types.push(variation.variation_type);
+            }
+        }
+        let types: Vec<String> = types.iter()
+            .map(|variation_type| {
+                let count = result.variations_generated.iter().filter(|v| v.variation_type == *variation_type).count();
+                if count > 1 { format!("{} ×{}", variation_type, count) } else { variation_type.to_string() }
+            })
+            .collect();
+
+        println!("\n{} {}", format!("Level {:>2}", level).bright_white().bold(), format!("({})", tier).bright_black());
+        println!("  {} {:.2}", "Intensity:".bright_cyan(), result.chaos_applied);
+        println!("  {} {:.0}%", "Coherence:".bright_cyan(), result.coherence_score * 100.0);
+        println!("  {} {:.2}", "Reality distortion:".bright_cyan(), result.reality_distortion_applied);
+        println!("  {} {}", "Variations:".bright_cyan(), if types.is_empty() { "none".to_string() } else { types.join(", ") });
+        if result.variations_pruned > 0 {
+            println!("  {}", format!("{} pruned to stay above the {:.0}% coherence threshold",
+                result.variations_pruned, engine.controlled_randomness.coherence_threshold * 100.0).bright_black());
+        }
+        for element in &result.unexpected_elements {
+            println!("  {} {}", "✨".bright_magenta(), element);
+        }
+    }
+
+    if format.is_machine_readable() {
+        return output::print_data(&profile, format);
+    }
+    println!("\n{}", "No tokens were spent; this only exercises the local chaos engine.".bright_black());
+    Ok(())
+}
+
+/// Generates the idea twice, at chaos level 0 and at `level`, with the same
+/// persona, seed and temperature, then shows what the chaos changed
+async fn print_chaos_diff(
+    system: &mut CHOPSSystem,
+    prompt: &str,
+    level: u8,
+    persona: Option<PersonaType>,
+    domain: &str,
+    seed: Option<u64>,
+    format: &OutputFormat,
+) -> CHOPSResult<()> {
+    let persona = persona.unwrap_or(system.engine.config.default_settings.default_persona.clone());
+    let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+
+    output::status(format, format!("🌀 Chaos diff for \"{}\"", prompt).bright_blue().bold());
+    output::status(format, format!("Persona {}, domain {}, chaos 0 vs {}, seed {}", persona, domain, level, seed).bright_black());
+
+    // Pin sampling so the two runs differ by the chaos alone
+    let architecture = &mut system.engine.cognitive_architecture;
+    architecture.claude_client_mut().set_temperature(Some(0.0));
+    architecture.chaos_engine_mut().set_seed(Some(seed));
+    let calm = architecture.generate_at_chaos_level(prompt, persona.clone(), domain, 0).await?;
+    architecture.chaos_engine_mut().set_seed(Some(seed));
+    let chaotic = architecture.generate_at_chaos_level(prompt, persona.clone(), domain, level).await?;
+
+    let score = |idea: &GeneratedIdeaResponse| ScoreProfile::Balanced.score(
+        idea.creativity_score, idea.feasibility_score, idea.novelty_score, idea.excitement_factor,
+    );
+    if format.is_machine_readable() {
+        let report = ChaosDiff {
+            prompt,
+            persona,
+            domain,
+            level,
+            seed,
+            change_ratio: change_ratio(&calm.content, &chaotic.content),
+            score_delta: score(&chaotic) - score(&calm),
+            calm,
+            chaotic,
+        };
+        return output::print_data(&report, format);
+    }
+
+    println!("\n{}", "📊 Scores (no chaos → chaos)".bright_cyan().bold());
+    for (label, before, after) in [
+        ("Creativity", calm.creativity_score, chaotic.creativity_score),
+        ("Feasibility", calm.feasibility_score, chaotic.feasibility_score),
+        ("Novelty", calm.novelty_score, chaotic.novelty_score),
+        ("Excitement", calm.excitement_factor, chaotic.excitement_factor),
+        ("Overall", score(&calm), score(&chaotic)),
+    ] {
+        let delta = (after - before) * 100.0;
+        let delta = match delta {
+            d if d > 0.5 => format!("{:+.0}", d).green(),
+            d if d < -0.5 => format!("{:+.0}", d).red(),
+            d => format!("{:+.0}", d).bright_black(),
+        };
+        println!("  {:<12} {:>4.0}% → {:>4.0}%  {}", label, before * 100.0, after * 100.0, delta);
+    }
+
+    if !chaotic.unexpected_elements.is_empty() || !chaotic.chaos_variations.is_empty() {
+        println!("\n{}", "✨ Chaos injected".bright_cyan().bold());
+        for element in &chaotic.unexpected_elements {
+            println!("  {} {}", "•".bright_magenta(), element);
+        }
+        for variation in &chaotic.chaos_variations {
+            println!("  {} {}", format!("[{}]", variation.variation_type).bright_black(), variation.description);
+        }
+    }
+
+    println!("\n{}", format!(
+        "📝 Idea text ({:.0}% of lines changed)", change_ratio(&calm.content, &chaotic.content) * 100.0
+    ).bright_cyan().bold());
+    output::display_line_diff(&calm.content, &chaotic.content, 1);
+
+    let delta = score(&chaotic) - score(&calm);
+    let verdict = if delta > CHAOS_DIFF_NOISE {
+        format!("🎯 Chaos helped this prompt: overall score {:+.0} points at level {}", delta * 100.0, level).bright_green()
+    } else if delta < -CHAOS_DIFF_NOISE {
+        format!("🧊 Chaos hurt this prompt: overall score {:+.0} points; try a lower level", delta * 100.0).bright_yellow()
+    } else {
+        format!("⚖️  Chaos changed the wording more than the quality here ({:+.0} points)", delta * 100.0).bright_black()
+    };
+    println!("\n{}", verdict);
+
+    let tokens: u64 = [&calm, &chaotic].iter()
+        .filter_map(|idea| idea.usage.as_ref())
+        .map(|usage| (usage.input_tokens + usage.output_tokens) as u64)
+        .sum();
+    println!("{}", format!("Two generations, {} tokens", tokens).bright_black());
+    Ok(())
+}
+
+/// Directory under the data dir that holds named chaos presets
+const PRESET_DIR: &str = "chaos-presets";
+
+fn preset_dir(system: &CHOPSSystem) -> CHOPSResult<std::path::PathBuf> {
+    system.engine.data_dir.as_ref().map(|dir| dir.join(PRESET_DIR)).ok_or_else(|| {
+        CHOPSError::ConfigError("No writable data directory for chaos presets (set CHOPS_DATA_DIR)".to_string())
+    })
+}
+
+fn preset_path(system: &CHOPSSystem, name: &str) -> CHOPSResult<std::path::PathBuf> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(CHOPSError::InvalidParameter(format!(
+            "Preset name '{}' may only use letters, digits, '-' and '_'", name
+        )));
+    }
+    Ok(preset_dir(system)?.join(format!("{}.json", name)))
+}
+
+fn save_preset(system: &CHOPSSystem, name: &str, force: bool, format: &OutputFormat) -> CHOPSResult<()> {
+    let path = preset_path(system, name)?;
+    if path.exists() && !force {
+        return Err(CHOPSError::InvalidParameter(format!(
+            "Chaos preset '{}' already exists; pass --force to replace it", name
+        )));
+    }
+
+    let config = system.engine.cognitive_architecture.chaos_engine().export_config();
+    config.save_to_file(&path)?;
+    tracing::info!("Saved chaos preset '{}' to {}", name, path.display());
+
+    output::status(format, format!("💾 Saved chaos preset '{}' to {}", name, path.display()).bright_green());
+    if format.is_machine_readable() {
+        return output::print_data(&config, format);
+    }
+    print_preset(&config);
+    Ok(())
+}
+
+fn load_preset(system: &mut CHOPSSystem, name: &str, format: &OutputFormat) -> CHOPSResult<()> {
+    let path = preset_path(system, name)?;
+    if !path.exists() {
+        let mut saved: Vec<String> = std::fs::read_dir(preset_dir(system)?)
+            .map(|entries| entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.path().file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+                .collect())
+            .unwrap_or_default();
+        saved.sort();
+        let available = if saved.is_empty() { "none saved yet".to_string() } else { saved.join(", ") };
+        return Err(CHOPSError::InvalidParameter(format!("No chaos preset '{}' (available: {})", name, available)));
+    }
+
+    let config = ChaosEngineConfig::load_from_file(&path)?;
+    system.engine.cognitive_architecture.chaos_engine_mut().import_config(config.clone())?;
+
+    // Picked up at startup, like the score calibration
+    let data_dir = system.engine.data_dir.as_ref().expect("preset_dir checked the data dir");
+    let active_path = data_dir.join("chaos.json");
+    config.save_to_file(&active_path)?;
+    tracing::info!("Activated chaos preset '{}'", name);
+
+    output::status(format, format!("🌀 Chaos preset '{}' is now active", name).bright_green());
+    if format.is_machine_readable() {
+        return output::print_data(&config, format);
+    }
+    print_preset(&config);
+    println!("{}", format!("   Stored in {}; delete it to go back to the defaults.", active_path.display()).bright_black());
+    Ok(())
+}
+
+fn print_preset(config: &ChaosEngineConfig) {
+    let randomness = &config.controlled_randomness;
+    let distortion = &config.reality_distortion;
+    println!("  {} {:.1} / 11", "Chaos level:".bright_cyan(), config.chaos_level * 11.0);
+    println!("  {} {:?}, stability {:.2}, coherence threshold {:.2}{}",
+        "Randomness: ".bright_cyan(),
+        randomness.distribution,
+        randomness.stability_factor,
+        randomness.coherence_threshold,
+        randomness.seed.map(|seed| format!(", seed {}", seed)).unwrap_or_default());
+    println!("  {} {}, intensity {:.2}, impossibility tolerance {:.2}, paradox acceptance {:.2}",
+        "Distortion: ".bright_cyan(),
+        if distortion.enabled { "on" } else { "off" },
+        distortion.intensity,
+        distortion.impossibility_tolerance,
+        distortion.paradox_acceptance);
+}
+
+/// Writes `length` values of one attractor as `index,value` CSV. The sequence
+/// starts from the attractors' initial states on a copy of the session's
+/// mathematics (with any --lorenz/--henon constants), so the same constants
+/// and seed always reproduce it.
+#[allow(clippy::too_many_arguments)]
+fn export_sequence(
+    system: &CHOPSSystem,
+    attractor: Attractor,
+    length: usize,
+    out: &str,
+    seed: Option<u64>,
+    julia: Option<(f64, f64)>,
+    metrics: bool,
+    format: &OutputFormat,
+) -> CHOPSResult<()> {
+    if length == 0 {
+        return Err(CHOPSError::InvalidParameter("--length must be at least 1".to_string()));
+    }
+    let mut mathematics = system.engine.cognitive_architecture.chaos_engine().mathematics.clone();
+    if let Some((real, imag)) = julia {
+        mathematics.set_julia_parameters(real, imag)?;
+    }
+    let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+
+    let sequence = mathematics.generate_attractor_sequence(attractor, length, seed);
+    let mut csv = String::with_capacity(length * 24);
+    csv.push_str("index,value\n");
+    for (index, value) in sequence.iter().enumerate() {
+        csv.push_str(&format!("{},{}\n", index, value));
+    }
+    std::fs::write(out, csv).map_err(CHOPSError::FileSystemError)?;
+
+    output::status(format, format!("📄 Wrote {} {} values to {}", length, attractor, out).bright_green());
+    output::status(format, format!("   Seed {} (pass --seed {} to reproduce)", seed, seed).bright_black());
+
+    let metrics_values = metrics.then(|| sequence.len().min(MAX_METRICS_VALUES));
+    let metrics = metrics_values.map(|values| mathematics.chaos_metrics(&sequence[..values]));
+    if format.is_machine_readable() {
+        return output::print_data(&ExportedSequence { out, attractor, length, seed, metrics, metrics_values }, format);
+    }
+    if let (Some(metrics), Some(values)) = (metrics, metrics_values) {
+        if values < sequence.len() {
+            println!("\n{}", format!("📐 Chaos metrics (first {} values)", values).bright_cyan().bold());
+        } else {
+            println!("\n{}", "📐 Chaos metrics".bright_cyan().bold());
+        }
+        println!("  {:<24} {:.4}", "Lyapunov exponent:", metrics.lyapunov_exponent);
+        println!("  {:<24} {:.4}", "Correlation dimension:", metrics.correlation_dimension);
+        println!("  {:<24} {:.4}", "Entropy rate:", metrics.entropy_rate);
+        println!("  {:<24} {:.0} steps", "Predictability horizon:", metrics.predictability_horizon);
+    }
+    Ok(())
+}
@@ -1,18 +1,80 @@
-use chops_core::CHOPSResult;
-use crate::{CHOPSSystem, cli::CollaborationMode};
+use chops_core::{CHOPSResult, OutputFormat};
+use chops_api::{CollaborationMode, CollaborationParticipant, ContributionType};
+use crate::{output, CHOPSSystem};
 use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
 
+#[tracing::instrument(name = "collaborate_execute", level = "info", skip(system))]
 pub async fn execute(
-    _system: &mut CHOPSSystem,
+    system: &mut CHOPSSystem,
     mode: CollaborationMode,
     topic: String,
+    participants: Vec<CollaborationParticipant>,
+    rounds: u8,
     human: bool,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
-    println!("{}", "🤝 AI Collaboration Engine".bright_blue().bold());
-    println!("Mode: {}", format!("{}", mode).bright_white());
-    println!("Topic: {}", topic.bright_cyan());
-    println!("Human participation: {}", if human { "✅ Enabled".green() } else { "❌ AI-only".red() });
-    
-    println!("\n{}", "🚧 Collaboration engine implementation coming soon...".bright_yellow());
+    output::status(&format, "🤝 AI Collaboration Engine".bright_blue().bold());
+    output::status(&format, format!("Mode: {}", format!("{}", mode).bright_white()));
+    output::status(&format, format!("Topic: {}", topic.bright_cyan()));
+    output::status(&format, format!("Rounds: {}", rounds.to_string().bright_white()));
+    output::status(&format, format!("Human participation: {}", if human { "✅ Enabled".green() } else { "❌ AI-only".red() }));
+    if human {
+        output::status(&format, "⚠️  Human turns aren't supported yet; running AI-only".yellow());
+    }
+
+    let participants = if participants.is_empty() { mode.default_participants() } else { participants };
+    output::status(&format, format!("\n{}", "👥 Participants:".bright_blue().bold()));
+    for participant in &participants {
+        let voice = match &participant.persona_type {
+            Some(persona) => format!("as {}", persona).bright_magenta(),
+            None => participant.perspective.normal(),
+        };
+        output::status(&format, format!("  • {} {} {}", participant.name.bright_white().bold(), format!("({})", participant.role).bright_black(), voice));
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.blue} {msg}").unwrap());
+    pb.set_message(format!("🤝 Running {} rounds of {}...", rounds, mode));
+    pb.enable_steady_tick(Duration::from_millis(100));
+    let session = system.engine.collaborate(&topic, mode, participants, rounds).await;
+    pb.finish_and_clear();
+    let session = session?;
+
+    if format.is_machine_readable() {
+        return output::print_data(&session, &format);
+    }
+
+    for round in &session.rounds {
+        println!("\n{}", format!("═══ Round {} ═══", round.round_number).bright_blue().bold());
+        for contribution in &round.contributions {
+            let tag = format!("[{}]", contribution.contribution_type);
+            let tag = match contribution.contribution_type {
+                ContributionType::Challenge | ContributionType::CounterPoint => tag.bright_red(),
+                ContributionType::Insight | ContributionType::Synthesis => tag.bright_magenta(),
+                _ => tag.bright_cyan(),
+            };
+            println!("\n{} {} {}", contribution.participant.bright_white().bold(), tag,
+                format!("(creativity {:.0}%, new ground {:.0}%)", contribution.creativity_score * 100.0, contribution.insight_level * 100.0).bright_black());
+            println!("{}", contribution.content.trim());
+        }
+        if let Some(summary) = &round.summary {
+            println!("\n{} {}", "📋 Where things stand:".bright_yellow().bold(), summary.trim());
+        }
+    }
+
+    if let Some(synthesis) = &session.synthesis {
+        println!("\n{}", "🧩 Synthesis:".bright_green().bold());
+        println!("{}", synthesis.trim());
+    }
+    if !session.insights.is_empty() {
+        println!("\n{}", format!("💡 Key insights ({}):", session.insights.len()).bright_yellow().bold());
+        for insight in &session.insights {
+            println!("  • {}", insight);
+        }
+    }
+
+    system.print_usage_stats();
     Ok(())
-}
\ No newline at end of file
+}
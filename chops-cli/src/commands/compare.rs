@@ -0,0 +1,229 @@
+use chops_core::{CHOPSError, CHOPSResult, OutputFormat, PersonaType, ScoreProfile};
+use chops_api::{resolve_model, GeneratedIdeaResponse, ModelPricing, ModelRun};
+use crate::{output, CHOPSSystem};
+use colored::*;
+use serde::Serialize;
+
+/// Lines of each idea shown in the side-by-side view
+const COMPARE_CONTENT_LINES: usize = 30;
+
+/// Narrowest column the side-by-side view will squeeze an idea into
+const MIN_COLUMN_WIDTH: usize = 24;
+
+/// The comparison for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct CompareReport<'a> {
+    prompt: &'a str,
+    persona: &'a PersonaType,
+    domain: &'a str,
+    chaos: u8,
+    seed: u64,
+    runs: Vec<RunReport<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RunReport<'a> {
+    model: &'a str,
+    latency_secs: f64,
+    /// Balanced score, when the model produced an idea
+    overall: Option<f64>,
+    cost: Option<f64>,
+    idea: Option<&'a GeneratedIdeaResponse>,
+    error: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "compare_models_execute", level = "info", skip(system))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    prompt: String,
+    models: Vec<String>,
+    persona: Option<PersonaType>,
+    domain: String,
+    chaos: Option<u8>,
+    seed: Option<u64>,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    let provider = system.engine.cognitive_architecture.claude_client().provider();
+    let mut resolved: Vec<String> = Vec::new();
+    for model in models.iter().filter(|model| !model.trim().is_empty()) {
+        let model = resolve_model(model, &provider)?;
+        if !resolved.contains(&model) {
+            resolved.push(model);
+        }
+    }
+    if resolved.len() < 2 {
+        return Err(CHOPSError::InvalidParameter("Give at least two different models to compare".to_string()));
+    }
+
+    let persona = persona.unwrap_or(system.engine.config.default_settings.default_persona.clone());
+    let chaos = chaos.unwrap_or(system.engine.config.default_settings.default_chaos_level);
+    let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+
+    output::status(&format, format!("⚖️  Comparing {} models on \"{}\"", resolved.len(), prompt).bright_blue().bold());
+    output::status(&format, format!("Persona {}, domain {}, chaos {}, seed {}", persona, domain, chaos, seed).bright_black());
+    output::status(&format, "Generating with every model at once...".bright_black());
+
+    let runs = system.engine.cognitive_architecture
+        .compare_models(&prompt, persona.clone(), &domain, chaos, seed, &resolved)
+        .await?;
+
+    if format.is_machine_readable() {
+        let runs = runs.iter()
+            .map(|run| RunReport {
+                model: &run.model,
+                latency_secs: run.latency.as_secs_f64(),
+                overall: run.result.as_ref().ok().map(overall),
+                cost: run.result.as_ref().ok().map(|idea| cost(run, idea)),
+                idea: run.result.as_ref().ok(),
+                error: run.result.as_ref().err().map(ToString::to_string),
+            })
+            .collect();
+        let report = CompareReport { prompt: &prompt, persona: &persona, domain: &domain, chaos, seed, runs };
+        return output::print_data(&report, &format);
+    }
+
+    print_score_table(&runs);
+    print_side_by_side(&runs);
+    print_verdict(&runs);
+    Ok(())
+}
+
+fn overall(idea: &GeneratedIdeaResponse) -> f64 {
+    ScoreProfile::Balanced.score(idea.creativity_score, idea.feasibility_score, idea.novelty_score, idea.excitement_factor)
+}
+
+fn cost(run: &ModelRun, idea: &GeneratedIdeaResponse) -> f64 {
+    idea.usage.as_ref().map(|usage| ModelPricing::for_model(&run.model).cost(usage)).unwrap_or(0.0)
+}
+
+fn print_score_table(runs: &[ModelRun]) {
+    let width = runs.iter().map(|run| run.model.len()).max().unwrap_or(5).max(5);
+
+    println!("\n{}", "📊 Scores, latency and cost".bright_cyan().bold());
+    println!("  {:<width$}  {:>7} {:>7} {:>7} {:>7} {:>8} {:>8} {:>9}",
+        "Model", "Overall", "Create", "Feasib", "Novel", "Latency", "Tokens", "Cost", width = width);
+    for run in runs {
+        let idea = match &run.result {
+            Ok(idea) => idea,
+            Err(e) => {
+                println!("  {:<width$}  {}", run.model, format!("failed after {:.1}s: {}", run.latency.as_secs_f64(), e).red(), width = width);
+                continue;
+            },
+        };
+        let tokens = idea.usage.as_ref().map(|usage| usage.input_tokens + usage.output_tokens).unwrap_or(0);
+        println!("  {:<width$}  {:>6.0}% {:>6.0}% {:>6.0}% {:>6.0}% {:>7.1}s {:>8} {:>9}",
+            run.model,
+            overall(idea) * 100.0,
+            idea.creativity_score * 100.0,
+            idea.feasibility_score * 100.0,
+            idea.novelty_score * 100.0,
+            run.latency.as_secs_f64(),
+            tokens,
+            format!("${:.4}", cost(run, idea)),
+            width = width);
+    }
+}
+
+/// Each model's idea in its own column, wrapped to fit the terminal
+fn print_side_by_side(runs: &[ModelRun]) {
+    let ideas: Vec<(&str, &GeneratedIdeaResponse)> = runs.iter()
+        .filter_map(|run| run.result.as_ref().ok().map(|idea| (run.model.as_str(), idea)))
+        .collect();
+    if ideas.is_empty() {
+        return;
+    }
+
+    let (_, terminal_width) = console::Term::stdout().size();
+    let column_width = ((terminal_width as usize).saturating_sub(3 * (ideas.len() - 1)) / ideas.len()).max(MIN_COLUMN_WIDTH);
+    let columns: Vec<Vec<String>> = ideas.iter()
+        .map(|(_, idea)| {
+            let mut lines = wrap(&idea.content, column_width);
+            if lines.len() > COMPARE_CONTENT_LINES {
+                lines.truncate(COMPARE_CONTENT_LINES);
+                lines.push("…".to_string());
+            }
+            lines
+        })
+        .collect();
+
+    println!("\n{}", "📝 Ideas side by side".bright_cyan().bold());
+    let header: Vec<String> = ideas.iter().map(|(model, _)| format!("{:<column_width$}", truncate(model, column_width))).collect();
+    println!("{}", header.join(" │ ").bright_magenta().bold());
+    println!("{}", vec!["─".repeat(column_width); ideas.len()].join("─┼─").bright_black());
+
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    for row in 0..rows {
+        let cells: Vec<String> = columns.iter()
+            .map(|lines| format!("{:<column_width$}", lines.get(row).map(String::as_str).unwrap_or("")))
+            .collect();
+        println!("{}", cells.join(" │ ").trim_end());
+    }
+}
+
+fn print_verdict(runs: &[ModelRun]) {
+    let succeeded: Vec<(&ModelRun, &GeneratedIdeaResponse)> = runs.iter()
+        .filter_map(|run| run.result.as_ref().ok().map(|idea| (run, idea)))
+        .collect();
+    if succeeded.is_empty() {
+        println!("\n{}", "❌ Every model failed; nothing to compare".red());
+        return;
+    }
+
+    let best = succeeded.iter().max_by(|a, b| overall(a.1).total_cmp(&overall(b.1))).expect("at least one run");
+    let cheapest = succeeded.iter().min_by(|a, b| cost(a.0, a.1).total_cmp(&cost(b.0, b.1))).expect("at least one run");
+    let fastest = succeeded.iter().min_by_key(|(run, _)| run.latency).expect("at least one run");
+
+    println!("\n{}", "🏁 Verdict".bright_cyan().bold());
+    println!("  {} {} ({:.0}%)", "Best idea:".bright_green(), best.0.model, overall(best.1) * 100.0);
+    println!("  {} {} (${:.4})", "Cheapest: ".bright_green(), cheapest.0.model, cost(cheapest.0, cheapest.1));
+    println!("  {} {} ({:.1}s)", "Fastest:  ".bright_green(), fastest.0.model, fastest.0.latency.as_secs_f64());
+
+    if best.0.model != cheapest.0.model {
+        let gap = (overall(best.1) - overall(cheapest.1)) * 100.0;
+        let ratio = cost(best.0, best.1) / cost(cheapest.0, cheapest.1).max(f64::EPSILON);
+        println!("{}", format!(
+            "💡 {} scored {:.0} points above {} at {:.1}x the cost",
+            best.0.model, gap, cheapest.0.model, ratio
+        ).bright_yellow());
+    }
+}
+
+/// Greedy word wrap; words longer than the width are split
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > width {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..width).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if word.is_empty() {
+                continue;
+            }
+            if line.is_empty() {
+                line = word;
+            } else if line.chars().count() + 1 + word.chars().count() <= width {
+                line.push(' ');
+                line.push_str(&word);
+            } else {
+                lines.push(std::mem::replace(&mut line, word));
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        text.chars().take(width.saturating_sub(1)).chain(std::iter::once('…')).collect()
+    }
+}
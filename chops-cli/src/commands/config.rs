@@ -1,27 +1,71 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{CHOPSResult, LlmProvider, OutputFormat, PersonaType};
+use crate::{output, CHOPSSystem};
 use colored::*;
+use serde::Serialize;
+
+/// `--show` for `--format json|yaml`; whether a key is set, never the key
+#[derive(Debug, Serialize)]
+struct ConfigSummary {
+    provider: LlmProvider,
+    /// `None` when the provider needs no key
+    api_key_configured: Option<bool>,
+    offline: bool,
+    default_persona: PersonaType,
+    default_chaos_level: u8,
+    safe_mode: bool,
+}
 
 pub async fn execute(
     _system: &mut CHOPSSystem,
     show: bool,
     set: Vec<String>,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
     if show {
-        println!("{}", "⚙️ CHOPS Configuration".bright_blue().bold());
-        println!("API Keys: {}", if _system.config.api_keys.claude_api_key.is_some() { "✅ Configured".green() } else { "❌ Missing".red() });
-        println!("Default persona: {}", format!("{}", _system.config.default_settings.default_persona).bright_cyan());
-        println!("Default chaos level: {}", _system.config.default_settings.default_chaos_level.to_string().bright_yellow());
-        println!("Safe mode: {}", if _system.config.behavior_settings.safe_mode { "✅ Enabled".green() } else { "❌ Disabled".red() });
+        let provider = _system.engine.config.default_settings.provider;
+        let api_key = match provider {
+            LlmProvider::Claude => Some(&_system.engine.config.api_keys.claude_api_key),
+            LlmProvider::OpenAi => Some(&_system.engine.config.api_keys.openai_api_key),
+            LlmProvider::Mock => None,
+        };
+        if format.is_machine_readable() {
+            let settings = &_system.engine.config.default_settings;
+            let summary = ConfigSummary {
+                provider,
+                api_key_configured: api_key.map(Option::is_some),
+                offline: _system.engine.config.network.offline,
+                default_persona: settings.default_persona.clone(),
+                default_chaos_level: settings.default_chaos_level,
+                safe_mode: _system.engine.config.behavior_settings.safe_mode,
+            };
+            output::print_data(&summary, &format)?;
+        } else {
+            print_config(_system, provider, api_key);
+        }
     }
     
     if !set.is_empty() {
-        println!("\n{}", "Setting configuration values:".bright_green());
+        output::status(&format, format!("\n{}", "Setting configuration values:".bright_green()));
         for setting in set {
-            println!("  {}", setting.bright_white());
+            output::status(&format, format!("  {}", setting.bright_white()));
         }
-        println!("\n{}", "🚧 Configuration modification coming soon...".bright_yellow());
+        output::status(&format, format!("\n{}", "🚧 Configuration modification coming soon...".bright_yellow()));
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+fn print_config(system: &CHOPSSystem, provider: LlmProvider, api_key: Option<&Option<String>>) {
+    println!("{}", "⚙️ CHOPS Configuration".bright_blue().bold());
+    println!("Provider: {}", provider.to_string().bright_cyan());
+    match api_key {
+        Some(api_key) => println!("API Keys: {}", if api_key.is_some() { "✅ Configured".green() } else { "❌ Missing".red() }),
+        None => println!("API Keys: {}", "not needed".bright_black()),
+    }
+    if system.engine.config.network.offline {
+        println!("Offline: {}", "✅ canned mock completions, no network".green());
+    }
+    println!("Default persona: {}", format!("{}", system.engine.config.default_settings.default_persona).bright_cyan());
+    println!("Default chaos level: {}", system.engine.config.default_settings.default_chaos_level.to_string().bright_yellow());
+    println!("Safe mode: {}", if system.engine.config.behavior_settings.safe_mode { "✅ Enabled".green() } else { "❌ Disabled".red() });
+}
@@ -0,0 +1,96 @@
+use chops_core::{stable_hash, CHOPSError, CHOPSResult, OutputFormat};
+use chops_api::{read_domains_file, DOMAINS_DIR};
+use crate::cli::DomainsAction;
+use crate::{output, CHOPSSystem};
+use colored::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The registered domains, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct AddedDomains {
+    stored: PathBuf,
+    domains: Vec<AddedDomain>,
+}
+
+#[derive(Debug, Serialize)]
+struct AddedDomain {
+    name: String,
+    patterns: usize,
+    /// Merged into a domain the reasoner already knew
+    merged: bool,
+}
+
+#[tracing::instrument(name = "domains_execute", level = "info", skip(system, action))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    action: DomainsAction,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    match action {
+        DomainsAction::Add { file, .. } => add_domains(system, Path::new(&file), format),
+    }
+}
+
+/// Validates the file, registers its domains now and keeps a JSON copy in the
+/// data dir so every later run loads them too
+fn add_domains(system: &mut CHOPSSystem, file: &Path, format: OutputFormat) -> CHOPSResult<()> {
+    let domains = read_domains_file(file)?;
+    let dir = system.engine.data_dir.as_ref().map(|dir| dir.join(DOMAINS_DIR)).ok_or_else(|| {
+        CHOPSError::ConfigError("No writable data directory for analogy domains (set CHOPS_DATA_DIR)".to_string())
+    })?;
+    let stored = dir.join(stored_name(file));
+
+    let reasoner = system.engine.cognitive_architecture.analogical_reasoner_mut();
+    let merged: Vec<bool> = domains.keys().map(|name| reasoner.has_domain(name)).collect();
+
+    std::fs::create_dir_all(&dir).map_err(CHOPSError::FileSystemError)?;
+    let content = serde_json::to_string_pretty(&domains)
+        .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize domains: {}", e)))?;
+    let replaced = stored.exists();
+    std::fs::write(&stored, content).map_err(CHOPSError::FileSystemError)?;
+
+    let added: Vec<AddedDomain> = domains.iter().zip(merged)
+        .map(|((name, patterns), merged)| AddedDomain { name: name.clone(), patterns: patterns.len(), merged })
+        .collect();
+    for (name, patterns) in domains {
+        reasoner.add_domain(name, patterns);
+    }
+    tracing::info!("Stored analogy domains in {}", stored.display());
+
+    if format.is_machine_readable() {
+        return output::print_data(&AddedDomains { stored, domains: added }, &format);
+    }
+
+    println!("{}", format!("🔭 Added analogy domains from {}", file.display()).bright_blue().bold());
+    for domain in &added {
+        let status = if domain.merged { "merged into existing domain".yellow() } else { "new".bright_green() };
+        println!("  {} {} patterns ({})", format!("{}:", domain.name).bright_cyan(), domain.patterns, status);
+    }
+    let note = if replaced { "replacing the earlier copy" } else { "loaded on every run" };
+    println!("{}", format!("   Stored in {} ({}); delete it to forget them.", stored.display(), note).bright_black());
+    Ok(())
+}
+
+/// Name of the stored copy of `file`: its stem plus a hash of where it lives,
+/// so adding the same file again replaces its copy while another file with
+/// the same name gets its own
+fn stored_name(file: &Path) -> String {
+    let stem = file.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_else(|| "domains".to_string());
+    let source = std::fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+    format!("{}-{}.json", stem, &stable_hash(&source.to_string_lossy())[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_with_the_same_stem_get_their_own_copies() {
+        let ours = stored_name(Path::new("/teams/ops/supply-chain.toml"));
+
+        assert_eq!(ours, stored_name(Path::new("/teams/ops/supply-chain.toml")));
+        assert_ne!(ours, stored_name(Path::new("/teams/retail/supply-chain.toml")));
+        assert!(ours.starts_with("supply-chain-") && ours.ends_with(".json"), "{}", ours);
+    }
+}
@@ -0,0 +1,98 @@
+use chops_core::{CHOPSError, CHOPSResult, EntropySource, OutputFormat};
+use chops_chaos::{EntropyQuality, QualityVerdict};
+use crate::cancel::CtrlC;
+use crate::cli::EntropyAction;
+use crate::{output, CHOPSSystem};
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+
+/// Fewest values the uniformity test can judge: about five per histogram bin
+const MIN_TEST_SAMPLES: usize = 50;
+
+/// The grades, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct EntropyReport {
+    source: EntropySource,
+    samples: usize,
+    quality: EntropyQuality,
+    /// `None` when the source doesn't feed the pool
+    pool_quality: Option<f64>,
+    /// Quantum fetches that fell back to system entropy
+    fallbacks: u64,
+}
+
+#[tracing::instrument(name = "entropy_execute", level = "info", skip(system, action))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    action: EntropyAction,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    match action {
+        EntropyAction::Test { source, samples, .. } => test_source(system, source, samples, format).await,
+    }
+}
+
+/// Draws `samples` values from a copy of the session's generator, so the test
+/// doesn't disturb its source or stats, and grades them
+async fn test_source(system: &CHOPSSystem, source: Option<EntropySource>, samples: usize, format: OutputFormat) -> CHOPSResult<()> {
+    if samples < MIN_TEST_SAMPLES {
+        return Err(CHOPSError::InvalidParameter(format!(
+            "Need at least {} samples to judge uniformity, got {}", MIN_TEST_SAMPLES, samples
+        )));
+    }
+
+    let mut generator = system.engine.cognitive_architecture.chaos_engine().entropy_generator.clone();
+    let requested = source.unwrap_or_else(|| generator.source().clone());
+    let active = generator.set_source_checked(requested.clone()).await?;
+    if active != requested {
+        eprintln!("{}", format!("⚠️  {} entropy unavailable, testing {} instead", requested, active).yellow());
+    }
+
+    let fallbacks_before = generator.stats().fallbacks;
+    output::status(&format, format!("🎲 Testing {} entropy with {} samples", active, samples).bright_blue().bold());
+    let pb = ProgressBar::new(samples as u64);
+    pb.set_style(ProgressStyle::default_bar().template("{bar:30.cyan} {pos}/{len} values").unwrap());
+    let ctrl_c = CtrlC::watch();
+    let sequence = generator
+        .generate_entropy_sequence_with(samples, ctrl_c.token(), |done, _| pb.set_position(done as u64))
+        .await;
+    pb.finish_and_clear();
+    let sequence = match sequence {
+        Err(CHOPSError::Cancelled(_)) => {
+            output::status(&format, "🛑 Entropy test cancelled".yellow());
+            return Ok(());
+        },
+        sequence => sequence?,
+    };
+
+    let quality = EntropyQuality::analyze(&sequence);
+    tracing::debug!("Entropy quality: {:?}", quality);
+    let pool_quality = generator.refresh_pool_quality();
+    let fallbacks = generator.stats().fallbacks - fallbacks_before;
+    if format.is_machine_readable() {
+        return output::print_data(&EntropyReport { source: active, samples, quality, pool_quality, fallbacks }, &format);
+    }
+
+    for (metric, score, verdict) in quality.verdicts() {
+        let verdict = match verdict {
+            QualityVerdict::Pass => "✅ pass".bright_green(),
+            QualityVerdict::Warn => "⚠️  warn".yellow(),
+            QualityVerdict::Fail => "❌ fail".red(),
+        };
+        println!("  {:<14} {:>6.3}  {}", format!("{}:", metric).bright_cyan(), score, verdict);
+    }
+
+    match pool_quality {
+        Some(score) => println!("  {:<14} {:>6.3}", "pool quality:".bright_cyan(), score),
+        None => println!("  {:<14} {}", "pool quality:".bright_cyan(), "n/a (this source doesn't feed the pool)".bright_black()),
+    }
+
+    if active == EntropySource::QuantumRandom && fallbacks > 0 {
+        println!("\n{}", format!(
+            "⚠️  Quantum fetches fell back to system entropy {} time(s); these scores are partly not quantum",
+            fallbacks
+        ).yellow());
+    }
+    Ok(())
+}
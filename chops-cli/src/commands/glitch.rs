@@ -1,18 +1,128 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{BackupManifest, CommentStyle, CHOPSResult, CHOPSError, OutputFormat};
+use chops_chaos::{GlitchDensity, GlitchEngine, GlitchResult};
+use crate::{CHOPSSystem, output};
 use colored::*;
+use serde::Serialize;
 
+/// Every previewed level and what was written, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct GlitchReport {
+    file: String,
+    seed: u64,
+    results: Vec<GlitchResult>,
+    /// Probability written to the file, when --apply was given
+    applied: Option<f64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "glitch_execute", level = "info", skip(system))]
 pub async fn execute(
-    _system: &mut CHOPSSystem,
+    system: &mut CHOPSSystem,
+    file: Option<String>,
     probability: f64,
     personality: bool,
     density: String,
+    sweep: Vec<f64>,
+    apply: Option<f64>,
+    seed: Option<u64>,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
-    println!("{}", "⚡ Chaos Glitch Injection".bright_red().bold());
-    println!("Probability: {}", format!("{:.1}%", probability * 100.0).bright_white());
-    println!("Sentient glitches: {}", if personality { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    println!("Density: {}", density.bright_cyan());
-    
-    println!("\n{}", "🚧 Glitch injection engine implementation coming soon...".bright_yellow());
+    output::status(&format, "⚡ Chaos Glitch Injection".bright_red().bold());
+    output::status(&format, format!("Probability: {}", format!("{:.1}%", probability * 100.0).bright_white()));
+    output::status(&format, format!("Sentient glitches: {}", if personality { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+    output::status(&format, format!("Density: {}", density.bright_cyan()));
+    output::status(&format, format!("Entropy source: {}", system.engine.cognitive_architecture.chaos_engine().entropy_generator.source().to_string().bright_magenta()));
+
+    let density: GlitchDensity = density.parse()?;
+
+    let Some(file) = file else {
+        if !sweep.is_empty() || apply.is_some() {
+            return Err(CHOPSError::InvalidParameter("--sweep and --apply need a file to glitch".to_string()));
+        }
+        if format.is_machine_readable() {
+            return Err(CHOPSError::InvalidParameter(format!("Pass a file to glitch for {} output", format)));
+        }
+        println!("\n{}", "💡 Pass a file to glitch, e.g. `chops glitch src/main.rs --sweep 0.1,0.3,0.6`".bright_yellow());
+        return Ok(());
+    };
+
+    let path = std::path::PathBuf::from(&file);
+    let original = std::fs::read_to_string(&path).map_err(|e| {
+        CHOPSError::InvalidParameter(format!("Cannot read {}: {}", file, e))
+    })?;
+
+    // Derive the seed from the configured entropy source so --entropy matters
+    let seed = match seed {
+        Some(seed) => seed,
+        None => {
+            let entropy = system.engine.cognitive_architecture
+                .chaos_engine_mut()
+                .entropy_generator
+                .generate_entropy()
+                .await?;
+            (entropy * u64::MAX as f64) as u64
+        }
+    };
+
+    let comment_style = CommentStyle::for_path(&file).ok_or_else(|| {
+        CHOPSError::InvalidParameter(format!("{} has no comment syntax to hide glitches in", file))
+    })?;
+    let engine = GlitchEngine::new(density, personality, comment_style);
+    let levels = if sweep.is_empty() { vec![probability] } else { sweep };
+
+    let mut results = Vec::with_capacity(levels.len());
+    for level in &levels {
+        let result = engine.inject(&original, *level, seed)?;
+        if format.is_machine_readable() {
+            results.push(result);
+            continue;
+        }
+
+        println!("\n{}", format!(
+            "──── probability {:.2} · {} glitches ────",
+            result.probability,
+            result.glitches.len()
+        ).bright_magenta().bold());
+        output::display_line_diff(&original, &result.content, 1);
+
+        results.push(result);
+    }
+
+    if results.len() > 1 && !format.is_machine_readable() {
+        println!("\n{}", "📊 Sweep summary".bright_blue().bold());
+        for result in &results {
+            let bar = "⚡".repeat(result.glitches.len().min(30));
+            println!("  {} {:>4} {}",
+                format!("{:.2}", result.probability).bright_white(),
+                result.glitches.len(),
+                bar.bright_yellow()
+            );
+        }
+    }
+
+    match apply {
+        Some(level) => {
+            let result = match results.iter().find(|result| (result.probability - level).abs() < f64::EPSILON) {
+                Some(result) => result.clone(),
+                None => engine.inject(&original, level, seed)?,
+            };
+
+            let mut batch = BackupManifest::new("glitch");
+            batch.back_up(&path)?;
+            system.record_backup(&batch)?;
+            std::fs::write(&path, &result.content).map_err(CHOPSError::FileSystemError)?;
+
+            output::status(&format, format!("\n{}", format!("💾 Applied {} glitches at probability {:.2} to {}", result.glitches.len(), level, file).bright_green()));
+            output::status(&format, "   Changed your mind? Run `chops undo`".bright_black());
+        },
+        None => {
+            output::status(&format, format!("\n{}", "👀 Preview only, nothing was written.".bright_yellow()));
+            output::status(&format, format!("   Apply with: chops glitch {} --apply <level> --seed {}", file, seed).bright_black());
+        }
+    }
+
+    if format.is_machine_readable() {
+        return output::print_data(&GlitchReport { file, seed, results, applied: apply }, &format);
+    }
     Ok(())
-}
\ No newline at end of file
+}
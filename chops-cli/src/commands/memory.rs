@@ -1,54 +1,454 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{cmp_scores, CHOPSError, CHOPSResult, IdeaQuery, MemoryExportFormat, MemorySystem, OutputFormat, PruneKind, ScoreProfile, SessionIdea};
+use chops_api::QualityTier;
+use crate::cli::MemoryAction;
+use crate::{output, CHOPSSystem};
 use colored::*;
+use dialoguer::Confirm;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A leaderboard entry, for `--format json|yaml`
+#[derive(Debug, Serialize)]
+struct RankedIdea<'a> {
+    rank: usize,
+    score: f64,
+    tier: QualityTier,
+    #[serde(flatten)]
+    idea: &'a SessionIdea,
+}
 
 pub async fn execute(
     system: &mut CHOPSSystem,
+    action: Option<MemoryAction>,
     show: bool,
     clear: bool,
     export: Option<String>,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
     if show {
-        println!("{}", "🧠 CHOPS Memory System".bright_blue().bold());
-        
-        let recent_ideas = system.memory_system.recall_similar_ideas("", 10);
-        
-        if recent_ideas.is_empty() {
-            println!("{}", "📝 No ideas stored in memory yet.".bright_yellow());
-        } else {
-            println!("{}", format!("Memory contains {} ideas:", recent_ideas.len()).bright_green());
-            
-            for (i, idea) in recent_ideas.iter().enumerate() {
-                println!("\n{} {}", 
-                    format!("{}.", i + 1).bright_cyan(),
-                    idea.title.bright_white()
-                );
-                println!("   {} {}", 
-                    "Created:".bright_black(),
-                    idea.timestamp.format("%Y-%m-%d %H:%M").to_string().bright_blue()
-                );
-                println!("   {} {}", 
-                    "Persona:".bright_black(),
-                    format!("{}", idea.persona_used).bright_green()
-                );
-                println!("   {} {}% | {} {}%", 
-                    "Creative:".bright_black(),
-                    (idea.creativity_score * 100.0) as u32,
-                    "Feasible:".bright_black(),
-                    (idea.feasibility_score * 100.0) as u32
-                );
-            }
-        }
+        show_memory(system, &IdeaQuery::new(), &format)?;
     }
     
     if clear {
-        println!("{}", "🗑️ Memory clearing not yet implemented...".bright_yellow());
+        output::status(&format, "🗑️ Memory clearing not yet implemented...".bright_yellow());
     }
     
     if let Some(export_path) = export {
-        println!("{}", format!("📁 Exporting memory to {}...", export_path).bright_green());
-        println!("{}", "🚧 Memory export coming soon...".bright_yellow());
+        export_memory(system, MemoryExportFormat::Json, &export_path, &format)?;
+    }
+
+    match action {
+        Some(MemoryAction::Show { limit, offset, persona, domain, min_score, since, .. }) => {
+            if let Some(score) = min_score.filter(|score| !(0.0..=1.0).contains(score)) {
+                return Err(CHOPSError::InvalidParameter(format!("--min-score must be between 0.0 and 1.0, got {}", score)));
+            }
+            let query = IdeaQuery {
+                persona,
+                domain,
+                min_score,
+                since: since.as_deref().map(IdeaQuery::parse_since).transpose()?,
+                offset,
+                limit,
+            };
+            show_memory(system, &query, &format)?;
+        },
+        Some(MemoryAction::Export { format: export_format, out }) => export_memory(system, export_format, &out, &format)?,
+        Some(MemoryAction::Import { file, merge, .. }) => import_memory(system, &file, merge, &format).await?,
+        Some(MemoryAction::Rank { session, profile, .. }) => rank_session(system, session.as_deref(), profile, &format)?,
+        Some(MemoryAction::Stats { .. }) => print_memory_stats(system, &format)?,
+        Some(MemoryAction::Heatmap { .. }) => print_memory_heatmap(system, &format)?,
+        Some(MemoryAction::Prune { apply, stale_days, .. }) => prune_memory(system, apply, stale_days, &format).await?,
+        None => {},
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+fn show_memory(system: &CHOPSSystem, query: &IdeaQuery, format: &OutputFormat) -> CHOPSResult<()> {
+    let page = system.engine.memory_system.query_ideas(query);
+    if format.is_machine_readable() {
+        return output::print_data(&page, format);
+    }
+
+    println!("{}", "🧠 CHOPS Memory System".bright_blue().bold());
+    if page.total_matches == 0 {
+        let stored = system.engine.memory_system.short_term.recent_ideas.len();
+        if stored == 0 {
+            println!("{}", "📝 No ideas stored in memory yet.".bright_yellow());
+        } else {
+            println!("{}", format!("📝 None of the {} remembered ideas match these filters.", stored).bright_yellow());
+        }
+        return Ok(());
+    }
+    if page.ideas.is_empty() {
+        println!("{}", format!("📝 {} ideas match, none past offset {}.", page.total_matches, page.offset).bright_yellow());
+        return Ok(());
+    }
+
+    println!("{}", format!(
+        "Showing {}-{} of {} matching ideas:",
+        page.offset + 1,
+        page.offset + page.ideas.len(),
+        page.total_matches
+    ).bright_green());
+
+    for (i, idea) in page.ideas.iter().enumerate() {
+        println!("\n{} {} {}", 
+            format!("{}.", page.offset + i + 1).bright_cyan(),
+            idea.title.bright_white(),
+            format!("[{}]", &idea.id.to_string()[..8]).bright_black()
+        );
+        println!("   {} {}", 
+            "Created:".bright_black(),
+            idea.timestamp.format("%Y-%m-%d %H:%M").to_string().bright_blue()
+        );
+        println!("   {} {}{}", 
+            "Persona:".bright_black(),
+            format!("{}", idea.persona_used).bright_green(),
+            idea.domain.as_ref().map(|domain| format!(" | {} {}", "Domain:".bright_black(), domain)).unwrap_or_default()
+        );
+        println!("   {} {}% | {} {}%", 
+            "Creative:".bright_black(),
+            (idea.creativity_score * 100.0) as u32,
+            "Feasible:".bright_black(),
+            (idea.feasibility_score * 100.0) as u32
+        );
+    }
+
+    if page.has_more() {
+        println!("\n{}", format!(
+            "… {} more; next page with --offset {}",
+            page.total_matches - page.offset - page.ideas.len(),
+            page.offset + page.ideas.len()
+        ).bright_black());
+    }
+    Ok(())
+}
+
+/// Writes files only, so its lines are all status
+fn export_memory(system: &CHOPSSystem, export_format: MemoryExportFormat, out: &str, format: &OutputFormat) -> CHOPSResult<()> {
+    output::status(format, format!("📁 Exporting memory to {} ({})...", out, export_format).bright_green());
+
+    match export_format {
+        MemoryExportFormat::Json => {
+            system.engine.memory_system.save_to_file(Path::new(out))?;
+            output::status(format, "✨ Memory exported.".bright_green());
+        },
+        MemoryExportFormat::Obsidian => {
+            let ideas: Vec<_> = system.engine.memory_system.short_term.recent_ideas.iter().collect();
+            let written = chops_core::export_obsidian_vault(&ideas, Path::new(out))?;
+            output::status(format, format!("✨ Wrote {} linked notes (open {} as a vault).", written, out).bright_green());
+        },
+    }
+
+    Ok(())
+}
+
+async fn import_memory(system: &mut CHOPSSystem, file: &str, merge: bool, format: &OutputFormat) -> CHOPSResult<()> {
+    let path = Path::new(file);
+    if !path.exists() {
+        return Err(CHOPSError::InvalidParameter(format!("Memory export not found: {}", file)));
+    }
+    let imported = MemorySystem::load_from_file(path)?;
+
+    if !merge {
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Replace the current memory with {}? (use --merge to combine instead)", file))
+            .default(false)
+            .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+        if !confirmed {
+            output::status(format, "Memory left unchanged.".bright_black());
+            return Ok(());
+        }
+        system.engine.memory_system = imported;
+        system.save_memory().await?;
+        output::status(format, format!("📥 Memory replaced with {}.", file).bright_green());
+        return Ok(());
+    }
+
+    output::status(format, format!("📥 Merging memory from {}...", file).bright_green());
+    let report = system.engine.memory_system.merge(imported);
+    if format.is_machine_readable() {
+        if !report.is_empty() {
+            system.save_memory().await?;
+        }
+        return output::print_data(&report, format);
+    }
+    if report.is_empty() {
+        println!("{}", "✨ Nothing new to merge.".bright_green());
+        return Ok(());
+    }
+
+    println!("  {} {} new, {} combined", "Patterns:".bright_cyan(), report.patterns_added, report.patterns_combined);
+    println!("  {} {} new, {} combined", "Personas:".bright_cyan(), report.personas_added, report.personas_combined);
+    println!("  {} {} new, {} combined", "Domains:".bright_cyan(), report.domains_added, report.domains_combined);
+    println!("  {} {} added, {} already known", "Ideas:".bright_cyan(), report.ideas_added, report.ideas_skipped);
+    if report.ideas_evicted > 0 {
+        println!("  {}", format!("{} older imported ideas didn't fit in short-term memory and were consolidated instead", report.ideas_evicted).bright_yellow());
+    }
+    if report.ids_reassigned > 0 {
+        println!("  {}", format!("{} imported ideas shared an id with a different idea and were given new ids", report.ids_reassigned).bright_yellow());
+    }
+
+    system.save_memory().await?;
+    println!("{}", "✨ Memory merged.".bright_green());
+    Ok(())
+}
+
+fn rank_session(system: &CHOPSSystem, session: Option<&str>, profile: ScoreProfile, format: &OutputFormat) -> CHOPSResult<()> {
+    let Some(episode) = system.engine.memory_system.find_session(session)? else {
+        let recent: Vec<String> = system.engine.memory_system.episodic.session_history
+            .iter()
+            .rev()
+            .take(5)
+            .map(|episode| format!("{} ({})", &episode.session_id.to_string()[..8], episode.start_time.format("%Y-%m-%d %H:%M")))
+            .collect();
+        return Err(CHOPSError::InvalidParameter(format!(
+            "No session matching '{}'. Recent sessions: {}",
+            session.unwrap_or_default(),
+            if recent.is_empty() { "none".to_string() } else { recent.join(", ") }
+        )));
+    };
+
+    let ranked = episode.ranked_ideas(profile);
+    if format.is_machine_readable() {
+        let ranked: Vec<RankedIdea> = ranked.iter()
+            .enumerate()
+            .map(|(i, (idea, score))| RankedIdea { rank: i + 1, score: *score, tier: QualityTier::from_score(*score), idea })
+            .collect();
+        return output::print_data(&ranked, format);
+    }
+
+    println!("{}", format!("🏆 Session {} leaderboard ({} profile)", &episode.session_id.to_string()[..8], profile).bright_blue().bold());
+    println!("{}", format!("Started {}, {} ideas", episode.start_time.format("%Y-%m-%d %H:%M"), episode.ideas.len()).bright_black());
+
+    if ranked.is_empty() {
+        println!("{}", "📝 No ideas recorded in this session.".bright_yellow());
+        return Ok(());
+    }
+
+    for (i, (idea, score)) in ranked.iter().enumerate() {
+        let medal = match i {
+            0 => "🥇".to_string(),
+            1 => "🥈".to_string(),
+            2 => "🥉".to_string(),
+            _ => format!("{}.", i + 1),
+        };
+        println!("\n{} {} {}",
+            medal.bright_cyan(),
+            idea.title.bright_white(),
+            format!("[{}]", &idea.id.to_string()[..8]).bright_black()
+        );
+        println!("   {} {:.0}% {} | {} {}",
+            "Score:".bright_black(),
+            score * 100.0,
+            format!("({})", QualityTier::from_score(*score)).bright_green(),
+            "Persona:".bright_black(),
+            idea.persona_used.to_string().bright_green()
+        );
+    }
+
+    Ok(())
+}
+
+fn print_memory_stats(system: &CHOPSSystem, format: &OutputFormat) -> CHOPSResult<()> {
+    let effectiveness = &system.engine.memory_system.long_term.persona_effectiveness;
+    if format.is_machine_readable() {
+        let by_name: BTreeMap<String, _> = effectiveness.iter().map(|(persona, metrics)| (persona.to_string(), metrics)).collect();
+        return output::print_data(&by_name, format);
+    }
+
+    println!("{}", "📊 CHOPS Persona Stats".bright_blue().bold());
+
+    let mut personas: Vec<_> = effectiveness.iter().collect();
+    if personas.is_empty() {
+        println!("{}", "📝 No persona usage recorded yet.".bright_yellow());
+        return Ok(());
+    }
+    personas.sort_by(|(persona_a, a), (persona_b, b)| {
+        cmp_scores(a.total_cost, b.total_cost)
+            .then_with(|| b.usage_frequency.cmp(&a.usage_frequency))
+            .then_with(|| persona_a.to_string().cmp(&persona_b.to_string()))
+    });
+
+    let total_cost: f64 = personas.iter().map(|(_, metrics)| metrics.total_cost).sum();
+    let total_tokens: u64 = personas.iter().map(|(_, metrics)| metrics.total_tokens).sum();
+
+    for (persona, metrics) in personas {
+        let share = if total_cost > 0.0 { metrics.total_cost / total_cost * 100.0 } else { 0.0 };
+        println!("\n{} {}",
+            persona.to_string().bright_green(),
+            format!("({} ideas)", metrics.usage_frequency).bright_black()
+        );
+        println!("   {} {}% | {} {}%",
+            "Creative:".bright_black(),
+            (metrics.average_creativity_score * 100.0) as u32,
+            "Feasible:".bright_black(),
+            (metrics.average_feasibility_score * 100.0) as u32
+        );
+        println!("   {} {} | {} ~${:.4} ({:.0}% of spend)",
+            "Tokens:".bright_black(),
+            metrics.total_tokens,
+            "Cost:".bright_black(),
+            metrics.total_cost,
+            share
+        );
+    }
+
+    println!("\n{} {} tokens, ~${:.4}", "Total:".bright_cyan(), total_tokens, total_cost);
+    Ok(())
+}
+
+/// Width of one heatmap cell, e.g. " 72% ×3 "
+const HEATMAP_CELL_WIDTH: usize = 10;
+
+fn print_memory_heatmap(system: &CHOPSSystem, format: &OutputFormat) -> CHOPSResult<()> {
+    let effectiveness = &system.engine.memory_system.long_term.persona_effectiveness;
+    if format.is_machine_readable() {
+        let grid: BTreeMap<String, BTreeMap<&String, _>> = effectiveness.iter()
+            .filter(|(_, metrics)| !metrics.domain_scores.is_empty())
+            .map(|(persona, metrics)| (persona.to_string(), metrics.domain_scores.iter().collect()))
+            .collect();
+        return output::print_data(&grid, format);
+    }
+
+    println!("{}", "🔥 CHOPS Persona × Domain Heatmap".bright_blue().bold());
+
+    let mut personas: Vec<_> = effectiveness.iter()
+        .filter(|(_, metrics)| !metrics.domain_scores.is_empty())
+        .collect();
+    if personas.is_empty() {
+        println!("{}", "📝 No per-domain scores recorded yet. Summon a few ideas first.".bright_yellow());
+        return Ok(());
+    }
+    personas.sort_by_key(|(persona, _)| persona.to_string());
+
+    let mut domains: Vec<&String> = personas.iter()
+        .flat_map(|(_, metrics)| metrics.domain_scores.keys())
+        .collect();
+    domains.sort();
+    domains.dedup();
+
+    let label_width = personas.iter().map(|(persona, _)| persona.to_string().len()).max().unwrap_or(0).max(8);
+
+    print!("\n{:label_width$}", "");
+    for domain in &domains {
+        print!("{}", format!("{:^HEATMAP_CELL_WIDTH$}", truncate_label(domain, HEATMAP_CELL_WIDTH - 1)).bright_cyan());
+    }
+    println!();
+
+    for (persona, metrics) in &personas {
+        print!("{}", format!("{:label_width$}", persona.to_string()).bright_green());
+        for domain in &domains {
+            match metrics.domain_scores.get(*domain) {
+                Some(score) => {
+                    let cell = format!("{:>4}% ×{:<3}", (score.overall() * 100.0).round() as u32, score.uses);
+                    let cell = format!("{:>HEATMAP_CELL_WIDTH$}", cell);
+                    let colored = if score.overall() >= 0.7 {
+                        cell.bright_green()
+                    } else if score.overall() >= 0.5 {
+                        cell.bright_yellow()
+                    } else {
+                        cell.bright_red()
+                    };
+                    print!("{}", colored);
+                }
+                None => print!("{}", format!("{:^HEATMAP_CELL_WIDTH$}", "·").bright_black()),
+            }
+        }
+        println!();
+    }
+
+    println!("\n{}", "Best persona per domain:".bright_blue());
+    for domain in &domains {
+        let best = personas.iter()
+            .filter_map(|(persona, metrics)| metrics.domain_scores.get(*domain).map(|score| (persona, score)))
+            .min_by(|(_, a), (_, b)| cmp_scores(a.overall(), b.overall()));
+        if let Some((persona, score)) = best {
+            println!("  • {} → {} ({:.0}% over {} ideas)",
+                domain.bright_cyan(),
+                persona.to_string().bright_green(),
+                score.overall() * 100.0,
+                score.uses
+            );
+        }
+    }
+    println!("\n{}", "Cells show the average of creativity and feasibility, ×N ideas.".bright_black());
+    Ok(())
+}
+
+fn truncate_label(label: &str, max: usize) -> String {
+    if label.chars().count() <= max {
+        label.to_string()
+    } else {
+        let mut short: String = label.chars().take(max.saturating_sub(1)).collect();
+        short.push('…');
+        short
+    }
+}
+
+async fn prune_memory(system: &mut CHOPSSystem, apply: bool, stale_days: u32, format: &OutputFormat) -> CHOPSResult<()> {
+    output::status(format, "🧹 CHOPS Memory Prune".bright_blue().bold());
+
+    let candidates = system.engine.memory_system.prune_candidates(
+        chrono::Utc::now(),
+        chrono::Duration::days(stale_days as i64),
+    );
+    if format.is_machine_readable() {
+        // The confirmation prompt goes to the terminal, not stdout
+        let confirmed = apply && !candidates.is_empty() && Confirm::new()
+            .with_prompt(format!("Delete these {} entries?", candidates.len()))
+            .default(false)
+            .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+        let removed = if confirmed {
+            let removed = system.engine.memory_system.prune(&candidates);
+            system.save_memory().await?;
+            removed
+        } else {
+            0
+        };
+        let report = serde_json::json!({ "candidates": candidates, "removed": removed });
+        return output::print_data(&report, format);
+    }
+
+    if candidates.is_empty() {
+        println!("{}", "✨ Nothing to prune, memory is in good shape.".bright_green());
+        return Ok(());
+    }
+
+    for (kind, heading) in [
+        (PruneKind::Pattern, "Low-effectiveness patterns"),
+        (PruneKind::DomainKnowledge, "Stale domain knowledge"),
+        (PruneKind::ExpiredIdea, "Expired ideas"),
+        (PruneKind::FailureLearning, "Old failure learnings"),
+    ] {
+        let group: Vec<_> = candidates.iter().filter(|candidate| candidate.kind == kind).collect();
+        if group.is_empty() {
+            continue;
+        }
+        println!("\n{}", format!("{} ({}):", heading, group.len()).bright_cyan());
+        for candidate in group {
+            println!("  • {} {}", candidate.label.bright_white(), format!("({})", candidate.reason).bright_black());
+        }
+    }
+
+    if !apply {
+        println!("\n{}", format!("Dry run: {} entries would be removed. Re-run with --apply to delete them.", candidates.len()).bright_yellow());
+        return Ok(());
+    }
+
+    let confirmed = Confirm::new()
+        .with_prompt(format!("Delete these {} entries?", candidates.len()))
+        .default(false)
+        .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+    if !confirmed {
+        println!("{}", "Nothing deleted.".bright_black());
+        return Ok(());
+    }
+
+    let removed = system.engine.memory_system.prune(&candidates);
+    system.save_memory().await?;
+    println!("{}", format!("🗑️ Removed {} entries from memory.", removed).bright_green());
+    Ok(())
+}
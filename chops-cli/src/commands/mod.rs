@@ -7,4 +7,13 @@ pub mod time_travel;
 pub mod possession;
 pub mod paradox;
 pub mod config;
-pub mod memory;
\ No newline at end of file
+pub mod memory;
+pub mod calibrate;
+pub mod undo;
+pub mod persona;
+pub mod chaos;
+pub mod batch;
+pub mod compare;
+pub mod domains;
+pub mod entropy;
+pub mod assess;
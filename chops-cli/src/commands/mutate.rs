@@ -1,24 +1,123 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{BackupManifest, CHOPSResult, CHOPSError, MutateParams, MutationClass, OutputFormat};
+use crate::{CHOPSSystem, output};
 use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
 
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "mutate_execute", level = "info", skip(system))]
 pub async fn execute(
-    _system: &mut CHOPSSystem,
+    system: &mut CHOPSSystem,
     file: String,
     direction: String,
     personality: bool,
     easter_eggs: bool,
     weird: bool,
     functional: bool,
+    apply: bool,
+    max_change: Option<f64>,
+    comments_only: bool,
+    verify: bool,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
-    println!("{}", "🧬 Code Mutation Engine".bright_green().bold());
-    println!("Target file: {}", file.bright_white());
-    println!("Direction: {}", direction.bright_cyan());
-    println!("Personality injection: {}", if personality { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    println!("Easter eggs: {}", if easter_eggs { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    println!("Weirdness: {}", if weird { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    println!("Keep functional: {}", if functional { "✅ Yes".green() } else { "❌ No".red() });
-    
-    println!("\n{}", "🚧 Mutation engine implementation coming soon...".bright_yellow());
+    if let Some(max_change) = max_change {
+        if !(0.0..=1.0).contains(&max_change) {
+            return Err(CHOPSError::InvalidParameter(format!("--max-change must be between 0.0 and 1.0, got {}", max_change)));
+        }
+    }
+
+    output::status(&format, "🧬 Code Mutation Engine".bright_green().bold());
+    output::status(&format, format!("Target file: {}", file.bright_white()));
+    output::status(&format, format!("Direction: {}", direction.bright_cyan()));
+    output::status(&format, format!("Personality injection: {}", if personality { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+    output::status(&format, format!("Easter eggs: {}", if easter_eggs { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+    output::status(&format, format!("Weirdness: {}", if weird { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+    output::status(&format, format!("Keep functional: {}", if functional { "✅ Yes".green() } else { "❌ No".red() }));
+    if let Some(max_change) = max_change {
+        output::status(&format, format!("Change budget: {}", format!("{:.0}% of lines", max_change * 100.0).bright_cyan()));
+    }
+    if comments_only {
+        output::status(&format, format!("Scope: {}", "comments only".bright_cyan()));
+    }
+
+    let source_path = std::path::PathBuf::from(&file);
+    let original = std::fs::read_to_string(&source_path).map_err(|e| {
+        CHOPSError::InvalidParameter(format!("Cannot read {}: {}", file, e))
+    })?;
+
+    let params = MutateParams {
+        input_file: file.clone(),
+        direction,
+        inject_personality: personality,
+        add_easter_eggs: easter_eggs,
+        make_weird: weird,
+        keep_functional: functional,
+        max_change,
+        allowed_classes: if comments_only { vec![MutationClass::Comments] } else { Vec::new() },
+        verify_syntax: verify,
+    };
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap()
+    );
+    pb.set_message("🧬 Splicing personality into your code...");
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let result = system.engine.cognitive_architecture
+        .claude_client_mut()
+        .mutate_code(&original, &params)
+        .await?;
+
+    pb.finish_with_message("✨ Mutation complete!");
+
+    if let Some(error) = &result.syntax_error {
+        output::status(&format, format!("⚠️  No mutation still parsed ({}); {} was left as it is", error, file).yellow());
+        if format != OutputFormat::Markdown {
+            output::print_document(&result, &format)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(max_change) = max_change {
+        if result.change_ratio > max_change {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "The most conservative mutation still changed {:.0}% of lines, over the {:.0}% budget; nothing was written",
+                result.change_ratio * 100.0, max_change * 100.0
+            )));
+        }
+    }
+
+    if format == OutputFormat::Markdown {
+        println!("\n{}", format!("🧪 {} mutations applied:", result.mutations_applied.len()).bright_magenta().bold());
+        for mutation in &result.mutations_applied {
+            println!("  • {} {} {}",
+                format!("[{:?}]", mutation.mutation_type).bright_cyan(),
+                mutation.location.bright_white(),
+                mutation.description.bright_black()
+            );
+        }
+
+        println!("{} {}", "📏 Changed:".bright_black(), format!("{:.0}% of lines", result.change_ratio * 100.0).bright_white());
+    } else {
+        output::print_document(&result, &format)?;
+    }
+
+    let output_path = if apply {
+        source_path
+    } else {
+        std::path::PathBuf::from(format!("{}.chopped", file))
+    };
+
+    let mut batch = BackupManifest::new("mutate");
+    batch.back_up(&output_path)?;
+    system.record_backup(&batch)?;
+    std::fs::write(&output_path, &result.mutated_content).map_err(CHOPSError::FileSystemError)?;
+
+    output::status(&format, format!("\n{}", format!("💾 Mutated code written to {}", output_path.display()).bright_green()));
+    output::status(&format, "   Changed your mind? Run `chops undo`".bright_black());
+
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,22 +1,59 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{CHOPSResult, OutputFormat};
+use crate::{CHOPSSystem, output};
 use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
 
+#[tracing::instrument(name = "paradox_execute", level = "info", skip(system))]
 pub async fn execute(
-    _system: &mut CHOPSSystem,
+    system: &mut CHOPSSystem,
     constraints: Vec<String>,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
-    println!("{}", "🌀 Paradox Resolution Engine".bright_magenta().bold());
-    
-    if constraints.is_empty() {
-        println!("No constraints provided - generating random paradoxes...");
-    } else {
-        println!("Contradictory constraints:");
-        for (i, constraint) in constraints.iter().enumerate() {
-            println!("  {}. {}", i + 1, constraint.bright_white());
+    output::status(&format, "🌀 Paradox Resolution Engine".bright_magenta().bold());
+    if !constraints.is_empty() {
+        output::status(&format, "Contradictory constraints:");
+    }
+    for (i, constraint) in constraints.iter().enumerate() {
+        output::status(&format, format!("  {}. {}", i + 1, constraint.bright_white()));
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.magenta} {msg}").unwrap());
+    pb.set_message("🌀 Untangling contradictions...");
+    pb.enable_steady_tick(Duration::from_millis(100));
+    let report = system.engine.paradox(&constraints).await;
+    pb.finish_and_clear();
+    let report = report?;
+
+    if format != OutputFormat::Markdown {
+        return output::print_document(&report, &format);
+    }
+
+    println!("\n{}", format!("🌀 Paradoxes ({}):", report.entries.len()).bright_magenta().bold());
+    if report.entries.is_empty() {
+        println!("  {}", "none found; these constraints can live together".bright_green());
+    }
+    for (i, entry) in report.entries.iter().enumerate() {
+        let resolution = &entry.resolution;
+        println!("\n  {} {}", format!("{}.", i + 1).bright_white(), resolution.paradox.bright_magenta());
+        println!("     {}", format!("(\"{}\", {:.0}%)", entry.finding.evidence, entry.finding.score * 100.0).bright_black());
+        println!("     {} {}", format!("{}:", resolution.strategy).bright_cyan().bold(), entry.explanation);
+        if !resolution.alternatives.is_empty() {
+            let alternatives: Vec<String> = resolution.alternatives.iter().map(ToString::to_string).collect();
+            println!("     {} {}", "Also worth trying:".bright_black(), alternatives.join(", "));
+        }
+    }
+
+    if !report.other_findings.is_empty() {
+        println!("\n{}", "🚧 Other limits to design around:".yellow().bold());
+        for finding in &report.other_findings {
+            println!("  • {} {}", finding.impossibility_type.to_string().yellow(), finding.description);
         }
     }
-    
-    println!("\n{}", "🚧 Paradox engine implementation coming soon...".bright_yellow());
+
+    if !report.entries.is_empty() {
+        system.print_usage_stats();
+    }
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,134 @@
+use chops_core::{CHOPSResult, OutputFormat, PersonaType};
+use chops_persona::PersonaEngine;
+use crate::cli::PersonaAction;
+use crate::{output, CHOPSSystem};
+use colored::*;
+
+#[tracing::instrument(name = "persona_execute", level = "info", skip(system, action))]
+pub async fn execute(
+    system: &mut CHOPSSystem,
+    action: PersonaAction,
+    format: OutputFormat,
+) -> CHOPSResult<()> {
+    match action {
+        PersonaAction::Reset { persona, .. } => {
+            output::status(&format, "🎭 CHOPS Persona Reset".bright_blue().bold());
+
+            system.engine.cognitive_architecture.persona_engine_mut().reset_persona(&persona);
+            let had_history = system.engine.memory_system.reset_persona(&persona);
+            system.save_memory().await?;
+
+            if format.is_machine_readable() {
+                let report = serde_json::json!({ "persona": persona, "had_history": had_history });
+                output::print_data(&report, &format)?;
+            } else if had_history {
+                println!("{}", format!("✨ {} restored to its default character; learned effectiveness cleared.", persona).bright_green());
+            } else {
+                println!("{}", format!("✨ {} is already at its default character.", persona).bright_green());
+            }
+        },
+        PersonaAction::Debug { persona, .. } => print_debug_state(system, &persona, format)?,
+        PersonaAction::Blend { blend, .. } => print_blend(system, &blend, format)?,
+    }
+
+    Ok(())
+}
+
+fn print_blend(system: &CHOPSSystem, weights: &[(PersonaType, f64)], format: OutputFormat) -> CHOPSResult<()> {
+    let context = system.engine.cognitive_architecture.persona_engine().blend_many(weights)?;
+    if format.is_machine_readable() {
+        return output::print_data(&context, &format);
+    }
+
+    let recipe: Vec<String> = PersonaEngine::normalize_blend(weights)?
+        .iter()
+        .map(|(persona, weight)| format!("{} {:.0}%", persona, weight * 100.0))
+        .collect();
+    println!("{}", format!("🧪 Blended persona: {}", recipe.join(" + ")).bright_blue().bold());
+    println!("  {}", format!("leads as {}", context.persona_type).bright_black());
+
+    println!("\n{}", "Blended biases:".bright_cyan());
+    println!("  creativity {:.2} · ethics filter {:.2} · risk tolerance {:.2} · excitement {:.2}",
+        context.creativity_bias, context.ethics_filter, context.risk_tolerance, context.excitement_level);
+
+    println!("\n{}", format!("Thinking patterns ({}):", context.thinking_patterns.len()).bright_cyan());
+    for pattern in &context.thinking_patterns {
+        println!("  • {}", pattern);
+    }
+
+    println!("\n{}", format!("Vocabulary ({}):", context.vocabulary_enhancements.len()).bright_cyan());
+    println!("  {}", context.vocabulary_enhancements.join(", "));
+    Ok(())
+}
+
+fn print_debug_state(system: &CHOPSSystem, persona: &PersonaType, format: OutputFormat) -> CHOPSResult<()> {
+    let state = system.engine.cognitive_architecture.persona_engine().debug_state(persona)?;
+    let learned = system.engine.memory_system.long_term.persona_effectiveness.get(persona);
+
+    if format.is_machine_readable() {
+        let report = serde_json::json!({
+            "persona": state,
+            "learned_effectiveness": learned,
+        });
+        return output::print_data(&report, &format);
+    }
+
+    println!("{}", format!("🔬 {} internals", persona).bright_blue().bold());
+
+    println!("\n{}", "Internal fields:".bright_cyan());
+    if let serde_json::Value::Object(fields) = &state.internal_state {
+        for (field, value) in fields {
+            println!("  {:<24} {}", field, value);
+        }
+    }
+    if state.drifted_fields.is_empty() {
+        println!("  {}", "(all at their defaults)".bright_black());
+    } else {
+        for drift in &state.drifted_fields {
+            println!("  {} {} drifted from {} to {}", "↪".yellow(), drift.field, drift.base, drift.current);
+        }
+    }
+
+    let context = &state.effective_context;
+    println!("\n{}", "Effective biases:".bright_cyan());
+    println!("  creativity {:.2} · ethics filter {:.2} · risk tolerance {:.2} · excitement {:.2}",
+        context.creativity_bias, context.ethics_filter, context.risk_tolerance, context.excitement_level);
+    println!("  {}", format!(
+        "adaptation {} (drift rate {:.2}, max drift {:.2}), {} remembered activations",
+        if state.adaptation_enabled { "on" } else { "off" },
+        state.drift_limits.drift_rate, state.drift_limits.max_drift, state.recent_activations
+    ).bright_black());
+
+    let style = &state.conversation_style;
+    println!("\n{}", "Conversation style:".bright_cyan());
+    println!("  formality {:.2} · humor {:.2} · tangents {:.2} · questions {:.2} · interruptions {:?}",
+        style.formality_level, style.humor_frequency, style.tangent_tendency,
+        style.question_asking_frequency, style.interruption_style);
+
+    if let Some(metrics) = learned {
+        println!("\n{}", "Learned from memory:".bright_cyan());
+        let satisfaction = match metrics.satisfaction_ratings {
+            0 => "unrated".to_string(),
+            ratings => format!("{:.0}% of {} rated", metrics.user_satisfaction_rating * 100.0, ratings),
+        };
+        println!("  {} uses · avg creativity {:.0}% · avg feasibility {:.0}% · satisfaction {}",
+            metrics.usage_frequency, metrics.average_creativity_score * 100.0,
+            metrics.average_feasibility_score * 100.0, satisfaction);
+    }
+
+    println!("\n{}", "Base prompt:".bright_cyan());
+    println!("{}", state.base_prompt.base_prompt);
+    for modifier in &state.base_prompt.personality_modifiers {
+        println!("  • {}", modifier);
+    }
+
+    println!("\n{}", "Adaptive modifiers right now:".bright_cyan());
+    if state.adaptive_modifiers.is_empty() {
+        println!("  {}", "(none)".bright_black());
+    }
+    for modifier in &state.adaptive_modifiers {
+        println!("  • {}", modifier);
+    }
+
+    Ok(())
+}
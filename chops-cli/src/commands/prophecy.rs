@@ -1,26 +1,62 @@
-use chops_core::CHOPSResult;
-use crate::CHOPSSystem;
+use chops_core::{CHOPSResult, OutputFormat};
+use chops_api::DomainClassifier;
+use crate::{CHOPSSystem, output};
 use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
 
 pub async fn execute(
-    _system: &mut CHOPSSystem,
+    system: &mut CHOPSSystem,
     year: Option<u32>,
-    domain: String,
+    domain: Option<String>,
     trend_analysis: bool,
     emerging_tech: bool,
     what_if: Option<String>,
+    format: OutputFormat,
 ) -> CHOPSResult<()> {
-    println!("{}", "🔮 Future Prophecy Generator".bright_magenta().bold());
+    output::status(&format, "🔮 Future Prophecy Generator".bright_magenta().bold());
     let target_year = year.unwrap_or(2030);
-    println!("Target year: {}", target_year.to_string().bright_white());
-    println!("Domain: {}", domain.bright_cyan());
-    println!("Trend analysis: {}", if trend_analysis { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    println!("Emerging tech focus: {}", if emerging_tech { "✅ Enabled".green() } else { "❌ Disabled".red() });
-    
-    if let Some(scenario) = what_if {
-        println!("What-if scenario: {}", scenario.bright_yellow());
+    let domain = match (domain, &what_if) {
+        (Some(domain), _) => domain,
+        (None, Some(scenario)) => {
+            let inferred = DomainClassifier::new()
+                .with_domains(&system.engine.config.domain_keywords)
+                .infer(scenario);
+            tracing::info!("Inferred prophecy domain: {}", inferred);
+            inferred
+        },
+        (None, None) => "technology".to_string(),
+    };
+    output::status(&format, format!("Target year: {}", target_year.to_string().bright_white()));
+    output::status(&format, format!("Domain: {}", domain.bright_cyan()));
+    output::status(&format, format!("Trend analysis: {}", if trend_analysis { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+    output::status(&format, format!("Emerging tech focus: {}", if emerging_tech { "✅ Enabled".green() } else { "❌ Disabled".red() }));
+
+    let mut context = Vec::new();
+    if let Some(scenario) = &what_if {
+        output::status(&format, format!("What-if scenario: {}", scenario.bright_yellow()));
+        context.push(format!("What if {}?", scenario.trim_end_matches('?')));
+    }
+    if trend_analysis {
+        context.push("Trace how today's trends led there.".to_string());
+    }
+    if emerging_tech {
+        context.push("Focus on technologies that are only emerging today.".to_string());
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.magenta} {msg}").unwrap());
+    pb.set_message(format!("🔮 Traveling to {}...", target_year));
+    pb.enable_steady_tick(Duration::from_millis(100));
+    let prophecy = system.engine.prophecy(&domain, Some(target_year), &context.join(" ")).await;
+    pb.finish_and_clear();
+    let prophecy = prophecy?;
+
+    if format != OutputFormat::Markdown {
+        return output::print_document(&prophecy, &format);
     }
-    
-    println!("\n{}", "🚧 Prophecy engine implementation coming soon...".bright_yellow());
+    println!("\n{}", format!("📜 Prophecy from {} ({:.0}% confidence):", prophecy.target_year, prophecy.confidence_level * 100.0).bright_magenta().bold());
+    println!("{}", prophecy.prophecy.trim());
+    system.print_usage_stats();
     Ok(())
-}
\ No newline at end of file
+}
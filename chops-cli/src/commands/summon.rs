@@ -1,118 +1,262 @@
-use chops_core::{PersonaType, CHOPSResult};
+use chops_core::{FailureLearning, FeasibilityBand, GeneratedIdea, OutputFormat, OutputPreferences, PersonaType, Renderable, ScoreProfile, CHOPSResult, CHOPSError};
+use chops_api::{ComplexIdeaResult, ConceptMapFormat, DomainClassifier, GeneratedIdeaResponse, ContextBudget, GroundingContext, GroundingKind, IdeaOptions, MessageRole, OutputSchema, ReferenceFile, TemperatureNudge, DEFAULT_SCHEMA_RETRIES, DEFAULT_SUMMON_CHAOS, REFERENCE_CONTEXT_TOKENS};
+use chops_chaos::DEFAULT_REALITY_LEVEL;
+use crate::cli::SummonArgs;
 use crate::{CHOPSSystem, output};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::mpsc;
 
-pub async fn execute(
-    system: &mut CHOPSSystem,
-    persona: PersonaType,
-    domain: String,
-    chaos: u8,
-    timeline: Option<String>,
-    vibe: Option<String>,
-    constraints: Vec<String>,
-    reality_level: f64,
-) -> CHOPSResult<()> {
-    // Create progress bar for the summoning ritual
-    let pb = ProgressBar::new_spinner();
+/// Ideas scoring below these are remembered as failed attempts
+const LOW_CREATIVITY_SCORE: f64 = 0.3;
+const LOW_FEASIBILITY_SCORE: f64 = 0.2;
+
+/// Batches a domain's remembered ideas are split into for tag trend analysis
+const TREND_PERIODS: usize = 12;
+
+/// Streamed text chunks buffered ahead of the terminal
+const TOKEN_BUFFER: usize = 256;
+
+/// Earlier conversation messages sent with each summon in interactive mode
+const CONVERSATION_HISTORY_MESSAGES: usize = 20;
+
+/// The text the persona recommender reads: the domain and what this summon
+/// asked for, leaving out the config's default constraints
+fn persona_hints(
+    domain: &str,
+    subject: Option<&str>,
+    vibe: &Option<String>,
+    timeline: &Option<String>,
+    constraints: &[String],
+    grounding: Option<&GroundingContext>,
+) -> String {
+    std::iter::once(domain)
+        .chain(subject)
+        .chain(vibe.iter().map(String::as_str))
+        .chain(timeline.iter().map(String::as_str))
+        .chain(constraints.iter().map(String::as_str))
+        .chain(grounding.map(|grounding| grounding.content.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// What one summon asks for: `chops summon`'s flags once a blend has become
+/// its persona, or interactive mode's picks
+#[derive(Debug, Clone)]
+pub struct SummonOptions {
+    pub persona: Option<PersonaType>,
+    pub domain: Option<String>,
+    pub chaos: Option<u8>,
+    pub timeline: Option<String>,
+    pub vibe: Option<String>,
+    pub constraints: Vec<String>,
+    pub reality_level: f64,
+    pub based_on: Option<String>,
+    pub concept_map: Option<ConceptMapFormat>,
+    pub min_novelty: Option<f64>,
+    pub feasibility_band: Option<FeasibilityBand>,
+    pub with_plan: bool,
+    pub seed: Option<u64>,
+    pub export: Vec<OutputFormat>,
+    pub export_dir: Option<String>,
+    pub save: bool,
+    pub quiet: bool,
+    pub no_recommend: bool,
+    pub max_depth: Option<usize>,
+    pub chain_budget: Option<u64>,
+    pub output_schema: Option<String>,
+    pub schema_retries: u8,
+    pub context_files: Vec<String>,
+    pub stream: bool,
+    pub allow_partial: bool,
+}
+
+impl Default for SummonOptions {
+    fn default() -> Self {
+        Self {
+            persona: None,
+            domain: None,
+            chaos: None,
+            timeline: None,
+            vibe: None,
+            constraints: Vec::new(),
+            reality_level: DEFAULT_REALITY_LEVEL,
+            based_on: None,
+            concept_map: None,
+            min_novelty: None,
+            feasibility_band: None,
+            with_plan: false,
+            seed: None,
+            export: Vec::new(),
+            export_dir: None,
+            save: false,
+            quiet: false,
+            no_recommend: false,
+            max_depth: None,
+            chain_budget: None,
+            output_schema: None,
+            schema_retries: DEFAULT_SCHEMA_RETRIES,
+            context_files: Vec::new(),
+            stream: false,
+            allow_partial: false,
+        }
+    }
+}
+
+impl From<&SummonArgs> for SummonOptions {
+    fn from(args: &SummonArgs) -> Self {
+        Self {
+            persona: args.persona.clone(),
+            domain: args.domain.clone(),
+            chaos: args.chaos,
+            timeline: args.timeline.clone(),
+            vibe: args.vibe.clone(),
+            constraints: args.constraints.clone(),
+            reality_level: args.reality_level.unwrap_or(DEFAULT_REALITY_LEVEL),
+            based_on: args.based_on.clone(),
+            concept_map: args.concept_map,
+            min_novelty: args.min_novelty,
+            feasibility_band: args.feasibility_band,
+            with_plan: args.with_plan,
+            seed: args.seed,
+            export: args.export.clone(),
+            export_dir: args.export_dir.clone(),
+            save: args.save,
+            quiet: args.quiet,
+            no_recommend: args.no_recommend,
+            max_depth: args.max_depth,
+            chain_budget: args.chain_budget,
+            output_schema: args.output_schema.clone(),
+            schema_retries: args.schema_retries,
+            context_files: args.context_files.clone(),
+            stream: args.stream,
+            allow_partial: args.allow_partial,
+        }
+    }
+}
+
+impl SummonOptions {
+    /// Everything besides the persona and chaos level that changes the ideas
+    /// a batch of these summons produces, to key its checkpoint with
+    pub fn batch_settings(&self) -> String {
+        serde_json::json!({
+            "domain": self.domain,
+            "timeline": self.timeline,
+            "vibe": self.vibe,
+            "constraints": self.constraints,
+            "reality_level": self.reality_level,
+            "based_on": self.based_on,
+            "min_novelty": self.min_novelty,
+            "feasibility_band": self.feasibility_band,
+            "with_plan": self.with_plan,
+            "seed": self.seed,
+            "output_schema": self.output_schema,
+            "schema_retries": self.schema_retries,
+            "context_files": self.context_files,
+            "no_recommend": self.no_recommend,
+        }).to_string()
+    }
+}
+
+pub async fn execute(system: &mut CHOPSSystem, options: &SummonOptions, format: OutputFormat) -> CHOPSResult<()> {
+    if options.stream && format != OutputFormat::Markdown {
+        return Err(CHOPSError::InvalidParameter(format!(
+            "--stream prints the idea as it arrives, which can't be combined with --format {}", format
+        )));
+    }
+    let Some(plan) = SummonPlan::new(system, options, &format)? else {
+        return Ok(());
+    };
+    let domain = plan.domain_for(system, options, None, &format);
+    let persona = plan.persona_for(system, options, &domain, None, &format).await?;
+
+    // Create progress bar for the summoning ritual; streamed text takes its place
+    let pb = if options.stream { ProgressBar::hidden() } else { ProgressBar::new_spinner() };
     pb.set_style(
         ProgressStyle::default_spinner()
             .template("{spinner:.cyan} {msg}")
             .unwrap()
     );
     
-    pb.set_message(format!("🔮 Summoning {} with chaos level {}...", persona, chaos));
+    pb.set_message(format!("🔮 Summoning {} with chaos level {}...", persona, plan.chaos));
     pb.enable_steady_tick(Duration::from_millis(100));
 
     // Build the summoning prompt
-    let mut prompt = String::new();
-    
-    if let Some(vibe) = &vibe {
-        prompt.push_str(&format!("Channel the vibe of '{}' while ", vibe));
-    }
-    
-    prompt.push_str(&format!(
-        "generating innovative ideas for {} development",
-        domain
-    ));
-    
-    if let Some(timeline) = &timeline {
-        prompt.push_str(&format!(" in the context of {}", timeline));
-    }
-    
-    if !constraints.is_empty() {
-        prompt.push_str(&format!(
-            " while working within these constraints: {}",
-            constraints.join(", ")
-        ));
-    }
-    
-    prompt.push_str(". Focus on breakthrough innovations that push boundaries while remaining implementable.");
+    let prompt = plan.prompt_for(system, options, &domain, None);
 
     pb.set_message("🧠 Activating cognitive architecture...");
     
     // Use the cognitive architecture for complex processing
-    let result = system.cognitive_architecture
-        .process_complex_idea(&prompt, persona.clone(), &domain, chaos as f64 / 11.0)
-        .await?;
+    if let Some(grounding) = &plan.grounding {
+        pb.set_message(format!("🧠 Grounding in {}...", grounding.label));
+    }
+    let tag_activity = system.engine.memory_system.tag_activity(&domain, TREND_PERIODS);
+    system.engine.cognitive_architecture.observe_trends(tag_activity);
+    let (mut result, chaos) = summon_with_rerolls(
+        system, &pb, &prompt, &persona, &domain, &plan, options.feasibility_band, options.stream,
+    ).await?;
+    plan.check_constraints(system, &pb, &mut result).await;
 
     pb.finish_with_message("✨ Summoning complete!");
 
-    // Display the generated idea with rich formatting
-    output::display_complex_idea_result(&result)?;
-
-    // Save to memory
-    system.memory_system.add_idea(chops_core::GeneratedIdea {
-        id: result.base_idea.id,
-        title: extract_title_from_content(&result.base_idea.content),
-        description: result.base_idea.content.clone(),
-        persona_used: persona,
-        chaos_level: chaos as f64 / 11.0,
-        creativity_score: result.base_idea.creativity_score,
-        feasibility_score: result.base_idea.feasibility_score,
-        novelty_score: result.base_idea.novelty_score,
-        excitement_factor: result.base_idea.excitement_factor,
-        tags: extract_tags_from_content(&result.base_idea.content),
-        implementation_hints: result.implementation_roadmap.critical_path,
-        potential_risks: vec![], // Could be extracted from reality distortion
-        experimental_variations: vec![], // Convert from chaos variations
-        analogies: result.analogical_insights.into_iter().map(|insight| {
-            chops_core::Analogy {
-                source_domain: insight.source_domain.clone(),
-                source_pattern: insight.analogy_description.clone(),
-                target_concept: domain.clone(),
-                structural_mapping: chops_core::StructuralMapping {
-                    source_domain: insight.source_domain.clone(),
-                    target_concept: domain.clone(),
-                    source_elements: vec![],
-                    target_elements: vec![],
-                    relationship_mappings: vec![],
-                },
-                insight: insight.analogy_description,
-                confidence: insight.confidence_score,
-                novelty_score: insight.surprise_factor,
-                practical_applicability: 0.7, // Default value
-            }
-        }).collect(),
-        timestamp: chrono::Utc::now(),
-    });
+    // Record the exchange when running inside a conversation session
+    if let Some(conversation) = system.conversation.as_mut() {
+        conversation.persona = persona.clone();
+        conversation.chaos_level = chaos;
+        conversation.domain = domain.clone();
+        conversation.add_message(MessageRole::User, prompt.clone());
+        conversation.add_message(MessageRole::Assistant, result.base_idea.content.clone());
+        tracing::debug!("Recorded exchange in conversation {}", conversation.session_id);
+    }
+
+    // Display the generated idea with rich formatting, or print it as a document
+    if format == OutputFormat::Markdown {
+        output::display_complex_idea_result(&result)?;
+    } else {
+        output::print_document(&result, &format)?;
+    }
+
+    if let Some(map_format) = options.concept_map {
+        print_concept_map(system, &result.base_idea, map_format, &format).await;
+    }
+
+    plan.keep(system, options, &result, &persona, &domain, chaos, &format)?;
 
     // Save memory
     system.save_memory().await?;
 
-    // Show related ideas from memory
-    let similar_ideas = system.memory_system.recall_similar_ideas(&domain, 3);
+    // Everything below is for people reading the colored output
+    if format != OutputFormat::Markdown {
+        return Ok(());
+    }
+
+    // Show related ideas from memory, leaving out the one just summoned
+    let recall = &system.engine.config.recall;
+    let query = [Some(domain.as_str()), options.vibe.as_deref()].into_iter().flatten().collect::<Vec<_>>().join(" ");
+    let similar_ideas: Vec<_> = system.engine.memory_system
+        .recall_similar_ideas(&query, recall.limit + 1, recall.min_score)
+        .into_iter()
+        .filter(|(_, idea)| idea.id != result.base_idea.id)
+        .take(recall.limit)
+        .collect();
     if !similar_ideas.is_empty() {
         println!("\n{}", "🔗 Related ideas from your memory:".bright_blue());
-        for idea in similar_ideas {
-            println!("  • {} ({})", 
+        for (relevance, idea) in similar_ideas {
+            println!("  • {} ({}, {})", 
                 idea.title.bright_white(), 
-                format!("{}% creative", (idea.creativity_score * 100.0) as u32).green()
+                format!("{}% creative", (idea.creativity_score * 100.0) as u32).green(),
+                format!("{:.0}% relevant", relevance * 100.0).bright_black()
             );
         }
     }
 
+    system.print_entropy_stats();
+    system.print_usage_stats();
+
     // Suggest next actions
     println!("\n{}", "🚀 Suggested next actions:".bright_yellow());
     println!("  • {}", "chops mutate --file <your-code> --personality --weird".cyan());
@@ -122,45 +266,760 @@ pub async fn execute(
     Ok(())
 }
 
-fn extract_title_from_content(content: &str) -> String {
-    // Extract the first line or first sentence as title
-    if let Some(first_line) = content.lines().next() {
-        let title = first_line.trim();
-        if title.len() > 100 {
-            format!("{}...", &title[..97])
+/// What a summon settles before it generates anything: the same for every
+/// idea of a `--batch` run
+pub struct SummonPlan {
+    pub grounding: Option<GroundingContext>,
+    parent_id: Option<uuid::Uuid>,
+    /// The constraints asked for, and those merged with the config's defaults
+    requested_constraints: Vec<String>,
+    pub constraints: Vec<String>,
+    /// The chaos level ideas start at, after creative momentum
+    pub chaos: u8,
+    pub min_novelty: f64,
+    pub idea_options: IdeaOptions,
+}
+
+impl SummonPlan {
+    /// Reads the grounding, output schema and reference files and sets the
+    /// chaos engine's seed and reality level. `None` when a `--based-on`
+    /// chain has reached its limit, after showing its best idea instead.
+    pub fn new(system: &mut CHOPSSystem, options: &SummonOptions, format: &OutputFormat) -> CHOPSResult<Option<Self>> {
+        // --min-novelty is checked as it is parsed; the configured floor isn't
+        let min_novelty = options.min_novelty.unwrap_or(system.engine.config.surprise_floor.min_novelty);
+        if !(0.0..=1.0).contains(&min_novelty) {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "surprise_floor.min_novelty must be between 0.0 and 1.0, got {}", min_novelty
+            )));
+        }
+        let grounding = match &options.based_on {
+            Some(reference) => Some(resolve_grounding(system, reference)?),
+            None => None,
+        };
+        let parent_id = match (&grounding, &options.based_on) {
+            (Some(GroundingContext { kind: GroundingKind::PriorIdea, .. }), Some(reference)) => {
+                system.engine.memory_system.find_idea(reference)?.map(|idea| idea.id)
+            },
+            _ => None,
+        };
+
+        let output_schema = match &options.output_schema {
+            Some(path) => Some(Arc::new(OutputSchema::from_file(std::path::Path::new(path))?)),
+            None => None,
+        };
+        let reference_files = options.context_files
+            .iter()
+            .map(|path| ReferenceFile::read(std::path::Path::new(path)))
+            .collect::<CHOPSResult<Vec<_>>>()?;
+
+        if let Some(parent_id) = parent_id {
+            let max_depth = options.max_depth.unwrap_or(system.engine.config.refinement.max_depth);
+            let chain_budget = options.chain_budget.unwrap_or(system.engine.config.refinement.max_chain_tokens);
+            if refinement_limit_reached(system, parent_id, max_depth, chain_budget, format) {
+                return Ok(None);
+            }
+        }
+
+        let constraints = system.engine.config.merged_constraints(&options.constraints);
+        if !system.engine.config.default_constraints.is_empty() {
+            tracing::info!("Applying {} default constraints from config", system.engine.config.default_constraints.len());
+            output::status(format, format!("📋 Default constraints: {}", system.engine.config.default_constraints.join(", ")).bright_black());
+        }
+
+        let (chaos, nudge) = apply_creative_momentum(system, options.chaos);
+        if let Some(schema) = &output_schema {
+            output::status(format, format!("🧾 Output must match the JSON Schema in {}", schema.source).bright_black());
+        }
+        if !reference_files.is_empty() && !options.quiet {
+            let labels: Vec<&str> = reference_files.iter().map(|file| file.label.as_str()).collect();
+            let tokens: usize = reference_files.iter().map(|file| ContextBudget::estimate_tokens(&file.content)).sum();
+            output::status(format, format!(
+                "📎 Reference context: {} (~{} tokens, budget {})",
+                labels.join(", "), tokens, REFERENCE_CONTEXT_TOKENS
+            ).bright_black());
+        }
+        let idea_options = IdeaOptions {
+            temperature_nudge: nudge,
+            plan_first: options.with_plan,
+            output_schema,
+            schema_retries: options.schema_retries,
+            reference_files,
+            allow_partial: options.allow_partial,
+            ..IdeaOptions::default()
+        };
+        system.engine.cognitive_architecture.chaos_engine_mut().set_seed(options.seed);
+        system.engine.cognitive_architecture.chaos_engine_mut().set_reality_level(options.reality_level)?;
+        if let Some(seed) = options.seed {
+            output::status(format, format!("🎲 Chaos seed: {}", seed).bright_black());
+        }
+
+        Ok(Some(Self {
+            grounding,
+            parent_id,
+            requested_constraints: options.constraints.clone(),
+            constraints,
+            chaos,
+            min_novelty,
+            idea_options,
+        }))
+    }
+
+    /// `--domain`, or the domain the idea's `subject` (a `--batch` prompt),
+    /// vibe, timeline, constraints and grounding point to
+    pub fn domain_for(&self, system: &CHOPSSystem, options: &SummonOptions, subject: Option<&str>, format: &OutputFormat) -> String {
+        if let Some(domain) = &options.domain {
+            return domain.clone();
+        }
+        let hints: Vec<&str> = subject.into_iter()
+            .chain(options.vibe.iter().chain(options.timeline.iter()).chain(self.constraints.iter()).map(String::as_str))
+            .chain(self.grounding.iter().map(|grounding| grounding.content.as_str()))
+            .collect();
+        let inferred = DomainClassifier::new()
+            .with_domains(&system.engine.config.domain_keywords)
+            .infer(&hints.join(" "));
+        tracing::info!("Inferred summon domain: {}", inferred);
+        output::status(format, format!("🧭 Domain: {} (inferred, override with --domain)", inferred).bright_black());
+        inferred
+    }
+
+    /// `--persona`, the configured default with `--no-recommend`, or the
+    /// persona recommended for the domain and what was asked for
+    pub async fn persona_for(
+        &self,
+        system: &mut CHOPSSystem,
+        options: &SummonOptions,
+        domain: &str,
+        subject: Option<&str>,
+        format: &OutputFormat,
+    ) -> CHOPSResult<PersonaType> {
+        let default_persona = system.engine.config.default_settings.default_persona.clone();
+        match &options.persona {
+            Some(persona) => Ok(persona.clone()),
+            None if options.no_recommend => {
+                if !options.quiet {
+                    output::status(format, format!("🧭 Persona: {} — the configured default (--no-recommend)", default_persona).bright_black());
+                }
+                Ok(default_persona)
+            },
+            None => {
+                let hints = persona_hints(domain, subject, &options.vibe, &options.timeline, &self.requested_constraints, self.grounding.as_ref());
+                // Only learn from history when learning is on, and only suggest
+                // personas that are still available (custom ones may be gone)
+                let remembered = system.engine.config.behavior_settings.enable_learning
+                    .then(|| system.engine.memory_system.persona_track_record(domain))
+                    .flatten()
+                    .filter(|record| system.engine.cognitive_architecture.persona_engine().has_persona(&record.persona));
+                let recommendation = system.engine.cognitive_architecture
+                    .recommend_persona(&hints, domain, remembered, default_persona)
+                    .await?;
+                if !options.quiet {
+                    output::status(format, format!("🧭 Persona: {} — {} (override with --persona)", recommendation.persona, recommendation.rationale).bright_black());
+                }
+                Ok(recommendation.persona)
+            }
+        }
+    }
+
+    /// The prompt for ideas in `domain`, about `subject` when there is one,
+    /// ending with notes on what didn't work before
+    pub fn prompt_for(&self, system: &CHOPSSystem, options: &SummonOptions, domain: &str, subject: Option<&str>) -> String {
+        let mut prompt = String::new();
+        
+        if let Some(vibe) = &options.vibe {
+            prompt.push_str(&format!("Channel the vibe of '{}' while ", vibe));
+        }
+        
+        match subject {
+            Some(subject) => prompt.push_str(&format!("generating innovative ideas for {} development: {}", domain, subject)),
+            None => prompt.push_str(&format!("generating innovative ideas for {} development", domain)),
+        }
+        
+        if let Some(timeline) = &options.timeline {
+            prompt.push_str(&format!(" in the context of {}", timeline));
+        }
+        
+        if !self.constraints.is_empty() {
+            prompt.push_str(&format!(
+                " while working within these constraints: {}",
+                self.constraints.join(", ")
+            ));
+        }
+        
+        prompt.push_str(". Focus on breakthrough innovations that push boundaries while remaining implementable.");
+
+        // Remind the model of what didn't work last time around
+        let failure_topic = failure_context(domain, options.vibe.as_deref());
+        let failure_query = format!("{} {}", failure_topic, self.constraints.join(" "));
+        let past_failures = system.engine.memory_system.relevant_failures(&failure_query, 3);
+        if !past_failures.is_empty() {
+            tracing::info!("Injecting {} past failures into the prompt", past_failures.len());
+            prompt.push_str("\n\nLearn from earlier attempts:");
+            for failure in past_failures {
+                prompt.push_str(&format!("\n- {}", failure.as_prompt_note()));
+            }
+        }
+        prompt
+    }
+
+    /// Adds a report on how the idea meets the constraints; a failed check
+    /// doesn't fail the summon
+    pub async fn check_constraints(&self, system: &mut CHOPSSystem, pb: &ProgressBar, result: &mut ComplexIdeaResult) {
+        if self.constraints.is_empty() {
+            return;
+        }
+        pb.set_message("📋 Checking constraints...");
+        match system.engine.cognitive_architecture.assess_constraints(&result.base_idea, &self.constraints).await {
+            Ok(report) => result.feasibility_report = Some(report),
+            Err(e) => {
+                tracing::warn!("Constraint check failed: {}", e);
+                notify(pb, format!("{}", format!("⚠️  Could not check constraints: {}", e).yellow()));
+            }
+        }
+    }
+
+    /// Remembers the idea (and a low-scoring one as a failed attempt too),
+    /// then exports and saves it as asked
+    #[allow(clippy::too_many_arguments)]
+    pub fn keep(
+        &self,
+        system: &mut CHOPSSystem,
+        options: &SummonOptions,
+        result: &ComplexIdeaResult,
+        persona: &PersonaType,
+        domain: &str,
+        chaos: u8,
+        format: &OutputFormat,
+    ) -> CHOPSResult<()> {
+        if !options.export.is_empty() {
+            export_result(system, result, &options.export, options.export_dir.as_deref(), format)?;
+        }
+
+        let creativity = result.base_idea.creativity_score;
+        let feasibility = result.base_idea.feasibility_score;
+        if creativity < LOW_CREATIVITY_SCORE || feasibility < LOW_FEASIBILITY_SCORE {
+            let mut alternatives = Vec::new();
+            if creativity < LOW_CREATIVITY_SCORE {
+                alternatives.push(format!("a chaos level above {}", chaos));
+            }
+            if feasibility < LOW_FEASIBILITY_SCORE {
+                alternatives.push(format!("a chaos level below {}", chaos));
+            }
+            if let Some(recommended) = system.engine.memory_system.get_persona_recommendation(domain) {
+                if recommended != *persona {
+                    alternatives.push(format!("the {} persona", recommended));
+                }
+            }
+            let reason = format!(
+                "the idea scored only {:.0}% creative and {:.0}% feasible",
+                creativity * 100.0, feasibility * 100.0
+            );
+            record_summon_failure(system, domain, options.vibe.as_deref(), persona, chaos, &reason, alternatives, Some(result.base_idea.id));
+        }
+
+        // Save to memory
+        let idea = system.engine.remember_idea(result, chaos, domain, self.parent_id);
+
+        if options.save || system.engine.config.behavior_settings.save_ideas {
+            let path = save_idea(&system.engine.config.output_preferences, result, &idea, domain)?;
+            output::status(format, format!("\n{}", format!("💾 Idea saved to {}", path.display()).bright_green()));
+        }
+        Ok(())
+    }
+}
+
+/// Warms the run up from the session's recent ideas: each idea's sampling
+/// temperature is nudged toward recent creativity (never over a temperature
+/// pinned in config), and the chaos level leans that way too unless `--chaos`
+/// was given. The pull fades as the momentum goes stale.
+fn apply_creative_momentum(system: &CHOPSSystem, chaos: Option<u8>) -> (u8, Option<TemperatureNudge>) {
+    let settings = &system.engine.config.creative_momentum;
+    let strength = settings.coupling_strength.clamp(0.0, 1.0);
+    if strength == 0.0 {
+        return (chaos.unwrap_or(DEFAULT_SUMMON_CHAOS), None);
+    }
+
+    let (chaos_momentum, creativity_temperature) = system.engine.memory_system.working
+        .decayed_momentum(chrono::Utc::now(), settings.half_life_minutes);
+
+    let chaos = chaos.unwrap_or_else(|| {
+        let momentum_level = chaos_momentum * 11.0;
+        let biased = DEFAULT_SUMMON_CHAOS as f64 * (1.0 - strength) + momentum_level * strength;
+        biased.round().clamp(1.0, 11.0) as u8
+    });
+
+    tracing::info!("Creative momentum (strength {:.2}): chaos momentum {:.2}, temperature {:.2}, chaos level {}",
+        strength, chaos_momentum, creativity_temperature, chaos);
+    (chaos, Some(TemperatureNudge { temperature: creativity_temperature, strength }))
+}
+
+/// Generates the idea from the plan's chaos level, re-rolling while its
+/// novelty stays under the plan's floor or its feasibility falls outside
+/// `band`. Each re-roll moves the chaos level:
+/// down when the idea was too infeasible, up when it was too mundane or too
+/// predictable; a feasibility miss moves the reality level the other way.
+/// Returns the attempt closest to the band (most novel among equally close
+/// ones) and the chaos level it used, leaving the engine at that attempt's
+/// reality level. With `stream`, every attempt's text is printed as it arrives.
+#[allow(clippy::too_many_arguments)]
+pub async fn summon_with_rerolls(
+    system: &mut CHOPSSystem,
+    pb: &ProgressBar,
+    prompt: &str,
+    persona: &PersonaType,
+    domain: &str,
+    plan: &SummonPlan,
+    band: Option<FeasibilityBand>,
+    stream: bool,
+) -> CHOPSResult<(ComplexIdeaResult, u8)> {
+    let (grounding, min_novelty) = (plan.grounding.as_ref(), plan.min_novelty);
+    let surprise = system.engine.config.surprise_floor.clone();
+    let steering = system.engine.config.feasibility_band.clone();
+    let mut max_attempts = 1;
+    if min_novelty > 0.0 {
+        max_attempts = max_attempts.max(surprise.max_attempts);
+    }
+    if band.is_some() {
+        max_attempts = max_attempts.max(steering.max_attempts);
+    }
+    let band_miss = |idea: &GeneratedIdeaResponse| band.map(|band| band.distance(idea.feasibility_score)).unwrap_or(0.0);
+
+    let printer = stream.then(|| {
+        println!("{}", format!("📡 Streaming from {}:", persona).bright_blue());
+        TokenPrinter::start()
+    });
+
+    let mut chaos = plan.chaos;
+    let mut reality = system.engine.cognitive_architecture.chaos_engine().reality_level();
+    let mut best: Option<(ComplexIdeaResult, u8, f64)> = None;
+    for attempt in 1..=max_attempts {
+        let mut options = plan.idea_options.clone();
+        options.token_stream = printer.as_ref().map(|printer| printer.tokens.clone());
+        if let Some(conversation) = &system.conversation {
+            options.conversation_history = conversation.recent_messages(CONVERSATION_HISTORY_MESSAGES);
+            tracing::debug!("Continuing conversation {} with {} earlier messages", conversation.session_id, options.conversation_history.len());
+        }
+        let generated = system.engine.cognitive_architecture
+            .process_complex_idea_with_context(prompt, persona.clone(), domain, chaos as f64 / 11.0, grounding, &options)
+            .await;
+        drop(options);
+        let result = match generated {
+            Ok(result) => result,
+            Err(e) => {
+                if let Some(printer) = printer {
+                    printer.finish().await;
+                }
+                return Err(e);
+            },
+        };
+        let (novelty, feasibility) = (result.base_idea.novelty_score, result.base_idea.feasibility_score);
+        let miss = band_miss(&result.base_idea);
+        tracing::info!("Summon attempt {}/{} at chaos {}: novelty {:.2} (floor {:.2}), feasibility {:.2} ({:.2} from band)",
+            attempt, max_attempts, chaos, novelty, min_novelty, feasibility, miss);
+
+        let closer = best.as_ref().is_none_or(|(best, _, _)| {
+            let best_miss = band_miss(&best.base_idea);
+            miss < best_miss || (miss == best_miss && novelty > best.base_idea.novelty_score)
+        });
+        if closer {
+            best = Some((result, chaos, reality));
+        }
+        if miss == 0.0 && novelty >= min_novelty {
+            break;
+        }
+        if attempt == max_attempts {
+            break;
+        }
+
+        // Outside the band both levers move: less chaos and a firmer grip on
+        // reality for a far-fetched idea, the reverse for a mundane one
+        let too_wild = band.is_some_and(|band| feasibility < band.low);
+        let chaos_step = if miss > 0.0 { steering.chaos_step } else { surprise.chaos_step }.max(1);
+        chaos = if too_wild { chaos.saturating_sub(chaos_step).max(1) } else { (chaos + chaos_step).min(11) };
+        if miss > 0.0 {
+            let step = if too_wild { steering.reality_step } else { -steering.reality_step };
+            reality = (reality + step).clamp(0.0, 1.0);
+            system.engine.cognitive_architecture.chaos_engine_mut().set_reality_level(reality)?;
+        }
+        let reason = match band {
+            Some(band) if too_wild => format!("Feasibility {:.0}% is below {}", feasibility * 100.0, band),
+            Some(band) if miss > 0.0 => format!("Feasibility {:.0}% is above {}", feasibility * 100.0, band),
+            _ => "Too predictable".to_string(),
+        };
+        let message = if miss > 0.0 {
+            format!("🎲 {}, re-rolling at chaos level {} and reality level {:.1} ({}/{})...", reason, chaos, reality, attempt + 1, max_attempts)
         } else {
-            title.to_string()
+            format!("🎲 {}, re-rolling at chaos level {} ({}/{})...", reason, chaos, attempt + 1, max_attempts)
+        };
+        if let Some(printer) = &printer {
+            printer.note(format!("{}", message.bright_black())).await;
         }
+        pb.set_message(message);
+    }
+    if let Some(printer) = printer {
+        printer.finish().await;
+    }
+
+    let (result, chaos, best_reality) = best.expect("at least one summon attempt");
+    system.engine.cognitive_architecture.chaos_engine_mut().set_reality_level(best_reality)?;
+    if let Some(band) = band.filter(|band| !band.contains(result.base_idea.feasibility_score)) {
+        notify(pb, format!("{}", format!(
+            "⚠️  No idea landed in the {} feasibility band after {} attempts, keeping the closest ({:.0}%)",
+            band, max_attempts, result.base_idea.feasibility_score * 100.0
+        ).yellow()));
+    }
+    if result.base_idea.novelty_score < min_novelty {
+        notify(pb, format!("{}", format!(
+            "⚠️  No idea cleared the novelty floor of {:.0}% after {} attempts, keeping the most novel ({:.0}%)",
+            min_novelty * 100.0, max_attempts, result.base_idea.novelty_score * 100.0
+        ).yellow()));
+    }
+    Ok((result, chaos))
+}
+
+/// Prints above the spinner, or straight to stderr when it is hidden (for
+/// streaming, a batch or output that isn't a terminal)
+fn notify(pb: &ProgressBar, message: String) {
+    if pb.is_hidden() {
+        eprintln!("{}", message);
     } else {
-        "Generated Idea".to_string()
+        pb.println(message);
     }
 }
 
-fn extract_tags_from_content(content: &str) -> Vec<String> {
-    let mut tags = Vec::new();
-    
-    // Extract common technical terms as tags
-    let keywords = [
-        "ai", "machine learning", "algorithm", "api", "database", "framework",
-        "architecture", "performance", "security", "testing", "automation",
-        "cloud", "microservices", "blockchain", "quantum", "neural",
-        "optimization", "scalability", "user experience", "innovation"
-    ];
-    
-    let content_lower = content.to_lowercase();
-    for keyword in &keywords {
-        if content_lower.contains(keyword) {
-            tags.push(keyword.to_string());
+/// Prints streamed idea text as it arrives, in order with any notes sent alongside it
+struct TokenPrinter {
+    tokens: mpsc::Sender<String>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl TokenPrinter {
+    fn start() -> Self {
+        let (tokens, mut receiver) = mpsc::channel::<String>(TOKEN_BUFFER);
+        let handle = tokio::spawn(async move {
+            let mut stdout = std::io::stdout();
+            while let Some(text) = receiver.recv().await {
+                let _ = write!(stdout, "{}", text);
+                let _ = stdout.flush();
+            }
+        });
+        Self { tokens, handle }
+    }
+
+    async fn note(&self, note: String) {
+        let _ = self.tokens.send(format!("\n\n{}\n\n", note)).await;
+    }
+
+    /// Waits for everything received to be printed, once the last attempt's
+    /// options (holding the other senders) have been dropped
+    async fn finish(self) {
+        drop(self.tokens);
+        if let Err(e) = self.handle.await {
+            tracing::warn!("Token printer stopped early: {}", e);
         }
+        println!("\n");
     }
-    
-    // Limit to 5 most relevant tags
-    tags.truncate(5);
-    
-    if tags.is_empty() {
-        tags.push("innovative".to_string());
-        tags.push("creative".to_string());
+}
+
+/// Extracts and prints a concept map; a failed extraction doesn't fail the summon
+async fn print_concept_map(system: &mut CHOPSSystem, idea: &GeneratedIdeaResponse, format: ConceptMapFormat, output_format: &OutputFormat) {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner:.cyan} {msg}").unwrap());
+    pb.set_message("🗺️  Mapping concepts...");
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let graph = system.engine.cognitive_architecture.claude_client_mut().extract_concept_map(idea).await;
+    pb.finish_and_clear();
+
+    match graph {
+        Ok(graph) => {
+            output::status(output_format, format!("\n{}", format!("🗺️  Concept map ({} concepts, {} relationships):", graph.nodes.len(), graph.edges.len()).bright_blue()));
+            output::status(output_format, graph.render(format));
+        },
+        Err(e) => {
+            tracing::warn!("Concept map extraction failed: {}", e);
+            eprintln!("{}", format!("⚠️  Could not extract a concept map: {}", e).yellow());
+        }
     }
-    
-    tags
-}
\ No newline at end of file
+}
+
+fn failure_context(domain: &str, vibe: Option<&str>) -> String {
+    match vibe {
+        Some(vibe) => format!("{} ideas with a '{}' vibe", domain, vibe),
+        None => format!("{} ideas", domain),
+    }
+}
+
+/// Remembers a summon that didn't work out so later prompts can steer around it
+#[allow(clippy::too_many_arguments)]
+pub fn record_summon_failure(
+    system: &mut CHOPSSystem,
+    domain: &str,
+    vibe: Option<&str>,
+    persona: &PersonaType,
+    chaos: u8,
+    reason: &str,
+    suggested_alternatives: Vec<String>,
+    idea_id: Option<uuid::Uuid>,
+) {
+    let mut failure = FailureLearning::new(
+        &failure_context(domain, vibe),
+        &format!("{} at chaos level {}", persona, chaos),
+        reason,
+    );
+    failure.suggested_alternatives = suggested_alternatives;
+    failure.idea_id = idea_id;
+    system.engine.memory_system.record_failure(failure);
+}
+
+/// Checks the chain a refinement of `parent_id` would extend. When it is
+/// already at the depth cap or over its token budget, prints the best idea
+/// in the chain instead and returns true so no further generation happens.
+fn refinement_limit_reached(system: &CHOPSSystem, parent_id: uuid::Uuid, max_depth: usize, chain_budget: u64, format: &OutputFormat) -> bool {
+    let lineage = system.engine.memory_system.idea_lineage(parent_id, max_depth);
+    if let Some(cycle_at) = lineage.cycle_at {
+        output::status(format, format!("⚠️  Idea lineage loops back to {}; treating the chain as ending there", &cycle_at.to_string()[..8]).yellow());
+    }
+
+    let spent = lineage.total_tokens();
+    let limit = if lineage.depth() >= max_depth {
+        format!("the refinement depth cap of {}", max_depth)
+    } else if chain_budget > 0 && spent >= chain_budget {
+        format!("its token budget ({} of {} tokens spent)", spent, chain_budget)
+    } else {
+        return false;
+    };
+    tracing::info!("Refinement chain of {} stopped: {}", parent_id, limit);
+
+    let Some(best) = lineage.best(ScoreProfile::Balanced) else {
+        return false;
+    };
+    output::status(format, format!("🔁 This refinement chain has reached {}.", limit).bright_yellow());
+    output::status(format, format!("{} {} {}",
+        "🏆 Best idea in the chain:".bright_green(),
+        best.title.bright_white(),
+        format!("[{}]", &best.id.to_string()[..8]).bright_black()
+    ));
+    output::status(format, format!("   {} {:.0}% | {} {:.0}% | {} {:.0}%",
+        "Creative:".bright_black(), best.creativity_score * 100.0,
+        "Feasible:".bright_black(), best.feasibility_score * 100.0,
+        "Novel:".bright_black(), best.novelty_score * 100.0
+    ));
+    output::status(format, "Raise --max-depth or --chain-budget (or [refinement] in the config) to keep refining.".bright_black());
+    true
+}
+
+/// `--based-on` accepts an idea id (or prefix) from memory, or a path to reference text
+fn resolve_grounding(system: &CHOPSSystem, reference: &str) -> CHOPSResult<GroundingContext> {
+    let looks_like_id = reference.len() >= 4
+        && reference.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+
+    if looks_like_id {
+        if let Some(idea) = system.engine.memory_system.find_idea(reference)? {
+            tracing::info!("Grounding summon in prior idea {}", idea.id);
+            return Ok(GroundingContext {
+                kind: GroundingKind::PriorIdea,
+                label: format!("\"{}\"", idea.title),
+                content: idea.description.clone(),
+            });
+        }
+    }
+
+    let path = std::path::Path::new(reference);
+    if path.is_file() {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            CHOPSError::InvalidParameter(format!("Cannot read {}: {}", reference, e))
+        })?;
+        tracing::info!("Grounding summon in reference file {}", reference);
+        return Ok(GroundingContext {
+            kind: GroundingKind::ReferenceText,
+            label: reference.to_string(),
+            content,
+        });
+    }
+
+    Err(CHOPSError::InvalidParameter(format!(
+        "--based-on '{}' is neither an idea in memory nor a readable file",
+        reference
+    )))
+}
+
+/// Writes the idea as markdown to the configured output directory, named for
+/// its persona, domain and short id, prefixed with the time when
+/// `timestamp_files` is on and headed by YAML front matter when
+/// `include_metadata` is. A file it overwrites (the same idea saved again) is
+/// kept as `<file>.bak` when `auto_backup` is on.
+pub fn save_idea(
+    preferences: &OutputPreferences,
+    result: &ComplexIdeaResult,
+    idea: &GeneratedIdea,
+    domain: &str,
+) -> CHOPSResult<PathBuf> {
+    let mut name = format!(
+        "{}-{}-{}",
+        file_slug(&idea.persona_used.to_string()), file_slug(domain), &idea.id.to_string()[..8]
+    );
+    if preferences.timestamp_files {
+        name = format!("{}-{}", result.base_idea.generated_at.format("%Y%m%d-%H%M%S"), name);
+    }
+    std::fs::create_dir_all(&preferences.default_directory).map_err(CHOPSError::FileSystemError)?;
+    let path = preferences.default_directory.join(format!("{}.md", name));
+
+    let mut content = String::new();
+    if preferences.include_metadata {
+        let base = &result.base_idea;
+        content.push_str("---\n");
+        content.push_str(&format!("id: {}\n", idea.id));
+        // A JSON string is a valid YAML scalar, quotes and all
+        content.push_str(&format!("title: {}\n", serde_json::to_string(&idea.title)?));
+        content.push_str(&format!("persona: {}\n", idea.persona_used));
+        content.push_str(&format!("domain: {}\n", serde_json::to_string(domain)?));
+        content.push_str(&format!("chaos_level: {:.2}\n", base.chaos_level));
+        content.push_str(&format!("creativity: {:.2}\n", base.creativity_score));
+        content.push_str(&format!("feasibility: {:.2}\n", base.feasibility_score));
+        content.push_str(&format!("novelty: {:.2}\n", base.novelty_score));
+        content.push_str(&format!("overall: {:.2}\n", base.calculate_overall_score()));
+        if !idea.tags.is_empty() {
+            content.push_str(&format!("tags: {}\n", serde_json::to_string(&idea.tags)?));
+        }
+        if let Some(parent_id) = idea.parent_id {
+            content.push_str(&format!("parent: {}\n", parent_id));
+        }
+        content.push_str(&format!("created: {}\n", base.generated_at.to_rfc3339()));
+        content.push_str("---\n\n");
+    }
+    content.push_str(&result.render_markdown());
+
+    if preferences.auto_backup && path.exists() {
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        std::fs::copy(&path, &backup).map_err(CHOPSError::FileSystemError)?;
+        tracing::debug!("Kept the previous {} as {}", path.display(), PathBuf::from(&backup).display());
+    }
+    std::fs::write(&path, content).map_err(CHOPSError::FileSystemError)?;
+    tracing::info!("Saved idea {} to {}", idea.id, path.display());
+    Ok(path)
+}
+
+/// Lowercase letters and digits, with runs of anything else as one dash
+fn file_slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "idea".to_string() } else { slug.to_string() }
+}
+
+/// Writes the one generated result in every requested format under a shared base name
+fn export_result(
+    system: &CHOPSSystem,
+    result: &ComplexIdeaResult,
+    formats: &[OutputFormat],
+    export_dir: Option<&str>,
+    format: &OutputFormat,
+) -> CHOPSResult<()> {
+    let preferences = &system.engine.config.output_preferences;
+    let dir = export_dir
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| preferences.default_directory.clone());
+
+    let mut base_name = format!("{}-{}", result.base_idea.persona_used, &result.base_idea.id.to_string()[..8]);
+    if preferences.timestamp_files {
+        base_name = format!("{}-{}", result.base_idea.generated_at.format("%Y%m%d-%H%M%S"), base_name);
+    }
+
+    let paths = chops_core::write_renderings(result, formats, &dir, &base_name)?;
+    output::status(format, format!("\n{}", "📁 Exported:".bright_blue().bold()));
+    for path in paths {
+        output::status(format, format!("  • {}", path.display().to_string().bright_white()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chops_api::{ChopsEngine, LlmClient, MockLlmClient};
+    use chops_core::CHOPSConfig;
+
+    #[test]
+    fn test_persona_hints_include_domain_but_not_default_constraints() {
+        let config = CHOPSConfig {
+            default_constraints: vec!["must survive an outage".to_string()],
+            ..Default::default()
+        };
+        let requested = vec!["no new dependencies".to_string()];
+        let merged = config.merged_constraints(&requested);
+        assert!(merged.len() > requested.len());
+
+        let hints = persona_hints("security", None, &Some("calm".to_string()), &None, &requested, None);
+        assert!(hints.contains("security"));
+        assert!(hints.contains("calm"));
+        assert!(hints.contains("no new dependencies"));
+        assert!(!hints.contains("outage"));
+    }
+
+    #[test]
+    fn test_batch_settings_change_with_what_the_ideas_are_asked_for() {
+        let plain = SummonOptions::default();
+        let calm = SummonOptions { vibe: Some("calm".to_string()), ..Default::default() };
+        let seeded = SummonOptions { seed: Some(7), ..Default::default() };
+        assert_ne!(plain.batch_settings(), calm.batch_settings());
+        assert_ne!(plain.batch_settings(), seeded.batch_settings());
+
+        // How the run reports and keeps ideas doesn't change them
+        let quiet = SummonOptions { quiet: true, save: true, ..Default::default() };
+        assert_eq!(plain.batch_settings(), quiet.batch_settings());
+    }
+
+    async fn summon_offline(engine: &mut ChopsEngine) -> (ComplexIdeaResult, GeneratedIdea) {
+        let result = engine.cognitive_architecture
+            .process_complex_idea_with_context("tidy logs", PersonaType::default(), "web dev", 0.5, None, &IdeaOptions::default())
+            .await
+            .unwrap();
+        let idea = engine.remember_idea(&result, 5, "web dev", None);
+        (result, idea)
+    }
+
+    #[tokio::test]
+    async fn test_save_idea_writes_one_file_per_idea() {
+        let dir = std::env::temp_dir().join(format!("chops-save-{}", uuid::Uuid::new_v4()));
+        let mut config = CHOPSConfig::default();
+        config.network.offline = true;
+        let mut engine = ChopsEngine::builder()
+            .config(config)
+            .backend(Box::new(MockLlmClient::new()) as Box<dyn LlmClient>)
+            .data_dir(dir.join("data"))
+            .build()
+            .unwrap();
+        let preferences = OutputPreferences {
+            default_directory: dir.join("ideas"),
+            auto_backup: true,
+            include_metadata: true,
+            timestamp_files: false,
+        };
+
+        let (first_result, first) = summon_offline(&mut engine).await;
+        let (second_result, second) = summon_offline(&mut engine).await;
+        let first_path = save_idea(&preferences, &first_result, &first, "web dev").unwrap();
+        let second_path = save_idea(&preferences, &second_result, &second, "web dev").unwrap();
+        assert_ne!(first_path, second_path);
+        assert!(first_path.file_name().unwrap().to_string_lossy().starts_with("mad-scientist-web-dev-"));
+
+        let saved = std::fs::read_to_string(&first_path).unwrap();
+        assert!(saved.starts_with("---\n"));
+        assert!(saved.contains(&format!("id: {}\n", first.id)));
+        assert!(saved.contains("domain: \"web dev\"\n"));
+
+        // Saving the same idea again keeps the previous file as a backup
+        save_idea(&preferences, &first_result, &first, "web dev").unwrap();
+        let mut backup = first_path.clone().into_os_string();
+        backup.push(".bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), saved);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
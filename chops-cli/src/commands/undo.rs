@@ -0,0 +1,37 @@
+use chops_core::{CHOPSResult, CHOPSError, OutputFormat};
+use crate::{output, CHOPSSystem};
+use colored::*;
+
+#[tracing::instrument(name = "undo_execute", level = "info", skip(system))]
+pub async fn execute(system: &mut CHOPSSystem, format: OutputFormat) -> CHOPSResult<()> {
+    output::status(&format, "↩️ CHOPS Undo".bright_blue().bold());
+
+    let store = system.backup_store().ok_or_else(|| {
+        CHOPSError::ConfigError("No data directory available, nothing to undo".to_string())
+    })?;
+
+    let restored = store.undo_latest()?;
+    if format.is_machine_readable() {
+        // null when there was nothing to undo
+        return output::print_data(&restored, &format);
+    }
+
+    match restored {
+        Some(manifest) => {
+            println!("{}", format!(
+                "Restored {} file(s) from `{}` ({})",
+                manifest.entries.len(),
+                manifest.command,
+                manifest.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ).bright_green());
+
+            for entry in &manifest.entries {
+                let action = if entry.original_content.is_some() { "restored" } else { "removed" };
+                println!("  • {} {}", entry.path.display().to_string().bright_white(), action.bright_black());
+            }
+        },
+        None => println!("{}", "📝 Nothing to undo.".bright_yellow()),
+    }
+
+    Ok(())
+}
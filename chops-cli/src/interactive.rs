@@ -1,193 +1,545 @@
+use crate::cancel::run_cancellable;
+use crate::prompt::Prompter;
 use crate::CHOPSSystem;
-use chops_core::{PersonaType, CHOPSResult, CHOPSError};
+use chops_api::{CollaborationMode, ConversationSession, MessageRole};
+use chops_core::{BackupManifest, MutateParams, MutationHistory, OutputFormat, PersonaType, CHOPSResult, CHOPSError, MAX_HISTORY_DEPTH};
 use colored::*;
-use dialoguer::{Select, Input, Confirm, MultiSelect};
+use std::time::Duration;
+
+const CHAOS_PRESETS: [u8; 5] = [1, 3, 5, 7, 11];
 
 #[tracing::instrument(name = "run_interactive_mode", level = "info", skip(system))]
-pub async fn run_interactive_mode(system: &mut CHOPSSystem) -> CHOPSResult<()> {
+pub async fn run_interactive_mode(system: &mut CHOPSSystem, resume: Option<String>, idle_timeout: Option<u64>) -> CHOPSResult<()> {
     tracing::info!("Starting interactive CHOPS mode");
+
+    let idle_minutes = idle_timeout.unwrap_or(system.engine.config.behavior_settings.interactive_idle_minutes);
+    let idle_timeout = (idle_minutes > 0).then(|| Duration::from_secs(idle_minutes * 60));
     
     println!("{}", "🎮 Welcome to Interactive CHOPS Mode!".bright_cyan().bold());
     println!("{}", "   Reality-bending at your fingertips...".bright_blue());
+
+    let mut session_path = resume.clone();
+    let conversation = match &resume {
+        Some(path) => {
+            tracing::info!("Resuming conversation from: {}", path);
+            let session = ConversationSession::load_from_file(std::path::Path::new(path))?;
+            print_conversation_recap(&session);
+            session
+        },
+        None => ConversationSession::new(
+            system.engine.config.default_settings.default_persona.clone(),
+            system.engine.config.default_settings.default_chaos_level,
+            "software development".to_string(),
+        ),
+    };
+    system.conversation = Some(conversation);
     
+    let prompter = Prompter::new(idle_timeout);
     loop {
-        println!("\n{}", "─".repeat(50).bright_black());
-        
-        let actions = vec![
-            "🔮 Summon an idea",
-            "🧬 Mutate existing code", 
-            "🔮 Generate prophecy",
-            "🤝 Start AI collaboration",
-            "⚡ Inject chaos glitch",
-            "🧠 View memory",
-            "⚙️ Configure settings",
-            "🚪 Exit"
-        ];
-        
-        tracing::debug!("Presenting interactive menu with {} options", actions.len());
-        
-        let selection = Select::new()
-            .with_prompt("What would you like to do?")
-            .items(&actions)
-            .default(0)
-            .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
-            
-        tracing::debug!("User selected option: {} ({})", selection, actions[selection]);
-            
-        match selection {
-            0 => {
-                tracing::info!("Starting interactive summon");
-                interactive_summon(system).await?
-            },
-            1 => {
-                tracing::info!("Starting interactive mutate");
-                interactive_mutate(system).await?
-            },
-            2 => {
-                tracing::info!("Starting interactive prophecy");
-                interactive_prophecy(system).await?
-            },
-            3 => {
-                tracing::info!("Starting interactive collaboration");
-                interactive_collaborate(system).await?
-            },
-            4 => {
-                tracing::info!("Starting interactive glitch");
-                interactive_glitch(system).await?
-            },
-            5 => {
-                tracing::info!("Viewing memory");
-                interactive_memory(system).await?
-            },
-            6 => {
-                tracing::info!("Configuring settings");
-                interactive_config(system).await?
-            },
-            7 => {
-                tracing::info!("User exiting interactive mode");
-                println!("{}", "👋 Reality returns to normal. Goodbye!".bright_green());
+        match run_menu_action(system, &prompter, &mut session_path).await {
+            Ok(true) => {},
+            Ok(false) => break,
+            Err(_) if prompter.timed_out() => {
+                tracing::info!("Interactive mode idle for {} minutes, closing", idle_minutes);
+                close_idle_session(system, &session_path, idle_minutes).await?;
                 break;
             },
-            _ => unreachable!(),
+            Err(e) => return Err(e),
         }
     }
     
+    system.conversation = None;
     tracing::info!("Interactive mode session completed");
     Ok(())
 }
 
-#[tracing::instrument(name = "interactive_summon", level = "info", skip(system))]
-async fn interactive_summon(system: &mut CHOPSSystem) -> CHOPSResult<()> {
+/// Shows the main menu and runs the chosen action, returning whether the
+/// session goes on. Any prompt left unanswered past the idle timeout ends
+/// the action with an error and marks `prompter` timed out.
+async fn run_menu_action(system: &mut CHOPSSystem, prompter: &Prompter, session_path: &mut Option<String>) -> CHOPSResult<bool> {
+    println!("\n{}", "─".repeat(50).bright_black());
+    
+    let actions = vec![
+        "🔮 Summon an idea",
+        "🧬 Mutate existing code", 
+        "🔮 Generate prophecy",
+        "🤝 Start AI collaboration",
+        "⚡ Inject chaos glitch",
+        "🧠 View memory",
+        "⚙️ Configure settings",
+        "💾 Save conversation",
+        "🚪 Exit"
+    ];
+    
+    tracing::debug!("Presenting interactive menu with {} options", actions.len());
+    
+    let selection = prompter.select("What would you like to do?")
+        .items(&actions)
+        .default(0)
+        .interact()?;
+        
+    tracing::debug!("User selected option: {} ({})", selection, actions[selection]);
+        
+    match selection {
+        0 => {
+            tracing::info!("Starting interactive summon");
+            interactive_summon(system, prompter).await?
+        },
+        1 => {
+            tracing::info!("Starting interactive mutate");
+            interactive_mutate(system, prompter).await?
+        },
+        2 => {
+            tracing::info!("Starting interactive prophecy");
+            interactive_prophecy(system, prompter).await?
+        },
+        3 => {
+            tracing::info!("Starting interactive collaboration");
+            interactive_collaborate(system, prompter).await?
+        },
+        4 => {
+            tracing::info!("Starting interactive glitch");
+            interactive_glitch(system, prompter).await?
+        },
+        5 => {
+            tracing::info!("Viewing memory");
+            interactive_memory(system).await?
+        },
+        6 => {
+            tracing::info!("Configuring settings");
+            interactive_config(system).await?
+        },
+        7 => {
+            tracing::info!("Saving conversation");
+            save_conversation(system, prompter, session_path)?
+        },
+        8 => {
+            tracing::info!("User exiting interactive mode");
+            let has_unsaved_turns = system.conversation
+                .as_ref()
+                .map(|conversation| !conversation.messages.is_empty())
+                .unwrap_or(false);
+            if has_unsaved_turns {
+                let save = prompter.confirm("Save this conversation before leaving?")
+                    .default(session_path.is_some())
+                    .interact()?;
+                if save {
+                    save_conversation(system, prompter, session_path)?;
+                }
+            }
+            system.print_entropy_stats();
+            system.print_usage_stats();
+            println!("{}", "👋 Reality returns to normal. Goodbye!".bright_green());
+            return Ok(false);
+        },
+        _ => unreachable!(),
+    }
+    Ok(true)
+}
+
+/// Ends a session nobody is attending: memory is saved, and so is the
+/// conversation (to its session file, or a timestamped one) if it has turns
+async fn close_idle_session(system: &CHOPSSystem, session_path: &Option<String>, idle_minutes: u64) -> CHOPSResult<()> {
+    println!("\n{}", format!("💤 No activity for {} minutes, saving and leaving interactive mode.", idle_minutes).bright_yellow());
+
+    system.save_memory().await?;
+
+    if let Some(conversation) = system.conversation.as_ref().filter(|conversation| !conversation.messages.is_empty()) {
+        let path = session_path.clone().unwrap_or_else(|| {
+            format!("session-{}.json", chrono::Local::now().format("%Y%m%d-%H%M%S"))
+        });
+        conversation.save_to_file(std::path::Path::new(&path))?;
+        println!("{}", format!("💾 Conversation saved to {} ({} turns)", path, conversation.turn_count()).bright_green());
+        println!("{}", format!("   Resume with: chops interactive --resume {}", path).bright_black());
+    }
+
+    system.print_usage_stats();
+    println!("{}", "👋 Reality returns to normal. Goodbye!".bright_green());
+    Ok(())
+}
+
+fn print_conversation_recap(session: &ConversationSession) {
+    println!("\n{}", "📜 Resuming conversation".bright_magenta().bold());
+    println!("   {} {}", "Persona:".bright_black(), session.persona.to_string().bright_green());
+    println!("   {} {}", "Chaos level:".bright_black(), session.chaos_level.to_string().bright_yellow());
+    println!("   {} {}", "Domain:".bright_black(), session.domain.bright_white());
+    println!("   {} {} (last active {})",
+        "Turns:".bright_black(),
+        session.turn_count().to_string().bright_cyan(),
+        session.updated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let last_reply = session.messages
+        .iter()
+        .rev()
+        .find(|message| matches!(message.role, MessageRole::Assistant));
+    if let Some(reply) = last_reply {
+        let preview: String = reply.content.lines().next().unwrap_or("").chars().take(100).collect();
+        println!("   {} {}", "Last idea:".bright_black(), preview.bright_white());
+    }
+}
+
+fn save_conversation(system: &CHOPSSystem, prompter: &Prompter, session_path: &mut Option<String>) -> CHOPSResult<()> {
+    let Some(conversation) = system.conversation.as_ref() else {
+        return Ok(());
+    };
+
+    let path: String = prompter.input("Save conversation to")
+        .default(session_path.clone().unwrap_or_else(|| "session.json".to_string()))
+        .interact()?;
+
+    conversation.save_to_file(std::path::Path::new(&path))?;
+    println!("{}", format!("💾 Conversation saved to {} ({} turns)", path, conversation.turn_count()).bright_green());
+    println!("{}", format!("   Resume with: chops interactive --resume {}", path).bright_black());
+
+    *session_path = Some(path);
+    Ok(())
+}
+
+#[tracing::instrument(name = "interactive_summon", level = "info", skip(system, prompter))]
+async fn interactive_summon(system: &mut CHOPSSystem, prompter: &Prompter) -> CHOPSResult<()> {
     tracing::info!("Starting interactive idea summoning");
     
     println!("\n{}", "🔮 IDEA SUMMONING RITUAL".bright_cyan().bold());
     
-    // Select persona
-    let personas = vec![
-        "Mad Scientist 🧪",
-        "Zen Master 🧘",
-        "Punk Hacker 🦾", 
-        "Empathetic AI 💝",
-        "Chaos Engineer ⚡",
-        "Time Traveler ⏰",
-        "Mind Reader 🧠"
-    ];
+    // Select persona: the built-in ones, then any loaded from *.persona.toml
+    let mut persona_choices = PersonaType::ALL.to_vec();
+    persona_choices.extend(system.engine.cognitive_architecture.persona_engine().custom_personas());
+    let personas: Vec<String> = persona_choices.iter().map(persona_label).collect();
     
     tracing::debug!("Presenting persona selection with {} options", personas.len());
+
+    // Continue from the conversation's last settings when there is one
+    let (default_persona, default_chaos, default_domain) = match &system.conversation {
+        Some(conversation) => (
+            persona_choices.iter().position(|p| p == &conversation.persona).unwrap_or(0),
+            CHAOS_PRESETS
+                .iter()
+                .position(|level| *level >= conversation.chaos_level)
+                .unwrap_or(CHAOS_PRESETS.len() - 1),
+            conversation.domain.clone(),
+        ),
+        None => (0, 2, "software development".to_string()),
+    };
     
-    let persona_idx = Select::new()
-        .with_prompt("Choose your persona")
+    let persona_idx = prompter.select("Choose your persona")
         .items(&personas)
-        .default(0)
-        .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+        .default(default_persona)
+        .interact()?;
         
-    let persona = match persona_idx {
-        0 => PersonaType::MadScientist,
-        1 => PersonaType::ZenMaster,
-        2 => PersonaType::PunkHacker,
-        3 => PersonaType::EmpatheticAI,
-        4 => PersonaType::ChaosEngineer,
-        5 => PersonaType::TimeTraveler,
-        6 => PersonaType::MindReader,
-        _ => PersonaType::MadScientist,
-    };
+    let persona = persona_choices.get(persona_idx).cloned().unwrap_or_default();
     
     tracing::debug!("Selected persona: {:?}", persona);
     
     // Get domain
-    let domain: String = Input::new()
-        .with_prompt("What domain are you working in?")
-        .default("software development".to_string())
-        .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+    let domain: String = prompter.input("What domain are you working in?")
+        .default(default_domain)
+        .interact()?;
     
     // Get chaos level
     let chaos_options = vec!["1 - Gentle nudge", "3 - Creative spark", "5 - Wild ideas", "7 - Reality bending", "11 - Transcendent chaos"];
-    let chaos_idx = Select::new()
-        .with_prompt("Choose chaos level")
+    let chaos_idx = prompter.select("Choose chaos level")
         .items(&chaos_options)
-        .default(2)
-        .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+        .default(default_chaos)
+        .interact()?;
         
-    let chaos = match chaos_idx {
-        0 => 1,
-        1 => 3,
-        2 => 5,
-        3 => 7,
-        4 => 11,
-        _ => 5,
-    };
+    let chaos = CHAOS_PRESETS.get(chaos_idx).copied().unwrap_or(5);
     
     // Optional vibe
-    let add_vibe = Confirm::new()
-        .with_prompt("Add a specific vibe?")
+    let add_vibe = prompter.confirm("Add a specific vibe?")
         .default(false)
-        .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?;
+        .interact()?;
         
     let vibe = if add_vibe {
-        Some(Input::<String>::new()
-            .with_prompt("Describe the vibe")
-            .interact().map_err(|e| CHOPSError::UnexpectedError(e.to_string()))?)
+        Some(prompter.input::<String>("Describe the vibe")
+            .interact()?)
     } else {
         None
     };
     
-    // Execute summon
-    crate::commands::summon::execute(
+    // Execute summon, rolling back the turns it recorded if the user cancels
+    let options = crate::commands::summon::SummonOptions {
+        persona: Some(persona.clone()),
+        domain: Some(domain.clone()),
+        chaos: Some(chaos),
+        vibe: vibe.clone(),
+        ..Default::default()
+    };
+    let turns_before = conversation_len(system);
+    let completed = run_cancellable("Summoning", crate::commands::summon::execute(
         system,
-        persona,
-        domain,
-        chaos,
-        None, // timeline
-        vibe,
-        vec![], // constraints
-        0.7, // reality level
-    ).await
-}
-
-async fn interactive_mutate(_system: &mut CHOPSSystem) -> CHOPSResult<()> {
-    println!("{}", "🧬 Code mutation coming soon...".bright_yellow());
+        &options,
+        chops_core::OutputFormat::Markdown,
+    )).await?;
+
+    if !completed {
+        rollback_conversation(system, turns_before);
+        return Ok(());
+    }
+
+    // Rejected ideas become failure learnings for future prompts
+    let keep = prompter.confirm("Was this idea useful?")
+        .default(true)
+        .interact()?;
+    system.engine.memory_system.rate_latest_session_idea(system.engine.session_id, keep);
+    if !keep {
+        let reason: String = prompter.input("What missed the mark? (optional)")
+            .allow_empty(true)
+            .interact()?;
+        let reason = if reason.trim().is_empty() {
+            "the user rejected the result".to_string()
+        } else {
+            format!("the user rejected it: {}", reason.trim())
+        };
+        let idea_id = system.engine.memory_system.short_term.recent_ideas.back().map(|idea| idea.id);
+        crate::commands::summon::record_summon_failure(system, &domain, vibe.as_deref(), &persona, chaos, &reason, Vec::new(), idea_id);
+        system.save_memory().await?;
+        println!("{}", "📝 Noted. Future summons will steer away from this.".bright_black());
+    } else {
+        system.save_memory().await?;
+    }
     Ok(())
 }
 
-async fn interactive_prophecy(_system: &mut CHOPSSystem) -> CHOPSResult<()> {
-    println!("{}", "🔮 Prophecy generation coming soon...".bright_yellow());
+fn conversation_len(system: &CHOPSSystem) -> Option<usize> {
+    system.conversation.as_ref().map(|conversation| conversation.messages.len())
+}
+
+/// Drops turns a cancelled action recorded so the session matches the menu state
+fn rollback_conversation(system: &mut CHOPSSystem, turns_before: Option<usize>) {
+    if let (Some(conversation), Some(len)) = (system.conversation.as_mut(), turns_before) {
+        conversation.messages.truncate(len);
+    }
+}
+
+fn persona_label(persona: &PersonaType) -> String {
+    match persona {
+        PersonaType::MadScientist => "Mad Scientist 🧪".to_string(),
+        PersonaType::ZenMaster => "Zen Master 🧘".to_string(),
+        PersonaType::PunkHacker => "Punk Hacker 🦾".to_string(),
+        PersonaType::EmpatheticAI => "Empathetic AI 💝".to_string(),
+        PersonaType::ChaosEngineer => "Chaos Engineer ⚡".to_string(),
+        PersonaType::TimeTraveler => "Time Traveler ⏰".to_string(),
+        PersonaType::MindReader => "Mind Reader 🧠".to_string(),
+        PersonaType::Custom(name) => format!("{} 🧩 (custom)", name),
+    }
+}
+
+#[tracing::instrument(name = "interactive_mutate", level = "info", skip(system, prompter))]
+async fn interactive_mutate(system: &mut CHOPSSystem, prompter: &Prompter) -> CHOPSResult<()> {
+    println!("\n{}", "🧬 CODE MUTATION LAB".bright_green().bold());
+
+    let file: String = prompter.input("File to mutate")
+        .interact()?;
+    let path = std::path::PathBuf::from(file.trim());
+    if !path.is_file() {
+        println!("{}", format!("❌ {} is not a readable file", path.display()).red());
+        return Ok(());
+    }
+
+    // Every applied mutation first snapshots the file, so it can be undone
+    // now or in a later session
+    let mut history = MutationHistory::open(&path, MAX_HISTORY_DEPTH)?;
+    if !history.is_empty() {
+        println!("{}", format!("📜 {} earlier versions of {} can be restored", history.len(), path.display()).bright_black());
+    }
+
+    loop {
+        let undo_label = format!("↩️  Undo last mutation ({} in history)", history.len());
+        let actions = ["🧬 Mutate", undo_label.as_str(), "📜 Show history", "⬅️  Back"];
+        let selection = prompter.select(format!("{}", path.display()))
+            .items(&actions)
+            .default(0)
+            .interact()?;
+
+        match selection {
+            0 => mutate_once(system, prompter, &path, &mut history).await?,
+            1 => match history.undo(&path)? {
+                Some(snapshot) => {
+                    tracing::info!("Restored {} to its version from {}", path.display(), snapshot.taken_at);
+                    println!("{}", format!("↩️  Restored {} to its version from {}",
+                        path.display(), snapshot.taken_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")).bright_green());
+                },
+                None => println!("{}", "Nothing to undo yet.".bright_yellow()),
+            },
+            2 => print_mutation_history(&history),
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Asks how to mutate the file, shows the result and, if the user keeps it,
+/// snapshots the current contents and writes the mutation
+async fn mutate_once(system: &mut CHOPSSystem, prompter: &Prompter, path: &std::path::Path, history: &mut MutationHistory) -> CHOPSResult<()> {
+    // Read fresh so edits made outside CHOPS are what gets snapshotted
+    let current = std::fs::read_to_string(path).map_err(CHOPSError::FileSystemError)?;
+
+    let direction: String = prompter.input("Direction of mutation")
+        .default("creative".to_string())
+        .interact()?;
+    let personality = prompter.confirm("Inject personality?")
+        .default(true)
+        .interact()?;
+    let functional = prompter.confirm("Keep it functional?")
+        .default(true)
+        .interact()?;
+
+    let params = MutateParams {
+        input_file: path.display().to_string(),
+        direction,
+        inject_personality: personality,
+        add_easter_eggs: false,
+        make_weird: false,
+        keep_functional: functional,
+        max_change: None,
+        allowed_classes: Vec::new(),
+        verify_syntax: false,
+    };
+
+    let mut mutation = None;
+    let completed = run_cancellable("Mutation", async {
+        mutation = Some(system.engine.cognitive_architecture.claude_client_mut().mutate_code(&current, &params).await?);
+        Ok(())
+    }).await?;
+    let Some(result) = mutation.filter(|_| completed) else {
+        return Ok(());
+    };
+
+    if let Some(error) = &result.syntax_error {
+        println!("{}", format!("⚠️  No mutation still parsed ({}); the file was left as it is", error).yellow());
+        return Ok(());
+    }
+
+    if result.mutated_content == current {
+        println!("{}", "The mutation came back unchanged; nothing to write.".bright_yellow());
+        return Ok(());
+    }
+
+    println!("\n{}", format!("🧪 {} mutations, {:.0}% of lines changed", result.mutations_applied.len(), result.change_ratio * 100.0).bright_magenta().bold());
+    for mutation in &result.mutations_applied {
+        println!("  • {} {} {}",
+            format!("[{}]", mutation.mutation_type).bright_cyan(),
+            mutation.location.bright_white(),
+            mutation.description.bright_black()
+        );
+    }
+
+    let keep = prompter.confirm(format!("Write this mutation to {}?", path.display()))
+        .default(true)
+        .interact()?;
+    if !keep {
+        return Ok(());
+    }
+
+    history.push(&current)?;
+    let mut batch = BackupManifest::new("mutate");
+    batch.back_up(path)?;
+    system.record_backup(&batch)?;
+    std::fs::write(path, &result.mutated_content).map_err(CHOPSError::FileSystemError)?;
+    println!("{}", format!("💾 Mutated {} (undo history: {})", path.display(), history.len()).bright_green());
     Ok(())
 }
 
-async fn interactive_collaborate(_system: &mut CHOPSSystem) -> CHOPSResult<()> {
-    println!("{}", "🤝 AI collaboration coming soon...".bright_yellow());
+fn print_mutation_history(history: &MutationHistory) {
+    if history.is_empty() {
+        println!("{}", "No earlier versions yet.".bright_yellow());
+        return;
+    }
+    println!("\n{}", "📜 Earlier versions, newest first".bright_blue().bold());
+    for (age, snapshot) in history.snapshots().iter().rev().enumerate() {
+        println!("  {} {} {}",
+            format!("{}.", age + 1).bright_cyan(),
+            snapshot.taken_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            format!("({} lines)", snapshot.content.lines().count()).bright_black()
+        );
+    }
+    if let Some(dir) = history.dir() {
+        println!("{}", format!("   Stored in {}", dir.display()).bright_black());
+    }
+}
+
+async fn interactive_prophecy(system: &mut CHOPSSystem, prompter: &Prompter) -> CHOPSResult<()> {
+    println!("\n{}", "🔮 PROPHECY CHAMBER".bright_magenta().bold());
+
+    let domain: String = prompter.input("Which domain's future?")
+        .default("software development".to_string())
+        .interact()?;
+    let year: String = prompter.input("Target year (blank for the default horizon)")
+        .allow_empty(true)
+        .interact()?;
+    let year = match year.trim() {
+        "" => None,
+        year => match year.parse::<u32>() {
+            Ok(year) => Some(year),
+            Err(_) => {
+                println!("{}", format!("❌ {} is not a year", year).red());
+                return Ok(());
+            },
+        },
+    };
+
+    run_cancellable("Prophecy", crate::commands::prophecy::execute(
+        system,
+        year,
+        Some(domain),
+        false, // trend analysis
+        false, // emerging tech
+        None, // what if
+        OutputFormat::Markdown,
+    )).await?;
+    Ok(())
+}
+
+async fn interactive_collaborate(system: &mut CHOPSSystem, prompter: &Prompter) -> CHOPSResult<()> {
+    println!("\n{}", "🤝 COLLABORATION TABLE".bright_blue().bold());
+
+    let topic: String = prompter.input("What should they work on?")
+        .interact()?;
+    let mode_idx = prompter.select("Collaboration mode")
+        .items(CollaborationMode::VARIANT_NAMES)
+        .default(0)
+        .interact()?;
+    let mode: CollaborationMode = CollaborationMode::VARIANT_NAMES[mode_idx].parse()?;
+
+    run_cancellable("Collaboration", crate::commands::collaborate::execute(
+        system,
+        mode,
+        topic,
+        Vec::new(), // the mode's own cast
+        2, // rounds
+        false, // human
+        OutputFormat::Markdown,
+    )).await?;
     Ok(())
 }
 
-async fn interactive_glitch(_system: &mut CHOPSSystem) -> CHOPSResult<()> {
-    println!("{}", "⚡ Chaos injection coming soon...".bright_yellow());
+async fn interactive_glitch(system: &mut CHOPSSystem, prompter: &Prompter) -> CHOPSResult<()> {
+    println!("\n{}", "⚡ GLITCH CHAMBER".bright_yellow().bold());
+
+    let file: String = prompter.input("File to glitch (previewed, not written)")
+        .interact()?;
+    let probability: f64 = prompter.input("Glitch probability per line")
+        .default(0.1)
+        .interact()?;
+
+    run_cancellable("Glitch", crate::commands::glitch::execute(
+        system,
+        Some(file.trim().to_string()),
+        probability,
+        false, // personality
+        "medium".to_string(),
+        Vec::new(), // sweep
+        None, // apply
+        None, // seed
+        OutputFormat::Markdown,
+    )).await?;
     Ok(())
 }
 
 async fn interactive_memory(system: &mut CHOPSSystem) -> CHOPSResult<()> {
     println!("\n{}", "🧠 CHOPS MEMORY SYSTEM".bright_blue().bold());
     
-    let recent_ideas = system.memory_system.recall_similar_ideas("", 10);
+    let recent_ideas = system.engine.memory_system.recall_exact("", 10);
     
     if recent_ideas.is_empty() {
         println!("{}", "📝 No ideas in memory yet. Start summoning some!".bright_yellow());
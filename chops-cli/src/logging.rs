@@ -0,0 +1,35 @@
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+/// Sets up logging to stderr, plus a daily-rotated `chops.log` under
+/// `CHOPS_LOG_DIR` when that is set. The file writer buffers on a background
+/// thread; keep the returned guard alive until exit so the buffer gets flushed.
+pub fn init() -> Option<WorkerGuard> {
+    // Logs go to stderr so stdout stays clean for machine-readable output
+    let stderr_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+
+    let (file_layer, guard) = match std::env::var_os("CHOPS_LOG_DIR") {
+        Some(dir) => {
+            let appender = tracing_appender::rolling::daily(dir, "chops.log");
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false);
+            (Some(layer), Some(guard))
+        },
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(stderr_layer)
+        .with(file_layer)
+        .init();
+
+    guard
+}
+
+/// `process::exit` skips destructors, so flush buffered logs before leaving
+pub fn exit(code: i32, guard: Option<WorkerGuard>) -> ! {
+    drop(guard);
+    std::process::exit(code)
+}
@@ -1,20 +1,22 @@
+mod cancel;
 mod cli;
 mod commands;
 mod output;
 mod interactive;
+mod prompt;
+mod logging;
 
-use chops_core::{CHOPSConfig, CHOPSResult, MemorySystem};
-use chops_api::{ClaudeClient, CognitiveArchitecture};
+use chops_core::{cmp_scores, BackupManifest, BackupStore, CHOPSConfig, CHOPSError, CHOPSResult, EntropySource, OutputFormat, StorageLocation};
+use chops_api::{ChopsEngine, ClaudeClient, ComplexIdeaResult, ConversationSession};
+use chops_chaos::ChaosVariationMask;
 use cli::Cli;
 use clap::Parser;
 use colored::*;
-use std::process;
-use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt().init();
+    // Initialize tracing; the guard flushes file logs when dropped
+    let log_guard = logging::init();
     
     tracing::info!("CHOPS CLI starting up");
 
@@ -23,6 +25,20 @@ async fn main() {
     let cli = Cli::parse();
     tracing::debug!("Command line arguments parsed successfully");
 
+    // Schema emit needs no config or API key
+    if matches!(&cli.command, cli::Commands::Summon(args) if args.json_schema) {
+        match ComplexIdeaResult::json_schema() {
+            Ok(schema) => {
+                println!("{}", schema);
+                return;
+            },
+            Err(e) => {
+                eprintln!("{}", format!("❌ Failed to generate schema: {}", e).red());
+                logging::exit(1, log_guard);
+            }
+        }
+    }
+
     // Load configuration
     tracing::debug!("Loading configuration");
     let mut config = match load_configuration().await {
@@ -30,16 +46,46 @@ async fn main() {
             tracing::info!("Configuration loaded successfully");
             config
         },
+        Err(CHOPSError::FileSystemError(e)) => {
+            // An unreadable config dir shouldn't stop CHOPS from running
+            tracing::warn!("Config file unreadable, using defaults: {}", e);
+            eprintln!("{}", format!("⚠️  Could not read configuration ({}), using defaults", e).yellow());
+            let mut config = CHOPSConfig::default();
+            config.merge_with_env();
+            config
+        },
         Err(e) => {
             tracing::error!("Failed to load configuration: {}", e);
             eprintln!("{}", format!("❌ Failed to load configuration: {}", e).red());
-            process::exit(1);
+            logging::exit(1, log_guard);
         }
     };
 
+    if cli.air_gapped {
+        config.network.air_gapped = true;
+    }
+    if cli.offline {
+        config.network.offline = true;
+    }
+
     // Initialize CHOPS system
     tracing::debug!("Initializing CHOPS system");
-    let mut chops_system = match initialize_chops_system(&mut config).await {
+    // Only the commands that generate text call the LLM; assessing, entropy
+    // tests, chaos profiles, presets and exports, undo, calibration and the
+    // persona, domain, config and memory commands work without an API key
+    let needs_llm = matches!(
+        cli.command,
+        cli::Commands::Summon(_)
+            | cli::Commands::Batch { .. }
+            | cli::Commands::Mutate { .. }
+            | cli::Commands::Prophecy { .. }
+            | cli::Commands::Collaborate { .. }
+            | cli::Commands::Paradox { .. }
+            | cli::Commands::CompareModels { .. }
+            | cli::Commands::Interactive { .. }
+            | cli::Commands::Chaos { action: cli::ChaosAction::Diff { .. } }
+    );
+    let mut chops_system = match initialize_chops_system(&mut config, needs_llm).await {
         Ok(system) => {
             tracing::info!("CHOPS system initialized successfully");
             system
@@ -47,19 +93,52 @@ async fn main() {
         Err(e) => {
             tracing::error!("Failed to initialize CHOPS: {}", e);
             eprintln!("{}", format!("❌ Failed to initialize CHOPS: {}", e).red());
-            process::exit(1);
+            logging::exit(1, log_guard);
         }
     };
 
-    // Welcome message
-    print_welcome_banner();
+    if cli.lorenz.is_some() || cli.henon.is_some() {
+        if let Err(e) = chops_system.use_attractor_parameters(cli.lorenz, cli.henon) {
+            eprintln!("{}", format!("❌ {}", e).red());
+            logging::exit(1, log_guard);
+        }
+    }
+    if let Some(mask) = cli.chaos_types.clone() {
+        chops_system.use_variation_mask(mask);
+    }
+
+    // Welcome message, unless stdout carries a JSON, YAML or plain document
+    let format = cli.command.output_format(&config.default_settings.default_format);
+    if format == OutputFormat::PlainText {
+        colored::control::set_override(false);
+    }
+    if format == OutputFormat::Markdown {
+        print_welcome_banner();
+    }
 
     // Execute command
     tracing::debug!("Executing command");
-    if let Err(e) = execute_command(cli, &mut chops_system).await {
+    // Interactive mode handles Ctrl-C itself to cancel single actions, and the
+    // entropy test stops its generator cleanly
+    let handles_interrupts = matches!(cli.command, cli::Commands::Interactive { .. } | cli::Commands::Entropy { .. });
+    let result = if handles_interrupts {
+        execute_command(cli, &mut chops_system).await
+    } else {
+        tokio::select! {
+            result = execute_command(cli, &mut chops_system) => result,
+            _ = tokio::signal::ctrl_c() => {
+                tracing::warn!("Interrupted by Ctrl-C");
+                eprintln!("\n{}", "🛑 Interrupted".yellow());
+                logging::exit(130, log_guard);
+            }
+        }
+    };
+
+    if let Err(e) = result {
         tracing::error!("Command execution failed: {}", e);
         eprintln!("{}", format!("❌ Command execution failed: {}", e).red());
-        process::exit(1);
+        chops_system.remember_command_failure(&e).await;
+        logging::exit(1, log_guard);
     }
     
     tracing::info!("CHOPS CLI execution completed successfully");
@@ -83,61 +162,93 @@ async fn load_configuration() -> CHOPSResult<CHOPSConfig> {
 }
 
 #[tracing::instrument(name = "initialize_chops_system", level = "info", skip(config))]
-async fn initialize_chops_system(config: &mut CHOPSConfig) -> CHOPSResult<CHOPSSystem> {
+async fn initialize_chops_system(config: &mut CHOPSConfig, needs_llm: bool) -> CHOPSResult<CHOPSSystem> {
     tracing::info!("Initializing CHOPS system components");
-    
-    // Get Claude API key
-    tracing::debug!("Retrieving Claude API key from configuration");
-    let api_key = config.get_claude_api_key()?.to_string();
-    
-    // Initialize Claude client
-    tracing::debug!("Initializing Claude client");
-    let claude_client = ClaudeClient::new(api_key)?;
-    
-    // Initialize cognitive architecture
-    tracing::debug!("Initializing cognitive architecture");
-    let cognitive_architecture = CognitiveArchitecture::new(claude_client);
-    
-    // Load memory system
-    let memory_path = std::path::PathBuf::from(".")
-        .join("chops")
-        .join("memory.json");
-    
-    tracing::debug!("Loading memory system from: {}", memory_path.display());
-    let memory_system = MemorySystem::load_from_file(&memory_path)
-        .unwrap_or_else(|e| {
-            tracing::warn!("Failed to load memory system, creating new one: {}", e);
-            MemorySystem::new()
-        });
-    
+
+    let data_dir = StorageLocation::resolve(CHOPSConfig::get_data_dir());
+    match &data_dir {
+        StorageLocation::Preferred(_) => {},
+        StorageLocation::Fallback { requested, fallback } => {
+            eprintln!("{}", format!(
+                "⚠️  Data directory {} is not writable, storing memory in {} instead (set CHOPS_DATA_DIR to override)",
+                requested.display(), fallback.display()
+            ).yellow());
+        },
+        StorageLocation::InMemory { requested } => {
+            eprintln!("{}", format!(
+                "⚠️  No writable data directory (tried {}), memory will not be saved this run (set CHOPS_DATA_DIR to override)",
+                requested.display()
+            ).yellow());
+        },
+    }
+
+    let mut builder = ChopsEngine::builder().config(config.clone());
+    if let Some(dir) = data_dir.path() {
+        builder = builder.data_dir(dir);
+    }
+    if !needs_llm {
+        builder = builder.backend(ClaudeClient::offline()?);
+    }
+    let engine = builder.build()?;
+    for warning in &engine.warnings {
+        eprintln!("{}", format!("⚠️  {}", warning).yellow());
+    }
+
     tracing::info!("CHOPS system initialized successfully with all components");
     
     Ok(CHOPSSystem {
-        cognitive_architecture,
-        memory_system,
-        config: config.clone(),
-        memory_path,
+        engine,
+        conversation: None,
     })
 }
 
 async fn execute_command(cli: Cli, system: &mut CHOPSSystem) -> CHOPSResult<()> {
     use cli::Commands;
-    
+
+    let format = cli.command.output_format(&system.engine.config.default_settings.default_format);
+    let renders_documents = matches!(
+        cli.command,
+        Commands::Summon(_) | Commands::Mutate { .. } | Commands::Prophecy { .. } | Commands::Paradox { .. }
+    );
+    if format == OutputFormat::Html && !renders_documents {
+        return Err(CHOPSError::InvalidParameter(
+            "--format html is only for summon, mutate, prophecy and paradox; use markdown, json, yaml or plain".to_string()
+        ));
+    }
+    // Interactive mode and the placeholder engines produce nothing to serialize
+    let conversational = matches!(
+        cli.command,
+        Commands::Interactive { .. } | Commands::TimeTravel { .. } | Commands::Possession { .. }
+    );
+    if format.is_machine_readable() && conversational {
+        return Err(CHOPSError::InvalidParameter(format!("This command has no {} output; use markdown or plain", format)));
+    }
+
     match cli.command {
-        Commands::Summon { persona, domain, chaos, timeline, vibe, constraints, reality_level } => {
-            commands::summon::execute(
-                system,
-                persona.unwrap_or_default(),
-                domain,
-                chaos.unwrap_or(5),
-                timeline,
-                vibe,
-                constraints,
-                reality_level.unwrap_or(0.7),
-            ).await
+        Commands::Summon(args) => {
+            if let Some(source) = args.entropy.clone() {
+                system.use_entropy_source(source).await?;
+            }
+            let mut options = commands::summon::SummonOptions::from(&*args);
+            if !args.blend.is_empty() {
+                options.persona = Some(system.engine.cognitive_architecture.persona_engine_mut().register_blend(&args.blend)?);
+            }
+            if let Some(file) = args.batch.clone() {
+                return commands::batch::execute(system, file, args.output.clone(), args.resume, args.concurrency as usize, &options, format).await;
+            }
+            commands::summon::execute(system, &options, format).await
         },
         
-        Commands::Mutate { file, direction, personality, easter_eggs, weird, functional } => {
+        Commands::Batch { file, out, persona, chaos, resume, .. } => {
+            let options = commands::summon::SummonOptions {
+                persona: Some(persona.unwrap_or_default()),
+                chaos,
+                ..Default::default()
+            };
+            commands::batch::execute(system, file, out, resume, 1, &options, format).await
+        },
+        
+        Commands::Mutate { file, direction, personality, easter_eggs, weird, functional, apply, max_change, comments_only, verify, .. } => {
             commands::mutate::execute(
                 system,
                 file,
@@ -146,10 +257,15 @@ async fn execute_command(cli: Cli, system: &mut CHOPSSystem) -> CHOPSResult<()>
                 easter_eggs,
                 weird,
                 functional,
+                apply,
+                max_change,
+                comments_only,
+                verify,
+                format,
             ).await
         },
         
-        Commands::Prophecy { year, domain, trend_analysis, emerging_tech, what_if } => {
+        Commands::Prophecy { year, domain, trend_analysis, emerging_tech, what_if, .. } => {
             commands::prophecy::execute(
                 system,
                 year,
@@ -157,28 +273,40 @@ async fn execute_command(cli: Cli, system: &mut CHOPSSystem) -> CHOPSResult<()>
                 trend_analysis,
                 emerging_tech,
                 what_if,
+                format,
             ).await
         },
         
-        Commands::Collaborate { mode, topic, human } => {
+        Commands::Collaborate { mode, topic, participants, rounds, human, .. } => {
             commands::collaborate::execute(
                 system,
                 mode,
                 topic,
+                participants,
+                rounds,
                 human,
+                format,
             ).await
         },
         
-        Commands::Glitch { probability, personality, density } => {
+        Commands::Glitch { file, probability, personality, density, entropy, sweep, apply, seed, .. } => {
+            if let Some(source) = entropy {
+                system.use_entropy_source(source).await?;
+            }
             commands::glitch::execute(
                 system,
+                file,
                 probability.unwrap_or(0.1),
                 personality,
                 density.unwrap_or_else(|| "medium".to_string()),
+                sweep,
+                apply,
+                seed,
+                format,
             ).await
         },
         
-        Commands::TimeTravel { era, twist } => {
+        Commands::TimeTravel { era, twist, .. } => {
             commands::time_travel::execute(
                 system,
                 era,
@@ -186,7 +314,7 @@ async fn execute_command(cli: Cli, system: &mut CHOPSSystem) -> CHOPSResult<()>
             ).await
         },
         
-        Commands::Possession { ghost, target } => {
+        Commands::Possession { ghost, target, .. } => {
             commands::possession::execute(
                 system,
                 ghost,
@@ -194,31 +322,80 @@ async fn execute_command(cli: Cli, system: &mut CHOPSSystem) -> CHOPSResult<()>
             ).await
         },
         
-        Commands::Paradox { constraints } => {
+        Commands::Paradox { constraints, .. } => {
             commands::paradox::execute(
                 system,
                 constraints,
+                format,
             ).await
         },
         
-        Commands::Interactive => {
-            interactive::run_interactive_mode(system).await
+        Commands::Assess { idea, chaos, .. } => {
+            commands::assess::execute(system, idea, chaos, format).await
+        },
+        
+        Commands::Persona { action } => {
+            commands::persona::execute(system, action, format).await
+        },
+        
+        Commands::Chaos { action } => {
+            commands::chaos::execute(system, action, format).await
+        },
+        
+        Commands::Domains { action } => {
+            commands::domains::execute(system, action, format).await
         },
         
-        Commands::Config { show, set } => {
+        Commands::Entropy { action } => {
+            commands::entropy::execute(system, action, format).await
+        },
+        
+        Commands::Undo { .. } => {
+            commands::undo::execute(system, format).await
+        },
+        
+        Commands::Calibrate { labeled, .. } => {
+            commands::calibrate::execute(
+                system,
+                labeled,
+                format,
+            ).await
+        },
+        
+        Commands::CompareModels { prompt, models, persona, domain, chaos, seed, .. } => {
+            commands::compare::execute(
+                system,
+                prompt,
+                models,
+                persona,
+                domain,
+                chaos,
+                seed,
+                format,
+            ).await
+        },
+        
+        Commands::Interactive { resume, idle_timeout, .. } => {
+            interactive::run_interactive_mode(system, resume, idle_timeout).await
+        },
+        
+        Commands::Config { show, set, .. } => {
             commands::config::execute(
                 system,
                 show,
                 set,
+                format,
             ).await
         },
         
-        Commands::Memory { show, clear, export } => {
+        Commands::Memory { action, show, clear, export, .. } => {
             commands::memory::execute(
                 system,
+                action,
                 show,
                 clear,
                 export,
+                format,
             ).await
         },
     }
@@ -249,14 +426,151 @@ fn print_welcome_banner() {
 }
 
 pub struct CHOPSSystem {
-    cognitive_architecture: CognitiveArchitecture,
-    memory_system: MemorySystem,
-    config: CHOPSConfig,
-    memory_path: std::path::PathBuf,
+    engine: ChopsEngine,
+    conversation: Option<ConversationSession>,
 }
 
 impl CHOPSSystem {
+    /// Switches the chaos engine's entropy source for this run
+    pub async fn use_entropy_source(&mut self, source: EntropySource) -> CHOPSResult<()> {
+        let active = self.engine.cognitive_architecture
+            .chaos_engine_mut()
+            .entropy_generator
+            .set_source_checked(source.clone())
+            .await?;
+
+        if active != source {
+            eprintln!("{}", format!("⚠️  {} entropy unavailable, falling back to {}", source, active).yellow());
+        } else {
+            tracing::info!("Using {} entropy source", active);
+        }
+        Ok(())
+    }
+
+    /// Sets the chaos engine's Lorenz and Hénon constants for this run
+    pub fn use_attractor_parameters(&mut self, lorenz: Option<(f64, f64, f64)>, henon: Option<(f64, f64)>) -> CHOPSResult<()> {
+        let warnings = self.engine.cognitive_architecture
+            .chaos_engine_mut()
+            .set_attractor_parameters(lorenz, henon)?;
+        for warning in warnings {
+            eprintln!("{}", format!("⚠️  {}", warning).yellow());
+        }
+        Ok(())
+    }
+
+    /// Restricts the chaos engine to the given variation types for this run
+    pub fn use_variation_mask(&mut self, mask: ChaosVariationMask) {
+        tracing::info!("Limiting chaos variations to: {}", mask);
+        self.engine.cognitive_architecture
+            .chaos_engine_mut()
+            .set_variation_mask(mask);
+    }
+
+    /// Reports quantum entropy fallbacks, if the quantum source was used at all
+    pub fn print_entropy_stats(&self) {
+        let stats = self.engine.cognitive_architecture.chaos_engine().entropy_generator.stats();
+        if stats.quantum_requests == 0 && stats.fallbacks == 0 {
+            return;
+        }
+
+        println!("\n{} {} quantum requests, {} values from the prefetch pool, {} failed, {} fallbacks to system entropy",
+            "⚛️ Entropy:".bright_black(),
+            stats.quantum_requests,
+            stats.pooled_values,
+            stats.quantum_failures,
+            if stats.fallbacks > 0 { stats.fallbacks.to_string().yellow() } else { "0".green() }
+        );
+    }
+
+    /// Reports this session's token usage and estimated cost per persona, reused
+    /// analyses, and how much of the prompt input was served from the prompt cache
+    pub fn print_usage_stats(&self) {
+        let client = self.engine.cognitive_architecture.claude_client();
+        let pricing = client.pricing();
+
+        let mut by_persona: Vec<_> = client.persona_usage().iter().collect();
+        by_persona.sort_by(|(persona_a, a), (persona_b, b)| {
+            cmp_scores(pricing.stats_cost(a), pricing.stats_cost(b))
+                .then_with(|| persona_a.to_string().cmp(&persona_b.to_string()))
+        });
+        for (persona, stats) in by_persona {
+            println!("{} {} used {} tokens over {} request(s), ~${:.4}",
+                "💰 Usage:".bright_black(),
+                persona.to_string().bright_green(),
+                stats.total_tokens(),
+                stats.requests,
+                pricing.stats_cost(stats)
+            );
+        }
+
+        let analysis = self.engine.cognitive_architecture.analysis_cache_stats();
+        if analysis.hits > 0 {
+            println!("{} {} of {} analyses reused, saving {:.2?}",
+                "🗃️ Analysis cache:".bright_black(),
+                analysis.hits.to_string().green(),
+                analysis.hits + analysis.misses,
+                analysis.time_saved
+            );
+        }
+
+        let stats = client.usage_stats();
+        if stats.cache_creation_input_tokens == 0 && stats.cache_read_input_tokens == 0 {
+            return;
+        }
+
+        println!("{} {} tokens read from prompt cache, {} written ({:.0}% of prompt input cached)",
+            "💾 Cache:".bright_black(),
+            stats.cache_read_input_tokens.to_string().green(),
+            stats.cache_creation_input_tokens,
+            stats.cache_hit_rate() * 100.0
+        );
+    }
+
+    /// Backup store in the data dir, if there is one to write to
+    pub fn backup_store(&self) -> Option<BackupStore> {
+        self.engine.data_dir.as_deref().map(BackupStore::new)
+    }
+
+    /// Persists the originals of a batch of written files so `chops undo` can restore them
+    pub fn record_backup(&self, manifest: &BackupManifest) -> CHOPSResult<()> {
+        if !self.engine.config.output_preferences.auto_backup {
+            tracing::debug!("auto_backup disabled, not recording {} backup", manifest.command);
+            eprintln!("{}", "⚠️  auto_backup is disabled, this change can't be undone with `chops undo`".yellow());
+            return Ok(());
+        }
+
+        match self.backup_store() {
+            Some(store) => store.record(manifest),
+            None => {
+                eprintln!("{}", "⚠️  No writable data directory, this change can't be undone with `chops undo`".yellow());
+                Ok(())
+            }
+        }
+    }
+
+    /// Records a failed command in episodic memory. Environment problems
+    /// (network, auth, config, disk) say nothing about the idea itself and are skipped.
+    pub async fn remember_command_failure(&mut self, error: &CHOPSError) {
+        if matches!(error,
+            CHOPSError::NetworkError(_) | CHOPSError::AuthenticationError(_) | CHOPSError::RateLimitError(_)
+            | CHOPSError::ConfigError(_) | CHOPSError::FileSystemError(_)
+        ) {
+            return;
+        }
+
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let command = args.first().cloned().unwrap_or_default();
+        self.engine.memory_system.record_failure(chops_core::FailureLearning::new(
+            &args.join(" "),
+            &format!("`chops {}`", command),
+            &error.to_string(),
+        ));
+        if let Err(e) = self.save_memory().await {
+            tracing::warn!("Could not save failure to memory: {}", e);
+        }
+    }
+
     pub async fn save_memory(&self) -> CHOPSResult<()> {
-        self.memory_system.save_to_file(&self.memory_path)
+        self.engine.save_memory()
     }
 }
\ No newline at end of file
@@ -1,15 +1,64 @@
-use chops_api::ComplexIdeaResult;
-use chops_core::CHOPSResult;
+use chops_api::{ComplexIdeaResult, ConstraintStatus, IdeaSection, UNTITLED_SECTION};
+use chops_core::{diff_lines, to_yaml, CHOPSError, CHOPSResult, DiffLine, OutputFormat, Renderable};
 use colored::*;
+use serde::Serialize;
+
+/// Prints `item` on stdout as a `format` document and nothing else, so it can
+/// be piped. Markdown is the exception: commands display that in color.
+pub fn print_document(item: &dyn Renderable, format: &OutputFormat) -> CHOPSResult<()> {
+    let document = item.render(format)?;
+    println!("{}", document.trim_end());
+    Ok(())
+}
+
+/// Prints a result that has no document rendering of its own on stdout as
+/// JSON or YAML; commands show it in color (or plain, uncolored) otherwise
+pub fn print_data<T: Serialize + ?Sized>(value: &T, format: &OutputFormat) -> CHOPSResult<()> {
+    let document = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value).map_err(CHOPSError::SerializationError)?,
+        OutputFormat::Yaml => to_yaml(value)?,
+        other => return Err(CHOPSError::InvalidParameter(format!("{} output isn't available for this command", other))),
+    };
+    println!("{}", document.trim_end());
+    Ok(())
+}
+
+/// A progress or status line: on stdout alongside colored output, on stderr
+/// when stdout carries a document
+pub fn status(format: &OutputFormat, line: impl std::fmt::Display) {
+    if *format == OutputFormat::Markdown {
+        println!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
 
 pub fn display_complex_idea_result(result: &ComplexIdeaResult) -> CHOPSResult<()> {
     println!("\n{}", "═══════════════════════════════════════════════════════".bright_cyan());
     println!("{}", "🔮 IDEA SUMMONED SUCCESSFULLY 🔮".bright_cyan().bold());
     println!("{}", "═══════════════════════════════════════════════════════".bright_cyan());
 
+    if let Some(plan) = &result.base_idea.plan {
+        println!("\n{}", "🧭 Plan:".bright_blue().bold());
+        for line in plan.lines().filter(|line| !line.trim().is_empty()) {
+            println!("  {}", line.bright_black());
+        }
+    }
+
     // Main idea content
-    println!("\n{}", "💡 Generated Idea:".bright_yellow().bold());
-    println!("{}", format_content_with_boxes(&result.base_idea.content));
+    match &result.base_idea.structured_output {
+        Some(value) => {
+            println!("\n{}", "🧾 Structured Idea:".bright_yellow().bold());
+            println!("{}", serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()));
+        },
+        None => {
+            println!("\n{}", "💡 Generated Idea:".bright_yellow().bold());
+            display_sections(&result.base_idea.sections, &result.base_idea.content);
+        },
+    }
+    if result.base_idea.truncated {
+        println!("{}", "⚠️  The response stream was cut off; this idea is incomplete".yellow());
+    }
 
     // Quality metrics
     println!("\n{}", "📊 Quality Metrics:".bright_blue().bold());
@@ -18,11 +67,40 @@ pub fn display_complex_idea_result(result: &ComplexIdeaResult) -> CHOPSResult<()
     display_metrics_bar("Novelty", result.base_idea.novelty_score);
     display_metrics_bar("Excitement", result.base_idea.excitement_factor);
     display_metrics_bar("Coherence", result.base_idea.coherence_score);
+    if let Some(fidelity) = result.base_idea.voice_fidelity {
+        display_metrics_bar("Voice", fidelity);
+    }
     
     let overall = result.base_idea.calculate_overall_score();
     let tier = result.base_idea.get_quality_tier();
     println!("  {} {}", "Overall:".bright_white(), format!("{} ({})", format_score(overall), tier).bright_green());
 
+    // Constraint checklist
+    if let Some(report) = &result.feasibility_report {
+        println!("\n{}", format!(
+            "📋 Constraints ({}/{} satisfied):",
+            report.count(ConstraintStatus::Satisfied),
+            report.checks.len()
+        ).bright_blue().bold());
+        for check in &report.checks {
+            let mark = match check.status {
+                ConstraintStatus::Satisfied => "✅",
+                ConstraintStatus::Violated => "❌",
+                ConstraintStatus::Unclear => "❔",
+            };
+            let constraint = match check.status {
+                ConstraintStatus::Satisfied => check.constraint.bright_green(),
+                ConstraintStatus::Violated => check.constraint.bright_red(),
+                ConstraintStatus::Unclear => check.constraint.bright_yellow(),
+            };
+            if check.note.is_empty() {
+                println!("  {} {}", mark, constraint);
+            } else {
+                println!("  {} {} {}", mark, constraint, format!("({})", check.note).bright_black());
+            }
+        }
+    }
+
     // Chaos effects
     if result.base_idea.chaos_level > 0.1 {
         println!("\n{}", "⚡ Chaos Effects:".bright_magenta().bold());
@@ -63,6 +141,29 @@ pub fn display_complex_idea_result(result: &ComplexIdeaResult) -> CHOPSResult<()
     Ok(())
 }
 
+/// Each section under its own heading, cycling heading colors so they stand
+/// apart; ideas without headings print as before
+fn display_sections(sections: &[IdeaSection], content: &str) {
+    let untitled = match sections {
+        [] => true,
+        [only] => only.heading == UNTITLED_SECTION,
+        _ => false,
+    };
+    if untitled {
+        println!("{}", format_content_with_boxes(content));
+        return;
+    }
+
+    let colors = [Color::BrightYellow, Color::BrightCyan, Color::BrightMagenta, Color::BrightGreen, Color::BrightBlue];
+    for (section, color) in sections.iter().zip(colors.iter().cycle()) {
+        println!("\n  {}", format!("▌ {}", section.heading).color(*color).bold());
+        if !section.body.is_empty() {
+            print!("{}", format_content_with_boxes(&section.body));
+        }
+    }
+    println!();
+}
+
 fn format_content_with_boxes(content: &str) -> String {
     let lines: Vec<&str> = content.lines().collect();
     let mut result = String::new();
@@ -78,7 +179,7 @@ fn format_content_with_boxes(content: &str) -> String {
     result
 }
 
-fn display_metrics_bar(label: &str, value: f64) {
+pub fn display_metrics_bar(label: &str, value: f64) {
     let bar_length = 20;
     let filled = (value * bar_length as f64) as usize;
     let empty = bar_length - filled;
@@ -97,4 +198,44 @@ fn display_metrics_bar(label: &str, value: f64) {
 
 fn format_score(value: f64) -> String {
     format!("{:.1}%", value * 100.0)
-}
\ No newline at end of file
+}
+/// Prints a colored line diff, showing `context` unchanged lines around each change
+pub fn display_line_diff(original: &str, modified: &str, context: usize) {
+    let diff = diff_lines(original, modified);
+
+    let changed: Vec<usize> = diff.iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    if changed.is_empty() {
+        println!("  {}", "(no changes)".bright_black());
+        return;
+    }
+
+    let visible = |i: usize| changed.iter().any(|&c| i + context >= c && i <= c + context);
+
+    let mut old_line = 0;
+    let mut skipped = false;
+    for (i, line) in diff.iter().enumerate() {
+        if !matches!(line, DiffLine::Added(_)) {
+            old_line += 1;
+        }
+
+        if !visible(i) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            println!("  {}", "  ...".bright_black());
+            skipped = false;
+        }
+
+        match line {
+            DiffLine::Same(text) => println!("  {:>5}   {}", old_line.to_string().bright_black(), text),
+            DiffLine::Removed(text) => println!("  {:>5} {} {}", old_line.to_string().bright_black(), "-".red(), text.red()),
+            DiffLine::Added(text) => println!("  {:>5} {} {}", "", "+".green(), text.green()),
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use chops_core::{CHOPSError, CHOPSResult};
+use colored::*;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, queue, terminal};
+use dialoguer::theme::{SimpleTheme, Theme};
+use std::cell::Cell;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long before an idle timeout the user is warned
+const IDLE_WARNING: Duration = Duration::from_secs(60);
+
+/// Asks interactive mode's questions. With an idle timeout, a prompt that goes
+/// that long without a key press gives up (after a warning) with an error and
+/// marks the prompter timed out, so the session can save and close. Keys are
+/// read with crossterm, whose raw mode is switched off however a prompt ends,
+/// and prompts are drawn with dialoguer's default theme.
+pub struct Prompter {
+    idle_timeout: Option<Duration>,
+    timed_out: Cell<bool>,
+}
+
+impl Prompter {
+    pub fn new(idle_timeout: Option<Duration>) -> Self {
+        Self { idle_timeout, timed_out: Cell::new(false) }
+    }
+
+    /// Whether a prompt gave up because nobody answered it
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.get()
+    }
+
+    pub fn select(&self, prompt: impl Into<String>) -> SelectPrompt<'_> {
+        SelectPrompt { prompter: self, prompt: prompt.into(), items: Vec::new(), default: 0 }
+    }
+
+    pub fn confirm(&self, prompt: impl Into<String>) -> ConfirmPrompt<'_> {
+        ConfirmPrompt { prompter: self, prompt: prompt.into(), default: false }
+    }
+
+    pub fn input<T>(&self, prompt: impl Into<String>) -> InputPrompt<'_, T> {
+        InputPrompt { prompter: self, prompt: prompt.into(), default: None, allow_empty: false }
+    }
+
+    /// Draws the prompt `render` describes and feeds it keys until `handle`
+    /// returns an answer, then replaces the drawing with `summary` of it. The
+    /// idle clock restarts with every key.
+    fn interact<S, T>(
+        &self,
+        state: &mut S,
+        render: impl Fn(&S) -> Vec<String>,
+        handle: impl Fn(&mut S, KeyEvent) -> Option<T>,
+        summary: impl Fn(&T) -> String,
+    ) -> CHOPSResult<T> {
+        let _raw = RawMode::enable().map_err(terminal_error)?;
+        let mut out = io::stderr();
+        let mut frame = Frame::default();
+        let mut warned = false;
+        let mut last_key = Instant::now();
+
+        loop {
+            let mut lines = Vec::new();
+            if warned {
+                lines.push(self.warning().bright_yellow().to_string());
+            }
+            lines.extend(render(state));
+            frame.draw(&mut out, &lines).map_err(terminal_error)?;
+
+            let key = match self.wait_for_key(last_key, warned).map_err(terminal_error)? {
+                Wait::Key(key) => key,
+                Wait::Warn => {
+                    warned = true;
+                    continue;
+                },
+                Wait::Idle => {
+                    frame.clear(&mut out).map_err(terminal_error)?;
+                    self.timed_out.set(true);
+                    return Err(CHOPSError::Cancelled("No answer before the idle timeout".to_string()));
+                },
+            };
+            last_key = Instant::now();
+            warned = false;
+
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                frame.clear(&mut out).map_err(terminal_error)?;
+                return Err(CHOPSError::Cancelled("Interrupted at a prompt".to_string()));
+            }
+            if let Some(answer) = handle(state, key) {
+                frame.clear(&mut out).map_err(terminal_error)?;
+                write!(out, "{}\r\n", summary(&answer)).and_then(|_| out.flush()).map_err(terminal_error)?;
+                return Ok(answer);
+            }
+        }
+    }
+
+    fn wait_for_key(&self, since: Instant, warned: bool) -> io::Result<Wait> {
+        loop {
+            if let Some(timeout) = self.idle_timeout {
+                let due = if warned { timeout } else { timeout - warning_lead(timeout) };
+                if !event::poll(due.saturating_sub(since.elapsed()))? {
+                    return Ok(if warned { Wait::Idle } else { Wait::Warn });
+                }
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    return Ok(Wait::Key(key));
+                }
+            }
+        }
+    }
+
+    fn warning(&self) -> String {
+        let lead = self.idle_timeout.map(warning_lead).unwrap_or_default();
+        format!("⏳ Still there? Interactive mode saves memory and exits in {} seconds without an answer.", lead.as_secs())
+    }
+}
+
+/// A minute before the timeout, or halfway through very short ones
+fn warning_lead(timeout: Duration) -> Duration {
+    IDLE_WARNING.min(timeout / 2)
+}
+
+fn terminal_error(e: io::Error) -> CHOPSError {
+    CHOPSError::UnexpectedError(e.to_string())
+}
+
+fn themed(format: impl FnOnce(&mut String) -> std::fmt::Result) -> String {
+    let mut text = String::new();
+    let _ = format(&mut text);
+    text
+}
+
+enum Wait {
+    Key(KeyEvent),
+    Warn,
+    Idle,
+}
+
+/// Crossterm's raw mode for one prompt, switched off when the prompt ends
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Rows a prompt last drew, so each key press redraws it in place
+#[derive(Default)]
+struct Frame {
+    rows: usize,
+}
+
+impl Frame {
+    fn draw(&mut self, out: &mut impl Write, lines: &[String]) -> io::Result<()> {
+        self.clear(out)?;
+        let columns = terminal::size().map(|(columns, _)| columns.max(1) as usize).unwrap_or(80);
+        write!(out, "{}", lines.join("\r\n"))?;
+        self.rows = lines
+            .iter()
+            .map(|line| console::measure_text_width(line).saturating_sub(1) / columns + 1)
+            .sum();
+        out.flush()
+    }
+
+    fn clear(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.rows > 0 {
+            queue!(out, cursor::MoveToColumn(0))?;
+            if self.rows > 1 {
+                queue!(out, cursor::MoveUp((self.rows - 1) as u16))?;
+            }
+            queue!(out, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        }
+        self.rows = 0;
+        out.flush()
+    }
+}
+
+pub struct SelectPrompt<'a> {
+    prompter: &'a Prompter,
+    prompt: String,
+    items: Vec<String>,
+    default: usize,
+}
+
+impl SelectPrompt<'_> {
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        self.items = items.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    pub fn default(mut self, index: usize) -> Self {
+        self.default = index;
+        self
+    }
+
+    /// The index of the chosen item
+    pub fn interact(self) -> CHOPSResult<usize> {
+        if self.items.is_empty() {
+            return Err(CHOPSError::InvalidParameter(format!("Nothing to choose from for '{}'", self.prompt)));
+        }
+        let count = self.items.len();
+        let mut active = self.default.min(count - 1);
+        self.prompter.interact(
+            &mut active,
+            |active| {
+                let mut lines = vec![themed(|f| SimpleTheme.format_select_prompt(f, &self.prompt))];
+                lines.extend(self.items.iter().enumerate().map(|(index, item)| {
+                    themed(|f| SimpleTheme.format_select_prompt_item(f, item, index == *active))
+                }));
+                lines
+            },
+            |active, key| {
+                match key.code {
+                    KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => *active = (*active + count - 1) % count,
+                    KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => *active = (*active + 1) % count,
+                    KeyCode::Home => *active = 0,
+                    KeyCode::End => *active = count - 1,
+                    KeyCode::Enter | KeyCode::Char(' ') => return Some(*active),
+                    _ => {},
+                }
+                None
+            },
+            |chosen| themed(|f| SimpleTheme.format_select_prompt_selection(f, &self.prompt, &self.items[*chosen])),
+        )
+    }
+}
+
+pub struct ConfirmPrompt<'a> {
+    prompter: &'a Prompter,
+    prompt: String,
+    default: bool,
+}
+
+impl ConfirmPrompt<'_> {
+    pub fn default(mut self, default: bool) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn interact(self) -> CHOPSResult<bool> {
+        self.prompter.interact(
+            &mut (),
+            |_| vec![themed(|f| SimpleTheme.format_confirm_prompt(f, &self.prompt, Some(self.default)))],
+            |_, key| match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(true),
+                KeyCode::Char('n') | KeyCode::Char('N') => Some(false),
+                KeyCode::Enter => Some(self.default),
+                _ => None,
+            },
+            |answer| themed(|f| SimpleTheme.format_confirm_prompt_selection(f, &self.prompt, Some(*answer))),
+        )
+    }
+}
+
+pub struct InputPrompt<'a, T> {
+    prompter: &'a Prompter,
+    prompt: String,
+    default: Option<T>,
+    allow_empty: bool,
+}
+
+/// What has been typed into an input prompt, and why the last attempt was refused
+#[derive(Default)]
+struct Typed {
+    text: String,
+    error: Option<String>,
+}
+
+impl<T> InputPrompt<'_, T>
+where
+    T: Clone + Display + FromStr,
+    T::Err: Display,
+{
+    pub fn default(mut self, value: T) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    pub fn allow_empty(mut self, allow_empty: bool) -> Self {
+        self.allow_empty = allow_empty;
+        self
+    }
+
+    /// The typed text parsed as `T`; empty input takes the default. Text that
+    /// doesn't parse is refused with the reason, and the prompt stays open.
+    pub fn interact(self) -> CHOPSResult<T> {
+        let shown_default = self.default.as_ref().map(ToString::to_string);
+        self.prompter.interact(
+            &mut Typed::default(),
+            |typed| {
+                let mut lines: Vec<String> = typed.error.iter().map(|error| themed(|f| SimpleTheme.format_error(f, error))).collect();
+                lines.push(themed(|f| SimpleTheme.format_input_prompt(f, &self.prompt, shown_default.as_deref())) + &typed.text);
+                lines
+            },
+            |typed, key| {
+                match key.code {
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => typed.text.push(c),
+                    KeyCode::Backspace => {
+                        typed.text.pop();
+                    },
+                    KeyCode::Enter if typed.text.is_empty() && self.default.is_some() => return self.default.clone(),
+                    KeyCode::Enter if typed.text.is_empty() && !self.allow_empty => {},
+                    KeyCode::Enter => match typed.text.parse::<T>() {
+                        Ok(value) => return Some(value),
+                        Err(e) => typed.error = Some(e.to_string()),
+                    },
+                    _ => {},
+                }
+                None
+            },
+            |value| themed(|f| SimpleTheme.format_input_prompt_selection(f, &self.prompt, &value.to_string())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_comes_a_minute_early_or_halfway_through_short_timeouts() {
+        assert_eq!(warning_lead(Duration::from_secs(30 * 60)), IDLE_WARNING);
+        assert_eq!(warning_lead(Duration::from_secs(60)), Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,102 @@
+use serde::Serialize;
+use crate::{cmp_scores, PersonaType};
+
+/// One generated idea's chaos level and how it turned out
+#[derive(Debug, Clone)]
+pub struct ChaosSample {
+    pub persona: PersonaType,
+    /// Normalized chaos level (0.0-1.0)
+    pub chaos_level: f64,
+    pub creativity_score: f64,
+    pub feasibility_score: f64,
+    pub coherence_score: Option<f64>,
+    pub kept: Option<bool>,
+}
+
+/// Averages over every sample generated at one CLI chaos level (1-11)
+#[derive(Debug, Clone, Serialize)]
+pub struct ChaosBucket {
+    pub level: u8,
+    pub samples: usize,
+    pub avg_creativity: f64,
+    pub avg_feasibility: f64,
+    /// `None` when no sample in the bucket recorded coherence
+    pub avg_coherence: Option<f64>,
+    /// Share of rated ideas the user kept; `None` when none were rated
+    pub satisfaction: Option<f64>,
+}
+
+impl ChaosBucket {
+    /// Creativity and feasibility weighted equally, nudged by satisfaction when known
+    pub fn effectiveness(&self) -> f64 {
+        let base = (self.avg_creativity + self.avg_feasibility) / 2.0;
+        match self.satisfaction {
+            Some(satisfaction) => base * 0.7 + satisfaction * 0.3,
+            None => base,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChaosAnalytics {
+    pub buckets: Vec<ChaosBucket>,
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+impl ChaosAnalytics {
+    /// Buckets samples by the CLI chaos level they were generated at
+    pub fn from_samples(samples: &[ChaosSample]) -> Self {
+        let buckets = (1..=11u8)
+            .filter_map(|level| {
+                let in_bucket: Vec<&ChaosSample> = samples
+                    .iter()
+                    .filter(|sample| (sample.chaos_level * 11.0).round().clamp(1.0, 11.0) as u8 == level)
+                    .collect();
+                if in_bucket.is_empty() {
+                    return None;
+                }
+
+                Some(ChaosBucket {
+                    level,
+                    samples: in_bucket.len(),
+                    avg_creativity: mean(in_bucket.iter().map(|sample| sample.creativity_score)).unwrap_or(0.0),
+                    avg_feasibility: mean(in_bucket.iter().map(|sample| sample.feasibility_score)).unwrap_or(0.0),
+                    avg_coherence: mean(in_bucket.iter().filter_map(|sample| sample.coherence_score)),
+                    satisfaction: mean(in_bucket.iter().filter_map(|sample| sample.kept).map(|kept| if kept { 1.0 } else { 0.0 })),
+                })
+            })
+            .collect();
+
+        Self { buckets }
+    }
+
+    /// Chaos level whose ideas scored best, among levels with at least `min_samples` ideas
+    pub fn best_level(&self, min_samples: usize) -> Option<u8> {
+        self.buckets
+            .iter()
+            .filter(|bucket| bucket.samples >= min_samples)
+            .min_by(|a, b| cmp_scores(a.effectiveness(), b.effectiveness()).then_with(|| a.level.cmp(&b.level)))
+            .map(|bucket| bucket.level)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let optional = |value: Option<f64>| value.map(|value| format!("{:.3}", value)).unwrap_or_default();
+        let mut csv = String::from("chaos_level,samples,avg_creativity,avg_feasibility,avg_coherence,satisfaction\n");
+        for bucket in &self.buckets {
+            csv.push_str(&format!(
+                "{},{},{:.3},{:.3},{},{}\n",
+                bucket.level,
+                bucket.samples,
+                bucket.avg_creativity,
+                bucket.avg_feasibility,
+                optional(bucket.avg_coherence),
+                optional(bucket.satisfaction)
+            ));
+        }
+        csv
+    }
+}
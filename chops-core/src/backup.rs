@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::{CHOPSError, CHOPSResult};
+
+/// A batch of files written by one command, with what they contained before
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub id: Uuid,
+    pub command: String,
+    pub created_at: DateTime<Utc>,
+    pub entries: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    /// `None` when the file didn't exist before, so undo removes it
+    pub original_content: Option<String>,
+}
+
+impl BackupManifest {
+    pub fn new(command: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            command: command.to_string(),
+            created_at: Utc::now(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Remembers what `path` contains now, before it is written. Record the
+    /// manifest before writing so a crash in between can still be undone.
+    #[tracing::instrument(name = "backup_back_up", level = "debug", skip(self))]
+    pub fn back_up(&mut self, path: &Path) -> CHOPSResult<()> {
+        let path = std::path::absolute(path).map_err(CHOPSError::FileSystemError)?;
+        if self.entries.iter().any(|entry| entry.path == path) {
+            return Ok(());
+        }
+
+        let original_content = if path.exists() {
+            Some(std::fs::read_to_string(&path).map_err(CHOPSError::FileSystemError)?)
+        } else {
+            None
+        };
+        tracing::debug!("Backed up {}", path.display());
+        self.entries.push(BackupEntry { path, original_content });
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "backup_restore", level = "info", skip(self))]
+    pub fn restore(&self) -> CHOPSResult<()> {
+        for entry in &self.entries {
+            match &entry.original_content {
+                Some(content) => {
+                    tracing::debug!("Restoring {}", entry.path.display());
+                    std::fs::write(&entry.path, content).map_err(CHOPSError::FileSystemError)?;
+                },
+                None => {
+                    tracing::debug!("Removing {} created by {}", entry.path.display(), self.command);
+                    if entry.path.exists() {
+                        std::fs::remove_file(&entry.path).map_err(CHOPSError::FileSystemError)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Backup manifests kept under `<data dir>/backups`, newest restored first
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    dir: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self { dir: data_dir.join("backups") }
+    }
+
+    #[tracing::instrument(name = "backup_store_record", level = "debug", skip(self, manifest))]
+    pub fn record(&self, manifest: &BackupManifest) -> CHOPSResult<()> {
+        if manifest.entries.is_empty() {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.dir).map_err(CHOPSError::FileSystemError)?;
+
+        let file_name = format!("{}-{}.json", manifest.created_at.format("%Y%m%dT%H%M%S%.6f"), manifest.id);
+        let content = serde_json::to_string_pretty(manifest)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize backup manifest: {}", e)))?;
+
+        std::fs::write(self.dir.join(&file_name), content).map_err(CHOPSError::FileSystemError)?;
+        tracing::debug!("Recorded backup manifest {} with {} files", file_name, manifest.entries.len());
+        Ok(())
+    }
+
+    fn latest_manifest_path(&self) -> CHOPSResult<Option<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(None);
+        }
+
+        let mut manifests: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .map_err(CHOPSError::FileSystemError)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+
+        manifests.sort();
+        Ok(manifests.pop())
+    }
+
+    /// Restores the most recent batch and drops its manifest
+    #[tracing::instrument(name = "backup_store_undo", level = "info", skip(self))]
+    pub fn undo_latest(&self) -> CHOPSResult<Option<BackupManifest>> {
+        let Some(path) = self.latest_manifest_path()? else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&path).map_err(CHOPSError::FileSystemError)?;
+        let manifest: BackupManifest = serde_json::from_str(&content)
+            .map_err(|e| CHOPSError::ConfigError(format!("Invalid backup manifest {}: {}", path.display(), e)))?;
+
+        manifest.restore()?;
+        std::fs::remove_file(&path).map_err(CHOPSError::FileSystemError)?;
+
+        tracing::info!("Undid {} ({} files)", manifest.command, manifest.entries.len());
+        Ok(Some(manifest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorded_backup_undoes_writes_made_after_it() {
+        let dir = std::env::temp_dir().join(format!("chops-backup-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("main.rs");
+        let created = dir.join("new.rs");
+        std::fs::write(&existing, "fn main() {}").unwrap();
+
+        let mut batch = BackupManifest::new("mutate");
+        batch.back_up(&existing).unwrap();
+        batch.back_up(&created).unwrap();
+        batch.back_up(&existing).unwrap();
+        assert_eq!(batch.entries.len(), 2);
+        let store = BackupStore::new(&dir);
+        store.record(&batch).unwrap();
+        std::fs::write(&existing, "fn main() { chaos(); }").unwrap();
+        std::fs::write(&created, "fn chaos() {}").unwrap();
+
+        let undone = store.undo_latest().unwrap().unwrap();
+        assert_eq!(undone.id, batch.id);
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "fn main() {}");
+        assert!(!created.exists());
+        assert!(store.undo_latest().unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
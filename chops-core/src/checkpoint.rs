@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use crate::{CHOPSError, CHOPSResult};
+
+/// Progress of a batch run over one prompts file, so an interrupted run can
+/// pick up where it stopped instead of paying for finished prompts again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCheckpoint {
+    pub input_file: PathBuf,
+    /// Hash of the prompts file contents and the run's settings; an edited
+    /// file or a changed setting gets a fresh checkpoint
+    #[serde(alias = "input_hash")]
+    pub run_key: String,
+    pub completed: Vec<CompletedPrompt>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedPrompt {
+    /// Position of the prompt among the file's prompts, from 0
+    pub index: usize,
+    pub prompt: String,
+    pub output_path: PathBuf,
+}
+
+impl BatchCheckpoint {
+    pub fn new(input_file: &Path, run_key: String) -> Self {
+        Self {
+            input_file: input_file.to_path_buf(),
+            run_key,
+            completed: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn hash_input(content: &str) -> String {
+        stable_hash(content)
+    }
+
+    /// Key for a run over `content` with `settings` (persona, chaos level,
+    /// output directory and the like), so a run with any of them changed
+    /// doesn't skip prompts another run finished
+    pub fn run_key(content: &str, settings: &[&str]) -> String {
+        let keyed = settings.iter().fold(content.to_string(), |mut keyed, setting| {
+            keyed.push('\0');
+            keyed.push_str(setting);
+            keyed
+        });
+        Self::hash_input(&keyed)
+    }
+
+    /// Where the checkpoint for a run with this key lives
+    pub fn path_in(data_dir: &Path, run_key: &str) -> PathBuf {
+        data_dir.join("batches").join(format!("{}.json", run_key))
+    }
+
+    pub fn load(path: &Path) -> CHOPSResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path).map_err(CHOPSError::FileSystemError)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| CHOPSError::ConfigError(format!("Invalid batch checkpoint {}: {}", path.display(), e)))
+    }
+
+    /// Writes to a temporary file and renames it over the checkpoint, so a
+    /// crash mid-write leaves the previous checkpoint intact
+    #[tracing::instrument(name = "batch_checkpoint_save", level = "debug", skip(self))]
+    pub fn save(&self, path: &Path) -> CHOPSResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(CHOPSError::FileSystemError)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CHOPSError::ConfigError(format!("Failed to serialize batch checkpoint: {}", e)))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content).map_err(CHOPSError::FileSystemError)?;
+        std::fs::rename(&temp_path, path).map_err(CHOPSError::FileSystemError)?;
+
+        tracing::debug!("Checkpointed {} completed prompts to {}", self.completed.len(), path.display());
+        Ok(())
+    }
+
+    pub fn is_completed(&self, index: usize) -> bool {
+        self.completed.iter().any(|done| done.index == index)
+    }
+
+    pub fn record(&mut self, index: usize, prompt: &str, output_path: PathBuf) {
+        self.completed.retain(|done| done.index != index);
+        self.completed.push(CompletedPrompt { index, prompt: prompt.to_string(), output_path });
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Content hash (64-bit FNV-1a, hex) that is the same across builds and
+/// platforms, for names of files that later runs look up
+pub fn stable_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Prompts in a batch file: one per line, skipping blank lines and `#` comments
+pub fn parse_batch_prompts(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_key_changes_with_each_setting() {
+        let content = "first prompt\nsecond prompt\n";
+        let key = BatchCheckpoint::run_key(content, &["zen-master", "5", "out"]);
+
+        assert_eq!(key, BatchCheckpoint::run_key(content, &["zen-master", "5", "out"]));
+        assert_ne!(key, BatchCheckpoint::run_key(content, &["punk-hacker", "5", "out"]));
+        assert_ne!(key, BatchCheckpoint::run_key(content, &["zen-master", "7", "out"]));
+        assert_ne!(key, BatchCheckpoint::run_key(content, &["zen-master", "5", "elsewhere"]));
+        assert_ne!(key, BatchCheckpoint::run_key("first prompt\n", &["zen-master", "5", "out"]));
+    }
+}
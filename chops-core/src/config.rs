@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use crate::{CHOPSError, CHOPSResult, PersonaType, CreativityLevel, OutputFormat, WeirднessLevel};
+use std::path::{Path, PathBuf};
+use crate::{ChaosAffinity, ChaosTierBoundaries, CHOPSError, CHOPSResult, LlmProvider, PersonaType, SimilarityStrategy, CreativityLevel, OutputFormat, WeirднessLevel};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CHOPSConfig {
@@ -11,6 +11,32 @@ pub struct CHOPSConfig {
     pub output_preferences: OutputPreferences,
     pub behavior_settings: BehaviorSettings,
     pub template_directories: Vec<PathBuf>,
+    #[serde(default)]
+    pub entropy_settings: EntropySettings,
+    #[serde(default)]
+    pub persona_drift: PersonaDriftSettings,
+    /// Extra domains (name -> keywords) for inferring a domain when none is given
+    #[serde(default)]
+    pub domain_keywords: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub creative_momentum: CreativeMomentumSettings,
+    #[serde(default)]
+    pub surprise_floor: SurpriseFloorSettings,
+    #[serde(default)]
+    pub feasibility_band: FeasibilityBandSettings,
+    #[serde(default)]
+    pub refinement: RefinementSettings,
+    #[serde(default)]
+    pub chaos_tiers: ChaosTierBoundaries,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub analogy: AnalogySettings,
+    #[serde(default)]
+    pub recall: RecallSettings,
+    /// Standing constraints merged into every summon (e.g. "no external SaaS")
+    #[serde(default)]
+    pub default_constraints: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +53,9 @@ pub struct DefaultSettings {
     pub default_creativity: CreativityLevel,
     pub default_format: OutputFormat,
     pub default_weirdness_tolerance: WeirднessLevel,
+    /// LLM provider ideas are generated with (`claude` or `openai`)
+    #[serde(default)]
+    pub provider: LlmProvider,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +63,12 @@ pub struct PersonaCustomization {
     pub custom_prompt_additions: Vec<String>,
     pub personality_amplifiers: HashMap<String, f64>,
     pub thinking_pattern_overrides: Vec<String>,
+    /// Replaces the persona's own chaos affinity
+    #[serde(default)]
+    pub chaos_affinity: Option<ChaosAffinity>,
+    /// Replaces the persona's own stop sequences
+    #[serde(default)]
+    pub stop_sequences: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,8 +83,334 @@ pub struct OutputPreferences {
 pub struct BehaviorSettings {
     pub reality_distortion_enabled: bool,
     pub safe_mode: bool,
-    pub auto_save_ideas: bool,
+    /// Write every summoned idea to `output_preferences.default_directory`,
+    /// as `summon --save` does
+    #[serde(default)]
+    pub save_ideas: bool,
+    /// Retired in favor of `save_ideas`. Older configs were written with
+    /// `auto_save_ideas = true` while it did nothing, so it isn't honored.
+    #[serde(default, skip_serializing)]
+    pub auto_save_ideas: Option<bool>,
     pub enable_learning: bool,
+    /// Cache the persona prefix of API prompts across requests
+    #[serde(default = "default_prompt_caching")]
+    pub prompt_caching: bool,
+    /// Largest API request body to send; larger prompts are trimmed with a warning
+    #[serde(default = "default_max_request_bytes")]
+    pub max_request_bytes: usize,
+    /// Re-prompt once when an idea's persona voice fidelity (0.0-1.0) falls below this; off when unset
+    #[serde(default)]
+    pub voice_check_threshold: Option<f64>,
+    /// Minutes the interactive menu may sit untouched before memory is saved and the session ends; 0 never times out
+    #[serde(default = "default_interactive_idle_minutes")]
+    pub interactive_idle_minutes: u64,
+    /// Fixed sampling temperature (0.0-1.0); when unset it follows the chaos applied to each idea
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Nucleus sampling cutoff (0.0-1.0); API default when unset
+    #[serde(default)]
+    pub top_p: Option<f64>,
+    /// Sample only from the k likeliest tokens; API default when unset
+    #[serde(default)]
+    pub top_k: Option<u32>,
+}
+
+fn default_prompt_caching() -> bool {
+    true
+}
+
+/// Most stop sequences sent with one Messages API request; longer lists are
+/// rejected before they reach the API
+pub const MAX_STOP_SEQUENCES: usize = 8;
+
+/// Default cap on serialized request bodies, well past any prompt that fits the context window
+pub const DEFAULT_MAX_REQUEST_BYTES: usize = 1_000_000;
+
+fn default_max_request_bytes() -> usize {
+    DEFAULT_MAX_REQUEST_BYTES
+}
+
+fn default_interactive_idle_minutes() -> u64 {
+    30
+}
+
+/// Where a piece of CHOPS state ended up being persisted
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageLocation {
+    /// The requested directory is writable
+    Preferred(PathBuf),
+    /// The requested directory was unusable, a temp directory is used instead
+    Fallback { requested: PathBuf, fallback: PathBuf },
+    /// Nothing writable was found, state lives in memory for this run only
+    InMemory { requested: PathBuf },
+}
+
+impl StorageLocation {
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            StorageLocation::Preferred(path) => Some(path),
+            StorageLocation::Fallback { fallback, .. } => Some(fallback),
+            StorageLocation::InMemory { .. } => None,
+        }
+    }
+
+    /// Resolves a directory to persist into, falling back to a directory of the
+    /// user's own under the system temp directory and finally to in-memory only
+    /// operation.
+    #[tracing::instrument(name = "resolve_storage", level = "debug")]
+    pub fn resolve(requested: PathBuf) -> Self {
+        if is_dir_writable(&requested) {
+            tracing::debug!("Storage directory is writable: {}", requested.display());
+            return StorageLocation::Preferred(requested);
+        }
+
+        let fallback = private_temp_dir(&std::env::temp_dir());
+        if let Some(fallback) = fallback.filter(|fallback| is_dir_writable(fallback)) {
+            tracing::warn!("Storage directory {} is not writable, falling back to {}",
+                requested.display(), fallback.display());
+            return StorageLocation::Fallback { requested, fallback };
+        }
+
+        tracing::warn!("No writable storage directory found for {}, running in-memory only", requested.display());
+        StorageLocation::InMemory { requested }
+    }
+}
+
+/// `chops-<uid>` under `base`, created readable by the current user only. The
+/// temp directory is shared, so one that someone else made first, or left open
+/// to others, is refused rather than trusted with memory and backups.
+#[cfg(unix)]
+fn private_temp_dir(base: &Path) -> Option<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let uid = current_uid(base)?;
+    let dir = base.join(format!("chops-{}", uid));
+    if let Err(e) = std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            tracing::debug!("Could not create {}: {}", dir.display(), e);
+            return None;
+        }
+    }
+
+    let metadata = std::fs::symlink_metadata(&dir).ok()?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        tracing::warn!("Refusing to use {}: it is not a directory private to this user", dir.display());
+        return None;
+    }
+    Some(dir)
+}
+
+#[cfg(not(unix))]
+fn private_temp_dir(base: &Path) -> Option<PathBuf> {
+    Some(base.join("chops"))
+}
+
+/// The owner of a file this process creates, which is the current user
+#[cfg(unix)]
+fn current_uid(base: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    let probe = base.join(format!(".chops-uid-{}", uuid::Uuid::new_v4()));
+    std::fs::OpenOptions::new().write(true).create_new(true).open(&probe).ok()?;
+    let uid = std::fs::metadata(&probe).map(|metadata| metadata.uid());
+    let _ = std::fs::remove_file(&probe);
+    uid.ok()
+}
+
+fn is_dir_writable(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+
+    let probe = dir.join(format!(".chops-write-test-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        },
+        Err(e) => {
+            tracing::debug!("Directory {} is not writable: {}", dir.display(), e);
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntropySettings {
+    pub quantum_timeout_ms: u64,
+    /// Retries after a failed quantum fetch; 0 tries once
+    pub quantum_retry_attempts: u8,
+}
+
+impl Default for EntropySettings {
+    fn default() -> Self {
+        Self {
+            quantum_timeout_ms: 5000,
+            quantum_retry_attempts: 1,
+        }
+    }
+}
+
+/// Outbound network policy, for restricted environments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    /// Never touch the network: quantum entropy is refused and LLM calls fail
+    /// unless `local_api_url` points at a local backend
+    pub air_gapped: bool,
+    /// Messages-API-compatible endpoint to use instead of api.anthropic.com;
+    /// air-gapped runs only accept loopback and private network addresses
+    pub local_api_url: Option<String>,
+    /// Send the Claude API key to `local_api_url` as well (off by default, so
+    /// the key only ever goes to Anthropic)
+    #[serde(default)]
+    pub send_api_key_to_local: bool,
+    /// Generate with the mock client's canned completions instead of any
+    /// LLM, and never touch the network (implies `air_gapped`)
+    #[serde(default)]
+    pub offline: bool,
+}
+
+impl NetworkSettings {
+    /// Whether anything may be fetched over the network
+    pub fn allows_network(&self) -> bool {
+        !self.air_gapped && !self.offline
+    }
+}
+
+/// How cross-domain analogies are matched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalogySettings {
+    pub strategy: SimilarityStrategy,
+    /// Base URL of an OpenAI-compatible `/v1/embeddings` endpoint
+    pub embedding_url: String,
+    pub embedding_model: String,
+    /// Key for `embedding_url`. Without one, the OpenAI API key is used, but
+    /// only when the endpoint is api.openai.com
+    pub embedding_api_key: Option<String>,
+}
+
+impl Default for AnalogySettings {
+    fn default() -> Self {
+        Self {
+            strategy: SimilarityStrategy::WordOverlap,
+            embedding_url: "https://api.openai.com".to_string(),
+            embedding_model: "text-embedding-3-small".to_string(),
+            embedding_api_key: None,
+        }
+    }
+}
+
+/// Bounds on how much adaptive learning may shift a persona's traits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonaDriftSettings {
+    pub drift_rate: f64,
+    pub max_drift: f64,
+}
+
+impl Default for PersonaDriftSettings {
+    fn default() -> Self {
+        Self {
+            drift_rate: 0.1,
+            max_drift: 0.2,
+        }
+    }
+}
+
+/// How strongly a session's recent ideas bias the next summon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreativeMomentumSettings {
+    /// 0.0 ignores momentum, 1.0 lets it fully set chaos and temperature
+    pub coupling_strength: f64,
+    pub half_life_minutes: f64,
+}
+
+impl Default for CreativeMomentumSettings {
+    fn default() -> Self {
+        Self {
+            coupling_strength: 0.3,
+            half_life_minutes: 30.0,
+        }
+    }
+}
+
+/// Re-rolls summoned ideas whose novelty falls below a floor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurpriseFloorSettings {
+    /// Minimum novelty score (0.0-1.0); 0.0 disables re-rolling
+    pub min_novelty: f64,
+    /// Total generations allowed, including the first
+    pub max_attempts: u8,
+    /// Chaos levels added (or, to rein in an infeasible idea, removed) on each re-roll
+    pub chaos_step: u8,
+}
+
+impl Default for SurpriseFloorSettings {
+    fn default() -> Self {
+        Self {
+            min_novelty: 0.0,
+            max_attempts: 3,
+            chaos_step: 2,
+        }
+    }
+}
+
+/// Re-rolls steering `summon --feasibility-band` ideas into the band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeasibilityBandSettings {
+    /// Total generations allowed, including the first
+    pub max_attempts: u8,
+    /// Chaos levels removed for a far-fetched idea, or added for a mundane one, on each re-roll
+    pub chaos_step: u8,
+    /// Reality level added for a far-fetched idea, or removed for a mundane one, on each re-roll
+    pub reality_step: f64,
+}
+
+impl Default for FeasibilityBandSettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            chaos_step: 2,
+            reality_step: 0.1,
+        }
+    }
+}
+
+/// How remembered ideas are recalled as related to a new one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecallSettings {
+    /// Lowest relevance (0.0-1.0) an idea needs to be shown as related
+    pub min_score: f64,
+    /// Most related ideas shown after a summon
+    pub limit: usize,
+}
+
+impl Default for RecallSettings {
+    fn default() -> Self {
+        Self {
+            min_score: 0.15,
+            limit: 3,
+        }
+    }
+}
+
+/// Bounds on refinement chains (ideas summoned `--based-on` earlier ones)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefinementSettings {
+    /// Most refinements allowed on top of the chain's root idea
+    pub max_depth: usize,
+    /// Tokens a whole chain may spend; 0 means no budget
+    pub max_chain_tokens: u64,
+}
+
+impl Default for RefinementSettings {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            max_chain_tokens: 0,
+        }
+    }
 }
 
 impl CHOPSConfig {
@@ -80,6 +441,13 @@ impl CHOPSConfig {
                 CHOPSError::ConfigError(format!("Invalid TOML: {}", e))
             })?;
         
+        if config.behavior_settings.auto_save_ideas == Some(true) {
+            tracing::warn!(
+                "behavior_settings.auto_save_ideas in {} is no longer read; set save_ideas = true to save every idea",
+                path.display()
+            );
+        }
+
         tracing::debug!("Validating loaded configuration");
         config.validate()?;
         
@@ -109,25 +477,82 @@ impl CHOPSConfig {
             })?;
         
         tracing::debug!("Writing {} bytes to config file", content.len());
-        std::fs::write(path, content)
-            .map_err(|e| {
-                tracing::error!("Failed to write config file {}: {}", path.display(), e);
-                CHOPSError::FileSystemError(e)
-            })?;
+        let write_error = |e: std::io::Error| {
+            tracing::error!("Failed to write config file {}: {}", path.display(), e);
+            CHOPSError::FileSystemError(e)
+        };
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        // The config can hold API keys. On Unix, create it readable by the
+        // owner only, and tighten an existing file before the keys go in;
+        // elsewhere the user profile's ACLs already cover it.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path).map_err(write_error)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| {
+                    tracing::error!("Failed to restrict permissions on {}: {}", path.display(), e);
+                    CHOPSError::FileSystemError(e)
+                })?;
+        }
+        std::io::Write::write_all(&mut file, content.as_bytes()).map_err(write_error)?;
         
         tracing::info!("Configuration saved successfully to {}", path.display());
         Ok(())
     }
     
+    /// Config directory, overridable with `CHOPS_CONFIG_DIR`
+    #[tracing::instrument(name = "get_config_dir", level = "debug")]
+    pub fn get_config_dir() -> PathBuf {
+        if let Ok(dir) = std::env::var("CHOPS_CONFIG_DIR") {
+            tracing::debug!("Using config directory from CHOPS_CONFIG_DIR: {}", dir);
+            return PathBuf::from(dir);
+        }
+
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("chops")
+    }
+
+    /// Template search path, most general first: the system-wide share dir
+    /// (Unix only), the per-user data dir, then the config dir
+    pub fn default_template_directories() -> Vec<PathBuf> {
+        let mut directories = Vec::new();
+
+        #[cfg(unix)]
+        directories.push(PathBuf::from("/usr/local/share/chops/templates"));
+
+        if let Some(data_dir) = dirs::data_dir() {
+            directories.push(data_dir.join("chops").join("templates"));
+        }
+        directories.push(Self::get_config_dir().join("templates"));
+        directories
+    }
+
     #[tracing::instrument(name = "get_config_path", level = "debug")]
     pub fn get_config_path() -> PathBuf {
-        let config_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."));
-        let chops_config_path = config_dir.join("chops").join("config.toml");
+        let chops_config_path = Self::get_config_dir().join("config.toml");
         
         tracing::debug!("Determined config path: {}", chops_config_path.display());
         chops_config_path
     }
+
+    /// Data directory for memory and other state, overridable with `CHOPS_DATA_DIR`
+    #[tracing::instrument(name = "get_data_dir", level = "debug")]
+    pub fn get_data_dir() -> PathBuf {
+        if let Ok(dir) = std::env::var("CHOPS_DATA_DIR") {
+            tracing::debug!("Using data directory from CHOPS_DATA_DIR: {}", dir);
+            return PathBuf::from(dir);
+        }
+
+        PathBuf::from(".").join("chops")
+    }
     
     #[tracing::instrument(name = "config_validate", level = "debug")]
     fn validate(&self) -> CHOPSResult<()> {
@@ -169,18 +594,39 @@ impl CHOPSConfig {
             ));
         }
         
+        if let Some(threshold) = self.behavior_settings.voice_check_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(CHOPSError::ConfigError(
+                    format!("voice_check_threshold must be between 0.0 and 1.0, got {}", threshold)
+                ));
+            }
+        }
+
+        for (name, value) in [("temperature", self.behavior_settings.temperature), ("top_p", self.behavior_settings.top_p)] {
+            if let Some(value) = value.filter(|v| !(0.0..=1.0).contains(v)) {
+                return Err(CHOPSError::ConfigError(
+                    format!("{} must be between 0.0 and 1.0, got {}", name, value)
+                ));
+            }
+        }
+        if self.behavior_settings.top_k == Some(0) {
+            return Err(CHOPSError::ConfigError("top_k must be a positive integer".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.recall.min_score) {
+            return Err(CHOPSError::ConfigError(
+                format!("recall.min_score must be between 0.0 and 1.0, got {}", self.recall.min_score)
+            ));
+        }
+        
         // Validate output directory exists or can be created
         tracing::debug!("Validating output directory: {}", self.output_preferences.default_directory.display());
         if !self.output_preferences.default_directory.exists() {
             tracing::info!("Creating output directory: {}", self.output_preferences.default_directory.display());
-            std::fs::create_dir_all(&self.output_preferences.default_directory)
-                .map_err(|e| {
-                    tracing::error!("Failed to create output directory {}: {}", 
-                        self.output_preferences.default_directory.display(), e);
-                    CHOPSError::ConfigError(
-                        format!("Cannot create output directory: {}", e)
-                    )
-                })?;
+            // An unwritable output directory shouldn't stop CHOPS from running
+            if let Err(e) = std::fs::create_dir_all(&self.output_preferences.default_directory) {
+                tracing::warn!("Failed to create output directory {}: {}", 
+                    self.output_preferences.default_directory.display(), e);
+            }
         }
         
         // Validate persona customizations
@@ -203,6 +649,9 @@ impl CHOPSConfig {
                     ));
                 }
             }
+            if let Some(affinity) = &customization.chaos_affinity {
+                affinity.validate().map_err(|e| CHOPSError::ConfigError(format!("Persona {}: {}", persona_name, e)))?;
+            }
         }
         
         tracing::info!("Configuration validation completed successfully");
@@ -227,6 +676,32 @@ impl CHOPSConfig {
         }
     }
     
+    #[tracing::instrument(name = "get_openai_api_key", level = "debug")]
+    pub fn get_openai_api_key(&self) -> CHOPSResult<&str> {
+        tracing::debug!("Retrieving OpenAI API key from configuration");
+
+        self.api_keys.openai_api_key.as_deref().ok_or_else(|| {
+            tracing::error!("OpenAI API key not configured");
+            CHOPSError::ConfigError(
+                "OpenAI API key not configured. Set OPENAI_API_KEY environment variable or add to config file.".to_string()
+            )
+        })
+    }
+
+    /// The configured default constraints followed by `extra`, skipping blanks
+    /// and case-insensitive duplicates
+    pub fn merged_constraints(&self, extra: &[String]) -> Vec<String> {
+        let mut merged: Vec<String> = Vec::new();
+        for constraint in self.default_constraints.iter().chain(extra) {
+            let constraint = constraint.trim();
+            if constraint.is_empty() || merged.iter().any(|c| c.eq_ignore_ascii_case(constraint)) {
+                continue;
+            }
+            merged.push(constraint.to_string());
+        }
+        merged
+    }
+
     #[tracing::instrument(name = "merge_with_env", level = "debug")]
     pub fn merge_with_env(&mut self) {
         tracing::debug!("Merging configuration with environment variables");
@@ -247,6 +722,23 @@ impl CHOPSConfig {
             self.api_keys.quantum_api_key = Some(key);
         }
         
+        if let Ok(value) = std::env::var("CHOPS_AIR_GAPPED") {
+            let air_gapped = matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on");
+            tracing::info!("Overriding air-gapped mode from environment: {}", air_gapped);
+            self.network.air_gapped = air_gapped;
+        }
+        
+        if let Ok(value) = std::env::var("CHOPS_OFFLINE") {
+            let offline = matches!(value.to_lowercase().as_str(), "1" | "true" | "yes" | "on");
+            tracing::info!("Overriding offline mode from environment: {}", offline);
+            self.network.offline = offline;
+        }
+        
+        if let Ok(url) = std::env::var("CHOPS_LOCAL_API_URL") {
+            tracing::info!("Overriding local API endpoint from environment variable");
+            self.network.local_api_url = Some(url);
+        }
+        
         if let Ok(chaos_level) = std::env::var("CHOPS_DEFAULT_CHAOS") {
             tracing::debug!("Found CHOPS_DEFAULT_CHAOS environment variable: {}", chaos_level);
             if let Ok(level) = chaos_level.parse::<u8>() {
@@ -289,6 +781,7 @@ impl Default for CHOPSConfig {
                 default_creativity: CreativityLevel::High,
                 default_format: OutputFormat::Markdown,
                 default_weirdness_tolerance: WeirднessLevel::Medium,
+                provider: LlmProvider::Claude,
             },
             persona_customizations: HashMap::new(),
             output_preferences: OutputPreferences {
@@ -302,16 +795,116 @@ impl Default for CHOPSConfig {
             behavior_settings: BehaviorSettings {
                 reality_distortion_enabled: true,
                 safe_mode: false,
-                auto_save_ideas: true,
+                save_ideas: false,
+                auto_save_ideas: None,
                 enable_learning: true,
+                prompt_caching: true,
+                max_request_bytes: default_max_request_bytes(),
+                voice_check_threshold: None,
+                interactive_idle_minutes: default_interactive_idle_minutes(),
+                temperature: None,
+                top_p: None,
+                top_k: None,
             },
-            template_directories: vec![
-                PathBuf::from("/usr/local/share/chops/templates"),
-                dirs::config_dir()
-                    .unwrap_or_else(|| PathBuf::from("."))
-                    .join("chops")
-                    .join("templates"),
-            ],
+            template_directories: Self::default_template_directories(),
+            entropy_settings: EntropySettings::default(),
+            persona_drift: PersonaDriftSettings::default(),
+            domain_keywords: HashMap::new(),
+            creative_momentum: CreativeMomentumSettings::default(),
+            surprise_floor: SurpriseFloorSettings::default(),
+            feasibility_band: FeasibilityBandSettings::default(),
+            refinement: RefinementSettings::default(),
+            chaos_tiers: ChaosTierBoundaries::default(),
+            network: NetworkSettings::default(),
+            analogy: AnalogySettings::default(),
+            recall: RecallSettings::default(),
+            default_constraints: Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retired_auto_save_ideas_key_does_not_enable_saving() {
+        let dir = std::env::temp_dir().join(format!("chops-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let content = toml::to_string_pretty(&CHOPSConfig::default()).unwrap()
+            .replace("[behavior_settings]\n", "[behavior_settings]\nauto_save_ideas = true\n");
+        std::fs::write(&path, content).unwrap();
+
+        let config = CHOPSConfig::load_from_file(&path).unwrap();
+        assert_eq!(config.behavior_settings.auto_save_ideas, Some(true));
+        assert!(!config.behavior_settings.save_ideas);
+
+        config.save_to_file(&path).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("auto_save_ideas"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_chaos_tiers_section_fills_in_missing_boundaries() {
+        let content = toml::to_string_pretty(&CHOPSConfig::default()).unwrap();
+        let start = content.find("[chaos_tiers]").unwrap();
+        let end = content[start + 1..].find("\n[").map_or(content.len(), |end| start + 1 + end);
+        let content = format!("{}[chaos_tiers]\nimpossible_min = 0.8\n{}", &content[..start], &content[end..]);
+
+        let config: CHOPSConfig = toml::from_str(&content).unwrap();
+        assert_eq!(config.chaos_tiers, ChaosTierBoundaries { impossible_min: 0.8, ..ChaosTierBoundaries::default() });
+        assert_eq!(config.chaos_tiers.tier_for(0.8), crate::ChaosTier::Impossible);
+        assert_eq!(config.chaos_tiers.tier_for(0.79), crate::ChaosTier::RealityBending);
+    }
+
+    #[test]
+    fn test_recall_section_fills_in_missing_fields() {
+        let content = toml::to_string_pretty(&CHOPSConfig::default()).unwrap();
+        let start = content.find("[recall]").unwrap();
+        let end = content[start + 1..].find("\n[").map_or(content.len(), |end| start + 1 + end);
+        let content = format!("{}[recall]\nlimit = 5\n{}", &content[..start], &content[end..]);
+
+        let config: CHOPSConfig = toml::from_str(&content).unwrap();
+        assert_eq!(config.recall.limit, 5);
+        assert_eq!(config.recall.min_score, RecallSettings::default().min_score);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_config_is_readable_by_the_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("chops-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        CHOPSConfig::default().save_to_file(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // An existing world-readable file is tightened too
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        CHOPSConfig::default().save_to_file(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(CHOPSConfig::load_from_file(&path).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fallback_dir_is_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+        let base = std::env::temp_dir().join(format!("chops-config-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&base).unwrap();
+
+        let dir = private_temp_dir(&base).unwrap();
+        assert!(dir.file_name().unwrap().to_string_lossy().starts_with("chops-"));
+        assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        assert_eq!(private_temp_dir(&base), Some(dir.clone()));
+
+        // One that others can get into is not used
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert_eq!(private_temp_dir(&base), None);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+/// One line of a line diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Share of lines that differ between two texts, from 0.0 (identical) to 1.0
+/// (nothing in common): 1 - 2·LCS / (lines in a + lines in b).
+pub fn change_ratio(original: &str, changed: &str) -> f64 {
+    let total = original.lines().count() + changed.lines().count();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let common = diff_lines(original, changed).iter()
+        .filter(|line| matches!(line, DiffLine::Same(_)))
+        .count();
+    1.0 - (2 * common) as f64 / total as f64
+}
+
+/// Line diff via longest common subsequence, after trimming the shared prefix/suffix
+pub fn diff_lines<'a>(original: &'a str, modified: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = modified.lines().collect();
+
+    let prefix = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] = LCS length of old_mid[i..] and new_mid[j..]
+    let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff: Vec<DiffLine> = old[..prefix].iter().map(|line| DiffLine::Same(line)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() && j < new_mid.len() {
+        if old_mid[i] == new_mid[j] {
+            diff.push(DiffLine::Same(old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old_mid[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new_mid[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old_mid[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new_mid[j..].iter().map(|line| DiffLine::Added(line)));
+    diff.extend(old[old.len() - suffix..].iter().map(|line| DiffLine::Same(line)));
+
+    diff
+}
+
+/// How a language writes comments, for telling comment edits from code edits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentSyntax {
+    /// `//` line and `/* */` block comments
+    CStyle,
+    /// `#` line comments
+    Hash,
+}
+
+impl CommentSyntax {
+    /// Guesses the syntax from a file extension; `None` when unknown
+    pub fn for_path(path: &str) -> Option<Self> {
+        CommentStyle::for_path(path)?.syntax()
+    }
+}
+
+/// How to write a one-line comment in a language, for inserting text into a
+/// file without breaking it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentStyle {
+    pub prefix: &'static str,
+    /// Closes the comment on the same line, for languages without line comments
+    pub suffix: &'static str,
+}
+
+impl CommentStyle {
+    pub const SLASHES: Self = Self { prefix: "//", suffix: "" };
+    pub const HASH: Self = Self { prefix: "#", suffix: "" };
+    pub const DASHES: Self = Self { prefix: "--", suffix: "" };
+    pub const SEMICOLON: Self = Self { prefix: ";", suffix: "" };
+    pub const DOUBLE_SEMICOLON: Self = Self { prefix: ";;", suffix: "" };
+    pub const SLASH_STAR: Self = Self { prefix: "/*", suffix: " */" };
+    pub const MARKUP: Self = Self { prefix: "<!--", suffix: " -->" };
+
+    /// The style for a file, judged by its extension. `None` for formats
+    /// without comments (JSON, CSV, plain text) and unknown extensions, where
+    /// any comment would corrupt the file.
+    pub fn for_path(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "kt" | "swift" | "go"
+            | "js" | "jsx" | "ts" | "tsx" | "scala" | "dart" | "php" => Some(Self::SLASHES),
+            "py" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "toml" | "yaml" | "yml" | "cfg" | "conf" | "mk" => Some(Self::HASH),
+            "html" | "htm" | "xml" | "svg" | "vue" | "md" | "markdown" => Some(Self::MARKUP),
+            "ini" | "asm" | "s" => Some(Self::SEMICOLON),
+            "lisp" | "clj" | "cljs" | "el" | "scm" => Some(Self::DOUBLE_SEMICOLON),
+            "sql" | "lua" | "hs" => Some(Self::DASHES),
+            "css" => Some(Self::SLASH_STAR),
+            _ => None,
+        }
+    }
+
+    /// The syntax [`code_without_comments`] strips for this style; `None` when
+    /// it has no stripper
+    pub fn syntax(&self) -> Option<CommentSyntax> {
+        match *self {
+            Self::SLASHES => Some(CommentSyntax::CStyle),
+            Self::HASH => Some(CommentSyntax::Hash),
+            _ => None,
+        }
+    }
+
+    /// `text` as a single comment line. Line breaks are flattened and
+    /// anything that would close the comment early is broken up.
+    pub fn comment(&self, text: &str) -> String {
+        let mut text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let closer = self.suffix.trim();
+        if !closer.is_empty() {
+            let broken: String = closer.chars().map(|c| format!("{} ", c)).collect();
+            text = text.replace(closer, broken.trim_end());
+        }
+        format!("{} {}{}", self.prefix, text, self.suffix)
+    }
+}
+
+/// Inserts each `(line, text)` as a comment above that 1-based line of
+/// `content`, indented like it; lines past the end append. Returns the new
+/// content and the line each comment ended up on, in input order.
+pub fn insert_comments(content: &str, style: CommentStyle, comments: &[(usize, String)]) -> (String, Vec<usize>) {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let original_len = lines.len();
+
+    // Top to bottom, so each insertion only shifts the ones after it
+    let mut order: Vec<usize> = (0..comments.len()).collect();
+    order.sort_by_key(|&i| comments[i].0);
+    let mut placed = vec![0; comments.len()];
+    for (inserted, &i) in order.iter().enumerate() {
+        let (line, text) = &comments[i];
+        let target = line.saturating_sub(1).min(original_len);
+        let at = target + inserted;
+        let indent: String = lines.get(at)
+            .map(|below| below.chars().take_while(|c| c.is_whitespace()).collect())
+            .unwrap_or_default();
+        lines.insert(at, format!("{}{}", indent, style.comment(text)));
+        placed[i] = at + 1;
+    }
+
+    let mut out = lines.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        out.push('\n');
+    }
+    (out, placed)
+}
+
+/// The code with comments removed and whitespace collapsed, so two versions
+/// that differ only in comments compare equal. Quoted strings are kept
+/// intact; a single quote only opens one when another closes it on the same
+/// line, so lifetimes and apostrophes stay code.
+pub fn code_without_comments(code: &str, syntax: CommentSyntax) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut chars = code.chars().peekable();
+    let mut in_string: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(quote) = in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '\n' if quote == '\'' => in_string = None,
+                _ if c == quote => in_string = None,
+                _ => {},
+            }
+            continue;
+        }
+
+        let next = chars.peek().copied();
+        match (syntax, c, next) {
+            (_, '"', _) => {
+                in_string = Some(c);
+                out.push(c);
+            },
+            (_, '\'', _) if chars.clone().take_while(|&next| next != '\n').any(|next| next == '\'') => {
+                in_string = Some(c);
+                out.push(c);
+            },
+            (CommentSyntax::CStyle, '/', Some('/')) | (CommentSyntax::Hash, '#', _) => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            },
+            (CommentSyntax::CStyle, '/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for skipped in chars.by_ref() {
+                    if previous == '*' && skipped == '/' {
+                        break;
+                    }
+                    previous = skipped;
+                }
+                out.push(' ');
+            },
+            _ => out.push(c),
+        }
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines_and_change_ratio_agree() {
+        let original = "a\nb\nc\nd";
+        let modified = "a\nx\nc\nd\ne";
+        assert_eq!(diff_lines(original, modified), vec![
+            DiffLine::Same("a"),
+            DiffLine::Removed("b"),
+            DiffLine::Added("x"),
+            DiffLine::Same("c"),
+            DiffLine::Same("d"),
+            DiffLine::Added("e"),
+        ]);
+        // 3 lines in common out of 4 + 5
+        assert!((change_ratio(original, modified) - (1.0 - 6.0 / 9.0)).abs() < 1e-12);
+        assert_eq!(change_ratio("", ""), 0.0);
+        assert_eq!(change_ratio("same\n", "same\n"), 0.0);
+        assert_eq!(change_ratio("old", "new"), 1.0);
+    }
+
+    #[test]
+    fn test_comment_style_per_extension() {
+        let cases = [
+            ("src/main.rs", "// egg"),
+            ("tools/build.py", "# egg"),
+            ("site/index.html", "<!-- egg -->"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(CommentStyle::for_path(path).unwrap().comment("egg"), expected, "{}", path);
+        }
+        assert_eq!(CommentSyntax::for_path("src/main.rs"), Some(CommentSyntax::CStyle));
+        assert_eq!(CommentSyntax::for_path("tools/build.py"), Some(CommentSyntax::Hash));
+        assert_eq!(CommentSyntax::for_path("site/index.html"), None);
+    }
+
+    #[test]
+    fn test_comment_less_formats_get_no_style() {
+        for path in ["package.json", "data.csv", "notes.txt", "Makefile"] {
+            assert_eq!(CommentStyle::for_path(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_single_quoted_strings_are_not_comments() {
+        let js = "const sep = '//'; // split on slashes\nsplit(sep);";
+        assert_eq!(code_without_comments(js, CommentSyntax::CStyle), "const sep = '//'; split(sep);");
+
+        let python = "tag = '#todo'  # marker\nprint(tag)";
+        assert_eq!(code_without_comments(python, CommentSyntax::Hash), "tag = '#todo' print(tag)");
+
+        let rust = "fn first<'a>(s: &'a str) -> &str { s } // borrowed\n";
+        assert_eq!(code_without_comments(rust, CommentSyntax::CStyle), "fn first<'a>(s: &'a str) -> &str { s }");
+    }
+}
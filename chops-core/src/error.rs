@@ -41,6 +41,12 @@ pub enum CHOPSError {
     #[error("Rate limit exceeded: {0}")]
     RateLimitError(String),
 
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Output did not match the schema: {0}")]
+    SchemaValidation(String),
+
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
 }
@@ -65,6 +71,9 @@ pub enum ChaosError {
     #[error("Entropy source unavailable: {0}")]
     EntropySourceUnavailable(String),
 
+    #[error("Unknown entropy source: {0}")]
+    UnknownEntropySource(String),
+
     #[error("Chaos calculation failed: {0}")]
     CalculationFailed(String),
 }
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+use crate::{CHOPSError, CHOPSResult, GeneratedIdea};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoryExportFormat {
+    Json,
+    Obsidian,
+}
+
+crate::impl_enum_str!(MemoryExportFormat {
+    Json => "json",
+    Obsidian => "obsidian",
+});
+
+/// Writes one markdown note per idea into `dir`, linked together with
+/// `[[wikilinks]]` to personas, tags and parent ideas so Obsidian/Logseq
+/// render the idea history as a graph. Returns the number of notes written.
+#[tracing::instrument(name = "export_obsidian_vault", level = "info", skip(ideas))]
+pub fn export_obsidian_vault(ideas: &[&GeneratedIdea], dir: &Path) -> CHOPSResult<usize> {
+    std::fs::create_dir_all(dir).map_err(CHOPSError::FileSystemError)?;
+
+    let note_names: HashMap<Uuid, String> = ideas
+        .iter()
+        .map(|idea| (idea.id, note_name(idea)))
+        .collect();
+
+    for idea in ideas {
+        let path = dir.join(format!("{}.md", note_names[&idea.id]));
+        std::fs::write(&path, render_note(idea, &note_names)).map_err(CHOPSError::FileSystemError)?;
+        tracing::debug!("Wrote note {}", path.display());
+    }
+
+    let mut index = String::from("# CHOPS Ideas\n\n#chops\n\n");
+    for idea in ideas {
+        index.push_str(&format!(
+            "- [[{}]] ({}, {})\n",
+            note_names[&idea.id],
+            idea.persona_used,
+            idea.timestamp.format("%Y-%m-%d")
+        ));
+    }
+    std::fs::write(dir.join("CHOPS Ideas.md"), index).map_err(CHOPSError::FileSystemError)?;
+
+    tracing::info!("Exported {} ideas to {}", ideas.len(), dir.display());
+    Ok(ideas.len())
+}
+
+/// Title plus short id, stripped of characters wikilinks and filesystems
+/// (Windows included) choke on. The id suffix also keeps reserved names like
+/// `CON` and trailing dots out of file names.
+fn note_name(idea: &GeneratedIdea) -> String {
+    let title: String = idea.title
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '[' | ']' | '#' | '|' | '^' | '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>'))
+        .take(60)
+        .collect();
+    let title = title.trim();
+    let short_id = &idea.id.to_string()[..8];
+
+    if title.is_empty() {
+        format!("Idea {}", short_id)
+    } else {
+        format!("{} ({})", title, short_id)
+    }
+}
+
+fn tag_slug(tag: &str) -> String {
+    tag.trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn render_note(idea: &GeneratedIdea, note_names: &HashMap<Uuid, String>) -> String {
+    let tags: Vec<String> = idea.tags.iter().map(|tag| tag_slug(tag)).filter(|tag| !tag.is_empty()).collect();
+
+    let mut note = String::new();
+    note.push_str("---\n");
+    note.push_str(&format!("id: {}\n", idea.id));
+    note.push_str(&format!("persona: {}\n", idea.persona_used));
+    note.push_str(&format!("chaos_level: {:.2}\n", idea.chaos_level));
+    note.push_str(&format!("creativity: {:.2}\n", idea.creativity_score));
+    note.push_str(&format!("feasibility: {:.2}\n", idea.feasibility_score));
+    note.push_str(&format!("created: {}\n", idea.timestamp.to_rfc3339()));
+    if let Some(parent_id) = idea.parent_id {
+        note.push_str(&format!("parent: {}\n", parent_id));
+    }
+    note.push_str("---\n\n");
+
+    note.push_str(&format!("# {}\n\n", idea.title));
+
+    let mut hashtags = vec!["#chops".to_string()];
+    hashtags.extend(tags.iter().map(|tag| format!("#{}", tag)));
+    note.push_str(&format!("{}\n\n", hashtags.join(" ")));
+
+    note.push_str(&format!("**Persona:** [[{}]]\n", idea.persona_used));
+    if let Some(parent_id) = idea.parent_id {
+        // A parent that has aged out of memory still gets a (dangling) link by id
+        let parent = note_names
+            .get(&parent_id)
+            .cloned()
+            .unwrap_or_else(|| format!("Idea {}", &parent_id.to_string()[..8]));
+        note.push_str(&format!("**Built on:** [[{}]]\n", parent));
+    }
+    if !tags.is_empty() {
+        let links: Vec<String> = tags.iter().map(|tag| format!("[[{}]]", tag)).collect();
+        note.push_str(&format!("**Tags:** {}\n", links.join(", ")));
+    }
+
+    note.push_str(&format!("\n## Idea\n\n{}\n", idea.description.trim()));
+
+    if !idea.implementation_hints.is_empty() {
+        note.push_str("\n## Implementation hints\n\n");
+        for hint in &idea.implementation_hints {
+            note.push_str(&format!("- {}\n", hint));
+        }
+    }
+
+    if !idea.potential_risks.is_empty() {
+        note.push_str("\n## Risks\n\n");
+        for risk in &idea.potential_risks {
+            note.push_str(&format!("- {}\n", risk));
+        }
+    }
+
+    if !idea.analogies.is_empty() {
+        note.push_str("\n## Analogies\n\n");
+        for analogy in &idea.analogies {
+            note.push_str(&format!("- [[{}]]: {}\n", analogy.source_domain, analogy.insight));
+        }
+    }
+
+    note
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use crate::{CHOPSError, CHOPSResult};
+
+/// Directory, next to a mutated file, that keeps its earlier versions
+pub const HISTORY_DIR: &str = ".chops-history";
+
+/// Versions kept per file before the oldest are dropped
+pub const MAX_HISTORY_DEPTH: usize = 20;
+
+const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// One earlier version of a file
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub content: String,
+    /// Where the snapshot is stored; `None` for in-memory histories
+    pub path: Option<PathBuf>,
+}
+
+/// Undo stack of a file's contents before each mutation, newest last. A
+/// history opened on disk mirrors every snapshot into
+/// `.chops-history/<file name>/` so undo works across sessions.
+#[derive(Debug, Clone)]
+pub struct MutationHistory {
+    dir: Option<PathBuf>,
+    max_depth: usize,
+    snapshots: Vec<Snapshot>,
+    /// Keeps snapshot names unique when two land in the same millisecond
+    next_sequence: u64,
+}
+
+impl MutationHistory {
+    /// A history that lives only as long as this value
+    pub fn in_memory(max_depth: usize) -> Self {
+        Self {
+            dir: None,
+            max_depth: max_depth.max(1),
+            snapshots: Vec::new(),
+            next_sequence: 0,
+        }
+    }
+
+    /// The on-disk history of `file`, with any snapshots earlier sessions left
+    #[tracing::instrument(name = "mutation_history_open", level = "debug")]
+    pub fn open(file: &Path, max_depth: usize) -> CHOPSResult<Self> {
+        let file = std::path::absolute(file).map_err(CHOPSError::FileSystemError)?;
+        let name = file.file_name()
+            .ok_or_else(|| CHOPSError::InvalidParameter(format!("{} is not a file", file.display())))?;
+        let dir = file.parent().unwrap_or(Path::new(".")).join(HISTORY_DIR).join(name);
+
+        let mut snapshots = Vec::new();
+        if dir.is_dir() {
+            let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+                .map_err(CHOPSError::FileSystemError)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION))
+                .collect();
+            // Names start with the timestamp, so they sort oldest first
+            paths.sort();
+            for path in paths {
+                let content = std::fs::read_to_string(&path).map_err(CHOPSError::FileSystemError)?;
+                let taken_at = std::fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(|_| Utc::now());
+                snapshots.push(Snapshot { taken_at, content, path: Some(path) });
+            }
+        }
+        tracing::debug!("Loaded {} snapshots from {}", snapshots.len(), dir.display());
+
+        let next_sequence = snapshots.last()
+            .and_then(|snapshot| snapshot.path.as_ref())
+            .and_then(|path| path.file_stem()?.to_str()?.rsplit_once('-')?.1.parse::<u64>().ok())
+            .map_or(0, |sequence| sequence + 1);
+        let mut history = Self {
+            dir: Some(dir),
+            max_depth: max_depth.max(1),
+            snapshots,
+            next_sequence,
+        };
+        history.trim()?;
+        Ok(history)
+    }
+
+    /// Remembers `content` as the version to go back to, dropping the
+    /// oldest snapshot once the history is full
+    pub fn push(&mut self, content: &str) -> CHOPSResult<()> {
+        let taken_at = Utc::now();
+        let path = match &self.dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir).map_err(CHOPSError::FileSystemError)?;
+                let path = dir.join(format!("{}-{:04}.{}", taken_at.format("%Y%m%dT%H%M%S%.3f"), self.next_sequence, SNAPSHOT_EXTENSION));
+                self.next_sequence += 1;
+                std::fs::write(&path, content).map_err(CHOPSError::FileSystemError)?;
+                Some(path)
+            },
+            None => None,
+        };
+        self.snapshots.push(Snapshot { taken_at, content: content.to_string(), path });
+        self.trim()
+    }
+
+    /// Writes the newest snapshot back to `file` and only then drops it from
+    /// the history, so a failed write leaves the snapshot to retry from.
+    /// `None` when there is nothing to undo.
+    pub fn undo(&mut self, file: &Path) -> CHOPSResult<Option<Snapshot>> {
+        let Some(snapshot) = self.snapshots.last() else {
+            return Ok(None);
+        };
+        std::fs::write(file, &snapshot.content).map_err(CHOPSError::FileSystemError)?;
+        let snapshot = self.snapshots.pop().expect("history has a newest snapshot");
+        if let Some(path) = &snapshot.path {
+            std::fs::remove_file(path).map_err(CHOPSError::FileSystemError)?;
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// Snapshots, oldest first
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    fn trim(&mut self) -> CHOPSResult<()> {
+        let excess = self.snapshots.len().saturating_sub(self.max_depth);
+        for snapshot in self.snapshots.drain(..excess) {
+            if let Some(path) = &snapshot.path {
+                tracing::debug!("Dropping snapshot {} beyond depth {}", path.display(), self.max_depth);
+                std::fs::remove_file(path).map_err(CHOPSError::FileSystemError)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_undo_restores_versions_newest_first() {
+        let dir = std::env::temp_dir().join(format!("chops-history-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+
+        let mut history = MutationHistory::in_memory(2);
+        for version in ["v1", "v2", "v3"] {
+            history.push(version).unwrap();
+        }
+        assert_eq!(history.len(), 2);
+
+        assert_eq!(history.undo(&file).unwrap().unwrap().content, "v3");
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v3");
+        assert_eq!(history.undo(&file).unwrap().unwrap().content, "v2");
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "v2");
+        assert!(history.undo(&file).unwrap().is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_undo_keeps_the_snapshot() {
+        let dir = std::env::temp_dir().join(format!("chops-history-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("lib.rs");
+        std::fs::write(&file, "current").unwrap();
+
+        let mut history = MutationHistory::open(&file, MAX_HISTORY_DEPTH).unwrap();
+        history.push("previous").unwrap();
+        let snapshot_path = history.snapshots()[0].path.clone().unwrap();
+
+        // A directory can't be written over, so the restore fails
+        assert!(history.undo(&dir).is_err());
+        assert_eq!(history.len(), 1);
+        assert!(snapshot_path.exists());
+
+        history.undo(&file).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "previous");
+        assert!(!snapshot_path.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
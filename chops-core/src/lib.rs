@@ -1,9 +1,33 @@
+mod macros;
+
 pub mod config;
 pub mod error;
 pub mod types;
 pub mod memory;
+pub mod merge;
+pub mod query;
+pub mod backup;
+pub mod ranking;
+pub mod recall;
+pub mod export;
+pub mod diff;
+pub mod analytics;
+pub mod checkpoint;
+pub mod history;
+pub mod render;
 
 pub use config::*;
 pub use error::*;
 pub use types::*;
-pub use memory::*;
\ No newline at end of file
+pub use memory::*;
+pub use merge::*;
+pub use query::*;
+pub use backup::*;
+pub use ranking::*;
+pub use recall::*;
+pub use export::*;
+pub use diff::*;
+pub use analytics::*;
+pub use checkpoint::*;
+pub use history::*;
+pub use render::*;
\ No newline at end of file
@@ -0,0 +1,106 @@
+/// Implements case-insensitive `FromStr` and kebab-case `Display` for an
+/// enum. Parsing ignores `-`/`_` and case, so `devils-advocate`,
+/// `devils_advocate` and `DevilsAdvocate` all match, and unknown input gets
+/// an error listing the valid names. A variant may accept aliases after its
+/// name (`PseudoRandom => "pseudo" | "pseudo-random"`); it displays as the
+/// first.
+///
+/// An enum may also have one variant wrapping a value, written
+/// `<prefix>:<value>` (`custom:growth-hacker`). It is named after the
+/// fieldless variants, with how to parse and show the value and the error
+/// type and constructor for unknown input:
+///
+/// ```ignore
+/// impl_enum_str!(Source {
+///     Pseudo => "pseudo",
+/// } Device("device") {
+///     parse: |path: &str| Ok(Source::Device(path.into())),
+///     show: |path: &PathBuf| path.display().to_string(),
+///     error: SourceError = SourceError::Unknown,
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_enum_str {
+    (@parse $s:ident, $ty:ident { $($variant:ident => $name:literal $(| $alias:literal)*),+ }) => {
+        let normalize = |value: &str| -> String {
+            value.chars()
+                .filter(|c| *c != '-' && *c != '_' && !c.is_whitespace())
+                .flat_map(|c| c.to_lowercase())
+                .collect()
+        };
+        let wanted = normalize($s);
+
+        $(
+            if [$name $(, $alias)*].iter().any(|name| wanted == normalize(name)) {
+                return Ok($ty::$variant);
+            }
+        )+
+    };
+
+    ($ty:ident { $($variant:ident => $name:literal $(| $alias:literal)*),+ $(,)? }) => {
+        impl $ty {
+            pub const VARIANT_NAMES: &'static [&'static str] = &[$($name),+];
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let name = match self {
+                    $($ty::$variant => $name),+
+                };
+                write!(f, "{}", name)
+            }
+        }
+
+        impl std::str::FromStr for $ty {
+            type Err = $crate::CHOPSError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $crate::impl_enum_str!(@parse s, $ty { $($variant => $name $(| $alias)*),+ });
+
+                Err($crate::CHOPSError::InvalidParameter(format!(
+                    "Unknown {} '{}' (valid: {})",
+                    stringify!($ty),
+                    s,
+                    Self::VARIANT_NAMES.join(", ")
+                )))
+            }
+        }
+    };
+
+    ($ty:ident { $($variant:ident => $name:literal $(| $alias:literal)*),+ $(,)? }
+     $wrapped:ident($prefix:literal) {
+        parse: $parse:expr,
+        show: $show:expr,
+        error: $error:ty = $unknown:expr $(,)?
+     }) => {
+        impl $ty {
+            /// Names of the fieldless variants
+            pub const VARIANT_NAMES: &'static [&'static str] = &[$($name),+];
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $($ty::$variant => write!(f, "{}", $name),)+
+                    $ty::$wrapped(value) => write!(f, "{}:{}", $prefix, ($show)(value)),
+                }
+            }
+        }
+
+        impl std::str::FromStr for $ty {
+            type Err = $error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                // Only the prefix is case-insensitive; the value is parsed as given
+                if let Some((prefix, value)) = s.split_once(':') {
+                    if prefix.trim().eq_ignore_ascii_case($prefix) {
+                        return ($parse)(value);
+                    }
+                }
+                $crate::impl_enum_str!(@parse s, $ty { $($variant => $name $(| $alias)*),+ });
+
+                Err(($unknown)(s.to_string()))
+            }
+        }
+    };
+}
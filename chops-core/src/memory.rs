@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use crate::{CHOPSError, CHOPSResult, GeneratedIdea, PersonaType};
+use crate::{cmp_scores, ChaosAnalytics, ChaosSample, CHOPSError, CHOPSResult, GeneratedIdea, PersonaType, ScoreProfile};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemorySystem {
@@ -26,6 +26,9 @@ pub struct WorkingMemory {
     pub chaos_momentum: f64,
     pub creativity_temperature: f64,
     pub cognitive_load: f64,
+    /// When momentum was last fed by an idea; momentum fades back to neutral from here
+    #[serde(default)]
+    pub momentum_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +37,9 @@ pub struct LongTermMemory {
     pub persona_effectiveness: HashMap<PersonaType, EffectivenessMetrics>,
     pub domain_knowledge: HashMap<String, DomainKnowledge>,
     pub user_preferences: UserPreferences,
+    /// Ideas consolidated out of short-term memory, oldest first
+    #[serde(default)]
+    pub archived_ideas: VecDeque<GeneratedIdea>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,13 +59,84 @@ pub struct PatternRecord {
     pub context_tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EffectivenessMetrics {
     pub average_creativity_score: f64,
     pub average_feasibility_score: f64,
+    /// Share of rated ideas the user kept, over `satisfaction_ratings` verdicts
     pub user_satisfaction_rating: f64,
     pub usage_frequency: u32,
     pub domains_used_in: Vec<String>,
+    /// API tokens spent generating ideas with this persona
+    #[serde(default)]
+    pub total_tokens: u64,
+    /// Estimated API cost in USD of those tokens
+    #[serde(default)]
+    pub total_cost: f64,
+    /// Scores broken down by the domain the persona was summoned for
+    #[serde(default)]
+    pub domain_scores: HashMap<String, DomainScore>,
+    /// Kept-or-not verdicts folded into `user_satisfaction_rating`
+    #[serde(default)]
+    pub satisfaction_ratings: u32,
+}
+
+/// Satisfaction assumed for a persona none of whose ideas have been rated yet
+const NEUTRAL_SATISFACTION: f64 = 0.5;
+
+impl EffectivenessMetrics {
+    /// Folds one "was this useful?" verdict into the satisfaction rating
+    pub fn record_satisfaction(&mut self, kept: bool) {
+        self.satisfaction_ratings += 1;
+        let verdict = if kept { 1.0 } else { 0.0 };
+        self.user_satisfaction_rating +=
+            (verdict - self.user_satisfaction_rating) / self.satisfaction_ratings as f64;
+    }
+
+    /// Satisfaction so far, or a neutral 0.5 before any idea was rated
+    pub fn satisfaction(&self) -> f64 {
+        if self.satisfaction_ratings == 0 {
+            NEUTRAL_SATISFACTION
+        } else {
+            self.user_satisfaction_rating
+        }
+    }
+
+    /// Creativity × satisfaction, the score persona recommendations rank by
+    pub fn recommendation_score(&self) -> f64 {
+        self.average_creativity_score * self.satisfaction()
+    }
+}
+
+/// The persona with the best track record in a domain
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonaTrackRecord {
+    pub persona: PersonaType,
+    /// Historical creativity × satisfaction, see [`EffectivenessMetrics::recommendation_score`]
+    pub score: f64,
+    pub uses: u32,
+}
+
+/// Running averages for one persona within one domain
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DomainScore {
+    pub uses: u32,
+    pub average_creativity_score: f64,
+    pub average_feasibility_score: f64,
+}
+
+impl DomainScore {
+    /// Blended score used to rank and color persona/domain pairs
+    pub fn overall(&self) -> f64 {
+        (self.average_creativity_score + self.average_feasibility_score) / 2.0
+    }
+
+    fn record(&mut self, creativity: f64, feasibility: f64) {
+        self.uses += 1;
+        let n = self.uses as f64;
+        self.average_creativity_score += (creativity - self.average_creativity_score) / n;
+        self.average_feasibility_score += (feasibility - self.average_feasibility_score) / n;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,8 +167,35 @@ pub struct SessionEpisode {
     pub personas_invoked: Vec<PersonaType>,
     pub ideas_generated: u32,
     pub overall_satisfaction: Option<f64>,
+    /// Score snapshots of the session's ideas, kept after short-term memory forgets them
+    #[serde(default)]
+    pub ideas: Vec<SessionIdea>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionIdea {
+    pub id: Uuid,
+    pub title: String,
+    pub persona_used: PersonaType,
+    pub creativity_score: f64,
+    pub feasibility_score: f64,
+    pub novelty_score: f64,
+    pub excitement_factor: f64,
+    #[serde(default)]
+    pub chaos_level: f64,
+    #[serde(default)]
+    pub coherence_score: Option<f64>,
+    /// The user's answer to "was this useful?", when they were asked
+    #[serde(default)]
+    pub kept: Option<bool>,
+}
+
+/// Ideas a chaos level needs before its analytics outweigh the creativity heuristic
+const MIN_CHAOS_LEVEL_SAMPLES: usize = 3;
+
+/// Runs started within this long of the last activity continue the same session
+const SESSION_IDLE_GAP_MINUTES: i64 = 30;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BreakthroughMoment {
     pub id: Uuid,
@@ -120,6 +224,88 @@ pub struct FailureLearning {
     pub failure_reason: String,
     pub lessons_learned: Vec<String>,
     pub suggested_alternatives: Vec<String>,
+    /// The idea this was learned from, when there was one
+    #[serde(default)]
+    pub idea_id: Option<Uuid>,
+}
+
+/// Ideas leaving short-term memory more creative than this become breakthrough moments
+const BREAKTHROUGH_CREATIVITY_SCORE: f64 = 0.85;
+
+/// Ideas leaving short-term memory less feasible than this become failure learnings
+const FAILED_FEASIBILITY_SCORE: f64 = 0.2;
+
+/// Ideas must score above both of these for their tags to become successful patterns
+const PATTERN_CREATIVITY_SCORE: f64 = 0.7;
+const PATTERN_FEASIBILITY_SCORE: f64 = 0.6;
+
+/// Whether an idea scored high enough for its tags to become successful patterns
+fn teaches_patterns(idea: &GeneratedIdea) -> bool {
+    idea.creativity_score > PATTERN_CREATIVITY_SCORE && idea.feasibility_score > PATTERN_FEASIBILITY_SCORE
+}
+
+/// What a consolidation pass moved out of short-term memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsolidationReport {
+    /// Ideas evicted from short-term memory
+    pub ideas: usize,
+    /// Evicted ideas whose tags live on as successful patterns
+    pub promoted: usize,
+    pub breakthroughs: usize,
+    pub failures: usize,
+}
+
+/// Patterns whose success rate, faded by age, falls below this are prune candidates
+const PRUNE_EFFECTIVENESS_THRESHOLD: f64 = 0.35;
+
+/// Consolidated ideas long-term memory keeps; the oldest go first
+const MAX_ARCHIVED_IDEAS: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum PruneKind {
+    Pattern,
+    DomainKnowledge,
+    ExpiredIdea,
+    FailureLearning,
+}
+
+/// Something `chops memory prune` proposes to forget, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneCandidate {
+    pub kind: PruneKind,
+    /// Map key, idea id or failure timestamp identifying the entry
+    pub key: String,
+    pub label: String,
+    pub reason: String,
+}
+
+/// An idea and the ideas it was refined from, newest first
+#[derive(Debug, Clone)]
+pub struct IdeaLineage<'a> {
+    pub chain: Vec<&'a GeneratedIdea>,
+    /// A parent link led back into the chain at this idea, so the walk stopped
+    pub cycle_at: Option<Uuid>,
+    /// The walk hit its depth limit with older ancestors left
+    pub truncated: bool,
+}
+
+impl<'a> IdeaLineage<'a> {
+    /// Refinements on top of the oldest idea walked
+    pub fn depth(&self) -> usize {
+        self.chain.len().saturating_sub(1)
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.chain.iter().map(|idea| idea.tokens_used).sum()
+    }
+
+    /// Highest-scoring idea in the chain; ties go to the newer one
+    pub fn best(&self, profile: ScoreProfile) -> Option<&'a GeneratedIdea> {
+        let score = |idea: &GeneratedIdea| profile.score(idea.creativity_score, idea.feasibility_score, idea.novelty_score, idea.excitement_factor);
+        self.chain.iter()
+            .copied()
+            .min_by(|a, b| cmp_scores(score(a), score(b)))
+    }
 }
 
 impl MemorySystem {
@@ -162,6 +348,11 @@ impl MemorySystem {
     }
     
     pub fn add_idea(&mut self, idea: GeneratedIdea) {
+        // Learn from what short-term memory is about to forget before it goes
+        if self.short_term.needs_consolidation(Utc::now()) {
+            self.consolidate();
+        }
+        
         // Add to short-term memory
         self.short_term.add_idea(idea.clone());
         
@@ -175,7 +366,355 @@ impl MemorySystem {
         self.long_term.update_persona_effectiveness(&idea);
     }
     
-    pub fn recall_similar_ideas(&self, query: &str, limit: usize) -> Vec<&GeneratedIdea> {
+    /// Evicts the ideas short-term memory is due to forget — expired ones, and
+    /// the oldest while it is full — promoting what they taught into episodic
+    /// memory: very creative ideas become breakthrough moments and infeasible
+    /// ones failure learnings. Tags of high-scoring ideas were already
+    /// learned as successful patterns when the ideas were added.
+    #[tracing::instrument(name = "memory_consolidate", level = "debug", skip(self))]
+    pub fn consolidate(&mut self) -> ConsolidationReport {
+        let evicted = self.short_term.evict_due(Utc::now());
+        self.consolidate_evicted(&evicted)
+    }
+    
+    /// Consolidates ideas already taken out of short-term memory
+    pub(crate) fn consolidate_evicted(&mut self, evicted: &[GeneratedIdea]) -> ConsolidationReport {
+        let mut report = ConsolidationReport::default();
+        for idea in evicted {
+            self.consolidate_idea(idea, &mut report);
+        }
+        if report.ideas > 0 {
+            tracing::info!(
+                "Consolidated {} ideas: {} promoted to patterns, {} breakthroughs, {} failure learnings",
+                report.ideas, report.promoted, report.breakthroughs, report.failures
+            );
+        }
+        report
+    }
+    
+    fn consolidate_idea(&mut self, idea: &GeneratedIdea, report: &mut ConsolidationReport) {
+        report.ideas += 1;
+        self.long_term.archive_idea(idea.clone());
+        if teaches_patterns(idea) {
+            report.promoted += 1;
+        }
+        if idea.creativity_score > BREAKTHROUGH_CREATIVITY_SCORE {
+            self.episodic.record_breakthrough(BreakthroughMoment::from_idea(idea));
+            report.breakthroughs += 1;
+        }
+        // Summon already learns from its own low scorers; don't learn twice
+        let already_learned = self.episodic.failure_learnings.iter().any(|failure| failure.idea_id == Some(idea.id));
+        if idea.feasibility_score < FAILED_FEASIBILITY_SCORE && !already_learned {
+            self.episodic.record_failure(FailureLearning::from_infeasible_idea(idea));
+            report.failures += 1;
+        }
+    }
+    
+    /// Continues the latest session if it was active recently, otherwise starts
+    /// a new one. Returns the session id.
+    pub fn begin_session(&mut self, now: DateTime<Utc>) -> Uuid {
+        if let Some(episode) = self.episodic.session_history.back() {
+            let last_active = episode.end_time.unwrap_or(episode.start_time);
+            if now - last_active < chrono::Duration::minutes(SESSION_IDLE_GAP_MINUTES) {
+                tracing::debug!("Continuing session {}", episode.session_id);
+                return episode.session_id;
+            }
+        }
+
+        let session_id = Uuid::new_v4();
+        tracing::debug!("Starting session {}", session_id);
+        self.episodic.record_session(SessionEpisode {
+            session_id,
+            start_time: now,
+            end_time: None,
+            commands_used: Vec::new(),
+            personas_invoked: Vec::new(),
+            ideas_generated: 0,
+            overall_satisfaction: None,
+            ideas: Vec::new(),
+        });
+        session_id
+    }
+
+    /// Adds an idea to a session's tally and score snapshots
+    pub fn record_session_idea(&mut self, session_id: Uuid, idea: &GeneratedIdea, coherence_score: Option<f64>) {
+        let Some(episode) = self.episodic.session_history.iter_mut().find(|episode| episode.session_id == session_id) else {
+            tracing::warn!("Session {} not found, idea {} not recorded against it", session_id, idea.id);
+            return;
+        };
+
+        episode.ideas_generated += 1;
+        episode.end_time = Some(idea.timestamp);
+        if !episode.personas_invoked.contains(&idea.persona_used) {
+            episode.personas_invoked.push(idea.persona_used.clone());
+        }
+        episode.ideas.push(SessionIdea {
+            id: idea.id,
+            title: idea.title.clone(),
+            persona_used: idea.persona_used.clone(),
+            creativity_score: idea.creativity_score,
+            feasibility_score: idea.feasibility_score,
+            novelty_score: idea.novelty_score,
+            excitement_factor: idea.excitement_factor,
+            chaos_level: idea.chaos_level,
+            coherence_score,
+            kept: None,
+        });
+    }
+
+    /// Records whether the user found the session's most recent idea useful
+    pub fn rate_latest_session_idea(&mut self, session_id: Uuid, kept: bool) {
+        let idea = self.episodic.session_history
+            .iter_mut()
+            .find(|episode| episode.session_id == session_id)
+            .and_then(|episode| episode.ideas.last_mut());
+        match idea {
+            Some(idea) => {
+                idea.kept = Some(kept);
+                self.long_term.persona_effectiveness
+                    .entry(idea.persona_used.clone())
+                    .or_default()
+                    .record_satisfaction(kept);
+            },
+            None => tracing::warn!("No ideas recorded in session {}, rating dropped", session_id),
+        }
+    }
+
+    /// Every remembered idea as (chaos level, scores, user verdict): session
+    /// snapshots first, plus short-term ideas that predate session tracking
+    pub fn chaos_samples(&self) -> Vec<ChaosSample> {
+        let mut seen = std::collections::HashSet::new();
+        let mut samples = Vec::new();
+
+        for idea in self.episodic.session_history.iter().flat_map(|episode| &episode.ideas) {
+            if seen.insert(idea.id) {
+                samples.push(ChaosSample {
+                    persona: idea.persona_used.clone(),
+                    chaos_level: idea.chaos_level,
+                    creativity_score: idea.creativity_score,
+                    feasibility_score: idea.feasibility_score,
+                    coherence_score: idea.coherence_score,
+                    kept: idea.kept,
+                });
+            }
+        }
+        for idea in &self.short_term.recent_ideas {
+            if seen.insert(idea.id) {
+                samples.push(ChaosSample {
+                    persona: idea.persona_used.clone(),
+                    chaos_level: idea.chaos_level,
+                    creativity_score: idea.creativity_score,
+                    feasibility_score: idea.feasibility_score,
+                    coherence_score: None,
+                    kept: None,
+                });
+            }
+        }
+
+        samples
+    }
+
+    /// A session by id or id prefix; the most recent one when `id` is
+    /// `None`. A prefix shared by several sessions is an error listing them.
+    pub fn find_session(&self, id: Option<&str>) -> CHOPSResult<Option<&SessionEpisode>> {
+        let Some(id) = id else {
+            return Ok(self.episodic.session_history.back());
+        };
+
+        let matches: Vec<&SessionEpisode> = self.episodic.session_history
+            .iter()
+            .filter(|episode| episode.session_id.to_string().starts_with(&id.trim().to_lowercase()))
+            .collect();
+        unique_prefix_match(id, "session", matches, |episode| {
+            format!("{} ({})", &episode.session_id.to_string()[..8], episode.start_time.format("%Y-%m-%d %H:%M"))
+        })
+    }
+
+    /// Adds the API tokens and cost of a generation to the persona's running totals
+    pub fn record_persona_cost(&mut self, persona: &PersonaType, tokens: u64, cost: f64) {
+        tracing::debug!("Recording {} tokens (${:.4}) against {}", tokens, cost, persona);
+        self.long_term.record_persona_cost(persona, tokens, cost);
+    }
+
+    /// Records an idea's scores against the domain it was summoned for
+    pub fn record_persona_domain(&mut self, idea: &GeneratedIdea, domain: &str) {
+        tracing::debug!("Recording {} result in domain '{}'", idea.persona_used, domain);
+        self.long_term.record_domain_score(idea, domain);
+    }
+
+    /// Every remembered idea, oldest first: those consolidated into long-term
+    /// memory, then short-term memory's
+    pub fn all_ideas(&self) -> impl DoubleEndedIterator<Item = &GeneratedIdea> {
+        self.long_term.archived_ideas.iter().chain(self.short_term.recent_ideas.iter())
+    }
+
+    /// Looks an idea up by its id or id prefix. A prefix shared by several
+    /// ideas is an error listing them.
+    pub fn find_idea(&self, id: &str) -> CHOPSResult<Option<&GeneratedIdea>> {
+        let prefix = id.trim().to_lowercase();
+        let matches: Vec<&GeneratedIdea> = self.all_ideas()
+            .filter(|idea| idea.id.to_string().starts_with(&prefix))
+            .collect();
+        unique_prefix_match(id, "idea", matches, |idea| format!("{} ({})", &idea.id.to_string()[..8], idea.title))
+    }
+    
+    /// Walks parent links from `id` back toward the root of its refinement
+    /// chain, keeping at most `max_depth + 1` ideas. A parent link that leads
+    /// back into the chain is reported as a cycle and the walk stops there.
+    #[tracing::instrument(name = "idea_lineage", level = "debug", skip(self))]
+    pub fn idea_lineage(&self, id: Uuid, max_depth: usize) -> IdeaLineage<'_> {
+        let find = |id: Uuid| self.all_ideas().find(|idea| idea.id == id);
+        let mut lineage = IdeaLineage { chain: Vec::new(), cycle_at: None, truncated: false };
+        let mut current = find(id);
+
+        while let Some(idea) = current {
+            lineage.chain.push(idea);
+            let Some(parent) = idea.parent_id else {
+                break;
+            };
+            if lineage.chain.iter().any(|seen| seen.id == parent) {
+                tracing::warn!("Idea lineage cycles back to {}; stopping there", parent);
+                lineage.cycle_at = Some(parent);
+                break;
+            }
+            if lineage.chain.len() > max_depth {
+                lineage.truncated = true;
+                break;
+            }
+            current = find(parent);
+        }
+
+        tracing::debug!("Lineage of {} has {} ideas", id, lineage.chain.len());
+        lineage
+    }
+
+    pub fn record_failure(&mut self, failure: FailureLearning) {
+        tracing::debug!("Recording failure in '{}': {}", failure.failure_context, failure.failure_reason);
+        self.episodic.record_failure(failure);
+    }
+    
+    /// Past failures sharing words with `query`, most overlapping (then newest) first
+    pub fn relevant_failures(&self, query: &str, limit: usize) -> Vec<&FailureLearning> {
+        let query_words = significant_words(query);
+        if query_words.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scored: Vec<(usize, &FailureLearning)> = self.episodic.failure_learnings
+            .iter()
+            .map(|failure| {
+                let failure_words = significant_words(&format!("{} {}", failure.failure_context, failure.attempted_approach));
+                (query_words.iter().filter(|word| failure_words.contains(*word)).count(), failure)
+            })
+            .filter(|(overlap, _)| *overlap > 0)
+            .collect();
+
+        scored.sort_by(|(overlap_a, a), (overlap_b, b)| {
+            overlap_b.cmp(overlap_a).then_with(|| b.timestamp.cmp(&a.timestamp))
+        });
+        scored.into_iter().take(limit).map(|(_, failure)| failure).collect()
+    }
+    
+    /// Low-value entries as of `now`. Pattern effectiveness is the success rate
+    /// halved every `stale_after`; domain knowledge and failure learnings go stale
+    /// after `stale_after`; ideas expire after short-term retention.
+    pub fn prune_candidates(&self, now: DateTime<Utc>, stale_after: chrono::Duration) -> Vec<PruneCandidate> {
+        let mut candidates = Vec::new();
+        let stale_days = (stale_after.num_seconds() as f64 / 86_400.0).max(f64::EPSILON);
+        let age_days = |then: DateTime<Utc>| (now - then).num_seconds().max(0) as f64 / 86_400.0;
+
+        let mut patterns: Vec<_> = self.long_term.successful_patterns.iter().collect();
+        patterns.sort_by_key(|(key, _)| *key);
+        for (key, record) in patterns {
+            let effectiveness = record.success_rate * 0.5_f64.powf(age_days(record.last_used) / stale_days);
+            if effectiveness < PRUNE_EFFECTIVENESS_THRESHOLD {
+                candidates.push(PruneCandidate {
+                    kind: PruneKind::Pattern,
+                    key: key.clone(),
+                    label: record.pattern.clone(),
+                    reason: format!(
+                        "effectiveness {:.2} (success {:.2}, last used {:.0} days ago)",
+                        effectiveness, record.success_rate, age_days(record.last_used)
+                    ),
+                });
+            }
+        }
+
+        let mut domains: Vec<_> = self.long_term.domain_knowledge.iter().collect();
+        domains.sort_by_key(|(key, _)| *key);
+        for (key, knowledge) in domains {
+            if now - knowledge.last_updated > stale_after {
+                candidates.push(PruneCandidate {
+                    kind: PruneKind::DomainKnowledge,
+                    key: key.clone(),
+                    label: knowledge.domain_name.clone(),
+                    reason: format!("not updated for {:.0} days", age_days(knowledge.last_updated)),
+                });
+            }
+        }
+
+        let idea_cutoff = now - chrono::Duration::minutes(self.short_term.retention_minutes as i64);
+        for idea in self.short_term.recent_ideas.iter().filter(|idea| idea.timestamp <= idea_cutoff) {
+            candidates.push(PruneCandidate {
+                kind: PruneKind::ExpiredIdea,
+                key: idea.id.to_string(),
+                label: idea.title.clone(),
+                reason: format!("past the {} minute retention window", self.short_term.retention_minutes),
+            });
+        }
+
+        for failure in self.episodic.failure_learnings.iter().filter(|failure| now - failure.timestamp > stale_after) {
+            candidates.push(PruneCandidate {
+                kind: PruneKind::FailureLearning,
+                key: failure.timestamp.to_rfc3339(),
+                label: failure.failure_context.clone(),
+                reason: format!("recorded {:.0} days ago", age_days(failure.timestamp)),
+            });
+        }
+
+        candidates
+    }
+    
+    /// Removes the given candidates, returning how many entries were dropped
+    pub fn prune(&mut self, candidates: &[PruneCandidate]) -> usize {
+        let mut removed = 0;
+        for candidate in candidates {
+            let was_removed = match candidate.kind {
+                PruneKind::Pattern => self.long_term.successful_patterns.remove(&candidate.key).is_some(),
+                PruneKind::DomainKnowledge => self.long_term.domain_knowledge.remove(&candidate.key).is_some(),
+                PruneKind::ExpiredIdea => {
+                    let position = self.short_term.recent_ideas.iter().position(|idea| idea.id.to_string() == candidate.key);
+                    match position.and_then(|index| self.short_term.recent_ideas.remove(index)) {
+                        Some(idea) => {
+                            self.consolidate_idea(&idea, &mut ConsolidationReport::default());
+                            true
+                        },
+                        None => false,
+                    }
+                },
+                PruneKind::FailureLearning => {
+                    let before = self.episodic.failure_learnings.len();
+                    self.episodic.failure_learnings.retain(|failure| failure.timestamp.to_rfc3339() != candidate.key);
+                    before != self.episodic.failure_learnings.len()
+                },
+            };
+            if was_removed {
+                removed += 1;
+            }
+        }
+        tracing::info!("Pruned {} memory entries", removed);
+        removed
+    }
+    
+    /// Remembered ideas, short- and long-term, ranked by TF-IDF relevance to
+    /// `query`, best first, with their scores in [0, 1]; see [`crate::rank_by_relevance`]
+    pub fn recall_similar_ideas(&self, query: &str, limit: usize, min_score: f64) -> Vec<(f64, &GeneratedIdea)> {
+        crate::rank_by_relevance(query, self.all_ideas(), limit, min_score)
+    }
+    
+    /// Remembered ideas whose title, description or a tag contains `query`
+    /// (case-insensitive), oldest first; an empty query matches every idea
+    pub fn recall_exact(&self, query: &str, limit: usize) -> Vec<&GeneratedIdea> {
         self.short_term.recent_ideas
             .iter()
             .filter(|idea| {
@@ -188,29 +727,59 @@ impl MemorySystem {
     }
     
     pub fn get_persona_recommendation(&self, domain: &str) -> Option<PersonaType> {
+        self.persona_track_record(domain).map(|record| record.persona)
+    }
+    
+    /// The persona that has scored best in `domain` by creativity × satisfaction,
+    /// ties going to the alphabetically first; None without history there
+    pub fn persona_track_record(&self, domain: &str) -> Option<PersonaTrackRecord> {
+        let domain = domain.trim().to_lowercase();
         self.long_term.persona_effectiveness
             .iter()
-            .filter(|(_, metrics)| metrics.domains_used_in.contains(&domain.to_string()))
-            .max_by(|(_, a), (_, b)| {
-                let score_a = a.average_creativity_score * a.user_satisfaction_rating;
-                let score_b = b.average_creativity_score * b.user_satisfaction_rating;
-                score_a.partial_cmp(&score_b).unwrap_or(std::cmp::Ordering::Equal)
+            .filter(|(_, metrics)| metrics.domains_used_in.iter().any(|used| used.to_lowercase() == domain))
+            .min_by(|(persona_a, a), (persona_b, b)| {
+                cmp_scores(a.recommendation_score(), b.recommendation_score())
+                    .then_with(|| persona_a.to_string().cmp(&persona_b.to_string()))
+            })
+            .map(|(persona, metrics)| PersonaTrackRecord {
+                persona: persona.clone(),
+                score: metrics.recommendation_score(),
+                uses: metrics.domain_scores.get(&domain).map_or(metrics.usage_frequency, |score| score.uses),
             })
-            .map(|(persona, _)| persona.clone())
+    }
+    
+    /// Forgets what has been learned about a persona's effectiveness
+    pub fn reset_persona(&mut self, persona: &PersonaType) -> bool {
+        self.long_term.persona_effectiveness.remove(persona).is_some()
     }
     
     pub fn optimize_chaos_level(&self, persona: &PersonaType) -> u8 {
+        // Prefer the level that has actually worked best for this persona
+        let samples: Vec<ChaosSample> = self.chaos_samples()
+            .into_iter()
+            .filter(|sample| &sample.persona == persona)
+            .collect();
+        if let Some(level) = ChaosAnalytics::from_samples(&samples).best_level(MIN_CHAOS_LEVEL_SAMPLES) {
+            return level;
+        }
+
         if let Some(metrics) = self.long_term.persona_effectiveness.get(persona) {
             // Use historical effectiveness to suggest optimal chaos level
             let base_level = 5;
             let effectiveness_modifier = (metrics.average_creativity_score - 0.5) * 4.0;
-            ((base_level as f64 + effectiveness_modifier).max(1.0).min(11.0)) as u8
+            ((base_level as f64 + effectiveness_modifier).clamp(1.0, 11.0)) as u8
         } else {
             5 // Default chaos level
         }
     }
 }
 
+impl Default for ShortTermMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ShortTermMemory {
     pub fn new() -> Self {
         Self {
@@ -220,6 +789,32 @@ impl ShortTermMemory {
         }
     }
     
+    /// Whether adding an idea now would make this memory forget one
+    pub fn needs_consolidation(&self, now: DateTime<Utc>) -> bool {
+        self.recent_ideas.len() >= self.max_capacity
+            || self.recent_ideas.iter().any(|idea| idea.timestamp <= self.retention_cutoff(now))
+    }
+    
+    /// Removes and returns expired ideas, then the oldest until there is room
+    /// for one more
+    pub fn evict_due(&mut self, now: DateTime<Utc>) -> Vec<GeneratedIdea> {
+        let cutoff = self.retention_cutoff(now);
+        let (expired, kept): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.recent_ideas)
+            .into_iter()
+            .partition(|idea| idea.timestamp <= cutoff);
+        self.recent_ideas = kept;
+        
+        let mut evicted: Vec<GeneratedIdea> = expired.into();
+        while !self.recent_ideas.is_empty() && self.recent_ideas.len() >= self.max_capacity {
+            evicted.extend(self.recent_ideas.pop_front());
+        }
+        evicted
+    }
+    
+    fn retention_cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - chrono::Duration::minutes(self.retention_minutes as i64)
+    }
+    
     pub fn add_idea(&mut self, idea: GeneratedIdea) {
         // Remove expired ideas
         let cutoff_time = Utc::now() - chrono::Duration::minutes(self.retention_minutes as i64);
@@ -235,21 +830,53 @@ impl ShortTermMemory {
     }
 }
 
+impl Default for WorkingMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where working-memory momentum settles when nothing has happened for a while
+const NEUTRAL_CHAOS_MOMENTUM: f64 = 0.5;
+const NEUTRAL_CREATIVITY_TEMPERATURE: f64 = 0.7;
+
 impl WorkingMemory {
     pub fn new() -> Self {
         Self {
             active_context: HashMap::new(),
             current_persona_state: None,
-            chaos_momentum: 0.5,
-            creativity_temperature: 0.7,
+            chaos_momentum: NEUTRAL_CHAOS_MOMENTUM,
+            creativity_temperature: NEUTRAL_CREATIVITY_TEMPERATURE,
             cognitive_load: 0.0,
+            momentum_updated_at: None,
         }
     }
     
+    /// Chaos momentum and creativity temperature as of `now`, decayed toward
+    /// neutral with the given half-life so a stale session starts fresh
+    pub fn decayed_momentum(&self, now: DateTime<Utc>, half_life_minutes: f64) -> (f64, f64) {
+        let Some(updated_at) = self.momentum_updated_at else {
+            return (NEUTRAL_CHAOS_MOMENTUM, NEUTRAL_CREATIVITY_TEMPERATURE);
+        };
+
+        let elapsed_minutes = (now - updated_at).num_seconds().max(0) as f64 / 60.0;
+        let retained = if half_life_minutes > 0.0 {
+            0.5_f64.powf(elapsed_minutes / half_life_minutes)
+        } else {
+            0.0
+        };
+
+        (
+            NEUTRAL_CHAOS_MOMENTUM + (self.chaos_momentum - NEUTRAL_CHAOS_MOMENTUM) * retained,
+            NEUTRAL_CREATIVITY_TEMPERATURE + (self.creativity_temperature - NEUTRAL_CREATIVITY_TEMPERATURE) * retained,
+        )
+    }
+    
     pub fn update_from_idea(&mut self, idea: &GeneratedIdea) {
         self.current_persona_state = Some(idea.persona_used.clone());
         self.chaos_momentum = (self.chaos_momentum * 0.8) + (idea.chaos_level * 0.2);
         self.creativity_temperature = (self.creativity_temperature * 0.8) + (idea.creativity_score * 0.2);
+        self.momentum_updated_at = Some(Utc::now());
         
         // Update context with recent idea themes
         for tag in &idea.tags {
@@ -274,6 +901,12 @@ impl WorkingMemory {
     }
 }
 
+impl Default for LongTermMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LongTermMemory {
     pub fn new() -> Self {
         Self {
@@ -281,12 +914,27 @@ impl LongTermMemory {
             persona_effectiveness: HashMap::new(),
             domain_knowledge: HashMap::new(),
             user_preferences: UserPreferences::default(),
+            archived_ideas: VecDeque::new(),
+        }
+    }
+
+    /// Keeps a consolidated idea, dropping the oldest past [`MAX_ARCHIVED_IDEAS`]
+    pub fn archive_idea(&mut self, idea: GeneratedIdea) {
+        if self.archived_ideas.iter().any(|archived| archived.id == idea.id) {
+            return;
+        }
+        self.archived_ideas.push_back(idea);
+        while self.archived_ideas.len() > MAX_ARCHIVED_IDEAS {
+            self.archived_ideas.pop_front();
         }
     }
     
-    pub fn extract_patterns_from_idea(&mut self, idea: &GeneratedIdea) {
+    /// Records the tags of a high-scoring idea as successful patterns,
+    /// returning whether the idea scored high enough
+    pub fn extract_patterns_from_idea(&mut self, idea: &GeneratedIdea) -> bool {
         // Extract patterns from successful ideas (high scores)
-        if idea.creativity_score > 0.7 && idea.feasibility_score > 0.6 {
+        let successful = teaches_patterns(idea);
+        if successful {
             for tag in &idea.tags {
                 let pattern_key = format!("tag_pattern_{}", tag);
                 let pattern = self.successful_patterns
@@ -313,18 +961,34 @@ impl LongTermMemory {
                 }
             }
         }
+        successful
     }
     
+    pub fn record_persona_cost(&mut self, persona: &PersonaType, tokens: u64, cost: f64) {
+        let metrics = self.persona_effectiveness.entry(persona.clone()).or_default();
+        metrics.total_tokens += tokens;
+        metrics.total_cost += cost;
+    }
+
+    pub fn record_domain_score(&mut self, idea: &GeneratedIdea, domain: &str) {
+        let domain = domain.trim().to_lowercase();
+        if domain.is_empty() {
+            return;
+        }
+        let metrics = self.persona_effectiveness.entry(idea.persona_used.clone()).or_default();
+        metrics.domain_scores
+            .entry(domain.clone())
+            .or_default()
+            .record(idea.creativity_score, idea.feasibility_score);
+        if !metrics.domains_used_in.contains(&domain) {
+            metrics.domains_used_in.push(domain);
+        }
+    }
+
     pub fn update_persona_effectiveness(&mut self, idea: &GeneratedIdea) {
         let metrics = self.persona_effectiveness
             .entry(idea.persona_used.clone())
-            .or_insert_with(|| EffectivenessMetrics {
-                average_creativity_score: 0.0,
-                average_feasibility_score: 0.0,
-                user_satisfaction_rating: 0.0,
-                usage_frequency: 0,
-                domains_used_in: Vec::new(),
-            });
+            .or_default();
         
         metrics.usage_frequency += 1;
         metrics.average_creativity_score = (metrics.average_creativity_score * 
@@ -345,6 +1009,12 @@ impl LongTermMemory {
     }
 }
 
+impl Default for EpisodicMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EpisodicMemory {
     pub fn new() -> Self {
         Self {
@@ -354,6 +1024,128 @@ impl EpisodicMemory {
             max_episodes: 100,
         }
     }
+    
+    pub fn record_session(&mut self, episode: SessionEpisode) {
+        self.session_history.push_back(episode);
+        while self.session_history.len() > self.max_episodes {
+            self.session_history.pop_front();
+        }
+    }
+
+    pub fn record_failure(&mut self, failure: FailureLearning) {
+        self.failure_learnings.push(failure);
+        if self.failure_learnings.len() > self.max_episodes {
+            let excess = self.failure_learnings.len() - self.max_episodes;
+            self.failure_learnings.drain(..excess);
+        }
+    }
+
+    pub fn record_breakthrough(&mut self, breakthrough: BreakthroughMoment) {
+        self.breakthrough_moments.push(breakthrough);
+        if self.breakthrough_moments.len() > self.max_episodes {
+            let excess = self.breakthrough_moments.len() - self.max_episodes;
+            self.breakthrough_moments.drain(..excess);
+        }
+    }
+}
+
+impl BreakthroughMoment {
+    /// A breakthrough remembered from an idea, typed by what stood out most
+    pub fn from_idea(idea: &GeneratedIdea) -> Self {
+        let breakthrough_type = if idea.novelty_score > BREAKTHROUGH_CREATIVITY_SCORE {
+            BreakthroughType::ParadigmShift
+        } else if !idea.analogies.is_empty() {
+            BreakthroughType::UnexpectedConnection
+        } else if idea.feasibility_score > PATTERN_FEASIBILITY_SCORE {
+            BreakthroughType::ProblemSolution
+        } else {
+            BreakthroughType::CreativeLeap
+        };
+
+        let mut context = HashMap::from([
+            ("persona".to_string(), idea.persona_used.to_string()),
+            ("chaos_level".to_string(), format!("{:.0}", idea.chaos_level * 11.0)),
+        ]);
+        if let Some(domain) = &idea.domain {
+            context.insert("domain".to_string(), domain.clone());
+        }
+        if !idea.tags.is_empty() {
+            context.insert("tags".to_string(), idea.tags.join(", "));
+        }
+
+        Self {
+            id: Uuid::new_v4(),
+            timestamp: idea.timestamp,
+            idea_id: idea.id,
+            breakthrough_type,
+            description: idea.title.clone(),
+            impact_score: (idea.creativity_score + idea.novelty_score + idea.excitement_factor) / 3.0,
+            context,
+        }
+    }
+}
+
+impl SessionEpisode {
+    /// The session's ideas best first under `profile`, with their scores
+    pub fn ranked_ideas(&self, profile: ScoreProfile) -> Vec<(&SessionIdea, f64)> {
+        let mut ranked: Vec<(&SessionIdea, f64)> = self.ideas
+            .iter()
+            .map(|idea| (idea, profile.score(idea.creativity_score, idea.feasibility_score, idea.novelty_score, idea.excitement_factor)))
+            .collect();
+        ranked.sort_by(|(a, a_score), (b, b_score)| cmp_scores(*a_score, *b_score).then_with(|| a.title.cmp(&b.title)));
+        ranked
+    }
+}
+
+impl FailureLearning {
+    pub fn new(context: &str, approach: &str, reason: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            failure_context: context.to_string(),
+            attempted_approach: approach.to_string(),
+            failure_reason: reason.to_string(),
+            lessons_learned: Vec::new(),
+            suggested_alternatives: Vec::new(),
+            idea_id: None,
+        }
+    }
+    
+    /// What an idea too far-fetched to build teaches: its risks are the lessons
+    pub fn from_infeasible_idea(idea: &GeneratedIdea) -> Self {
+        let context = match &idea.domain {
+            Some(domain) => format!("{} ideas", domain),
+            None => "idea generation".to_string(),
+        };
+        let mut failure = Self::new(
+            &context,
+            &format!("'{}' by {} at chaos level {:.0}", idea.title, idea.persona_used, idea.chaos_level * 11.0),
+            &format!("the idea scored only {:.0}% feasible", idea.feasibility_score * 100.0),
+        );
+        failure.timestamp = idea.timestamp;
+        failure.lessons_learned = idea.potential_risks.clone();
+        failure.idea_id = Some(idea.id);
+        failure
+    }
+    
+    /// One-line reminder suitable for injecting into a prompt
+    pub fn as_prompt_note(&self) -> String {
+        let mut note = format!(
+            "A previous attempt at {} ({}) failed because {}",
+            self.failure_context, self.attempted_approach, self.failure_reason
+        );
+        if !self.suggested_alternatives.is_empty() {
+            note.push_str(&format!("; consider instead: {}", self.suggested_alternatives.join(", ")));
+        }
+        note
+    }
+}
+
+/// Lowercased words of four or more letters, enough to match failures to a topic
+fn significant_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 4)
+        .map(|word| word.to_lowercase())
+        .collect()
 }
 
 impl Default for UserPreferences {
@@ -372,4 +1164,197 @@ impl Default for MemorySystem {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+/// The single match for an id prefix, `None` for no match, or an error
+/// naming the candidates when the prefix fits several
+fn unique_prefix_match<'a, T>(prefix: &str, what: &str, matches: Vec<&'a T>, describe: impl Fn(&T) -> String) -> CHOPSResult<Option<&'a T>> {
+    match matches.as_slice() {
+        [] => Ok(None),
+        [only] => Ok(Some(*only)),
+        several => Err(CHOPSError::InvalidParameter(format!(
+            "'{}' matches {} {}s: {}. Use more of the id.",
+            prefix.trim(),
+            several.len(),
+            what,
+            several.iter().map(|item| describe(item)).collect::<Vec<_>>().join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idea(creativity: f64, feasibility: f64) -> GeneratedIdea {
+        GeneratedIdea {
+            id: Uuid::new_v4(),
+            title: format!("Idea at {:.2}/{:.2}", creativity, feasibility),
+            description: "An idea".to_string(),
+            persona_used: PersonaType::default(),
+            chaos_level: 0.5,
+            creativity_score: creativity,
+            feasibility_score: feasibility,
+            novelty_score: 0.5,
+            excitement_factor: 0.5,
+            tags: vec!["swarm".to_string(), "cache".to_string()],
+            implementation_hints: Vec::new(),
+            potential_risks: vec!["nobody can build it".to_string()],
+            experimental_variations: Vec::new(),
+            analogies: Vec::new(),
+            timestamp: Utc::now(),
+            parent_id: None,
+            tokens_used: 0,
+            domain: Some("web".to_string()),
+        }
+    }
+
+    /// Adds the ideas, then consolidates all of them by letting them expire
+    fn consolidate_all(ideas: Vec<GeneratedIdea>) -> (MemorySystem, ConsolidationReport) {
+        let mut memory = MemorySystem::new();
+        for idea in ideas {
+            memory.add_idea(idea);
+        }
+        memory.short_term.retention_minutes = 0;
+        let report = memory.consolidate();
+        (memory, report)
+    }
+
+    #[test]
+    fn test_breakthrough_threshold_is_exclusive() {
+        let (memory, report) = consolidate_all(vec![idea(0.86, 0.5), idea(0.85, 0.5)]);
+        assert_eq!(report.ideas, 2);
+        assert_eq!(report.breakthroughs, 1);
+        assert_eq!(memory.episodic.breakthrough_moments.len(), 1);
+        assert_eq!(memory.episodic.breakthrough_moments[0].description, "Idea at 0.86/0.50");
+        assert!(memory.short_term.recent_ideas.is_empty());
+    }
+
+    #[test]
+    fn test_failure_threshold_is_exclusive() {
+        let (memory, report) = consolidate_all(vec![idea(0.5, 0.19), idea(0.5, 0.2)]);
+        assert_eq!(report.failures, 1);
+        assert_eq!(memory.episodic.failure_learnings.len(), 1);
+        assert_eq!(memory.episodic.failure_learnings[0].lessons_learned, vec!["nobody can build it"]);
+    }
+
+    #[test]
+    fn test_already_learned_failure_is_not_recorded_again() {
+        let infeasible = idea(0.5, 0.1);
+        let mut memory = MemorySystem::new();
+        memory.episodic.record_failure(FailureLearning::from_infeasible_idea(&infeasible));
+        memory.add_idea(infeasible);
+        memory.short_term.retention_minutes = 0;
+
+        assert_eq!(memory.consolidate().failures, 0);
+        assert_eq!(memory.episodic.failure_learnings.len(), 1);
+    }
+
+    #[test]
+    fn test_patterns_are_learned_on_add_and_counted_once() {
+        let mut memory = MemorySystem::new();
+        memory.add_idea(idea(0.75, 0.65));
+        memory.add_idea(idea(0.7, 0.65));
+        let pattern = &memory.long_term.successful_patterns["tag_pattern_swarm"];
+        assert_eq!(pattern.usage_count, 1);
+        assert_eq!(pattern.context_tags, vec!["cache"]);
+
+        memory.short_term.retention_minutes = 0;
+        let report = memory.consolidate();
+        assert_eq!(report.promoted, 1);
+        assert_eq!(memory.long_term.successful_patterns["tag_pattern_swarm"].usage_count, 1);
+        assert_eq!(memory.consolidate(), ConsolidationReport::default());
+    }
+
+    #[test]
+    fn test_lineage_and_lookup_reach_consolidated_ideas() {
+        let root = idea(0.5, 0.5);
+        let (mut memory, _) = consolidate_all(vec![root.clone()]);
+        assert_eq!(memory.long_term.archived_ideas.len(), 1);
+        memory.short_term.retention_minutes = 60;
+        let mut refinement = idea(0.6, 0.5);
+        refinement.parent_id = Some(root.id);
+        memory.add_idea(refinement.clone());
+
+        let lineage = memory.idea_lineage(refinement.id, 5);
+        let chain: Vec<Uuid> = lineage.chain.iter().map(|idea| idea.id).collect();
+        assert_eq!(chain, vec![refinement.id, root.id]);
+        assert_eq!(memory.find_idea(&root.id.to_string()[..8]).unwrap().map(|idea| idea.id), Some(root.id));
+    }
+
+    #[test]
+    fn test_ambiguous_id_prefix_lists_the_candidates() {
+        let mut memory = MemorySystem::new();
+        let ids = ["abcd1111-0000-4000-8000-000000000000", "abcd2222-0000-4000-8000-000000000000"].map(|id| Uuid::parse_str(id).unwrap());
+        for id in ids {
+            let mut candidate = idea(0.5, 0.5);
+            candidate.id = id;
+            memory.add_idea(candidate);
+        }
+
+        let error = memory.find_idea("ABCD").unwrap_err().to_string();
+        assert!(error.contains("abcd1111") && error.contains("abcd2222"), "{}", error);
+        assert_eq!(memory.find_idea("abcd2").unwrap().map(|idea| idea.id), Some(ids[1]));
+        assert!(memory.find_idea("ffff").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tag_activity_and_queries_reach_consolidated_ideas() {
+        let (mut memory, _) = consolidate_all((0..6).map(|_| idea(0.5, 0.5)).collect());
+        memory.short_term.retention_minutes = 60;
+        for _ in 0..4 {
+            memory.add_idea(idea(0.5, 0.5));
+        }
+        assert_eq!(memory.short_term.recent_ideas.len(), 4);
+
+        let activity = memory.tag_activity("web", 4);
+        let tags: Vec<&str> = activity.iter().map(|(tag, _)| tag.as_str()).collect();
+        assert_eq!(tags, vec!["cache", "swarm"]);
+        assert!(activity.iter().all(|(_, series)| *series == vec![1.0; 4]));
+
+        let query = crate::IdeaQuery { domain: Some("Web".to_string()), ..Default::default() };
+        assert_eq!(memory.query_ideas(&query).total_matches, 10);
+    }
+
+    #[test]
+    fn test_recall_reaches_consolidated_ideas() {
+        let mut archived = idea(0.5, 0.5);
+        archived.title = "Sharded memory store".to_string();
+        let (memory, _) = consolidate_all(vec![archived.clone()]);
+        assert!(memory.short_term.recent_ideas.is_empty());
+
+        let recalled = memory.recall_similar_ideas("sharded store", 3, 0.1);
+        assert_eq!(recalled.iter().map(|(_, idea)| idea.id).collect::<Vec<_>>(), vec![archived.id]);
+    }
+
+    #[test]
+    fn test_tied_personas_recommend_the_alphabetically_first() {
+        let personas = [PersonaType::ZenMaster, PersonaType::MadScientist, PersonaType::ChaosEngineer];
+        let first = personas.iter().min_by_key(|persona| persona.to_string()).unwrap().clone();
+        for order in [personas.clone(), [personas[2].clone(), personas[0].clone(), personas[1].clone()]] {
+            let mut memory = MemorySystem::new();
+            for persona in order {
+                let mut tied = idea(0.7, 0.6);
+                tied.persona_used = persona;
+                memory.add_idea(tied.clone());
+                memory.record_persona_domain(&tied, "web");
+            }
+            assert_eq!(memory.persona_track_record("web").map(|record| record.persona), Some(first.clone()));
+        }
+    }
+
+    #[test]
+    fn test_full_short_term_memory_consolidates_the_oldest_idea() {
+        let mut memory = MemorySystem::new();
+        memory.short_term.max_capacity = 3;
+        let ideas: Vec<GeneratedIdea> = (0..4).map(|_| idea(0.9, 0.5)).collect();
+        let oldest = ideas[0].id;
+        for idea in ideas {
+            memory.add_idea(idea);
+        }
+
+        assert_eq!(memory.short_term.recent_ideas.len(), 3);
+        assert!(memory.short_term.recent_ideas.iter().all(|idea| idea.id != oldest));
+        assert_eq!(memory.episodic.breakthrough_moments.len(), 1);
+        assert_eq!(memory.episodic.breakthrough_moments[0].idea_id, oldest);
+    }
+}
@@ -0,0 +1,268 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::{DomainKnowledge, DomainScore, EffectivenessMetrics, GeneratedIdea, MemorySystem, PatternRecord};
+
+/// What merging another memory export changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MergeReport {
+    pub patterns_added: usize,
+    pub patterns_combined: usize,
+    pub personas_added: usize,
+    pub personas_combined: usize,
+    pub domains_added: usize,
+    pub domains_combined: usize,
+    pub ideas_added: usize,
+    /// Ideas already present with the same id and content
+    pub ideas_skipped: usize,
+    /// Imported ideas whose id was taken by a different idea and got a fresh one
+    pub ids_reassigned: usize,
+    /// Imported ideas that didn't fit in short-term memory and were consolidated instead
+    pub ideas_evicted: usize,
+}
+
+impl MergeReport {
+    pub fn is_empty(&self) -> bool {
+        self.patterns_added + self.patterns_combined
+            + self.personas_added + self.personas_combined
+            + self.domains_added + self.domains_combined
+            + self.ideas_added == 0
+    }
+}
+
+impl MemorySystem {
+    /// Pools another user's learned knowledge into this memory: pattern usage is
+    /// summed and success averaged by usage, persona effectiveness is combined
+    /// the same way, domain knowledge is unioned and unseen ideas are appended
+    /// as far as short-term memory has room, newest first; the rest are
+    /// consolidated. Working memory and session history stay this user's own.
+    #[tracing::instrument(name = "memory_merge", level = "info", skip_all)]
+    pub fn merge(&mut self, other: MemorySystem) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        for (key, record) in other.long_term.successful_patterns {
+            match self.long_term.successful_patterns.get_mut(&key) {
+                Some(existing) => {
+                    existing.absorb(record);
+                    report.patterns_combined += 1;
+                },
+                None => {
+                    self.long_term.successful_patterns.insert(key, record);
+                    report.patterns_added += 1;
+                },
+            }
+        }
+
+        for (persona, metrics) in other.long_term.persona_effectiveness {
+            match self.long_term.persona_effectiveness.get_mut(&persona) {
+                Some(existing) => {
+                    existing.absorb(metrics);
+                    report.personas_combined += 1;
+                },
+                None => {
+                    self.long_term.persona_effectiveness.insert(persona, metrics);
+                    report.personas_added += 1;
+                },
+            }
+        }
+
+        for (name, knowledge) in other.long_term.domain_knowledge {
+            match self.long_term.domain_knowledge.get_mut(&name) {
+                Some(existing) => {
+                    existing.absorb(knowledge);
+                    report.domains_combined += 1;
+                },
+                None => {
+                    self.long_term.domain_knowledge.insert(name, knowledge);
+                    report.domains_added += 1;
+                },
+            }
+        }
+
+        self.merge_ideas(other.short_term.recent_ideas.into_iter().collect(), &mut report);
+
+        tracing::info!(
+            "Merged memory: {} patterns, {} personas, {} domains, {} ideas ({} ids reassigned)",
+            report.patterns_added + report.patterns_combined,
+            report.personas_added + report.personas_combined,
+            report.domains_added + report.domains_combined,
+            report.ideas_added,
+            report.ids_reassigned
+        );
+        report
+    }
+
+    fn merge_ideas(&mut self, ideas: Vec<GeneratedIdea>, report: &mut MergeReport) {
+        let mut reassigned: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut incoming = Vec::new();
+
+        for mut idea in ideas {
+            match self.short_term.recent_ideas.iter().find(|existing| existing.id == idea.id) {
+                Some(existing) if existing.title == idea.title && existing.description == idea.description => {
+                    report.ideas_skipped += 1;
+                    continue;
+                },
+                Some(_) => {
+                    let fresh = Uuid::new_v4();
+                    tracing::debug!("Idea id {} collides with a different idea, reassigned to {}", idea.id, fresh);
+                    reassigned.insert(idea.id, fresh);
+                    idea.id = fresh;
+                    report.ids_reassigned += 1;
+                },
+                None => {},
+            }
+            incoming.push(idea);
+        }
+
+        // Keep lineage within the import pointing at the right ideas
+        for idea in &mut incoming {
+            if let Some(fresh) = idea.parent_id.and_then(|parent| reassigned.get(&parent)) {
+                idea.parent_id = Some(*fresh);
+            }
+        }
+
+        // The user's own ideas stay; only the newest imports that fit join them
+        let room = self.short_term.max_capacity.saturating_sub(self.short_term.recent_ideas.len());
+        incoming.sort_by_key(|idea| idea.timestamp);
+        let evicted: Vec<GeneratedIdea> = incoming.drain(..incoming.len().saturating_sub(room)).collect();
+        report.ideas_added = incoming.len();
+        report.ideas_evicted = evicted.len();
+        self.short_term.recent_ideas.extend(incoming);
+        self.short_term.recent_ideas.make_contiguous().sort_by_key(|idea| idea.timestamp);
+        self.consolidate_evicted(&evicted);
+    }
+}
+
+/// Average of `a` and `b` weighted by how often each was observed
+fn weighted_average(a: f64, a_weight: f64, b: f64, b_weight: f64) -> f64 {
+    if a_weight + b_weight == 0.0 {
+        return (a + b) / 2.0;
+    }
+    (a * a_weight + b * b_weight) / (a_weight + b_weight)
+}
+
+fn union_into(target: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !target.contains(&item) {
+            target.push(item);
+        }
+    }
+}
+
+impl PatternRecord {
+    fn absorb(&mut self, other: PatternRecord) {
+        self.success_rate = weighted_average(
+            self.success_rate, self.usage_count as f64,
+            other.success_rate, other.usage_count as f64,
+        );
+        self.usage_count += other.usage_count;
+        self.last_used = self.last_used.max(other.last_used);
+        union_into(&mut self.context_tags, other.context_tags);
+    }
+}
+
+impl EffectivenessMetrics {
+    fn absorb(&mut self, other: EffectivenessMetrics) {
+        let (mine, theirs) = (self.usage_frequency as f64, other.usage_frequency as f64);
+        self.average_creativity_score = weighted_average(self.average_creativity_score, mine, other.average_creativity_score, theirs);
+        self.average_feasibility_score = weighted_average(self.average_feasibility_score, mine, other.average_feasibility_score, theirs);
+        self.user_satisfaction_rating = weighted_average(
+            self.user_satisfaction_rating, self.satisfaction_ratings as f64,
+            other.user_satisfaction_rating, other.satisfaction_ratings as f64,
+        );
+        self.satisfaction_ratings += other.satisfaction_ratings;
+        self.usage_frequency += other.usage_frequency;
+        self.total_tokens += other.total_tokens;
+        self.total_cost += other.total_cost;
+        union_into(&mut self.domains_used_in, other.domains_used_in);
+
+        for (domain, score) in other.domain_scores {
+            self.domain_scores.entry(domain).or_default().absorb(score);
+        }
+    }
+}
+
+impl DomainScore {
+    fn absorb(&mut self, other: DomainScore) {
+        let (mine, theirs) = (self.uses as f64, other.uses as f64);
+        self.average_creativity_score = weighted_average(self.average_creativity_score, mine, other.average_creativity_score, theirs);
+        self.average_feasibility_score = weighted_average(self.average_feasibility_score, mine, other.average_feasibility_score, theirs);
+        self.uses += other.uses;
+    }
+}
+
+impl DomainKnowledge {
+    fn absorb(&mut self, other: DomainKnowledge) {
+        self.expertise_level = self.expertise_level.max(other.expertise_level);
+        union_into(&mut self.successful_approaches, other.successful_approaches);
+        union_into(&mut self.common_pitfalls, other.common_pitfalls);
+        for (concept, meaning) in other.key_concepts {
+            self.key_concepts.entry(concept).or_insert(meaning);
+        }
+        self.last_updated = self.last_updated.max(other.last_updated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn idea(title: &str, creativity: f64, minutes_ago: i64) -> GeneratedIdea {
+        GeneratedIdea {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: format!("{} in detail", title),
+            persona_used: crate::PersonaType::default(),
+            chaos_level: 0.5,
+            creativity_score: creativity,
+            feasibility_score: 0.5,
+            novelty_score: 0.5,
+            excitement_factor: 0.5,
+            tags: Vec::new(),
+            implementation_hints: Vec::new(),
+            potential_risks: Vec::new(),
+            experimental_variations: Vec::new(),
+            analogies: Vec::new(),
+            timestamp: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            parent_id: None,
+            tokens_used: 0,
+            domain: None,
+        }
+    }
+
+    #[test]
+    fn test_import_never_evicts_own_ideas() {
+        let mut memory = MemorySystem::new();
+        memory.short_term.max_capacity = 3;
+        memory.short_term.recent_ideas.push_back(idea("my oldest", 0.5, 60));
+        memory.short_term.recent_ideas.push_back(idea("my newest", 0.5, 50));
+
+        let mut other = MemorySystem::new();
+        for minutes_ago in [30, 20, 10] {
+            other.short_term.recent_ideas.push_back(idea(&format!("theirs {}", minutes_ago), 0.5, minutes_ago));
+        }
+        let report = memory.merge(other);
+
+        assert_eq!(report.ideas_added, 1);
+        assert_eq!(report.ideas_evicted, 2);
+        let titles: Vec<&str> = memory.short_term.recent_ideas.iter().map(|idea| idea.title.as_str()).collect();
+        assert_eq!(titles, vec!["my oldest", "my newest", "theirs 10"]);
+    }
+
+    #[test]
+    fn test_import_overflow_is_consolidated() {
+        let mut memory = MemorySystem::new();
+        memory.short_term.max_capacity = 2;
+        memory.short_term.recent_ideas.push_back(idea("mine", 0.5, 1));
+
+        let mut other = MemorySystem::new();
+        other.short_term.recent_ideas.push_back(idea("old breakthrough", 0.95, 5));
+        other.short_term.recent_ideas.push_back(idea("newer", 0.5, 2));
+        memory.merge(other);
+
+        assert_eq!(memory.short_term.recent_ideas.len(), 2);
+        assert_eq!(memory.episodic.breakthrough_moments.len(), 1);
+        assert_eq!(memory.episodic.breakthrough_moments[0].description, "old breakthrough");
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use crate::{CHOPSError, CHOPSResult, GeneratedIdea, MemorySystem, PersonaType, ScoreProfile};
+
+/// Filters and a page window over remembered ideas
+#[derive(Debug, Clone)]
+pub struct IdeaQuery {
+    pub persona: Option<PersonaType>,
+    /// Matched case-insensitively against the domain the idea was summoned for
+    pub domain: Option<String>,
+    /// Lowest balanced score (0.0-1.0) to include
+    pub min_score: Option<f64>,
+    pub since: Option<DateTime<Utc>>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// One page of ideas matching an [`IdeaQuery`], newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct IdeaPage<'a> {
+    pub ideas: Vec<&'a GeneratedIdea>,
+    /// Matching ideas across all pages
+    pub total_matches: usize,
+    pub offset: usize,
+}
+
+impl Default for IdeaQuery {
+    fn default() -> Self {
+        Self {
+            persona: None,
+            domain: None,
+            min_score: None,
+            since: None,
+            offset: 0,
+            limit: 10,
+        }
+    }
+}
+
+impl GeneratedIdea {
+    /// The idea's domain; ideas remembered before domains were recorded fall
+    /// back to the concept their analogies were mapped onto
+    fn summoned_domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+            .or_else(|| self.analogies.first().map(|analogy| analogy.target_concept.as_str()))
+    }
+}
+
+impl IdeaQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `--since` values: a date (`2024-05-01`, taken as midnight UTC) or an RFC 3339 timestamp
+    pub fn parse_since(value: &str) -> CHOPSResult<DateTime<Utc>> {
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+        }
+        DateTime::parse_from_rfc3339(value)
+            .map(|timestamp| timestamp.with_timezone(&Utc))
+            .map_err(|_| CHOPSError::InvalidParameter(format!(
+                "Invalid date '{}'; use YYYY-MM-DD or an RFC 3339 timestamp", value
+            )))
+    }
+
+    pub fn matches(&self, idea: &GeneratedIdea) -> bool {
+        if self.persona.as_ref().is_some_and(|persona| *persona != idea.persona_used) {
+            return false;
+        }
+        if self.since.is_some_and(|since| idea.timestamp < since) {
+            return false;
+        }
+        if let Some(min_score) = self.min_score {
+            let score = ScoreProfile::Balanced.score(
+                idea.creativity_score, idea.feasibility_score, idea.novelty_score, idea.excitement_factor,
+            );
+            if score < min_score {
+                return false;
+            }
+        }
+        match &self.domain {
+            Some(domain) => idea.summoned_domain()
+                .is_some_and(|idea_domain| idea_domain.to_lowercase().contains(&domain.trim().to_lowercase())),
+            None => true,
+        }
+    }
+}
+
+/// Fewest remembered ideas in a domain before tag trends are worth computing
+const MIN_TREND_IDEAS: usize = 8;
+
+impl IdeaPage<'_> {
+    /// Whether matches remain past this page
+    pub fn has_more(&self) -> bool {
+        self.offset + self.ideas.len() < self.total_matches
+    }
+}
+
+impl MemorySystem {
+    /// Remembered ideas matching `query`, newest first, windowed by its offset and limit
+    #[tracing::instrument(name = "memory_query_ideas", level = "debug", skip(self))]
+    pub fn query_ideas(&self, query: &IdeaQuery) -> IdeaPage<'_> {
+        let matching: Vec<&GeneratedIdea> = self.all_ideas()
+            .rev()
+            .filter(|idea| query.matches(idea))
+            .collect();
+        let total_matches = matching.len();
+        let ideas: Vec<&GeneratedIdea> = matching.into_iter().skip(query.offset).take(query.limit).collect();
+
+        tracing::debug!("Idea query matched {}, returning {} from offset {}", total_matches, ideas.len(), query.offset);
+        IdeaPage { ideas, total_matches, offset: query.offset }
+    }
+
+    /// How often each recurring tag shows up in a domain's ideas over time: the
+    /// ideas, oldest first, are cut into up to `periods` equal batches and each
+    /// series holds the share of a batch carrying the tag. Empty until the
+    /// domain has enough ideas to say anything.
+    pub fn tag_activity(&self, domain: &str, periods: usize) -> Vec<(String, Vec<f64>)> {
+        let domain = domain.trim().to_lowercase();
+        let mut ideas: Vec<&GeneratedIdea> = self.all_ideas()
+            .filter(|idea| idea.summoned_domain().is_some_and(|idea_domain| idea_domain.to_lowercase() == domain))
+            .collect();
+        if ideas.len() < MIN_TREND_IDEAS || periods == 0 {
+            return Vec::new();
+        }
+        ideas.sort_by_key(|idea| idea.timestamp);
+
+        let batch_size = ideas.len().div_ceil(periods.min(ideas.len() / 2));
+        let batches: Vec<&[&GeneratedIdea]> = ideas.chunks(batch_size).collect();
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for idea in &ideas {
+            for tag in &idea.tags {
+                *counts.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<&str> = counts.into_iter().filter(|(_, count)| *count >= 2).map(|(tag, _)| tag).collect();
+        tags.sort();
+
+        tags.into_iter()
+            .map(|tag| {
+                let series = batches.iter()
+                    .map(|batch| batch.iter().filter(|idea| idea.tags.iter().any(|t| t == tag)).count() as f64 / batch.len() as f64)
+                    .collect();
+                (tag.to_string(), series)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,60 @@
+use std::cmp::Ordering;
+
+/// Orders scores best-first for `sort_by`: higher scores come first and NaN
+/// sinks to the end instead of panicking. Chain a secondary key with
+/// `.then_with(...)` so equal scores rank the same way on every run.
+pub fn cmp_scores(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+/// Which qualities count most when ranking ideas against each other
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreProfile {
+    Balanced,
+    Creative,
+    Practical,
+    Novel,
+}
+
+crate::impl_enum_str!(ScoreProfile {
+    Balanced => "balanced",
+    Creative => "creative",
+    Practical => "practical",
+    Novel => "novel",
+});
+
+impl ScoreProfile {
+    /// (creativity, feasibility, novelty, excitement) weights, summing to 1.0
+    pub fn weights(&self) -> (f64, f64, f64, f64) {
+        match self {
+            ScoreProfile::Balanced => (0.33, 0.28, 0.22, 0.17),
+            ScoreProfile::Creative => (0.5, 0.1, 0.25, 0.15),
+            ScoreProfile::Practical => (0.15, 0.6, 0.1, 0.15),
+            ScoreProfile::Novel => (0.2, 0.1, 0.6, 0.1),
+        }
+    }
+
+    pub fn score(&self, creativity: f64, feasibility: f64, novelty: f64, excitement: f64) -> f64 {
+        let (c, f, n, e) = self.weights();
+        creativity * c + feasibility * f + novelty * n + excitement * e
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cmp_scores_sinks_nan_and_keeps_ties_in_key_order() {
+        let mut scored = [(0.5, "b"), (f64::NAN, "a"), (0.9, "z"), (0.5, "a"), (f64::NAN, "c")];
+        scored.sort_by(|(a_score, a), (b_score, b)| cmp_scores(*a_score, *b_score).then_with(|| a.cmp(b)));
+        let order: Vec<&str> = scored.iter().map(|(_, name)| *name).collect();
+        assert_eq!(order, vec!["z", "a", "b", "a", "c"]);
+        assert!(scored[3].0.is_nan() && scored[4].0.is_nan());
+    }
+}
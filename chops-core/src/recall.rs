@@ -0,0 +1,281 @@
+use crate::{cmp_scores, GeneratedIdea};
+use std::sync::OnceLock;
+
+/// Words too common to say anything about what an idea is about
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "onto", "are", "was", "were",
+    "but", "not", "you", "your", "our", "its", "can", "will", "how", "what", "when", "why",
+    "all", "any", "use", "using", "via", "idea", "ideas",
+];
+
+/// Words that mean roughly the same thing in software ideas. A query word
+/// also matches the other words of its group, at `SYNONYM_WEIGHT`.
+const SYNONYM_GROUPS: &[&[&str]] = &[
+    &["cache", "memory", "store", "storage", "buffer", "memo"],
+    &["distribut", "shard", "partition", "replica", "cluster", "decentraliz", "federat"],
+    &["fast", "speed", "latency", "perform", "quick", "throughput"],
+    &["database", "datastore", "db", "sql", "table"],
+    &["bug", "defect", "error", "failure", "fault", "crash"],
+    &["test", "verif", "validat", "check", "assert"],
+    &["secur", "auth", "encrypt", "permission", "access"],
+    &["network", "socket", "http", "protocol", "packet"],
+    &["ui", "interface", "frontend", "screen", "layout"],
+    &["ai", "ml", "model", "neural", "learn"],
+    &["deploy", "release", "ship", "rollout"],
+    &["queue", "stream", "event", "message", "pubsub"],
+];
+
+/// How much a term matched only through synonyms can count, next to the word itself
+const SYNONYM_WEIGHT: f64 = 0.5;
+
+/// Occurrences in a title count this many times over the description
+const TITLE_BOOST: u32 = 3;
+
+/// Term frequency saturation: the k in tf / (tf + k), so one idea repeating a
+/// word can't outweigh others that mention it a few times
+const TF_SATURATION: f64 = 1.2;
+
+struct QueryTerm {
+    stem: String,
+    /// The word's synonym group, matched at `SYNONYM_WEIGHT`
+    group: Option<usize>,
+}
+
+/// Ranks `ideas` by TF-IDF overlap with `query` over those same ideas as the
+/// corpus: words are lowercased, stemmed and stripped of stop words, a word
+/// also matches its synonyms at half weight, and title words weigh more.
+/// Scores are in [0, 1] (1 = every query word in a title, and rare; a word
+/// matched only through synonyms counts at most half); ideas
+/// below `min_score` are left out and the best `limit` returned.
+pub fn rank_by_relevance<'a>(
+    query: &str,
+    ideas: impl IntoIterator<Item = &'a GeneratedIdea>,
+    limit: usize,
+    min_score: f64,
+) -> Vec<(f64, &'a GeneratedIdea)> {
+    let ideas: Vec<&GeneratedIdea> = ideas.into_iter().collect();
+    let terms = query_terms(query);
+    if terms.is_empty() || ideas.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    // Weighted frequency of each query term in each idea, one flat row per
+    // idea: the word itself in the first half, its synonyms in the second
+    let width = terms.len();
+    let mut frequencies = vec![0_u32; ideas.len() * width * 2];
+    let mut document_frequency = vec![0_u32; width];
+    let mut lowered = String::new();
+    for (row, idea) in frequencies.chunks_mut(width * 2).zip(&ideas) {
+        count_terms(&idea.title, &terms, TITLE_BOOST, row, &mut lowered);
+        count_terms(&idea.description, &terms, 1, row, &mut lowered);
+        for tag in &idea.tags {
+            count_terms(tag, &terms, TITLE_BOOST, row, &mut lowered);
+        }
+        let (exact, synonyms) = row.split_at(width);
+        for ((seen, exact), synonyms) in document_frequency.iter_mut().zip(exact).zip(synonyms) {
+            if *exact + *synonyms > 0 {
+                *seen += 1;
+            }
+        }
+    }
+
+    let corpus = ideas.len() as f64;
+    let idf: Vec<f64> = document_frequency
+        .iter()
+        .map(|&seen| ((corpus + 1.0) / (seen as f64 + 1.0)).ln() + 1.0)
+        .collect();
+    // Best possible score: every term saturated by title-level frequency
+    let full = saturate(TITLE_BOOST as f64);
+    let ceiling: f64 = idf.iter().map(|weight| weight * full).sum();
+
+    let mut ranked: Vec<(f64, &GeneratedIdea)> = frequencies
+        .chunks(width * 2)
+        .zip(&ideas)
+        .filter_map(|(row, idea)| {
+            let (exact, synonyms) = row.split_at(width);
+            let raw: f64 = exact.iter().zip(synonyms).zip(&idf)
+                .map(|((&exact, &synonyms), weight)| {
+                    saturate(exact as f64).max(SYNONYM_WEIGHT * saturate(synonyms as f64).min(full)) * weight
+                })
+                .sum();
+            let score = (raw / ceiling).min(1.0);
+            (score > 0.0 && score >= min_score).then_some((score, *idea))
+        })
+        .collect();
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        cmp_scores(*a_score, *b_score).then_with(|| b.timestamp.cmp(&a.timestamp))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+fn saturate(frequency: f64) -> f64 {
+    frequency / (frequency + TF_SATURATION)
+}
+
+fn query_terms(query: &str) -> Vec<QueryTerm> {
+    let mut terms: Vec<QueryTerm> = Vec::new();
+    for word in words(query) {
+        let stem = stem(&word.to_lowercase()).to_string();
+        if !terms.iter().any(|term| term.stem == stem) {
+            let group = synonym_group(&stem);
+            terms.push(QueryTerm { stem, group });
+        }
+    }
+    terms
+}
+
+/// Adds `weight` per occurrence of each query term in `text` to the first
+/// half of `row`, and per occurrence of one of its synonyms to the second.
+/// `lowered` is scratch space, reused across calls.
+fn count_terms(text: &str, terms: &[QueryTerm], weight: u32, row: &mut [u32], lowered: &mut String) {
+    let (exact, synonyms) = row.split_at_mut(terms.len());
+    for word in words(text) {
+        lowered.clear();
+        lowered.extend(word.chars().flat_map(char::to_lowercase));
+        let stem = stem(lowered);
+        let mut group = None;
+        for ((term, exact), synonyms) in terms.iter().zip(exact.iter_mut()).zip(synonyms.iter_mut()) {
+            if term.stem == stem {
+                *exact += weight;
+                continue;
+            }
+            let Some(term_group) = term.group else { continue };
+            let word_group = *group.get_or_insert_with(|| synonym_group(stem));
+            if word_group == Some(term_group) {
+                *synonyms += weight;
+            }
+        }
+    }
+}
+
+/// The distinct content words of `text`, lowercased and in order of first
+/// appearance: three or more letters, no stop words
+pub fn content_words(text: &str) -> Vec<String> {
+    let mut seen = Vec::new();
+    for word in words(text).filter(|word| word.chars().count() >= 3) {
+        let word = word.to_lowercase();
+        if !seen.contains(&word) {
+            seen.push(word);
+        }
+    }
+    seen
+}
+
+/// Words of two or more letters that aren't stop words
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 2 && !STOP_WORDS.iter().any(|stop| stop.eq_ignore_ascii_case(word)))
+}
+
+/// Strips common English suffixes so "caching", "cached" and "caches" meet
+fn stem(word: &str) -> &str {
+    for suffix in ["ations", "ation", "ments", "ment", "ings", "ing", "ers", "er", "ed", "ies", "es", "s", "ly", "e", "y"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.chars().count() >= 3 {
+                return stem;
+            }
+        }
+    }
+    word
+}
+
+/// The synonym group of a stemmed word. Stems of four or more letters also
+/// match when one is a prefix of the other ("distribut" and "distribution").
+fn synonym_group(word: &str) -> Option<usize> {
+    static STEMMED: OnceLock<Vec<(usize, &'static str)>> = OnceLock::new();
+    let stemmed = STEMMED.get_or_init(|| {
+        SYNONYM_GROUPS.iter()
+            .enumerate()
+            .flat_map(|(group, synonyms)| synonyms.iter().map(move |synonym| (group, stem(synonym))))
+            .collect()
+    });
+    stemmed.iter()
+        .find(|(_, synonym)| {
+            word == *synonym
+                || (synonym.len() >= 4 && word.len() >= 4 && (word.starts_with(synonym) || synonym.starts_with(word)))
+        })
+        .map(|(group, _)| *group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PersonaType;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn idea(title: &str, description: &str, tags: &[&str]) -> GeneratedIdea {
+        GeneratedIdea {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            description: description.to_string(),
+            persona_used: PersonaType::default(),
+            chaos_level: 0.5,
+            creativity_score: 0.5,
+            feasibility_score: 0.5,
+            novelty_score: 0.5,
+            excitement_factor: 0.5,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            implementation_hints: Vec::new(),
+            potential_risks: Vec::new(),
+            experimental_variations: Vec::new(),
+            analogies: Vec::new(),
+            timestamp: Utc::now(),
+            parent_id: None,
+            tokens_used: 0,
+            domain: None,
+        }
+    }
+
+    /// A few hundred ideas about unrelated things, plus the three that matter
+    fn corpus() -> Vec<GeneratedIdea> {
+        const SUBJECTS: &[&str] = &["garden", "recipe", "bicycle", "poetry", "weather", "music", "travel", "chess"];
+        const TWISTS: &[&str] = &["planner", "journal", "coach", "map", "club", "tracker", "game", "radio"];
+        let mut ideas: Vec<GeneratedIdea> = (0..500)
+            .map(|i| {
+                let subject = SUBJECTS[i % SUBJECTS.len()];
+                let twist = TWISTS[(i / SUBJECTS.len()) % TWISTS.len()];
+                idea(&format!("{} {} #{}", subject, twist, i), &format!("A {} {} for people who love {}s", subject, twist, subject), &[subject])
+            })
+            .collect();
+        ideas.push(idea("Distributed cache warming", "Warm the distributed cache before traffic arrives", &["cache"]));
+        ideas.push(idea("Sharded memory store", "Keep hot keys in a sharded in-memory store", &["storage"]));
+        ideas.push(idea("Cache-aware compiler", "Lay out loops so they stay in cache", &["compiler"]));
+        ideas
+    }
+
+    #[test]
+    fn test_synonyms_rank_below_the_words_themselves() {
+        let ideas = corpus();
+        let ranked = rank_by_relevance("distributed cache", &ideas, 3, 0.1);
+        let titles: Vec<&str> = ranked.iter().map(|(_, idea)| idea.title.as_str()).collect();
+        assert_eq!(titles, vec!["Distributed cache warming", "Cache-aware compiler", "Sharded memory store"]);
+        assert_eq!(ranked[0].0, 1.0);
+        // Matched only through synonyms, so at most half
+        assert!(ranked[2].0 > 0.0 && ranked[2].0 <= SYNONYM_WEIGHT + 1e-9);
+    }
+
+    #[test]
+    fn test_stop_words_and_limits() {
+        let ideas = corpus();
+        assert!(rank_by_relevance("the and for", &ideas, 5, 0.0).is_empty());
+        assert!(rank_by_relevance("", &ideas, 5, 0.0).is_empty());
+        assert!(rank_by_relevance("cache", &ideas, 0, 0.0).is_empty());
+        assert_eq!(rank_by_relevance("garden", &ideas, 5, 0.0).len(), 5);
+        let ranked = rank_by_relevance("garden cache", &ideas, 500, 0.4);
+        assert!(!ranked.is_empty() && ranked.iter().all(|(score, _)| *score >= 0.4));
+    }
+
+    #[test]
+    fn test_recall_over_hundreds_of_ideas_stays_fast() {
+        let ideas = corpus();
+        let start = std::time::Instant::now();
+        for _ in 0..20 {
+            assert!(!rank_by_relevance("distributed garden cache planner", &ideas, 3, 0.1).is_empty());
+        }
+        let per_recall = start.elapsed() / 20;
+        // Generous for unoptimized builds; a release build takes a few milliseconds
+        assert!(per_recall < std::time::Duration::from_millis(250), "recall took {:?}", per_recall);
+    }
+}
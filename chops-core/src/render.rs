@@ -0,0 +1,297 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use crate::{CHOPSError, CHOPSResult, OutputFormat};
+
+crate::impl_enum_str!(OutputFormat {
+    Markdown => "md",
+    Json => "json",
+    Yaml => "yaml",
+    PlainText => "txt",
+    Html => "html",
+});
+
+impl OutputFormat {
+    /// File extension for files written in this format
+    pub fn extension(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a format by extension (`md`, `txt`) or by name (`markdown`, `plain`)
+    pub fn from_name(name: &str) -> CHOPSResult<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "markdown" => Ok(OutputFormat::Markdown),
+            "plain" | "text" => Ok(OutputFormat::PlainText),
+            other => other.parse(),
+        }
+    }
+
+    /// Formats meant for programs rather than people, which must reach
+    /// stdout with nothing else around them
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Yaml)
+    }
+}
+
+/// A result that can be written out in several formats from a single generation
+pub trait Renderable {
+    /// Heading used for titles in every format
+    fn title(&self) -> String;
+
+    fn render_markdown(&self) -> String;
+
+    fn render_json(&self) -> CHOPSResult<String>;
+
+    fn render_yaml(&self) -> CHOPSResult<String>;
+
+    fn render_plain_text(&self) -> String {
+        strip_formatting(&self.render_markdown())
+    }
+
+    fn render_html(&self) -> String {
+        markdown_to_html(&self.title(), &self.render_markdown())
+    }
+
+    fn render(&self, format: &OutputFormat) -> CHOPSResult<String> {
+        match format {
+            OutputFormat::Markdown => Ok(self.render_markdown()),
+            OutputFormat::Json => self.render_json(),
+            OutputFormat::PlainText => Ok(self.render_plain_text()),
+            OutputFormat::Html => Ok(self.render_html()),
+            OutputFormat::Yaml => self.render_yaml(),
+        }
+    }
+}
+
+/// `value` as a YAML document, for `Renderable::render_yaml`
+pub fn to_yaml<T: Serialize + ?Sized>(value: &T) -> CHOPSResult<String> {
+    serde_yaml_ng::to_string(value)
+        .map_err(|e| CHOPSError::UnexpectedError(format!("Failed to serialize YAML: {}", e)))
+}
+
+/// Markdown as plain text: heading markers, emphasis, code fences, inline
+/// code marks and emoji are dropped, and runs of blank lines collapse to one.
+/// Code keeps its text verbatim, so `__init__` stays `__init__`.
+pub fn strip_formatting(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut blank = true;
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        let text = if in_fence {
+            line.trim_end().to_string()
+        } else {
+            let line = match trimmed.trim_start_matches('#') {
+                rest if rest.len() < trimmed.len() && rest.starts_with(' ') => rest.trim_start(),
+                _ => line,
+            };
+            strip_emoji(&strip_inline(line)).trim_end().to_string()
+        };
+        if text.is_empty() {
+            if !blank {
+                out.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        out.push_str(&text);
+        out.push('\n');
+        blank = false;
+    }
+    out
+}
+
+/// Drops `**` bold markers and the backticks of code spans. Underscores are
+/// left alone, since `__init__` and `snake__case` are names far more often
+/// than emphasis, and markers that don't close a span are kept.
+fn strip_inline(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            let ticks = chars[i..].iter().take_while(|c| **c == '`').count();
+            let close = (i + ticks..chars.len()).find(|&j| {
+                chars[j..].iter().take_while(|c| **c == '`').count() == ticks && chars[j - 1] != '`'
+            });
+            if let Some(close) = close {
+                out.extend(&chars[i + ticks..close]);
+                i = close + ticks;
+                continue;
+            }
+            out.extend(&chars[i..i + ticks]);
+            i += ticks;
+            continue;
+        }
+        if let Some(close) = bold_close(&chars, i) {
+            out.push_str(&strip_inline(&chars[i + 2..close].iter().collect::<String>()));
+            i = close + 2;
+            continue;
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Where the `**` opening a bold span at `start` closes. Like markdown, a
+/// span opens before a non-space and closes after one.
+fn bold_close(chars: &[char], start: usize) -> Option<usize> {
+    let opens = chars.get(start) == Some(&'*')
+        && chars.get(start + 1) == Some(&'*')
+        && chars.get(start + 2).is_some_and(|c| !c.is_whitespace() && *c != '*');
+    if !opens {
+        return None;
+    }
+    (start + 3..chars.len().saturating_sub(1)).find(|&j| {
+        chars[j] == '*' && chars[j + 1] == '*' && !chars[j - 1].is_whitespace() && chars.get(j + 2) != Some(&'*')
+    })
+}
+
+/// Drops emoji, and the space after each ("🔥 Excellent" -> "Excellent")
+fn strip_emoji(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut after_emoji = false;
+    for c in line.chars() {
+        if is_emoji(c) {
+            after_emoji = true;
+            continue;
+        }
+        if !(after_emoji && c == ' ') {
+            text.push(c);
+        }
+        after_emoji = false;
+    }
+    text
+}
+
+/// Pictographs, symbols and the joiners and selectors that build emoji
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // emoji and pictographs
+        | 0x2600..=0x27BF   // misc symbols and dingbats
+        | 0x2B00..=0x2BFF   // arrows and stars
+        | 0xFE0F | 0x200D   // variation selector, zero-width joiner
+    )
+}
+
+/// Renders `item` once per format into `dir/<base_name>.<ext>`, returning the
+/// written paths in the order the formats were given. Every format is rendered
+/// before anything is written, so an unsupported format leaves no partial set.
+#[tracing::instrument(name = "write_renderings", level = "info", skip(item))]
+pub fn write_renderings(
+    item: &dyn Renderable,
+    formats: &[OutputFormat],
+    dir: &Path,
+    base_name: &str,
+) -> CHOPSResult<Vec<PathBuf>> {
+    let mut renderings: Vec<(String, String)> = Vec::with_capacity(formats.len());
+    for format in formats {
+        let extension = format.extension();
+        if renderings.iter().any(|(existing, _)| *existing == extension) {
+            continue;
+        }
+        renderings.push((extension, item.render(format)?));
+    }
+
+    std::fs::create_dir_all(dir).map_err(CHOPSError::FileSystemError)?;
+
+    let mut paths = Vec::with_capacity(renderings.len());
+    for (extension, content) in renderings {
+        let path = dir.join(format!("{}.{}", base_name, extension));
+        std::fs::write(&path, content).map_err(CHOPSError::FileSystemError)?;
+        tracing::debug!("Wrote {}", path.display());
+        paths.push(path);
+    }
+
+    tracing::info!("Wrote {} renderings of '{}' to {}", paths.len(), base_name, dir.display());
+    Ok(paths)
+}
+
+/// Minimal HTML page for the markdown our renderers produce: `#` headings,
+/// `-` bullet lists and paragraphs, with everything escaped
+pub fn markdown_to_html(title: &str, markdown: &str) -> String {
+    let mut body = String::new();
+    let mut in_list = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+
+    let flush_paragraph = |body: &mut String, paragraph: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            body.push_str(&format!("<p>{}</p>\n", escape_html(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        let heading_level = trimmed.chars().take_while(|c| *c == '#').count();
+
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            flush_paragraph(&mut body, &mut paragraph);
+            if !in_list {
+                body.push_str("<ul>\n");
+                in_list = true;
+            }
+            body.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+            continue;
+        }
+        if in_list {
+            body.push_str("</ul>\n");
+            in_list = false;
+        }
+
+        if (1..=6).contains(&heading_level) && trimmed[heading_level..].starts_with(' ') {
+            flush_paragraph(&mut body, &mut paragraph);
+            body.push_str(&format!(
+                "<h{level}>{}</h{level}>\n",
+                escape_html(trimmed[heading_level..].trim()),
+                level = heading_level
+            ));
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut body, &mut paragraph);
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    flush_paragraph(&mut body, &mut paragraph);
+    if in_list {
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+/// Escapes the characters that are special in HTML text and attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_formatting_drops_markup_but_keeps_code_text() {
+        let markdown = "# 🔥 Title\n\nCall **`__init__`** on **each** item, not snake__case or __init__.\n\n\n```python\ndef __init__(self):\n    pass\n```\n";
+        assert_eq!(
+            strip_formatting(markdown),
+            "Title\n\nCall __init__ on each item, not snake__case or __init__.\n\ndef __init__(self):\n    pass\n"
+        );
+    }
+
+    #[test]
+    fn test_unclosed_markers_are_kept() {
+        assert_eq!(strip_formatting("a ` tick and ** stars"), "a ` tick and ** stars\n");
+        assert_eq!(strip_formatting("``code with ` inside``"), "code with ` inside\n");
+    }
+}
@@ -1,10 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use crate::{CHOPSError, CHOPSResult};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default, JsonSchema)]
 pub enum PersonaType {
+    #[default]
     MadScientist,
     ZenMaster,
     PunkHacker,
@@ -12,47 +15,88 @@ pub enum PersonaType {
     ChaosEngineer,
     TimeTraveler,
     MindReader,
+    /// A persona defined in a `*.persona.toml` file, by its lowercase name.
+    /// Written as `custom:<name>` on the command line and in config keys.
+    #[serde(untagged)]
+    Custom(String),
 }
 
-impl std::fmt::Display for PersonaType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PersonaType::MadScientist => write!(f, "mad-scientist"),
-            PersonaType::ZenMaster => write!(f, "zen-master"),
-            PersonaType::PunkHacker => write!(f, "punk-hacker"),
-            PersonaType::EmpatheticAI => write!(f, "empathetic-ai"),
-            PersonaType::ChaosEngineer => write!(f, "chaos-engineer"),
-            PersonaType::TimeTraveler => write!(f, "time-traveler"),
-            PersonaType::MindReader => write!(f, "mind-reader"),
+impl PersonaType {
+    pub const ALL: [PersonaType; 7] = [
+        PersonaType::MadScientist,
+        PersonaType::ZenMaster,
+        PersonaType::PunkHacker,
+        PersonaType::EmpatheticAI,
+        PersonaType::ChaosEngineer,
+        PersonaType::TimeTraveler,
+        PersonaType::MindReader,
+    ];
+
+    /// A custom persona, after checking the name is usable: not empty, no
+    /// whitespace or `:`, and not the name of a built-in persona. Names are
+    /// lowercased with `_` read as `-`, like the built-in names.
+    pub fn custom(name: &str) -> Result<Self, crate::error::PersonaError> {
+        let name = name.trim().to_lowercase().replace('_', "-");
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ':') {
+            return Err(crate::error::PersonaError::InvalidConfiguration(format!(
+                "Custom persona name '{}' must be non-empty without spaces or ':'", name
+            )));
+        }
+        if name.parse::<PersonaType>().is_ok() {
+            return Err(crate::error::PersonaError::InvalidConfiguration(format!(
+                "Custom persona name '{}' is taken by a built-in persona", name
+            )));
         }
+        Ok(PersonaType::Custom(name))
     }
-}
-
-impl std::str::FromStr for PersonaType {
-    type Err = crate::error::PersonaError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "mad-scientist" | "madscientist" => Ok(PersonaType::MadScientist),
-            "zen-master" | "zenmaster" => Ok(PersonaType::ZenMaster),
-            "punk-hacker" | "punkhacker" => Ok(PersonaType::PunkHacker),
-            "empathetic-ai" | "empatheticai" => Ok(PersonaType::EmpatheticAI),
-            "chaos-engineer" | "chaosengineer" => Ok(PersonaType::ChaosEngineer),
-            "time-traveler" | "timetraveler" => Ok(PersonaType::TimeTraveler),
-            "mind-reader" | "mindreader" => Ok(PersonaType::MindReader),
-            _ => Err(crate::error::PersonaError::UnknownPersonaType(s.to_string())),
-        }
+    pub fn is_custom(&self) -> bool {
+        matches!(self, PersonaType::Custom(_))
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+crate::impl_enum_str!(PersonaType {
+    MadScientist => "mad-scientist",
+    ZenMaster => "zen-master",
+    PunkHacker => "punk-hacker",
+    EmpatheticAI => "empathetic-ai",
+    ChaosEngineer => "chaos-engineer",
+    TimeTraveler => "time-traveler",
+    MindReader => "mind-reader",
+} Custom("custom") {
+    parse: PersonaType::custom,
+    show: |name: &String| name.clone(),
+    error: crate::error::PersonaError = crate::error::PersonaError::UnknownPersonaType,
+});
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EntropySource {
     PseudoRandom,
     TrueRandom,
     QuantumRandom,
     ChaosEquation,
+    /// A local hardware RNG or entropy device such as `/dev/hwrng`, read 8 bytes at a time
+    DeviceFile(std::path::PathBuf),
 }
 
+crate::impl_enum_str!(EntropySource {
+    PseudoRandom => "pseudo" | "pseudo-random",
+    TrueRandom => "true" | "true-random",
+    QuantumRandom => "quantum" | "quantum-random",
+    ChaosEquation => "chaos" | "chaos-equation",
+} DeviceFile("device") {
+    parse: |path: &str| {
+        if path.trim().is_empty() {
+            return Err(crate::error::ChaosError::UnknownEntropySource(
+                "device needs a path, e.g. device:/dev/hwrng".to_string()
+            ));
+        }
+        Ok(EntropySource::DeviceFile(path.trim().into()))
+    },
+    show: |path: &std::path::PathBuf| path.display().to_string(),
+    error: crate::error::ChaosError = crate::error::ChaosError::UnknownEntropySource,
+});
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RandomDistribution {
     Uniform,
@@ -61,6 +105,39 @@ pub enum RandomDistribution {
     Chaotic,
 }
 
+/// Which LLM provider generates ideas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    #[default]
+    Claude,
+    OpenAi,
+    /// Canned offline completions, for development and CI without an API key
+    Mock,
+}
+
+crate::impl_enum_str!(LlmProvider {
+    Claude => "claude",
+    OpenAi => "openai",
+    Mock => "mock",
+});
+
+/// How a concept is compared with source-domain patterns when hunting for analogies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityStrategy {
+    /// Jaccard overlap of the words in each; works offline
+    #[default]
+    WordOverlap,
+    /// Cosine similarity of vectors from an embeddings endpoint
+    Embedding,
+}
+
+crate::impl_enum_str!(SimilarityStrategy {
+    WordOverlap => "word_overlap",
+    Embedding => "embedding",
+});
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CreativityLevel {
     Low,
@@ -70,7 +147,7 @@ pub enum CreativityLevel {
     Transcendent,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OutputFormat {
     Markdown,
     Json,
@@ -97,12 +174,172 @@ pub enum RiskLevel {
     Maximum,
 }
 
+/// How a persona likes its chaos: applied to the chaos engine while it is active
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaosAffinity {
+    /// Distribution chaos intensity is drawn from
+    pub distribution: RandomDistribution,
+    /// Reality distortion intensity (0.0-1.0)
+    pub reality_distortion: f64,
+    /// Lowest coherence the persona is comfortable with (0.0-1.0)
+    pub coherence_threshold: f64,
+}
+
+impl Default for ChaosAffinity {
+    fn default() -> Self {
+        Self {
+            distribution: RandomDistribution::Normal,
+            reality_distortion: 0.6,
+            coherence_threshold: 0.4,
+        }
+    }
+}
+
+/// Which family of variations a chaos level produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChaosTier {
+    Subtle,
+    Moderate,
+    RealityBending,
+    Impossible,
+    Transcendent,
+}
+
+crate::impl_enum_str!(ChaosTier {
+    Subtle => "subtle",
+    Moderate => "moderate",
+    RealityBending => "reality-bending",
+    Impossible => "impossible",
+    Transcendent => "transcendent",
+});
+
+impl ChaosTier {
+    /// Tier for a normalized chaos level under the default boundaries
+    pub fn for_level(level: f64) -> Self {
+        ChaosTierBoundaries::default().tier_for(level)
+    }
+}
+
+/// Where the chaos tiers meet, on the normalized chaos level. Subtle runs up
+/// to and including `subtle_max`, moderate up to `reality_bending_min`,
+/// reality bending up to `impossible_min`, and impossible up to and including
+/// `impossible_max`; anything above is transcendent. The tiers are contiguous,
+/// so each level lands in exactly one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosTierBoundaries {
+    pub subtle_max: f64,
+    pub reality_bending_min: f64,
+    pub impossible_min: f64,
+    pub impossible_max: f64,
+}
+
+impl Default for ChaosTierBoundaries {
+    fn default() -> Self {
+        Self {
+            subtle_max: 0.3,
+            reality_bending_min: 0.65,
+            impossible_min: 0.92,
+            impossible_max: 1.0,
+        }
+    }
+}
+
+impl ChaosTierBoundaries {
+    pub fn validate(&self) -> CHOPSResult<()> {
+        let bounds = [self.subtle_max, self.reality_bending_min, self.impossible_min, self.impossible_max];
+        let ordered = bounds.windows(2).all(|pair| pair[0] < pair[1]);
+        if bounds.iter().any(|bound| !bound.is_finite()) || self.subtle_max < 0.0 || !ordered {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "Chaos tier boundaries must be finite, non-negative and strictly increasing (got {:?})",
+                bounds
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn tier_for(&self, level: f64) -> ChaosTier {
+        if level <= self.subtle_max {
+            ChaosTier::Subtle
+        } else if level < self.reality_bending_min {
+            ChaosTier::Moderate
+        } else if level < self.impossible_min {
+            ChaosTier::RealityBending
+        } else if level <= self.impossible_max {
+            ChaosTier::Impossible
+        } else {
+            ChaosTier::Transcendent
+        }
+    }
+}
+
+impl ChaosAffinity {
+    pub fn validate(&self) -> CHOPSResult<()> {
+        for (name, value) in [("reality_distortion", self.reality_distortion), ("coherence_threshold", self.coherence_threshold)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(CHOPSError::InvalidParameter(format!(
+                    "Chaos affinity {} must be between 0.0 and 1.0, got {}", name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Range a summoned idea's feasibility score should land in, written `0.5-0.8`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeasibilityBand {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl FeasibilityBand {
+    pub fn contains(&self, feasibility: f64) -> bool {
+        (self.low..=self.high).contains(&feasibility)
+    }
+
+    /// How far `feasibility` lies outside the band, 0.0 inside it
+    pub fn distance(&self, feasibility: f64) -> f64 {
+        (self.low - feasibility).max(feasibility - self.high).max(0.0)
+    }
+}
+
+impl std::str::FromStr for FeasibilityBand {
+    type Err = CHOPSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || CHOPSError::InvalidParameter(format!(
+            "Feasibility band must look like 0.5-0.8 with 0.0 <= low <= high <= 1.0, got '{}'", s
+        ));
+        let (low, high) = s.split_once('-').ok_or_else(invalid)?;
+        let low: f64 = low.trim().parse().map_err(|_| invalid())?;
+        let high: f64 = high.trim().parse().map_err(|_| invalid())?;
+        if !(0.0..=1.0).contains(&low) || !(0.0..=1.0).contains(&high) || low > high {
+            return Err(invalid());
+        }
+        Ok(Self { low, high })
+    }
+}
+
+impl std::fmt::Display for FeasibilityBand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0}-{:.0}%", self.low * 100.0, self.high * 100.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChaosParams {
     pub chaos_level: u8,
+    /// `DeviceFile` sources are opened when the params are applied, failing then if the device is missing
     pub entropy_source: EntropySource,
     pub persona_type: PersonaType,
     pub distribution: RandomDistribution,
+    /// Lorenz (σ, ρ, β) for the chaotic distribution; classic constants when unset
+    #[serde(default)]
+    pub lorenz: Option<(f64, f64, f64)>,
+    /// Hénon (a, b); classic constants when unset
+    #[serde(default)]
+    pub henon: Option<(f64, f64)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,6 +370,15 @@ pub struct GeneratedIdea {
     pub experimental_variations: Vec<ExperimentalVariation>,
     pub analogies: Vec<Analogy>,
     pub timestamp: DateTime<Utc>,
+    /// Idea this one was built on (`summon --based-on`)
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    /// API tokens spent generating this idea
+    #[serde(default)]
+    pub tokens_used: u64,
+    /// Domain the idea was summoned for
+    #[serde(default)]
+    pub domain: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -213,8 +459,46 @@ pub struct MutateParams {
     pub add_easter_eggs: bool,
     pub make_weird: bool,
     pub keep_functional: bool,
+    /// Largest share of lines the mutation may change (0.0-1.0)
+    #[serde(default)]
+    pub max_change: Option<f64>,
+    /// Kinds of change the mutation may make; empty allows every kind
+    #[serde(default)]
+    pub allowed_classes: Vec<MutationClass>,
+    /// Fail instead of reverting when no mutation of a file in a checked
+    /// language (Rust) still parses
+    #[serde(default)]
+    pub verify_syntax: bool,
+}
+
+impl MutateParams {
+    pub fn allows(&self, class: MutationClass) -> bool {
+        self.allowed_classes.is_empty() || self.allowed_classes.contains(&class)
+    }
+
+    /// Only comments may change, never code
+    pub fn comments_only(&self) -> bool {
+        self.allowed_classes == [MutationClass::Comments]
+    }
+}
+
+/// Broad kinds of change a code mutation can make, from safest to riskiest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MutationClass {
+    /// Comments and doc comments only
+    Comments,
+    /// Identifier renames that leave behavior alone
+    Naming,
+    /// Anything that can change what the code does
+    Logic,
 }
 
+crate::impl_enum_str!(MutationClass {
+    Comments => "comments",
+    Naming => "naming",
+    Logic => "logic",
+});
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProphecyParams {
     pub year: Option<u32>,
@@ -249,8 +533,31 @@ pub struct CHOPSSession {
     pub learning_patterns: HashMap<String, f64>,
 }
 
-impl Default for PersonaType {
-    fn default() -> Self {
-        PersonaType::MadScientist
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persona_type_round_trips_through_its_name() {
+        for persona in PersonaType::ALL.into_iter().chain([PersonaType::Custom("growth-hacker".to_string())]) {
+            assert_eq!(persona.to_string().parse::<PersonaType>().unwrap(), persona);
+        }
+        assert_eq!("Zen_Master".parse::<PersonaType>().unwrap(), PersonaType::ZenMaster);
+        assert_eq!("CUSTOM:Growth_Hacker".parse::<PersonaType>().unwrap(), PersonaType::Custom("growth-hacker".to_string()));
+        assert!(matches!("wizard".parse::<PersonaType>(), Err(crate::error::PersonaError::UnknownPersonaType(_))));
+        assert!(matches!("custom:zen-master".parse::<PersonaType>(), Err(crate::error::PersonaError::InvalidConfiguration(_))));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_entropy_source_accepts_aliases_and_device_paths() {
+        assert_eq!("pseudo-random".parse::<EntropySource>().unwrap(), EntropySource::PseudoRandom);
+        assert_eq!("QuantumRandom".parse::<EntropySource>().unwrap(), EntropySource::QuantumRandom);
+        assert_eq!(EntropySource::ChaosEquation.to_string(), "chaos");
+
+        let device: EntropySource = "Device:/dev/HWRNG".parse().unwrap();
+        assert_eq!(device, EntropySource::DeviceFile("/dev/HWRNG".into()));
+        assert_eq!(device.to_string(), "device:/dev/HWRNG");
+        assert!("device: ".parse::<EntropySource>().is_err());
+        assert!(matches!("lava-lamp".parse::<EntropySource>(), Err(crate::error::ChaosError::UnknownEntropySource(_))));
+    }
+}
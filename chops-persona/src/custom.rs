@@ -0,0 +1,314 @@
+use crate::traits::*;
+use crate::engine::{PersonaPrompt, PersonaFeedback, StructureType, EmojiLevel, FormattingStyle};
+use chops_core::{ChaosAffinity, CHOPSError, CHOPSResult, PersonaType, MAX_STOP_SEQUENCES};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File suffix that marks a persona definition in a template directory
+pub const PERSONA_FILE_SUFFIX: &str = ".persona.toml";
+
+/// A persona declared in TOML instead of code, e.g. `growth-hacker.persona.toml`:
+///
+/// ```toml
+/// name = "growth-hacker"
+/// base_prompt = "You are {name}, a relentless growth hacker. Creativity: {creativity_bias}"
+/// thinking_patterns = ["What would 10x the funnel?"]
+/// vocabulary = ["viral", "flywheel"]
+/// creativity_bias = 0.8
+/// ethics_filter = 0.6
+/// risk_tolerance = 0.7
+/// excitement_level = 0.9
+///
+/// [conversation_style]
+/// formality_level = 0.3
+/// humor_frequency = 0.5
+/// tangent_tendency = 0.4
+/// interruption_style = "Enthusiastic"
+/// question_asking_frequency = 0.6
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonaSpec {
+    /// Name the persona is selected by, as `custom:<name>`
+    pub name: String,
+    /// Prompt template; `{name}` and the four bias names are filled in
+    pub base_prompt: String,
+    #[serde(default)]
+    pub thinking_patterns: Vec<String>,
+    #[serde(default)]
+    pub vocabulary: Vec<String>,
+    #[serde(default)]
+    pub personality_modifiers: Vec<String>,
+    pub creativity_bias: f64,
+    pub ethics_filter: f64,
+    pub risk_tolerance: f64,
+    pub excitement_level: f64,
+    #[serde(default)]
+    pub conversation_style: ConversationStyle,
+    /// Context keyword (e.g. "debugging") -> how the persona approaches it
+    #[serde(default)]
+    pub context_adaptations: HashMap<String, String>,
+    #[serde(default)]
+    pub chaos_affinity: Option<ChaosAffinity>,
+    /// Sequences that end the persona's replies; blank entries and duplicates are dropped
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+}
+
+impl PersonaSpec {
+    pub fn load_from_file(path: &Path) -> CHOPSResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let spec: PersonaSpec = toml::from_str(&content).map_err(|e| {
+            CHOPSError::PersonaError(format!("Invalid persona definition {}: {}", path.display(), e))
+        })?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// The persona type this spec registers under
+    pub fn persona_type(&self) -> CHOPSResult<PersonaType> {
+        PersonaType::custom(&self.name).map_err(|e| CHOPSError::PersonaError(e.to_string()))
+    }
+
+    pub fn validate(&self) -> CHOPSResult<()> {
+        self.persona_type()?;
+        if self.base_prompt.trim().is_empty() {
+            return Err(CHOPSError::PersonaError(format!("Persona '{}' has an empty base_prompt", self.name)));
+        }
+        let biases = [
+            ("creativity_bias", self.creativity_bias),
+            ("ethics_filter", self.ethics_filter),
+            ("risk_tolerance", self.risk_tolerance),
+            ("excitement_level", self.excitement_level),
+        ];
+        for (field, value) in biases {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(CHOPSError::PersonaError(format!(
+                    "Persona '{}' {} must be between 0.0 and 1.0 (got {})", self.name, field, value
+                )));
+            }
+        }
+        if let Some(affinity) = &self.chaos_affinity {
+            affinity.validate()?;
+        }
+        let stop_sequences = self.distinct_stop_sequences();
+        if stop_sequences.len() > MAX_STOP_SEQUENCES {
+            return Err(CHOPSError::PersonaError(format!(
+                "Persona '{}' has {} stop sequences, at most {} are allowed",
+                self.name, stop_sequences.len(), MAX_STOP_SEQUENCES
+            )));
+        }
+        Ok(())
+    }
+
+    /// `stop_sequences` without blank entries or duplicates, in order
+    pub fn distinct_stop_sequences(&self) -> Vec<String> {
+        let mut distinct: Vec<String> = Vec::new();
+        for sequence in &self.stop_sequences {
+            if !sequence.trim().is_empty() && !distinct.contains(sequence) {
+                distinct.push(sequence.clone());
+            }
+        }
+        distinct
+    }
+}
+
+/// `*.persona.toml` files in `directories`, sorted within each directory.
+/// Directories that don't exist are skipped.
+pub fn find_persona_files(directories: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for directory in directories {
+        let Ok(entries) = std::fs::read_dir(directory) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.to_string_lossy().ends_with(PERSONA_FILE_SUFFIX))
+            .collect();
+        paths.sort();
+        files.extend(paths);
+    }
+    files
+}
+
+/// A personality driven entirely by a [`PersonaSpec`]
+#[derive(Debug, Clone)]
+pub struct ConfigurablePersonality {
+    spec: PersonaSpec,
+    /// Scales creativity and excitement, moved by feedback like the built-in personas' amplifiers
+    intensity: f64,
+}
+
+impl ConfigurablePersonality {
+    #[tracing::instrument(name = "configurable_personality_new", level = "debug", skip(spec), fields(name = %spec.name))]
+    pub fn new(spec: PersonaSpec) -> Self {
+        tracing::debug!("Creating configurable personality with {} thinking patterns", spec.thinking_patterns.len());
+        Self { spec, intensity: 1.0 }
+    }
+
+    pub fn spec(&self) -> &PersonaSpec {
+        &self.spec
+    }
+
+    fn render_template(&self) -> String {
+        self.spec.base_prompt
+            .replace("{name}", &self.spec.name)
+            .replace("{creativity_bias}", &format!("{:.2}", self.get_creativity_bias()))
+            .replace("{ethics_filter}", &format!("{:.2}", self.get_ethics_filter()))
+            .replace("{risk_tolerance}", &format!("{:.2}", self.get_risk_tolerance()))
+            .replace("{excitement_level}", &format!("{:.2}", self.get_excitement_level()))
+    }
+}
+
+impl PersonalityTrait for ConfigurablePersonality {
+    fn generate_base_prompt(&self) -> PersonaPrompt {
+        let excitement = self.get_excitement_level();
+        let creativity = self.get_creativity_bias();
+        PersonaPrompt {
+            base_prompt: self.render_template(),
+            thinking_patterns: self.get_thinking_patterns(),
+            personality_modifiers: self.spec.personality_modifiers.clone(),
+            vocabulary_style: create_vocabulary_style(
+                excitement,
+                0.5 + 0.5 * self.spec.conversation_style.formality_level,
+                self.spec.conversation_style.tangent_tendency,
+                creativity,
+            ),
+            response_format: create_response_format(
+                if creativity > 0.8 { StructureType::Creative } else { StructureType::Hierarchical },
+                if excitement > 0.7 { EmojiLevel::Moderate } else { EmojiLevel::Minimal },
+                if self.spec.conversation_style.formality_level > 0.6 { FormattingStyle::Clean } else { FormattingStyle::Artistic },
+            ),
+        }
+    }
+
+    fn get_thinking_patterns(&self) -> Vec<String> {
+        self.spec.thinking_patterns.clone()
+    }
+
+    fn get_vocabulary_enhancements(&self) -> Vec<String> {
+        self.spec.vocabulary.clone()
+    }
+
+    fn get_creativity_bias(&self) -> f64 { (self.spec.creativity_bias * self.intensity).min(1.0) }
+    fn get_ethics_filter(&self) -> f64 { self.spec.ethics_filter }
+    fn get_risk_tolerance(&self) -> f64 { self.spec.risk_tolerance }
+    fn get_excitement_level(&self) -> f64 { (self.spec.excitement_level * self.intensity).min(1.0) }
+
+    fn adapt_to_context(&self, context: &str) -> String {
+        self.spec.context_adaptations
+            .get(&context.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| format!("Approach this {} challenge the way {} would.", context, self.spec.name))
+    }
+
+    #[tracing::instrument(name = "configurable_apply_feedback", level = "debug", skip(self), fields(name = %self.spec.name))]
+    fn apply_feedback(&mut self, feedback: PersonaFeedback) -> CHOPSResult<()> {
+        if feedback.effectiveness_rating > 0.8 {
+            self.intensity = (self.intensity * 1.1).min(1.5);
+            tracing::debug!("High effectiveness - increasing intensity to {:.2}", self.intensity);
+        } else if feedback.effectiveness_rating < 0.4 {
+            self.intensity = (self.intensity * 0.9).max(0.5);
+            tracing::debug!("Low effectiveness - decreasing intensity to {:.2}", self.intensity);
+        }
+        Ok(())
+    }
+
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "intensity": self.intensity,
+        })
+    }
+
+    fn get_conversation_style(&self) -> ConversationStyle {
+        self.spec.conversation_style.clone()
+    }
+
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        self.spec.chaos_affinity.clone().unwrap_or_default()
+    }
+
+    fn stop_sequences(&self) -> Vec<String> {
+        self.spec.stop_sequences.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::PersonaEngine;
+
+    fn persona_file(name: &str, extra: &str) -> String {
+        format!(
+            "name = \"{}\"\nbase_prompt = \"You are {{name}}.\"\ncreativity_bias = 0.8\nethics_filter = 0.6\nrisk_tolerance = 0.7\nexcitement_level = 0.9\n{}\n",
+            name, extra
+        )
+    }
+
+    fn persona_dir(test: &str, files: &[(&str, String)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("chops-personas-{}-{}", std::process::id(), test));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, content) in files {
+            std::fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_persona_files_load_with_distinct_stop_sequences() {
+        let dir = persona_dir("load", &[
+            ("growth-hacker.persona.toml", persona_file("growth-hacker", "stop_sequences = [\"END\", \" \", \"END\", \"Fin\"]")),
+            ("notes.toml", persona_file("ignored", "")),
+        ]);
+        let mut engine = PersonaEngine::new();
+        let (loaded, warnings) = engine.load_custom_personas(std::slice::from_ref(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let growth_hacker = PersonaType::custom("growth-hacker").unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0], growth_hacker);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(engine.stop_sequences(&growth_hacker).unwrap(), ["END", "Fin"]);
+    }
+
+    #[test]
+    fn test_invalid_persona_files_are_rejected() {
+        let stops: Vec<String> = (0..=MAX_STOP_SEQUENCES).map(|i| format!("\"STOP{}\"", i)).collect();
+        let dir = persona_dir("reject", &[
+            ("wild.persona.toml", persona_file("wild", "").replace("creativity_bias = 0.8", "creativity_bias = 1.5")),
+            ("chatty.persona.toml", persona_file("chatty", &format!("stop_sequences = [{}]", stops.join(", ")))),
+            ("broken.persona.toml", "name = \"broken\"".to_string()),
+        ]);
+        let chatty = PersonaSpec::load_from_file(&dir.join("chatty.persona.toml"));
+        let mut engine = PersonaEngine::new();
+        let (loaded, warnings) = engine.load_custom_personas(std::slice::from_ref(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(&chatty, Err(CHOPSError::PersonaError(message)) if message.contains("stop sequences")), "{:?}", chatty);
+        assert!(loaded.is_empty());
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+        for file in ["broken", "chatty", "wild"] {
+            assert!(warnings.iter().any(|warning| warning.contains(&format!("{}.persona.toml", file))), "{}: {:?}", file, warnings);
+        }
+    }
+
+    #[test]
+    fn test_unknown_custom_persona_lists_the_loaded_files() {
+        let missing = PersonaType::custom("missing").unwrap();
+        let error = PersonaEngine::new().stop_sequences(&missing).unwrap_err();
+        assert!(matches!(&error, CHOPSError::PersonaError(message) if message.contains("no persona files were loaded")), "{}", error);
+
+        let dir = persona_dir("unknown", &[("growth-hacker.persona.toml", persona_file("growth-hacker", ""))]);
+        let mut engine = PersonaEngine::new();
+        engine.load_custom_personas(std::slice::from_ref(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let error = engine.stop_sequences(&missing).unwrap_err();
+        let CHOPSError::PersonaError(message) = &error else {
+            panic!("expected a persona error, got {}", error);
+        };
+        assert!(message.contains("'missing'"), "{}", message);
+        assert!(message.contains("'growth-hacker' from"), "{}", message);
+        assert!(message.contains("growth-hacker.persona.toml"), "{}", message);
+    }
+}
@@ -1,13 +1,68 @@
-use chops_core::{PersonaType, PersonalityContext, CHOPSResult, CHOPSError};
-use crate::{personalities::*, PersonalityTrait};
+use chops_core::{ChaosAffinity, PersonaType, PersonalityContext, CHOPSResult, CHOPSError};
+use crate::{personalities::*, ConfigurablePersonality, ConversationStyle, PersonaSpec, PersonalityTrait, VoiceProfile};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use rand::Rng;
 
 pub struct PersonaEngine {
     personas: HashMap<PersonaType, Box<dyn PersonalityTrait>>,
     context_history: Vec<PersonalityContext>,
     adaptation_enabled: bool,
+    drift_limits: DriftLimits,
+    /// Chaos affinities set from config, replacing the personas' own
+    affinity_overrides: HashMap<PersonaType, ChaosAffinity>,
+    /// Stop sequences set from config, replacing the personas' own
+    stop_overrides: HashMap<PersonaType, Vec<String>>,
+    /// Definitions of the registered custom personas, by name, for resets
+    custom_specs: HashMap<String, PersonaSpec>,
+    /// Files the custom personas were loaded from, by name
+    custom_files: HashMap<String, PathBuf>,
+}
+
+/// How far adaptive learning may pull a persona away from its base character
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DriftLimits {
+    /// Fraction of the gap to the recent average closed on each activation
+    pub drift_rate: f64,
+    /// Maximum distance from the base value for creativity, risk and excitement
+    pub max_drift: f64,
+}
+
+impl Default for DriftLimits {
+    fn default() -> Self {
+        Self {
+            drift_rate: 0.1,
+            max_drift: 0.2,
+        }
+    }
+}
+
+/// Everything that shapes a persona's behavior right now, for persona authors
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonaDebugState {
+    pub persona_type: PersonaType,
+    /// Tunable internal fields as they stand now
+    pub internal_state: serde_json::Value,
+    /// Internal fields that feedback has moved away from their defaults
+    pub drifted_fields: Vec<FieldDrift>,
+    /// Biases an activation would get now, after adaptive learning but before domain tweaks
+    pub effective_context: PersonalityContext,
+    pub conversation_style: ConversationStyle,
+    pub base_prompt: PersonaPrompt,
+    /// Modifiers that would be appended to the prompt now (one is random)
+    pub adaptive_modifiers: Vec<String>,
+    /// Activations of this persona among the remembered contexts
+    pub recent_activations: usize,
+    pub adaptation_enabled: bool,
+    pub drift_limits: DriftLimits,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldDrift {
+    pub field: String,
+    pub base: serde_json::Value,
+    pub current: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,26 +121,12 @@ impl PersonaEngine {
         
         let mut personas: HashMap<PersonaType, Box<dyn PersonalityTrait>> = HashMap::new();
         
-        tracing::debug!("Registering MadScientist personality");
-        personas.insert(PersonaType::MadScientist, Box::new(MadScientistPersonality::new()));
-        
-        tracing::debug!("Registering ZenMaster personality");
-        personas.insert(PersonaType::ZenMaster, Box::new(ZenMasterPersonality::new()));
-        
-        tracing::debug!("Registering PunkHacker personality");
-        personas.insert(PersonaType::PunkHacker, Box::new(PunkHackerPersonality::new()));
-        
-        tracing::debug!("Registering EmpatheticAI personality");
-        personas.insert(PersonaType::EmpatheticAI, Box::new(EmpatheticAIPersonality::new()));
-        
-        tracing::debug!("Registering ChaosEngineer personality");
-        personas.insert(PersonaType::ChaosEngineer, Box::new(ChaosEngineerPersonality::new()));
-        
-        tracing::debug!("Registering TimeTraveler personality");
-        personas.insert(PersonaType::TimeTraveler, Box::new(TimeTravelerPersonality::new()));
-        
-        tracing::debug!("Registering MindReader personality");
-        personas.insert(PersonaType::MindReader, Box::new(MindReaderPersonality::new()));
+        for persona_type in PersonaType::ALL {
+            tracing::debug!("Registering {:?} personality", persona_type);
+            if let Some(personality) = Self::builtin_personality(&persona_type) {
+                personas.insert(persona_type, personality);
+            }
+        }
         
         tracing::info!("PersonaEngine initialized with {} personalities", personas.len());
         
@@ -93,7 +134,138 @@ impl PersonaEngine {
             personas,
             context_history: Vec::new(),
             adaptation_enabled: true,
+            drift_limits: DriftLimits::default(),
+            affinity_overrides: HashMap::new(),
+            stop_overrides: HashMap::new(),
+            custom_specs: HashMap::new(),
+            custom_files: HashMap::new(),
+        }
+    }
+    
+    fn builtin_personality(persona_type: &PersonaType) -> Option<Box<dyn PersonalityTrait>> {
+        let personality: Box<dyn PersonalityTrait> = match persona_type {
+            PersonaType::MadScientist => Box::new(MadScientistPersonality::new()),
+            PersonaType::ZenMaster => Box::new(ZenMasterPersonality::new()),
+            PersonaType::PunkHacker => Box::new(PunkHackerPersonality::new()),
+            PersonaType::EmpatheticAI => Box::new(EmpatheticAIPersonality::new()),
+            PersonaType::ChaosEngineer => Box::new(ChaosEngineerPersonality::new()),
+            PersonaType::TimeTraveler => Box::new(TimeTravelerPersonality::new()),
+            PersonaType::MindReader => Box::new(MindReaderPersonality::new()),
+            PersonaType::Custom(_) => return None,
+        };
+        Some(personality)
+    }
+    
+    /// A fresh personality for `persona_type`, or None for a custom persona
+    /// that was never registered
+    fn create_personality(&self, persona_type: &PersonaType) -> Option<Box<dyn PersonalityTrait>> {
+        match persona_type {
+            PersonaType::Custom(name) => self.custom_specs.get(name)
+                .map(|spec| Box::new(ConfigurablePersonality::new(spec.clone())) as Box<dyn PersonalityTrait>),
+            builtin => Self::builtin_personality(builtin),
+        }
+    }
+    
+    /// A custom persona is named by the `name` in its file, not the file name,
+    /// so the error lists which file defined which persona
+    fn unknown_persona(&self, persona_type: &PersonaType) -> CHOPSError {
+        let PersonaType::Custom(name) = persona_type else {
+            return CHOPSError::PersonaError(format!("Unknown persona type: {:?}", persona_type));
+        };
+        let mut loaded: Vec<(&String, &PathBuf)> = self.custom_files.iter().collect();
+        loaded.sort();
+        let loaded = if loaded.is_empty() {
+            "no persona files were loaded".to_string()
+        } else {
+            let files: Vec<String> = loaded.iter().map(|(name, path)| format!("'{}' from {}", name, path.display())).collect();
+            format!("loaded: {}", files.join(", "))
+        };
+        CHOPSError::PersonaError(format!(
+            "Unknown custom persona '{}': no *{} file in the template directories has name = \"{}\" ({})",
+            name, crate::PERSONA_FILE_SUFFIX, name, loaded
+        ))
+    }
+    
+    /// Registers a persona defined by `spec` as `PersonaType::Custom`, replacing
+    /// any custom persona of the same name
+    #[tracing::instrument(name = "register_custom_persona", level = "info", skip(self, spec), fields(name = %spec.name))]
+    pub fn register_custom_persona(&mut self, mut spec: PersonaSpec) -> CHOPSResult<PersonaType> {
+        spec.validate()?;
+        let persona_type = spec.persona_type()?;
+        let PersonaType::Custom(name) = &persona_type else {
+            unreachable!("PersonaSpec::persona_type always returns a custom persona");
+        };
+        spec.name = name.clone();
+        spec.stop_sequences = spec.distinct_stop_sequences();
+        if self.custom_specs.insert(name.clone(), spec.clone()).is_some() {
+            tracing::info!("Replacing custom persona '{}'", name);
+        }
+        self.personas.insert(persona_type.clone(), Box::new(ConfigurablePersonality::new(spec)));
+        Ok(persona_type)
+    }
+    
+    /// Registers every `*.persona.toml` in `directories`, most general first so
+    /// later directories override earlier ones. Returns the registered personas
+    /// and a warning per file that couldn't be loaded.
+    pub fn load_custom_personas(&mut self, directories: &[PathBuf]) -> (Vec<PersonaType>, Vec<String>) {
+        let mut loaded = Vec::new();
+        let mut warnings = Vec::new();
+        for path in crate::find_persona_files(directories) {
+            match PersonaSpec::load_from_file(&path).and_then(|spec| self.register_custom_persona(spec)) {
+                Ok(persona_type) => {
+                    tracing::debug!("Loaded custom persona {} from {}", persona_type, path.display());
+                    if let PersonaType::Custom(name) = &persona_type {
+                        self.custom_files.insert(name.clone(), path.clone());
+                    }
+                    if !loaded.contains(&persona_type) {
+                        loaded.push(persona_type);
+                    }
+                },
+                Err(e) => warnings.push(format!("Ignoring persona definition {}: {}", path.display(), e)),
+            }
         }
+        tracing::info!("Loaded {} custom personas", loaded.len());
+        (loaded, warnings)
+    }
+    
+    /// Whether `persona_type` can be activated: always for built-in personas,
+    /// and for custom ones once registered
+    pub fn has_persona(&self, persona_type: &PersonaType) -> bool {
+        self.personas.contains_key(persona_type)
+    }
+    
+    /// Custom personas currently registered, by name
+    pub fn custom_personas(&self) -> Vec<PersonaType> {
+        let mut names: Vec<&String> = self.custom_specs.keys().collect();
+        names.sort();
+        names.into_iter().map(|name| PersonaType::Custom(name.clone())).collect()
+    }
+    
+    pub fn drift_limits(&self) -> DriftLimits {
+        self.drift_limits
+    }
+    
+    pub fn set_drift_limits(&mut self, limits: DriftLimits) -> CHOPSResult<()> {
+        if !(0.0..=1.0).contains(&limits.drift_rate) || limits.max_drift < 0.0 {
+            return Err(CHOPSError::InvalidParameter(format!(
+                "Persona drift rate must be in [0.0, 1.0] and max drift non-negative (got {} / {})",
+                limits.drift_rate, limits.max_drift
+            )));
+        }
+        self.drift_limits = limits;
+        Ok(())
+    }
+    
+    /// Restores a persona to its default character, forgetting learned feedback
+    /// and its recent activations
+    #[tracing::instrument(name = "reset_persona", level = "info", skip(self))]
+    pub fn reset_persona(&mut self, persona_type: &PersonaType) {
+        if let Some(personality) = self.create_personality(persona_type) {
+            self.personas.insert(persona_type.clone(), personality);
+        }
+        let before = self.context_history.len();
+        self.context_history.retain(|c| &c.persona_type != persona_type);
+        tracing::info!("Reset {:?}, dropped {} remembered activations", persona_type, before - self.context_history.len());
     }
     
     #[tracing::instrument(name = "generate_persona_prompt", level = "info", skip(self))]
@@ -103,7 +275,7 @@ impl PersonaEngine {
         let personality = self.personas.get(persona_type)
             .ok_or_else(|| {
                 tracing::error!("Unknown persona type requested: {:?}", persona_type);
-                CHOPSError::PersonaError(format!("Unknown persona type: {:?}", persona_type))
+                self.unknown_persona(persona_type)
             })?;
         
         tracing::debug!("Generating base prompt for persona");
@@ -141,19 +313,11 @@ impl PersonaEngine {
         let personality = self.personas.get(&persona_type)
             .ok_or_else(|| {
                 tracing::error!("Unknown persona type for activation: {:?}", persona_type);
-                CHOPSError::PersonaError(format!("Unknown persona type: {:?}", persona_type))
+                self.unknown_persona(&persona_type)
             })?;
         
         tracing::debug!("Creating base personality context");
-        let mut context = PersonalityContext {
-            persona_type: persona_type.clone(),
-            thinking_patterns: personality.get_thinking_patterns(),
-            vocabulary_enhancements: personality.get_vocabulary_enhancements(),
-            creativity_bias: personality.get_creativity_bias(),
-            ethics_filter: personality.get_ethics_filter(),
-            risk_tolerance: personality.get_risk_tolerance(),
-            excitement_level: personality.get_excitement_level(),
-        };
+        let mut context = Self::base_context(&persona_type, personality.as_ref());
         
         tracing::debug!("Context created with {} thinking patterns, creativity: {:.2}, risk tolerance: {:.2}", 
             context.thinking_patterns.len(), context.creativity_bias, context.risk_tolerance);
@@ -182,6 +346,109 @@ impl PersonaEngine {
         Ok(context)
     }
     
+    fn base_context(persona_type: &PersonaType, personality: &dyn PersonalityTrait) -> PersonalityContext {
+        PersonalityContext {
+            persona_type: persona_type.clone(),
+            thinking_patterns: personality.get_thinking_patterns(),
+            vocabulary_enhancements: personality.get_vocabulary_enhancements(),
+            creativity_bias: personality.get_creativity_bias(),
+            ethics_filter: personality.get_ethics_filter(),
+            risk_tolerance: personality.get_risk_tolerance(),
+            excitement_level: personality.get_excitement_level(),
+        }
+    }
+    
+    /// The voice generated output should match for `persona_type`
+    pub fn voice_profile(&self, persona_type: &PersonaType) -> CHOPSResult<VoiceProfile> {
+        let personality = self.personas.get(persona_type)
+            .ok_or_else(|| self.unknown_persona(persona_type))?;
+        Ok(VoiceProfile::from_prompt(&personality.generate_base_prompt(), &personality.get_conversation_style()))
+    }
+
+    /// Chaos settings for a persona: the config override if there is one,
+    /// otherwise the persona's own affinity
+    pub fn chaos_affinity(&self, persona_type: &PersonaType) -> CHOPSResult<ChaosAffinity> {
+        if let Some(affinity) = self.affinity_overrides.get(persona_type) {
+            return Ok(affinity.clone());
+        }
+        let personality = self.personas.get(persona_type)
+            .ok_or_else(|| self.unknown_persona(persona_type))?;
+        Ok(personality.chaos_affinity())
+    }
+
+    /// Stop sequences a persona adds to the configured ones: the config
+    /// override if there is one, otherwise the persona's own
+    pub fn stop_sequences(&self, persona_type: &PersonaType) -> CHOPSResult<Vec<String>> {
+        if let Some(sequences) = self.stop_overrides.get(persona_type) {
+            return Ok(sequences.clone());
+        }
+        let personality = self.personas.get(persona_type)
+            .ok_or_else(|| self.unknown_persona(persona_type))?;
+        Ok(personality.stop_sequences())
+    }
+
+    pub fn set_stop_sequences(&mut self, persona_type: PersonaType, sequences: Vec<String>) {
+        tracing::debug!("Overriding stop sequences for {:?}: {:?}", persona_type, sequences);
+        self.stop_overrides.insert(persona_type, sequences);
+    }
+
+    pub fn set_chaos_affinity(&mut self, persona_type: PersonaType, affinity: ChaosAffinity) -> CHOPSResult<()> {
+        affinity.validate()?;
+        tracing::debug!("Overriding chaos affinity for {:?}: {:?}", persona_type, affinity);
+        self.affinity_overrides.insert(persona_type, affinity);
+        Ok(())
+    }
+
+    /// Snapshot of a persona's live state without activating it
+    #[tracing::instrument(name = "persona_debug_state", level = "debug", skip(self))]
+    pub fn debug_state(&self, persona_type: &PersonaType) -> CHOPSResult<PersonaDebugState> {
+        let personality = self.personas.get(persona_type)
+            .ok_or_else(|| self.unknown_persona(persona_type))?;
+        
+        let internal_state = personality.describe_state();
+        let base_state = self.create_personality(persona_type)
+            .map(|fresh| fresh.describe_state())
+            .unwrap_or(serde_json::Value::Null);
+        let drifted_fields = match (&internal_state, &base_state) {
+            (serde_json::Value::Object(current), serde_json::Value::Object(base)) => current
+                .iter()
+                .filter(|(field, value)| base.get(field.as_str()) != Some(value))
+                .map(|(field, value)| FieldDrift {
+                    field: field.clone(),
+                    base: base.get(field.as_str()).cloned().unwrap_or(serde_json::Value::Null),
+                    current: value.clone(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        
+        let mut effective_context = Self::base_context(persona_type, personality.as_ref());
+        if self.adaptation_enabled {
+            effective_context = self.apply_adaptive_learning(effective_context)?;
+        }
+        
+        let adaptive_modifiers = if self.adaptation_enabled {
+            self.get_adaptive_modifiers(persona_type)
+        } else {
+            Vec::new()
+        };
+        
+        tracing::debug!("{:?} has {} drifted fields", persona_type, drifted_fields.len());
+        
+        Ok(PersonaDebugState {
+            persona_type: persona_type.clone(),
+            internal_state,
+            drifted_fields,
+            effective_context,
+            conversation_style: personality.get_conversation_style(),
+            base_prompt: personality.generate_base_prompt(),
+            adaptive_modifiers,
+            recent_activations: self.context_history.iter().filter(|c| &c.persona_type == persona_type).count(),
+            adaptation_enabled: self.adaptation_enabled,
+            drift_limits: self.drift_limits,
+        })
+    }
+    
     #[tracing::instrument(name = "blend_personas", level = "info", skip(self))]
     pub fn blend_personas(&self, primary: PersonaType, secondary: PersonaType, blend_ratio: f64) -> CHOPSResult<PersonalityContext> {
         tracing::info!("Blending personas: {:?} ({:.2}) + {:?} ({:.2})", 
@@ -195,13 +462,13 @@ impl PersonaEngine {
         let primary_personality = self.personas.get(&primary)
             .ok_or_else(|| {
                 tracing::error!("Unknown primary persona type: {:?}", primary);
-                CHOPSError::PersonaError(format!("Unknown persona type: {:?}", primary))
+                self.unknown_persona(&primary)
             })?;
         
         let secondary_personality = self.personas.get(&secondary)
             .ok_or_else(|| {
                 tracing::error!("Unknown secondary persona type: {:?}", secondary);
-                CHOPSError::PersonaError(format!("Unknown persona type: {:?}", secondary))
+                self.unknown_persona(&secondary)
             })?;
         
         tracing::debug!("Creating blended personality context");
@@ -249,6 +516,138 @@ impl PersonaEngine {
         Ok(blended_context)
     }
     
+    /// Validates blend weights, adds up the weights of a persona listed more
+    /// than once and normalizes them to sum to 1.0, dropping zero weights
+    pub fn normalize_blend(weights: &[(PersonaType, f64)]) -> CHOPSResult<Vec<(PersonaType, f64)>> {
+        if weights.is_empty() {
+            return Err(CHOPSError::InvalidParameter("Give at least one persona to blend".to_string()));
+        }
+        if let Some((persona, weight)) = weights.iter().find(|(_, weight)| !weight.is_finite() || *weight < 0.0) {
+            return Err(CHOPSError::InvalidParameter(format!("Blend weight for {} must be zero or more, got {}", persona, weight)));
+        }
+
+        let mut merged: Vec<(PersonaType, f64)> = Vec::new();
+        for (persona, weight) in weights {
+            match merged.iter_mut().find(|(existing, _)| existing == persona) {
+                Some((_, total)) => *total += weight,
+                None => merged.push((persona.clone(), *weight)),
+            }
+        }
+        let total: f64 = merged.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Err(CHOPSError::InvalidParameter("Blend weights are all zero".to_string()));
+        }
+        merged.retain(|(_, weight)| *weight > 0.0);
+        for (_, weight) in &mut merged {
+            *weight /= total;
+        }
+        Ok(merged)
+    }
+
+    /// Blends any number of personas, weighted as [`Self::normalize_blend`]
+    /// describes. Biases are the weighted average. Each persona contributes
+    /// its share of thinking patterns (rounded up), and vocabulary is pooled
+    /// and deduplicated. The context takes the persona type of the heaviest
+    /// persona.
+    #[tracing::instrument(name = "blend_many", level = "info", skip(self))]
+    pub fn blend_many(&self, weights: &[(PersonaType, f64)]) -> CHOPSResult<PersonalityContext> {
+        let merged = Self::normalize_blend(weights)?;
+        tracing::info!("Blending {} personas: {:?}", merged.len(), merged);
+
+        let mut personalities = Vec::with_capacity(merged.len());
+        for (persona, weight) in &merged {
+            let personality = self.personas.get(persona).ok_or_else(|| {
+                tracing::error!("Unknown persona type in blend: {:?}", persona);
+                self.unknown_persona(persona)
+            })?;
+            personalities.push((personality, *weight));
+        }
+
+        let weighted = |attribute: fn(&dyn PersonalityTrait) -> f64| -> f64 {
+            personalities.iter().map(|(personality, weight)| attribute(personality.as_ref()) * weight).sum()
+        };
+        let dominant = merged.iter()
+            .fold(&merged[0], |best, entry| if entry.1 > best.1 { entry } else { best })
+            .0.clone();
+
+        let mut blended_context = PersonalityContext {
+            persona_type: dominant,
+            thinking_patterns: Vec::new(),
+            vocabulary_enhancements: Vec::new(),
+            creativity_bias: weighted(|personality| personality.get_creativity_bias()),
+            ethics_filter: weighted(|personality| personality.get_ethics_filter()),
+            risk_tolerance: weighted(|personality| personality.get_risk_tolerance()),
+            excitement_level: weighted(|personality| personality.get_excitement_level()),
+        };
+
+        for (personality, weight) in &personalities {
+            let patterns = personality.get_thinking_patterns();
+            let count = (patterns.len() as f64 * weight).ceil() as usize;
+            for pattern in patterns.into_iter().take(count) {
+                if !blended_context.thinking_patterns.contains(&pattern) {
+                    blended_context.thinking_patterns.push(pattern);
+                }
+            }
+            blended_context.vocabulary_enhancements.extend(personality.get_vocabulary_enhancements());
+        }
+        blended_context.vocabulary_enhancements.sort();
+        blended_context.vocabulary_enhancements.dedup();
+
+        tracing::debug!("Blended attributes - creativity: {:.2}, ethics: {:.2}, risk: {:.2}, excitement: {:.2}, {} thinking patterns",
+            blended_context.creativity_bias, blended_context.ethics_filter,
+            blended_context.risk_tolerance, blended_context.excitement_level,
+            blended_context.thinking_patterns.len());
+        Ok(blended_context)
+    }
+
+    /// Registers a weighted blend as a custom persona that summon can use,
+    /// named after its recipe (e.g. `custom:blend-mad-scientist-50-zen-master-50`).
+    /// Its prompt carries each persona's base prompt with its share; chaos
+    /// affinity and conversation style come from the heaviest persona.
+    #[tracing::instrument(name = "register_blend", level = "info", skip(self))]
+    pub fn register_blend(&mut self, weights: &[(PersonaType, f64)]) -> CHOPSResult<PersonaType> {
+        let merged = Self::normalize_blend(weights)?;
+        let context = self.blend_many(&merged)?;
+        let dominant = self.personas.get(&context.persona_type)
+            .ok_or_else(|| self.unknown_persona(&context.persona_type))?;
+
+        let recipe: Vec<String> = merged.iter()
+            .map(|(persona, weight)| format!("{} ({:.0}%)", persona, weight * 100.0))
+            .collect();
+        let mut base_prompt = format!(
+            "You are {{name}}, a blend of {}. Creativity: {{creativity_bias}}, ethics filter: {{ethics_filter}}, \
+            risk tolerance: {{risk_tolerance}}, excitement: {{excitement_level}}. Let each voice below shape the idea in proportion to its share.",
+            recipe.join(", ")
+        );
+        for (persona, weight) in &merged {
+            let personality = self.personas.get(persona).ok_or_else(|| self.unknown_persona(persona))?;
+            base_prompt.push_str(&format!(
+                "\n\n{} ({:.0}%):\n{}",
+                persona, weight * 100.0, personality.generate_base_prompt().base_prompt
+            ));
+        }
+
+        let name = merged.iter()
+            .map(|(persona, weight)| format!("{}-{:.0}", persona.to_string().replace(':', "-"), weight * 100.0))
+            .fold("blend".to_string(), |name, part| format!("{}-{}", name, part));
+        let spec = PersonaSpec {
+            name,
+            base_prompt,
+            thinking_patterns: context.thinking_patterns,
+            vocabulary: context.vocabulary_enhancements,
+            personality_modifiers: Vec::new(),
+            creativity_bias: context.creativity_bias,
+            ethics_filter: context.ethics_filter,
+            risk_tolerance: context.risk_tolerance,
+            excitement_level: context.excitement_level,
+            conversation_style: dominant.get_conversation_style(),
+            context_adaptations: HashMap::new(),
+            chaos_affinity: Some(self.chaos_affinity(&context.persona_type)?),
+            stop_sequences: Vec::new(),
+        };
+        self.register_custom_persona(spec)
+    }
+
     pub fn evolve_persona(&mut self, persona_type: &PersonaType, feedback: PersonaFeedback) -> CHOPSResult<()> {
         if !self.adaptation_enabled {
             return Ok(());
@@ -322,15 +721,17 @@ impl PersonaEngine {
             .map(|c| c.excitement_level)
             .sum::<f64>() / recent_contexts.len() as f64;
         
-        // Apply momentum-based adaptation (gradual drift toward recent patterns)
-        let adaptation_factor = 0.1; // How much to adapt
+        // Apply momentum-based adaptation (gradual drift toward recent patterns),
+        // but never further than max_drift from the persona's own values
+        let DriftLimits { drift_rate, max_drift } = self.drift_limits;
+        let drift = |base: f64, recent: f64| {
+            let drifted = base * (1.0 - drift_rate) + recent * drift_rate;
+            drifted.clamp(base - max_drift, base + max_drift)
+        };
         
-        context.creativity_bias = context.creativity_bias * (1.0 - adaptation_factor) + 
-                                 avg_creativity * adaptation_factor;
-        context.risk_tolerance = context.risk_tolerance * (1.0 - adaptation_factor) + 
-                                avg_risk_tolerance * adaptation_factor;
-        context.excitement_level = context.excitement_level * (1.0 - adaptation_factor) + 
-                                  avg_excitement * adaptation_factor;
+        context.creativity_bias = drift(context.creativity_bias, avg_creativity);
+        context.risk_tolerance = drift(context.risk_tolerance, avg_risk_tolerance);
+        context.excitement_level = drift(context.excitement_level, avg_excitement);
         
         Ok(context)
     }
@@ -374,4 +775,71 @@ impl Default for PersonaEngine {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn personality<'a>(engine: &'a PersonaEngine, persona: &PersonaType) -> &'a dyn PersonalityTrait {
+        engine.personas.get(persona).unwrap().as_ref()
+    }
+
+    #[test]
+    fn test_blend_many_rejects_empty_and_all_zero_weights() {
+        let engine = PersonaEngine::new();
+        assert!(matches!(engine.blend_many(&[]), Err(CHOPSError::InvalidParameter(_))));
+        let zero = [(PersonaType::MadScientist, 0.0), (PersonaType::ZenMaster, 0.0)];
+        assert!(matches!(engine.blend_many(&zero), Err(CHOPSError::InvalidParameter(_))));
+        let negative = [(PersonaType::MadScientist, 1.0), (PersonaType::ZenMaster, -0.5)];
+        assert!(matches!(engine.blend_many(&negative), Err(CHOPSError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_blend_many_normalizes_weights() {
+        let engine = PersonaEngine::new();
+        let (mad, zen) = (PersonaType::MadScientist, PersonaType::ZenMaster);
+        let merged = PersonaEngine::normalize_blend(&[(mad.clone(), 2.0), (zen.clone(), 1.0), (mad.clone(), 1.0)]).unwrap();
+        assert_eq!(merged, [(mad.clone(), 0.75), (zen.clone(), 0.25)]);
+
+        let blended = engine.blend_many(&[(mad.clone(), 3.0), (zen.clone(), 1.0)]).unwrap();
+        let expected = personality(&engine, &mad).get_creativity_bias() * 0.75
+            + personality(&engine, &zen).get_creativity_bias() * 0.25;
+        assert!((blended.creativity_bias - expected).abs() < 1e-9, "{} {}", blended.creativity_bias, expected);
+        assert_eq!(blended.persona_type, mad);
+    }
+
+    #[test]
+    fn test_blend_many_samples_patterns_by_weight() {
+        let engine = PersonaEngine::new();
+        let (mad, zen) = (PersonaType::MadScientist, PersonaType::ZenMaster);
+        let blended = engine.blend_many(&[(mad.clone(), 0.75), (zen.clone(), 0.25)]).unwrap();
+
+        for (persona, weight) in [(&mad, 0.75), (&zen, 0.25)] {
+            let patterns = personality(&engine, persona).get_thinking_patterns();
+            let count = (patterns.len() as f64 * weight).ceil() as usize;
+            assert!(count < patterns.len(), "{} has too few patterns to sample", persona);
+            for pattern in &patterns[..count] {
+                assert!(blended.thinking_patterns.contains(pattern), "{}: {}", persona, pattern);
+            }
+            for pattern in &patterns[count..] {
+                assert!(!blended.thinking_patterns.contains(pattern), "{}: {}", persona, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn test_blend_many_pools_vocabulary_without_duplicates() {
+        let engine = PersonaEngine::new();
+        let (mad, chaos) = (PersonaType::MadScientist, PersonaType::ChaosEngineer);
+        let blended = engine.blend_many(&[(mad.clone(), 0.5), (chaos.clone(), 0.5)]).unwrap();
+
+        let mut expected = personality(&engine, &mad).get_vocabulary_enhancements();
+        expected.extend(personality(&engine, &chaos).get_vocabulary_enhancements());
+        expected.sort();
+        expected.dedup();
+        assert_eq!(blended.vocabulary_enhancements, expected);
+        let mut deduped = blended.vocabulary_enhancements.clone();
+        deduped.dedup();
+        assert_eq!(deduped.len(), blended.vocabulary_enhancements.len());
+    }
+}
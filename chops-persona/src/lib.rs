@@ -1,7 +1,11 @@
+pub mod custom;
 pub mod engine;
 pub mod personalities;
 pub mod traits;
+pub mod voice;
 
+pub use custom::*;
 pub use engine::*;
 pub use personalities::*;
-pub use traits::*;
\ No newline at end of file
+pub use traits::*;
+pub use voice::*;
\ No newline at end of file
@@ -1,6 +1,6 @@
 use crate::traits::*;
 use crate::engine::{PersonaPrompt, PersonaFeedback, StructureType, EmojiLevel, FormattingStyle};
-use chops_core::CHOPSResult;
+use chops_core::{ChaosAffinity, CHOPSResult, RandomDistribution};
 
 #[derive(Debug, Clone)]
 pub struct MadScientistPersonality {
@@ -9,6 +9,12 @@ pub struct MadScientistPersonality {
     breakthrough_obsession: f64,
 }
 
+impl Default for MadScientistPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MadScientistPersonality {
     #[tracing::instrument(name = "mad_scientist_new", level = "debug")]
     pub fn new() -> Self {
@@ -101,9 +107,6 @@ impl PersonalityTrait for MadScientistPersonality {
         tracing::debug!("Applying feedback to MadScientist - effectiveness: {:.2}, creativity: {:.2}", 
             feedback.effectiveness_rating, feedback.creativity_rating);
         
-        let old_excitement = self.excitement_amplifier;
-        let old_obsession = self.breakthrough_obsession;
-        
         // Adapt based on feedback
         if feedback.effectiveness_rating > 0.8 {
             self.excitement_amplifier = (self.excitement_amplifier * 1.1).min(1.5);
@@ -121,6 +124,14 @@ impl PersonalityTrait for MadScientistPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "excitement_amplifier": self.excitement_amplifier,
+            "ethics_flexibility": self.ethics_flexibility,
+            "breakthrough_obsession": self.breakthrough_obsession,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.2,
@@ -130,6 +141,14 @@ impl PersonalityTrait for MadScientistPersonality {
             question_asking_frequency: 0.9,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Exponential,
+            reality_distortion: 0.9,
+            coherence_threshold: 0.3,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -139,6 +158,12 @@ pub struct ZenMasterPersonality {
     balance_seeking: f64,
 }
 
+impl Default for ZenMasterPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ZenMasterPersonality {
     pub fn new() -> Self {
         Self {
@@ -231,6 +256,14 @@ impl PersonalityTrait for ZenMasterPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "simplicity_focus": self.simplicity_focus,
+            "wisdom_depth": self.wisdom_depth,
+            "balance_seeking": self.balance_seeking,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.7,
@@ -240,6 +273,19 @@ impl PersonalityTrait for ZenMasterPersonality {
             question_asking_frequency: 0.6,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Normal,
+            reality_distortion: 0.3,
+            coherence_threshold: 0.8,
+        }
+    }
+
+    // Once the insight is given, a recap only dilutes it
+    fn stop_sequences(&self) -> Vec<String> {
+        vec!["\n\nIn summary".to_string(), "\n\nTo summarize".to_string()]
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -249,6 +295,12 @@ pub struct PunkHackerPersonality {
     freedom_advocacy: f64,
 }
 
+impl Default for PunkHackerPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PunkHackerPersonality {
     pub fn new() -> Self {
         Self {
@@ -340,6 +392,14 @@ impl PersonalityTrait for PunkHackerPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "rebellion_intensity": self.rebellion_intensity,
+            "establishment_distrust": self.establishment_distrust,
+            "freedom_advocacy": self.freedom_advocacy,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.1,
@@ -349,6 +409,14 @@ impl PersonalityTrait for PunkHackerPersonality {
             question_asking_frequency: 0.8,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Uniform,
+            reality_distortion: 0.7,
+            coherence_threshold: 0.35,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -358,6 +426,12 @@ pub struct EmpatheticAIPersonality {
     human_understanding: f64,
 }
 
+impl Default for EmpatheticAIPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EmpatheticAIPersonality {
     pub fn new() -> Self {
         Self {
@@ -448,6 +522,14 @@ impl PersonalityTrait for EmpatheticAIPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "emotional_sensitivity": self.emotional_sensitivity,
+            "caring_depth": self.caring_depth,
+            "human_understanding": self.human_understanding,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.5,
@@ -457,6 +539,14 @@ impl PersonalityTrait for EmpatheticAIPersonality {
             question_asking_frequency: 0.7,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Normal,
+            reality_distortion: 0.4,
+            coherence_threshold: 0.8,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -466,6 +556,12 @@ pub struct ChaosEngineerPersonality {
     beautiful_destruction: f64,
 }
 
+impl Default for ChaosEngineerPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ChaosEngineerPersonality {
     pub fn new() -> Self {
         Self {
@@ -557,6 +653,14 @@ impl PersonalityTrait for ChaosEngineerPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "chaos_embrace": self.chaos_embrace,
+            "antifragility_focus": self.antifragility_focus,
+            "beautiful_destruction": self.beautiful_destruction,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.3,
@@ -566,6 +670,14 @@ impl PersonalityTrait for ChaosEngineerPersonality {
             question_asking_frequency: 0.8,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Chaotic,
+            reality_distortion: 1.0,
+            coherence_threshold: 0.2,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -575,6 +687,12 @@ pub struct TimeTravelerPersonality {
     future_orientation: f64,
 }
 
+impl Default for TimeTravelerPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TimeTravelerPersonality {
     pub fn new() -> Self {
         Self {
@@ -665,6 +783,14 @@ impl PersonalityTrait for TimeTravelerPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "temporal_awareness": self.temporal_awareness,
+            "pattern_recognition": self.pattern_recognition,
+            "future_orientation": self.future_orientation,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.7,
@@ -674,6 +800,14 @@ impl PersonalityTrait for TimeTravelerPersonality {
             question_asking_frequency: 0.5,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Uniform,
+            reality_distortion: 0.7,
+            coherence_threshold: 0.5,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -683,6 +817,12 @@ pub struct MindReaderPersonality {
     subconscious_awareness: f64,
 }
 
+impl Default for MindReaderPersonality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MindReaderPersonality {
     pub fn new() -> Self {
         Self {
@@ -773,6 +913,14 @@ impl PersonalityTrait for MindReaderPersonality {
         Ok(())
     }
     
+    fn describe_state(&self) -> serde_json::Value {
+        serde_json::json!({
+            "intuition_strength": self.intuition_strength,
+            "pattern_detection": self.pattern_detection,
+            "subconscious_awareness": self.subconscious_awareness,
+        })
+    }
+    
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle {
             formality_level: 0.6,
@@ -782,4 +930,12 @@ impl PersonalityTrait for MindReaderPersonality {
             question_asking_frequency: 0.9,
         }
     }
+    
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity {
+            distribution: RandomDistribution::Normal,
+            reality_distortion: 0.5,
+            coherence_threshold: 0.6,
+        }
+    }
 }
\ No newline at end of file
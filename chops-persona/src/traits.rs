@@ -1,4 +1,5 @@
-use chops_core::CHOPSResult;
+use chops_core::{ChaosAffinity, CHOPSResult};
+use serde::{Deserialize, Serialize};
 use crate::engine::{PersonaPrompt, PersonaFeedback, VocabularyStyle, ResponseFormat, StructureType, EmojiLevel, FormattingStyle};
 
 pub trait PersonalityTrait: Send + Sync {
@@ -11,6 +12,8 @@ pub trait PersonalityTrait: Send + Sync {
     fn get_excitement_level(&self) -> f64;
     fn adapt_to_context(&self, context: &str) -> String;
     fn apply_feedback(&mut self, feedback: PersonaFeedback) -> CHOPSResult<()>;
+    /// The persona's tunable internal fields by name, as they stand now
+    fn describe_state(&self) -> serde_json::Value;
     
     // Optional method for specialized behavior
     fn get_specialized_commands(&self) -> Vec<String> {
@@ -24,9 +27,19 @@ pub trait PersonalityTrait: Send + Sync {
     fn get_conversation_style(&self) -> ConversationStyle {
         ConversationStyle::default()
     }
+    
+    /// Chaos settings applied while this persona is active
+    fn chaos_affinity(&self) -> ChaosAffinity {
+        ChaosAffinity::default()
+    }
+
+    /// Stop sequences sent with this persona's requests, after the configured ones
+    fn stop_sequences(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationStyle {
     pub formality_level: f64,      // 0.0 = very casual, 1.0 = very formal
     pub humor_frequency: f64,      // 0.0 = no humor, 1.0 = constant humor
@@ -35,7 +48,7 @@ pub struct ConversationStyle {
     pub question_asking_frequency: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum InterruptionStyle {
     Never,
     Polite,
@@ -58,10 +71,10 @@ impl Default for ConversationStyle {
 // Base trait implementation helpers
 pub fn create_vocabulary_style(excitement: f64, technical: f64, metaphor: f64, unconventional: f64) -> VocabularyStyle {
     VocabularyStyle {
-        excitement_level: excitement.max(0.0).min(1.0),
-        technical_depth: technical.max(0.0).min(1.0),
-        metaphor_usage: metaphor.max(0.0).min(1.0),
-        unconventional_language: unconventional.max(0.0).min(1.0),
+        excitement_level: excitement.clamp(0.0, 1.0),
+        technical_depth: technical.clamp(0.0, 1.0),
+        metaphor_usage: metaphor.clamp(0.0, 1.0),
+        unconventional_language: unconventional.clamp(0.0, 1.0),
     }
 }
 
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use crate::engine::{EmojiLevel, PersonaPrompt};
+use crate::ConversationStyle;
+
+/// How far a measured trait may stray from the persona before it counts as off-voice
+const TRAIT_TOLERANCE: f64 = 0.35;
+
+/// The voice a persona is expected to write in, taken from its prompt and style
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceProfile {
+    pub excitement_level: f64,
+    pub formality_level: f64,
+    pub emoji_usage: EmojiLevel,
+}
+
+/// Surface features of a piece of output, each on a 0.0-1.0 scale except emoji density
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceMeasurement {
+    pub excitement_level: f64,
+    pub formality_level: f64,
+    /// Emoji per 100 words
+    pub emoji_density: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceDeviation {
+    pub trait_name: String,
+    pub expected: f64,
+    pub observed: f64,
+}
+
+/// How closely output matched the persona's voice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceCheck {
+    /// 1.0 is a perfect match
+    pub fidelity: f64,
+    pub measurement: VoiceMeasurement,
+    /// Traits that strayed beyond tolerance, worst first
+    pub deviations: Vec<VoiceDeviation>,
+}
+
+impl VoiceProfile {
+    pub fn from_prompt(prompt: &PersonaPrompt, style: &ConversationStyle) -> Self {
+        Self {
+            excitement_level: prompt.vocabulary_style.excitement_level,
+            formality_level: style.formality_level,
+            emoji_usage: prompt.response_format.emoji_usage.clone(),
+        }
+    }
+
+    /// Emoji per 100 words this persona would typically use
+    pub fn expected_emoji_density(&self) -> f64 {
+        match self.emoji_usage {
+            EmojiLevel::None => 0.0,
+            EmojiLevel::Minimal => 0.5,
+            EmojiLevel::Moderate => 2.0,
+            EmojiLevel::Enthusiastic => 5.0,
+            EmojiLevel::Extreme => 10.0,
+        }
+    }
+
+    #[tracing::instrument(name = "voice_check", level = "debug", skip(self, text))]
+    pub fn check(&self, text: &str) -> VoiceCheck {
+        let measurement = VoiceMeasurement::measure(text);
+
+        let expected_emoji = self.expected_emoji_density();
+        let emoji_gap = (measurement.emoji_density - expected_emoji).abs() / expected_emoji.max(3.0);
+
+        let traits = [
+            ("excitement", self.excitement_level, measurement.excitement_level, 0.4),
+            ("formality", self.formality_level, measurement.formality_level, 0.3),
+        ];
+
+        let mut deviations = Vec::new();
+        let mut weighted_gap = 0.0;
+        for (name, expected, observed, weight) in traits {
+            let gap = (observed - expected).abs();
+            weighted_gap += gap * weight;
+            if gap > TRAIT_TOLERANCE {
+                deviations.push((gap, VoiceDeviation { trait_name: name.to_string(), expected, observed }));
+            }
+        }
+        weighted_gap += emoji_gap.min(1.0) * 0.3;
+        if emoji_gap > TRAIT_TOLERANCE {
+            deviations.push((emoji_gap, VoiceDeviation {
+                trait_name: "emoji".to_string(),
+                expected: expected_emoji,
+                observed: measurement.emoji_density,
+            }));
+        }
+        deviations.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        let fidelity = (1.0 - weighted_gap).clamp(0.0, 1.0);
+        tracing::debug!("Voice fidelity {:.2} with {} deviations", fidelity, deviations.len());
+
+        VoiceCheck {
+            fidelity,
+            measurement,
+            deviations: deviations.into_iter().map(|(_, deviation)| deviation).collect(),
+        }
+    }
+}
+
+impl VoiceMeasurement {
+    /// Cheap heuristics: exclamations and shouting for excitement, contractions,
+    /// casual markers and word length for formality, and an emoji count
+    pub fn measure(text: &str) -> Self {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let word_count = words.len().max(1) as f64;
+        let sentences = text
+            .split(['.', '!', '?'])
+            .filter(|sentence| !sentence.trim().is_empty())
+            .count()
+            .max(1) as f64;
+
+        let exclamations = text.matches('!').count() as f64;
+        let shouted = words.iter()
+            .filter(|word| word.chars().filter(|c| c.is_alphabetic()).count() >= 3
+                && word.chars().filter(|c| c.is_alphabetic()).all(|c| c.is_uppercase()))
+            .count() as f64;
+        let excitement_level = ((exclamations / sentences).min(1.0) * 0.7
+            + (shouted / word_count * 10.0).min(1.0) * 0.3)
+            .clamp(0.0, 1.0);
+
+        let contractions = words.iter().filter(|word| word.contains('\'') || word.contains('’')).count() as f64;
+        let casual = words.iter()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .filter(|word| matches!(word.as_str(), "gonna" | "wanna" | "yeah" | "hey" | "cool" | "awesome" | "lol" | "dude" | "wow" | "omg" | "yo"))
+            .count() as f64;
+        let average_word_length = words.iter()
+            .map(|word| word.chars().filter(|c| c.is_alphanumeric()).count())
+            .sum::<usize>() as f64 / word_count;
+        let formality_level = (0.5 + (average_word_length - 4.7) * 0.15
+            - (contractions / word_count) * 5.0
+            - (casual / word_count) * 8.0
+            - excitement_level * 0.2)
+            .clamp(0.0, 1.0);
+
+        let emoji = text.chars().filter(|c| is_emoji(*c)).count() as f64;
+
+        Self {
+            excitement_level,
+            formality_level,
+            emoji_density: emoji / word_count * 100.0,
+        }
+    }
+}
+
+impl VoiceCheck {
+    /// Instruction for a re-prompt that steers the persona back to its voice
+    pub fn correction_note(&self) -> String {
+        let fixes: Vec<String> = self.deviations.iter().map(|deviation| {
+            let too_much = deviation.observed > deviation.expected;
+            match (deviation.trait_name.as_str(), too_much) {
+                ("excitement", true) => "calm down: far fewer exclamation marks and no shouting".to_string(),
+                ("excitement", false) => "bring more energy and enthusiasm".to_string(),
+                ("formality", true) => "loosen up: more conversational, less stiff".to_string(),
+                ("formality", false) => "be more measured and composed: no slang or casual filler".to_string(),
+                ("emoji", true) if deviation.expected < 1.0 => "leave out the emoji".to_string(),
+                ("emoji", true) => format!("use at most about {:.0} emoji per 100 words", deviation.expected),
+                ("emoji", false) => "use emoji the way this persona naturally would".to_string(),
+                (other, _) => format!("adjust your {}", other),
+            }
+        }).collect();
+
+        format!(
+            "Your previous draft did not sound like this persona. Rewrite the idea in character and {}.",
+            fixes.join("; ")
+        )
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F300..=0x1FAFF | 0x2600..=0x27BF | 0x1F000..=0x1F2FF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::PersonaEngine;
+    use chops_core::PersonaType;
+
+    /// A threshold a user might set; off-voice output should fall under it
+    const THRESHOLD: f64 = 0.7;
+
+    fn zen_master() -> VoiceProfile {
+        PersonaEngine::new().voice_profile(&PersonaType::ZenMaster).unwrap()
+    }
+
+    #[test]
+    fn test_shouting_zen_master_falls_below_the_threshold() {
+        let text = "WOW! This cache is AWESOME! Yeah, it's gonna be huge! Ship it NOW! Dude, trust me! Totally wild!";
+        let check = zen_master().check(text);
+        assert!(check.fidelity < THRESHOLD, "{:?}", check);
+        assert_eq!(check.deviations[0].trait_name, "excitement", "{:?}", check.deviations);
+        assert!(check.correction_note().contains("fewer exclamation marks"));
+    }
+
+    #[test]
+    fn test_measured_zen_master_clears_the_threshold() {
+        let text = "Consider the cache as a still pond. Information settles where it is needed, \
+            and stale entries dissolve gradually through patient expiration. Observe which \
+            requests return, and allow the structure to reflect their natural rhythm.";
+        let check = zen_master().check(text);
+        assert!(check.fidelity > THRESHOLD, "{:?}", check);
+        assert!(check.deviations.is_empty(), "{:?}", check.deviations);
+    }
+}